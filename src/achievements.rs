@@ -0,0 +1,265 @@
+//! Achievements defined in "achievements.achievement_meta.ron",
+//! progressed by the [`GameplayEvent`]s and saved to the profile.
+
+use bevy::asset::{AssetLoader, io::Reader};
+use bevy::asset::{AsyncReadExt, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::asset_pipeline::loading::TrackedAssets;
+use crate::gameplay_event::{GameplayEvent, GameplayEventAppExt};
+use crate::player::PlayerType;
+use crate::storage::Profile;
+use crate::tower::tower_attack::Tower;
+use crate::ui::Screen;
+use crate::ui::toast_ui::Toast;
+
+pub(super) struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AchievementMetaAsset>()
+            .init_asset_loader::<AchievementMetaAssetLoader>()
+            .insert_resource(AchievementProfile::load())
+            .init_resource::<LevelAchievements>()
+            .add_systems(PreStartup, load_achievements)
+            .add_systems(
+                OnEnter(Screen::EnterLevel),
+                reset_level_achievements,
+            )
+            // Lifetime counters are only saved once in a while.
+            .add_systems(
+                OnExit(Screen::EnterLevel),
+                save_achievement_profile,
+            )
+            .add_gameplay_subscriber(track_achievements);
+    }
+}
+
+/// Startup system: load "achievements.achievement_meta.ron" and
+/// insert the handle as a resource.
+fn load_achievements(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
+) {
+    let handle =
+        asset_server.load("achievements.achievement_meta.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(AchievementMetaAssetHandle(handle));
+}
+
+fn reset_level_achievements(mut level: ResMut<LevelAchievements>) {
+    *level = LevelAchievements::default();
+}
+
+fn save_achievement_profile(profile: Res<AchievementProfile>) {
+    profile.save();
+}
+
+/// Progress the achievements and unlock the ones whose goal is
+/// reached, announcing them on both viewports.
+fn track_achievements(
+    mut commands: Commands,
+    mut events: EventReader<GameplayEvent>,
+    q_towers: Query<(), With<Tower>>,
+    registry: AchievementRegistry,
+    mut profile: ResMut<AchievementProfile>,
+    mut level: ResMut<LevelAchievements>,
+) {
+    let mut progressed = false;
+
+    for event in events.read() {
+        match event {
+            GameplayEvent::EnemyKilled { source, .. } => {
+                profile.enemies_killed += 1;
+                if q_towers.contains(*source) {
+                    *level.tower_kills.entry(*source).or_default() +=
+                        1;
+                }
+            }
+            GameplayEvent::RecipeCooked { .. } => {
+                profile.recipes_cooked += 1;
+            }
+            GameplayEvent::TowerPlaced { .. } => {
+                level.towers_placed += 1;
+            }
+            GameplayEvent::TowerDestroyed { .. } => {
+                level.towers_lost += 1;
+            }
+            GameplayEvent::LevelCleared => {
+                level.cleared = true;
+            }
+            GameplayEvent::WaveStarted { .. }
+            | GameplayEvent::DamageDealt { .. }
+            | GameplayEvent::ProjectileFired { .. }
+            | GameplayEvent::CookingStarted { .. }
+            | GameplayEvent::ItemCollected { .. } => continue,
+        }
+
+        progressed = true;
+    }
+
+    if progressed == false {
+        return;
+    }
+
+    let Some(achievements) = registry.get() else {
+        return;
+    };
+
+    let mut reached = achievements
+        .iter()
+        .filter(|(id, meta)| {
+            profile.unlocked.contains(*id) == false
+                && meta.goal.is_reached(&profile, &level)
+        })
+        .collect::<Vec<_>>();
+
+    if reached.is_empty() {
+        return;
+    }
+
+    // Announce in a stable order.
+    reached.sort_by_key(|(id, _)| *id);
+
+    for (id, meta) in reached {
+        info!("Achievement unlocked: {id}");
+        profile.unlocked.insert(id.clone());
+        level.unlocked.push(meta.name.clone());
+
+        for player_type in [PlayerType::A, PlayerType::B] {
+            commands.trigger(Toast {
+                player_type,
+                message: format!(
+                    "Achievement unlocked: {}",
+                    meta.name
+                ),
+            });
+        }
+    }
+
+    profile.save();
+}
+
+#[derive(Asset, TypePath, Deref, Debug, Clone, Deserialize)]
+pub struct AchievementMetaAsset(HashMap<String, AchievementMeta>);
+
+/// Achievement metadata loaded from RON files.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AchievementMeta {
+    pub name: String,
+    pub description: String,
+    pub goal: AchievementGoal,
+}
+
+/// What needs to be done to unlock an achievement.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AchievementGoal {
+    /// Defeat enemies with a single tower within a level.
+    TowerKills(u32),
+    /// Cook recipes across every run.
+    RecipesCooked(u32),
+    /// Defeat enemies across every run.
+    EnemiesKilled(u32),
+    /// Clear a level without losing any of the placed towers.
+    FlawlessClear,
+}
+
+impl AchievementGoal {
+    pub fn is_reached(
+        &self,
+        profile: &AchievementProfile,
+        level: &LevelAchievements,
+    ) -> bool {
+        match *self {
+            AchievementGoal::TowerKills(count) => {
+                level.tower_kills.values().any(|k| *k >= count)
+            }
+            AchievementGoal::RecipesCooked(count) => {
+                profile.recipes_cooked >= count
+            }
+            AchievementGoal::EnemiesKilled(count) => {
+                profile.enemies_killed >= count
+            }
+            // Placing no towers at all doesn't count.
+            AchievementGoal::FlawlessClear => {
+                level.cleared
+                    && level.towers_placed > 0
+                    && level.towers_lost == 0
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct AchievementMetaAssetHandle(Handle<AchievementMetaAsset>);
+
+#[derive(SystemParam)]
+pub struct AchievementRegistry<'w> {
+    pub handle: Res<'w, AchievementMetaAssetHandle>,
+    pub assets: Res<'w, Assets<AchievementMetaAsset>>,
+}
+
+impl AchievementRegistry<'_> {
+    pub fn get(&self) -> Option<&AchievementMetaAsset> {
+        self.assets.get(&self.handle.0)
+    }
+}
+
+/// Unlocked achievements and the lifetime counters towards the
+/// rest.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct AchievementProfile {
+    /// Ids of the unlocked achievements.
+    pub unlocked: HashSet<String>,
+    pub recipes_cooked: u32,
+    pub enemies_killed: u32,
+}
+
+impl Profile for AchievementProfile {
+    const KEY: &'static str = "achievements.profile.ron";
+}
+
+/// Progress towards the achievements within the current level.
+#[derive(Resource, Default, Debug)]
+pub struct LevelAchievements {
+    /// Enemies defeated by each [`Tower`].
+    pub tower_kills: HashMap<Entity, u32>,
+    pub towers_placed: u32,
+    pub towers_lost: u32,
+    pub cleared: bool,
+    /// Names of the achievements unlocked during the level.
+    pub unlocked: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct AchievementMetaAssetLoader;
+
+impl AssetLoader for AchievementMetaAssetLoader {
+    type Asset = AchievementMetaAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut ron_str = String::new();
+        reader.read_to_string(&mut ron_str).await?;
+
+        // Surface as a failed load instead of panicking.
+        ron::from_str::<AchievementMetaAsset>(&ron_str)
+            .map_err(std::io::Error::other)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievement_meta.ron"]
+    }
+}
@@ -1,20 +1,35 @@
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
 use crate::player::{PlayerState, PlayerType, QueryPlayers};
 use crate::util::PropagateComponentAppExt;
 
+pub mod touch_controls;
+
 pub(super) struct ActionPlugin;
 
 impl Plugin for ActionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(InputManagerPlugin::<PlayerAction>::default())
-            .add_systems(
-                Update,
-                hookup_target_action
-                    .run_if(in_state(PlayerState::Possessed)),
-            )
-            .add_observer(setup_gamepad_index).propagate_component::<TargetAction, Children>();
+        app.add_plugins((
+            InputManagerPlugin::<PlayerAction>::default(),
+            touch_controls::TouchControlsPlugin,
+        ))
+        // Gamepad chords hold `Select` first, which then keeps the
+        // single button actions they contain from firing (e.g.
+        // `RecipeBook` under `Ready`).
+        .insert_resource(ClashStrategy::PrioritizeLongest)
+        .register_required_components::<
+            InputMap<PlayerAction>,
+            InputLocks,
+        >()
+        .add_systems(
+            Update,
+            hookup_target_action
+                .run_if(in_state(PlayerState::Possessed)),
+        )
+        .add_observer(setup_gamepad_index)
+        .propagate_component::<TargetAction, Children>();
     }
 }
 
@@ -66,17 +81,58 @@ pub enum PlayerAction {
     Move,
     #[actionlike(DualAxis)]
     Aim,
+    /// Positive zooms the camera in.
+    #[actionlike(Axis)]
+    Zoom,
     Jump,
+    Climb,
+    Sprint,
+    Dash,
     Interact,
     Attack,
+    /// Switch to the next weapon in the inventory.
+    SwitchWeapon,
+    /// Reload the weapon from the inventory.
+    Reload,
+    /// Swing the bread, repeat for a combo.
+    Melee,
+    /// Lock onto the best enemy in view.
+    LockOn,
+    /// Mark an enemy for towers to focus on.
+    Paint,
+    /// Ping a spot for the partner.
+    Ping,
+    /// Hold to view the scoreboard of the level.
+    Scoreboard,
     // Inventory actions.
     CycleNext,
     CyclePrev,
+    Hotbar1,
+    Hotbar2,
+    Hotbar3,
+    Hotbar4,
+    Hotbar5,
     Placement,
     Cancel,
+    RecipeBook,
+    /// Buy and place cosmetic decorations.
+    Decorate,
+    /// Rename the closest owned tower.
+    Rename,
+    /// Toggle being ready for the next wave.
+    Ready,
 }
 
 impl PlayerAction {
+    /// Actions for selecting each hotbar slot.
+    pub const HOTBAR: [Self; 5] = [
+        Self::Hotbar1,
+        Self::Hotbar2,
+        Self::Hotbar3,
+        Self::Hotbar4,
+        Self::Hotbar5,
+    ];
+
     /// Create a new [`InputMap`] for gamepads.
     pub fn new_gamepad() -> InputMap<Self> {
         InputMap::default()
@@ -89,13 +145,110 @@ impl PlayerAction {
                 Self::Aim,
                 GamepadStick::RIGHT.with_deadzone_symmetric(0.1),
             )
+            // Holding `Select` turns the pitch of the right stick
+            // into zoom, leaving the d-pad for the hotbar.
+            .with_axis(
+                Self::Zoom,
+                AxislikeChord::new(
+                    GamepadButton::Select,
+                    GamepadControlAxis::RIGHT_Y
+                        .with_deadzone_symmetric(0.1),
+                ),
+            )
             .with(Self::Jump, GamepadButton::South)
+            .with(Self::Climb, GamepadButton::RightThumb)
+            .with(Self::Sprint, GamepadButton::LeftThumb)
+            .with(Self::Dash, GamepadButton::LeftTrigger2)
             .with(Self::Interact, GamepadButton::West)
             .with(Self::Attack, GamepadButton::RightTrigger2)
+            // `Select` alone is the modifier of every chord below.
+            .with(
+                Self::Paint,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::RightTrigger,
+                ]),
+            )
+            .with(
+                Self::SwitchWeapon,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::South,
+                ]),
+            )
+            .with(
+                Self::Reload,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::LeftTrigger,
+                ]),
+            )
+            .with(
+                Self::Melee,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::RightTrigger2,
+                ]),
+            )
+            .with(
+                Self::LockOn,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::East,
+                ]),
+            )
             .with(Self::CycleNext, GamepadButton::LeftTrigger)
             .with(Self::CyclePrev, GamepadButton::RightTrigger)
+            .with(Self::Hotbar1, GamepadButton::DPadLeft)
+            .with(Self::Hotbar2, GamepadButton::DPadUp)
+            .with(Self::Hotbar3, GamepadButton::DPadRight)
+            .with(Self::Hotbar4, GamepadButton::DPadDown)
+            // `Select` shifts the last d-pad slot to the fifth.
+            .with(
+                Self::Hotbar5,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::DPadDown,
+                ]),
+            )
             .with(Self::Placement, GamepadButton::North)
             .with(Self::Cancel, GamepadButton::East)
+            .with(Self::RecipeBook, GamepadButton::Start)
+            .with(
+                Self::Decorate,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::North,
+                ]),
+            )
+            .with(
+                Self::Rename,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::West,
+                ]),
+            )
+            .with(
+                Self::Ready,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::Start,
+                ]),
+            )
+            .with(
+                Self::Ping,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::RightThumb,
+                ]),
+            )
+            .with(
+                Self::Scoreboard,
+                ButtonlikeChord::new([
+                    GamepadButton::Select,
+                    GamepadButton::LeftThumb,
+                ]),
+            )
     }
 
     /// Create a new [`InputMap`] for keyboard and mouse.
@@ -104,16 +257,123 @@ impl PlayerAction {
             // KbM input bindings.
             .with_dual_axis(Self::Move, VirtualDPad::wasd())
             .with_dual_axis(Self::Aim, MouseMove::default())
+            // Plain scrolling cycles.
+            .with_axis(
+                Self::Zoom,
+                AxislikeChord::new(
+                    KeyCode::ControlLeft,
+                    MouseScrollAxis::Y,
+                ),
+            )
             .with(Self::Jump, KeyCode::Space)
+            .with(Self::Climb, KeyCode::KeyC)
+            .with(Self::Sprint, KeyCode::ShiftLeft)
+            .with(Self::Dash, KeyCode::KeyF)
             .with(Self::Interact, KeyCode::KeyE)
             .with(Self::Attack, MouseButton::Left)
+            .with(Self::Paint, MouseButton::Middle)
+            .with(Self::SwitchWeapon, KeyCode::KeyX)
+            .with(Self::Reload, KeyCode::KeyG)
+            .with(Self::Melee, KeyCode::KeyR)
+            .with(Self::LockOn, KeyCode::Tab)
             .with(Self::CycleNext, MouseScrollDirection::DOWN)
             .with(Self::CyclePrev, MouseScrollDirection::UP)
+            .with(Self::Hotbar1, KeyCode::Digit1)
+            .with(Self::Hotbar2, KeyCode::Digit2)
+            .with(Self::Hotbar3, KeyCode::Digit3)
+            .with(Self::Hotbar4, KeyCode::Digit4)
+            .with(Self::Hotbar5, KeyCode::Digit5)
             .with(Self::Placement, MouseButton::Right)
             .with(Self::Cancel, KeyCode::KeyQ)
+            .with(Self::RecipeBook, KeyCode::KeyB)
+            .with(Self::Decorate, KeyCode::KeyV)
+            .with(Self::Rename, KeyCode::KeyN)
+            .with(Self::Ready, KeyCode::KeyT)
+            .with(Self::Ping, KeyCode::KeyZ)
+            .with(Self::Scoreboard, KeyCode::KeyI)
+    }
+}
+
+/// Short labels of every input bound to the action, e.g.
+/// "T / Select + Start".
+pub fn binding_labels(
+    input_map: &InputMap<PlayerAction>,
+    action: &PlayerAction,
+) -> Option<String> {
+    let inputs = input_map.get_buttonlike(action)?;
+
+    Some(
+        inputs
+            .iter()
+            .map(|input| {
+                let input = format!("{input:?}");
+                // Chords are debugged as "ButtonlikeChord([A, B])".
+                input
+                    .strip_prefix("ButtonlikeChord([")
+                    .and_then(|i| i.strip_suffix("])"))
+                    .unwrap_or(&input)
+                    .split(", ")
+                    .map(binding_label)
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            })
+            .collect::<Vec<_>>()
+            .join(" / "),
+    )
+}
+
+/// Convert the debug name of an input into a short label.
+fn binding_label(input: &str) -> String {
+    match input {
+        "South" => "Ⓐ".to_string(),
+        "East" => "Ⓑ".to_string(),
+        "West" => "Ⓧ".to_string(),
+        "North" => "Ⓨ".to_string(),
+        "Left" => "LMB".to_string(),
+        "Right" => "RMB".to_string(),
+        input => input
+            .strip_prefix("Key")
+            .or_else(|| input.strip_prefix("Digit"))
+            .unwrap_or(input)
+            .to_string(),
     }
 }
 
+/// Reasons for the [`ActionState`] to be disabled, it's only
+/// enabled again once every lock is released.
+#[derive(Component, Default, Debug)]
+pub struct InputLocks(HashSet<InputLock>);
+
+impl InputLocks {
+    pub fn lock(
+        &mut self,
+        lock: InputLock,
+        action: &mut ActionState<PlayerAction>,
+    ) {
+        self.0.insert(lock);
+        action.disable_all();
+    }
+
+    pub fn release(
+        &mut self,
+        lock: InputLock,
+        action: &mut ActionState<PlayerAction>,
+    ) {
+        self.0.remove(&lock);
+        if self.0.is_empty() {
+            action.enable_all();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputLock {
+    /// Typing in the dev console.
+    Console,
+    /// Typing a tower name.
+    Renaming,
+}
+
 #[derive(Component)]
 pub struct GamepadIndex(u8);
 
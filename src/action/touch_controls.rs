@@ -0,0 +1,283 @@
+//! On-screen controls for touchscreens (e.g. the web build on a
+//! tablet), feeding the same [`ActionState`] as the other devices.
+//!
+//! - Left region: dragging from anywhere tilts a stick for
+//!   [`PlayerAction::Move`].
+//! - Right region: dragging for [`PlayerAction::Aim`].
+//! - Buttons in the bottom right for the rest.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy::window::PrimaryWindow;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use leafwing_input_manager::prelude::*;
+
+use crate::camera_controller::UI_RENDER_LAYER;
+
+use super::PlayerAction;
+
+/// Logical pixels the finger needs to move away from the stick's
+/// origin for a full tilt.
+const STICK_RADIUS: f32 = 60.0;
+/// Fraction of the window width (from the left) that spawns the
+/// stick when touched.
+const STICK_REGION: f32 = 0.4;
+const BUTTONS: [(PlayerAction, &str); 4] = [
+    (PlayerAction::Jump, "Jump"),
+    (PlayerAction::Interact, "Use"),
+    (PlayerAction::Attack, "Attack"),
+    (PlayerAction::Placement, "Place"),
+];
+
+pub(super) struct TouchControlsPlugin;
+
+impl Plugin for TouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            update_touch_actions
+                .in_set(InputManagerSystem::ManualControl),
+        )
+        .add_observer(spawn_touch_overlay)
+        .add_observer(despawn_touch_overlay);
+    }
+}
+
+fn update_touch_actions(
+    mut q_controls: Query<(
+        &mut TouchControls,
+        &mut ActionState<PlayerAction>,
+    )>,
+    mut q_buttons: Query<(
+        &TouchButton,
+        &ComputedNode,
+        &GlobalTransform,
+        &mut BackgroundColor,
+    )>,
+    mut q_knobs: Query<&mut Node, With<TouchStickKnob>>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+) {
+    let Ok((mut controls, mut action)) = q_controls.single_mut()
+    else {
+        return;
+    };
+    let Ok(window) = q_windows.single() else {
+        return;
+    };
+
+    // Touch positions are in logical pixels, ui nodes are not.
+    let button_rects = q_buttons
+        .iter()
+        .map(|(button, node, transform, _)| {
+            let scale = node.inverse_scale_factor();
+            let rect = Rect::from_center_size(
+                transform.translation().xy() * scale,
+                node.size() * scale,
+            );
+            (button.0, rect)
+        })
+        .collect::<Vec<_>>();
+    let button_at = |position: Vec2| {
+        button_rects
+            .iter()
+            .find(|(_, rect)| rect.contains(position))
+            .map(|(action, _)| *action)
+    };
+
+    // Assign new fingers to the stick or aim.
+    for touch in touches.iter_just_pressed() {
+        let position = touch.position();
+        if button_at(position).is_some() {
+            continue;
+        }
+
+        if position.x < window.width() * STICK_REGION {
+            if controls.stick.is_none() {
+                controls.stick = Some((touch.id(), position));
+            }
+        } else if controls.aim.is_none() {
+            controls.aim = Some(touch.id());
+        }
+    }
+
+    // Free up lifted fingers.
+    if let Some((id, _)) = controls.stick {
+        if touches.get_pressed(id).is_none() {
+            controls.stick = None;
+        }
+    }
+    if let Some(id) = controls.aim {
+        if touches.get_pressed(id).is_none() {
+            controls.aim = None;
+        }
+    }
+
+    let stick_offset = controls
+        .stick
+        .and_then(|(id, origin)| {
+            let touch = touches.get_pressed(id)?;
+            Some(
+                (touch.position() - origin)
+                    .clamp_length_max(STICK_RADIUS),
+            )
+        })
+        .unwrap_or_default();
+    // Screen space y points down.
+    action.set_axis_pair(
+        &PlayerAction::Move,
+        Vec2::new(stick_offset.x, -stick_offset.y) / STICK_RADIUS,
+    );
+
+    // Treated like mouse motion by the camera.
+    let aim_delta = controls
+        .aim
+        .and_then(|id| touches.get_pressed(id))
+        .map(|touch| touch.delta())
+        .unwrap_or_default();
+    action.set_axis_pair(&PlayerAction::Aim, aim_delta);
+
+    // Any other finger can hold the buttons.
+    let stick_id = controls.stick.map(|(id, _)| id);
+    let pressed_actions = touches
+        .iter()
+        .filter(|touch| {
+            Some(touch.id()) != stick_id
+                && Some(touch.id()) != controls.aim
+        })
+        .filter_map(|touch| button_at(touch.position()))
+        .collect::<Vec<_>>();
+
+    for (button, _, _, mut background) in q_buttons.iter_mut() {
+        match pressed_actions.contains(&button.0) {
+            true => {
+                action.press(&button.0);
+                background.0 = ZINC_200.with_alpha(0.6).into();
+            }
+            false => {
+                action.release(&button.0);
+                background.0 = ZINC_900.with_alpha(0.4).into();
+            }
+        }
+    }
+
+    for mut node in q_knobs.iter_mut() {
+        node.left = Val::Px(STICK_RADIUS * 0.5 + stick_offset.x);
+        node.top = Val::Px(STICK_RADIUS * 0.5 + stick_offset.y);
+    }
+}
+
+fn spawn_touch_overlay(
+    _: Trigger<OnAdd, TouchControls>,
+    mut commands: Commands,
+) {
+    let button = |(action, label): (PlayerAction, &str)| {
+        (
+            TouchButton(action),
+            Node {
+                width: Val::Px(80.0),
+                height: Val::Px(80.0),
+                margin: UiRect::all(Val::Px(8.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+            BackgroundColor(ZINC_900.with_alpha(0.4).into()),
+            BorderRadius::MAX,
+            Children::spawn(Spawn((
+                Text::new(label),
+                TextFont::from_font_size(16.0),
+                Pickable::IGNORE,
+            ))),
+        )
+    };
+
+    commands.spawn((
+        TouchOverlay,
+        UI_RENDER_LAYER,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(40.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::End,
+            ..default()
+        },
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+        Children::spawn((
+            // Stick.
+            Spawn((
+                Node {
+                    width: Val::Px(STICK_RADIUS * 2.0),
+                    height: Val::Px(STICK_RADIUS * 2.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                FocusPolicy::Pass,
+                BackgroundColor(ZINC_900.with_alpha(0.4).into()),
+                BorderRadius::MAX,
+                Children::spawn(Spawn((
+                    TouchStickKnob,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(STICK_RADIUS),
+                        height: Val::Px(STICK_RADIUS),
+                        left: Val::Px(STICK_RADIUS * 0.5),
+                        top: Val::Px(STICK_RADIUS * 0.5),
+                        ..default()
+                    },
+                    Pickable::IGNORE,
+                    BackgroundColor(ZINC_200.with_alpha(0.6).into()),
+                    BorderRadius::MAX,
+                ))),
+            )),
+            // Buttons.
+            Spawn((
+                Node {
+                    width: Val::Px(192.0),
+                    flex_wrap: FlexWrap::Wrap,
+                    ..default()
+                },
+                Pickable::IGNORE,
+                FocusPolicy::Pass,
+                Children::spawn(SpawnIter(
+                    BUTTONS.into_iter().map(button),
+                )),
+            )),
+        )),
+    ));
+}
+
+fn despawn_touch_overlay(
+    _: Trigger<OnRemove, TouchControls>,
+    mut commands: Commands,
+    q_overlays: Query<Entity, With<TouchOverlay>>,
+) {
+    for entity in q_overlays.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Drives the [`ActionState`] of the entity from touch inputs and
+/// shows the on-screen controls while it exists.
+#[derive(Component, Default, Debug)]
+pub struct TouchControls {
+    /// Finger id and origin of the stick.
+    stick: Option<(u64, Vec2)>,
+    /// Finger id of the aim.
+    aim: Option<u64>,
+}
+
+/// Root of the on-screen controls.
+#[derive(Component)]
+struct TouchOverlay;
+
+#[derive(Component)]
+struct TouchStickKnob;
+
+#[derive(Component)]
+struct TouchButton(PlayerAction);
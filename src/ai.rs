@@ -0,0 +1,357 @@
+//! Data driven utility AI, shared by the companion and the enemies.
+//!
+//! Every [`Brain`] picks a behavior out of the ones listed under
+//! its name in "brains.ai.ron":
+//!
+//! 1. Domain systems in [`AiSet::Sense`] fill in the [`AiInputs`]
+//!    (normalized to `0.0..=1.0`).
+//! 2. Each behavior is scored by multiplying its weight with the
+//!    [`Curve`] of every consideration, the highest score becomes
+//!    the [`Decision`].
+//! 3. Domain systems carry out the [`Decision`] afterwards.
+
+use bevy::asset::{AssetLoader, io::Reader};
+use bevy::asset::{AsyncReadExt, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use serde::Deserialize;
+
+use crate::asset_pipeline::loading::TrackedAssets;
+use crate::enemy::time_slice::{AiBudget, TimeSlicer};
+
+/// Score multiplier of the current behavior, so that brains don't
+/// flicker between behaviors with similar scores.
+const MOMENTUM: f32 = 1.1;
+
+pub(super) struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BrainsAsset>()
+            .init_asset_loader::<BrainsAssetLoader>()
+            .configure_sets(
+                PreUpdate,
+                (AiSet::Sense, AiSet::Decide)
+                    .chain()
+                    // Possessors drive the actions from decisions.
+                    .before(InputManagerSystem::ManualControl),
+            )
+            .add_systems(PreStartup, load_brains)
+            .add_systems(PreUpdate, decide.in_set(AiSet::Decide));
+
+        app.register_type::<Brain>().register_type::<Decision>();
+
+        #[cfg(feature = "dev")]
+        app.add_systems(
+            bevy_inspector_egui::bevy_egui::EguiContextPass,
+            brains_panel,
+        );
+    }
+}
+
+fn load_brains(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
+) {
+    let handle = asset_server.load("brains.ai.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(BrainsHandle(handle));
+}
+
+/// Score the behaviors of every [`Brain`] and pick the best one.
+fn decide(
+    mut q_brains: Query<(
+        &Brain,
+        &AiInputs,
+        &mut Decision,
+        &mut DecisionScores,
+    )>,
+    brain_registry: BrainRegistry,
+    budget: Res<AiBudget>,
+    mut slicer: Local<TimeSlicer>,
+) {
+    slicer.run(
+        q_brains.iter_mut(),
+        budget.0,
+        |(brain, inputs, mut decision, mut scores)| {
+            let Some(behaviors) = brain_registry.get_brain(brain)
+            else {
+                return;
+            };
+
+            scores.0.clear();
+            for behavior in behaviors {
+                let mut score = behavior.score(inputs);
+                if decision.is(&behavior.name) {
+                    score *= MOMENTUM;
+                }
+
+                scores.0.push((behavior.name.clone(), score));
+            }
+
+            let best = scores
+                .iter()
+                .filter(|(_, score)| *score > 0.0)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(name, _)| name.clone());
+
+            decision.set_if_neq(Decision(best));
+        },
+    );
+}
+
+#[cfg(feature = "dev")]
+fn brains_panel(
+    mut contexts: bevy_inspector_egui::bevy_egui::EguiContexts,
+    q_brains: Query<(
+        &Brain,
+        &Decision,
+        &DecisionScores,
+        &AiInputs,
+        Entity,
+    )>,
+) {
+    use bevy_inspector_egui::egui;
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new("AI Brains").default_open(false).show(
+        ctx,
+        |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (brain, decision, scores, inputs, entity) in
+                    q_brains.iter()
+                {
+                    let decision =
+                        decision.as_deref().unwrap_or("none");
+
+                    egui::CollapsingHeader::new(format!(
+                        "{entity} ({}): {decision}",
+                        brain.0
+                    ))
+                    .id_salt(entity)
+                    .show(ui, |ui| {
+                        for (name, score) in scores.iter() {
+                            ui.label(format!("{name}: {score:.2}"));
+                        }
+
+                        ui.separator();
+
+                        let mut inputs =
+                            inputs.iter().collect::<Vec<_>>();
+                        inputs.sort_by_key(|(name, _)| *name);
+                        for (name, value) in inputs {
+                            ui.weak(format!("{name} = {value:.2}"));
+                        }
+                    });
+                }
+            });
+        },
+    );
+}
+
+/// Ordering of the AI systems in [`PreUpdate`].
+#[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AiSet {
+    /// Fill in the [`AiInputs`].
+    Sense,
+    /// Pick the [`Decision`] of every [`Brain`].
+    Decide,
+}
+
+/// Name of the brain in "brains.ai.ron" that decides for this
+/// entity.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(AiInputs, Decision, DecisionScores)]
+pub struct Brain(pub String);
+
+impl Brain {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// Sensed values that considerations read, missing inputs read
+/// as `0.0`.
+#[derive(Component, Deref, Default, Debug)]
+pub struct AiInputs(HashMap<String, f32>);
+
+impl AiInputs {
+    /// Set an input, clamped to `0.0..=1.0`.
+    pub fn set(&mut self, name: &str, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+
+        match self.0.get_mut(name) {
+            Some(input) => *input = value,
+            None => {
+                self.0.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> f32 {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+}
+
+/// The behavior picked by the [`Brain`], [`None`] if every
+/// behavior scored `0.0`.
+#[derive(Component, Reflect, Deref, Default, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct Decision(Option<String>);
+
+impl Decision {
+    pub fn is(&self, behavior: &str) -> bool {
+        self.0.as_deref() == Some(behavior)
+    }
+}
+
+/// Scores of every behavior from the last decision.
+#[derive(Component, Deref, Default, Debug)]
+pub struct DecisionScores(Vec<(String, f32)>);
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BehaviorMeta {
+    pub name: String,
+    /// Score when every consideration is fully satisfied.
+    pub weight: f32,
+    /// Behaviors without considerations always score their
+    /// weight.
+    #[serde(default)]
+    pub considerations: Vec<Consideration>,
+}
+
+impl BehaviorMeta {
+    pub fn score(&self, inputs: &AiInputs) -> f32 {
+        self.considerations
+            .iter()
+            .map(|c| c.curve.evaluate(inputs.get(&c.input)))
+            .product::<f32>()
+            * self.weight
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Consideration {
+    /// Name of the [`AiInputs`] value.
+    pub input: String,
+    pub curve: Curve,
+}
+
+/// Maps an input in `0.0..=1.0` to a score multiplier.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub enum Curve {
+    Linear,
+    /// `1.0 - x`
+    Inverse,
+    /// `x * x`
+    Quadratic,
+    /// `1.0` at or above the threshold, `0.0` otherwise.
+    Above(f32),
+    /// `1.0` below the threshold, `0.0` otherwise.
+    Below(f32),
+}
+
+impl Curve {
+    pub fn evaluate(&self, x: f32) -> f32 {
+        match *self {
+            Curve::Linear => x,
+            Curve::Inverse => 1.0 - x,
+            Curve::Quadratic => x * x,
+            Curve::Above(threshold) => match x >= threshold {
+                true => 1.0,
+                false => 0.0,
+            },
+            Curve::Below(threshold) => match x < threshold {
+                true => 1.0,
+                false => 0.0,
+            },
+        }
+    }
+}
+
+/// Behaviors of every brain by name.
+#[derive(Asset, TypePath, Deref, Debug, Clone, Deserialize)]
+pub struct BrainsAsset(HashMap<String, Vec<BehaviorMeta>>);
+
+#[derive(Resource)]
+pub struct BrainsHandle(Handle<BrainsAsset>);
+
+#[derive(SystemParam)]
+pub struct BrainRegistry<'w> {
+    pub handle: Res<'w, BrainsHandle>,
+    pub assets: Res<'w, Assets<BrainsAsset>>,
+}
+
+impl BrainRegistry<'_> {
+    pub fn get(&self) -> Option<&BrainsAsset> {
+        self.assets.get(&self.handle.0)
+    }
+
+    pub fn get_brain(
+        &self,
+        brain: &Brain,
+    ) -> Option<&[BehaviorMeta]> {
+        self.get()?.get(&brain.0).map(Vec::as_slice)
+    }
+}
+
+#[derive(Default)]
+pub struct BrainsAssetLoader;
+
+impl AssetLoader for BrainsAssetLoader {
+    type Asset = BrainsAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut ron_str = String::new();
+        reader.read_to_string(&mut ron_str).await?;
+
+        // Surface as a failed load instead of panicking.
+        ron::from_str::<BrainsAsset>(&ron_str)
+            .map_err(std::io::Error::other)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ai.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_behavior_score() {
+        let behavior = ron::from_str::<BehaviorMeta>(
+            r#"(
+                name: "flee",
+                weight: 2.0,
+                considerations: [
+                    (input: "health", curve: Inverse),
+                    (input: "danger", curve: Above(0.5)),
+                ],
+            )"#,
+        )
+        .unwrap();
+
+        let mut inputs = AiInputs::default();
+        inputs.set("health", 0.25);
+        assert_eq!(behavior.score(&inputs), 0.0);
+
+        inputs.set("danger", 3.0);
+        assert_eq!(behavior.score(&inputs), 1.5);
+    }
+}
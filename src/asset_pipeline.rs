@@ -1,21 +1,35 @@
+use std::path::Path;
+
 use animation_pipeline::AnimationGraphMap;
 use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
+use loading::AssetLoadErrors;
+
+use crate::inventory::item::ItemRegistry;
+use crate::level::{LEVELS, LevelInfo};
 
+pub mod animation_controller;
 pub mod animation_pipeline;
+pub mod loading;
+#[cfg(feature = "dev")]
+mod prefab_reload;
 
 pub(super) struct AssetPipelinePlugin;
 
 impl Plugin for AssetPipelinePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(animation_pipeline::AnimationPipelinePlugin);
+        app.add_plugins((
+            animation_pipeline::AnimationPipelinePlugin,
+            loading::LoadingPlugin,
+        ));
 
         let loading_state = LoadingState::new(
             AssetState::LoadingGltf,
         )
         .continue_to_state(AssetState::LoadingAnimation)
+        .on_failure_continue_to_state(AssetState::Failed)
         .with_dynamic_assets_file::<StandardDynamicAssetCollection>(
             "dynamic_asset.assets.ron",
         )
@@ -24,14 +38,92 @@ impl Plugin for AssetPipelinePlugin {
 
         app.init_state::<AssetState>()
             .init_resource::<CurrentScene>()
-            .add_loading_state(loading_state);
+            .add_loading_state(loading_state)
+            .add_systems(
+                OnEnter(AssetState::LoadingRegistries),
+                (validate_prefab_ids, validate_level_scenes),
+            )
+            .add_systems(
+                Update,
+                validate_item_prefabs
+                    .run_if(in_state(AssetState::LoadingRegistries))
+                    .before(loading::finish_loading),
+            );
 
         #[cfg(feature = "dev")]
-        app.register_type::<SceneAssets>()
+        app.add_plugins(prefab_reload::PrefabReloadPlugin)
+            .register_type::<SceneAssets>()
             .register_type::<PrefabAssets>();
     }
 }
 
+/// Make sure that every [`PrefabId`] is in the manifest so that
+/// missing prefabs are caught at startup.
+fn validate_prefab_ids(
+    prefabs: Res<PrefabAssets>,
+    mut errors: ResMut<AssetLoadErrors>,
+) {
+    for id in PrefabId::ALL {
+        let path = PrefabName::from(id).cast();
+
+        if prefabs.named_prefabs.contains_key(&path) == false {
+            error!("{id:?} prefab '{path}' is not in the manifest!");
+            errors.push(format!("{path}: missing from the manifest"));
+        }
+    }
+}
+
+/// Make sure that the prefab of every item is in the manifest
+/// once the item registry has been loaded.
+fn validate_item_prefabs(
+    prefabs: Res<PrefabAssets>,
+    item_registry: ItemRegistry,
+    mut errors: ResMut<AssetLoadErrors>,
+    mut validated: Local<bool>,
+) {
+    if *validated {
+        return;
+    }
+    let Some(item_meta_asset) = item_registry.get() else {
+        return;
+    };
+    *validated = true;
+
+    for (item_id, item_meta) in item_meta_asset.iter() {
+        let Some(prefab_name) = item_meta.prefab_name() else {
+            continue;
+        };
+        let path = prefab_name.cast();
+
+        if prefabs.named_prefabs.contains_key(&path) == false {
+            error!(
+                "{item_id} prefab '{path}' is not in the manifest!"
+            );
+            errors.push(format!("{path}: missing from the manifest"));
+        }
+    }
+}
+
+/// Make sure that the scene of every level in [`LEVELS`] is
+/// preloaded.
+fn validate_level_scenes(
+    scenes: Res<SceneAssets>,
+    mut errors: ResMut<AssetLoadErrors>,
+) {
+    for level in LEVELS.iter() {
+        if scenes.level(level).is_none() {
+            error!(
+                "{} scene '{}' is not in the manifest!",
+                level.name, level.scene
+            );
+            errors.push(format!(
+                "{}: missing from the manifest",
+                level.scene
+            ));
+        }
+    }
+}
+
 #[derive(SystemParam)]
 pub struct SceneAssetsLoader<'w, 's> {
     commands: Commands<'w, 's>,
@@ -56,10 +148,14 @@ impl SceneAssetsLoader<'_, '_> {
         Ok(())
     }
 
-    pub fn load_level1(&mut self) -> Result {
+    pub fn load_level(&mut self, level: &LevelInfo) -> Result {
+        let handle = self
+            .scenes
+            .level(level)
+            .ok_or(format!("{} is not preloaded!", level.scene))?;
         let gltf = self
             .gltfs
-            .get(&self.scenes.level1)
+            .get(handle)
             .ok_or("Scene should have been loaded")?;
 
         self.load_scene(
@@ -74,13 +170,11 @@ impl SceneAssetsLoader<'_, '_> {
     /// Despawn the last scene and spawns a new scene,
     /// overwritting the [`CurrentScene`].
     fn load_scene(&mut self, scene: Handle<Scene>) {
-        if let Some(last_scene) = self.current_scene.get() {
-            self.commands.entity(last_scene).despawn();
-        }
-
         let id = self.commands.spawn(SceneRoot(scene)).id();
 
-        self.current_scene.0 = Some(id);
+        if let Some(last_scene) = self.current_scene.replace(id) {
+            self.commands.entity(last_scene).despawn();
+        }
     }
 }
 
@@ -90,8 +184,19 @@ impl SceneAssetsLoader<'_, '_> {
 pub struct SceneAssets {
     #[asset(key = "scenes.default")]
     default_scene: Handle<Gltf>,
-    #[asset(key = "scenes.level1")]
-    level1: Handle<Gltf>,
+    /// Scenes of the [`LEVELS`].
+    #[asset(key = "scenes.levels", collection(typed))]
+    levels: Vec<Handle<Gltf>>,
+}
+
+impl SceneAssets {
+    fn level(&self, level: &LevelInfo) -> Option<&Handle<Gltf>> {
+        self.levels.iter().find(|handle| {
+            handle.path().is_some_and(|path| {
+                path.path() == Path::new(level.scene)
+            })
+        })
+    }
 }
 
 #[derive(AssetCollection, Resource, Debug)]
@@ -140,12 +245,67 @@ impl PrefabName<'_> {
     }
 }
 
+/// Prefabs that are referenced directly in code.
+///
+/// Validated against the manifest ("dynamic_asset.assets.ron")
+/// at load time, data driven prefabs (e.g. items and recipes)
+/// use [`PrefabName::FileName`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrefabId {
+    PoloBun,
+    Baguette,
+    PoloBunSmall,
+    BaguetteSmall,
+    MouseA,
+    Corn,
+    Popcorn,
+    RoastedCorn,
+}
+
+impl PrefabId {
+    pub const ALL: [Self; 8] = [
+        Self::PoloBun,
+        Self::Baguette,
+        Self::PoloBunSmall,
+        Self::BaguetteSmall,
+        Self::MouseA,
+        Self::Corn,
+        Self::Popcorn,
+        Self::RoastedCorn,
+    ];
+
+    /// The file name in `prefabs/`.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            PrefabId::PoloBun => "polo_bun",
+            PrefabId::Baguette => "baguette",
+            PrefabId::PoloBunSmall => "polo_bun_small",
+            PrefabId::BaguetteSmall => "baguette_small",
+            PrefabId::MouseA => "mouse_a",
+            PrefabId::Corn => "corn",
+            PrefabId::Popcorn => "popcorn",
+            PrefabId::RoastedCorn => "roasted_corn",
+        }
+    }
+}
+
+impl From<PrefabId> for PrefabName<'static> {
+    fn from(id: PrefabId) -> Self {
+        PrefabName::FileName(id.file_name())
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
 pub enum AssetState {
     #[default]
     LoadingGltf,
     LoadingAnimation,
+    /// Waiting for [`loading::TrackedAssets`], e.g. the item and
+    /// recipe registries.
+    LoadingRegistries,
     Loaded,
+    /// An asset failed to load, see [`loading::AssetLoadErrors`].
+    Failed,
 }
 
 /// The current loaded scene instance.
@@ -156,4 +316,14 @@ impl CurrentScene {
     pub fn get(&self) -> Option<Entity> {
         self.0
     }
+
+    /// Set a new scene entity, returning the previous one if any.
+    pub fn replace(&mut self, entity: Entity) -> Option<Entity> {
+        self.0.replace(entity)
+    }
+
+    /// Clear the current scene, returning it if any.
+    pub fn take(&mut self) -> Option<Entity> {
+        self.0.take()
+    }
 }
@@ -0,0 +1,483 @@
+//! State machine on top of the [`AnimationTransitions`], shared by
+//! every animated gameplay entity.
+//!
+//! Each kind of entity describes its states with an
+//! [`AnimationState`] enum, mapping every state to a [`StateClip`].
+//! The gameplay only decides on the state, the
+//! [`AnimationGraphController`] crossfades between the clips and
+//! triggers [`AnimationStateFinished`] when a clip ends.
+//!
+//! ```ignore
+//! app.add_animation_controller::<EnemyAnimation>();
+//!
+//! fn walk(mut q_controllers: Query<&mut AnimationGraphController<EnemyAnimation>>) {
+//!     for mut controller in q_controllers.iter_mut() {
+//!         controller.set(EnemyAnimation::Walk);
+//!     }
+//! }
+//! ```
+
+use core::fmt::Debug;
+use core::time::Duration;
+use std::borrow::Cow;
+
+use bevy::animation::AnimationTarget;
+use bevy::prelude::*;
+
+use super::AssetState;
+use super::animation_pipeline::NodeMap;
+
+/// States of an animated entity, each played with a clip from the
+/// entity's [`NodeMap`].
+pub trait AnimationState:
+    Send + Sync + Clone + PartialEq + Debug + 'static
+{
+    fn clip(&self) -> StateClip;
+
+    /// State to continue with once the clip of this one ends.
+    fn next(&self) -> Option<Self> {
+        None
+    }
+
+    /// Returns `true` if the clip (or its fallback) exists, for
+    /// states that are optional.
+    fn is_available(&self, node_map: &NodeMap) -> bool {
+        self.clip().node(node_map).is_some()
+    }
+}
+
+/// How the clip of an [`AnimationState`] is played.
+#[derive(Debug, Clone)]
+pub struct StateClip {
+    /// Name of the clip in the [`NodeMap`].
+    pub name: Cow<'static, str>,
+    /// Played instead if the clip doesn't exist.
+    pub fallback: Option<&'static str>,
+    /// Crossfade duration from the previous clip.
+    pub transition: Duration,
+    pub speed: f32,
+    pub repeat: bool,
+}
+
+impl StateClip {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            fallback: None,
+            transition: Duration::ZERO,
+            speed: 1.0,
+            repeat: false,
+        }
+    }
+
+    pub fn with_fallback(mut self, fallback: &'static str) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    pub fn with_transition(mut self, millis: u64) -> Self {
+        self.transition = Duration::from_millis(millis);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    pub fn node(
+        &self,
+        node_map: &NodeMap,
+    ) -> Option<AnimationNodeIndex> {
+        node_map
+            .get(self.name.as_ref())
+            .or_else(|| node_map.get(self.fallback?))
+            .copied()
+    }
+}
+
+/// Plays the clips of the [`AnimationState`]s, placed on the
+/// [`AnimationOwner`](super::animation_pipeline::AnimationOwner)
+/// next to its [`NodeMap`].
+#[derive(Component, Debug)]
+pub struct AnimationGraphController<S: AnimationState> {
+    /// State decided by the gameplay.
+    state: Option<S>,
+    /// Played over [`Self::state`] until its clip ends.
+    one_shot: Option<S>,
+    /// The state whose clip is playing, without a node if the
+    /// clip is missing.
+    playing: Option<(S, Option<AnimationNodeIndex>)>,
+    /// Whether [`AnimationStateFinished`] was triggered for the
+    /// playing clip.
+    finished: bool,
+}
+
+impl<S: AnimationState> Default for AnimationGraphController<S> {
+    fn default() -> Self {
+        Self {
+            state: None,
+            one_shot: None,
+            playing: None,
+            finished: false,
+        }
+    }
+}
+
+impl<S: AnimationState> AnimationGraphController<S> {
+    pub fn new(state: S) -> Self {
+        Self {
+            state: Some(state),
+            ..default()
+        }
+    }
+
+    /// Switch to the state, nothing happens if it's already the
+    /// current one.
+    pub fn set(&mut self, state: S) {
+        self.state = Some(state);
+    }
+
+    /// Switch to the state right away, cancelling the
+    /// [`Self::play_once`] state, e.g. for dying.
+    pub fn interrupt(&mut self, state: S) {
+        self.one_shot = None;
+        self.state = Some(state);
+    }
+
+    /// Play the state once over the current one, e.g. for hit
+    /// reactions.
+    pub fn play_once(&mut self, state: S) {
+        self.one_shot = Some(state);
+        // Restart even if it's already playing.
+        self.playing = None;
+    }
+
+    /// The state that should be playing.
+    pub fn current(&self) -> Option<&S> {
+        self.one_shot.as_ref().or(self.state.as_ref())
+    }
+}
+
+/// Triggered on the owner of the [`AnimationGraphController`] when
+/// the clip of a state that doesn't repeat ends.
+#[derive(Event, Debug, Clone)]
+pub struct AnimationStateFinished<S: AnimationState> {
+    pub state: S,
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationControllerSystems;
+
+pub trait AnimationControllerAppExt {
+    /// Play the clips of every [`AnimationGraphController<S>`],
+    /// the states should be decided before
+    /// [`AnimationControllerSystems`].
+    fn add_animation_controller<S: AnimationState>(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl AnimationControllerAppExt for App {
+    fn add_animation_controller<S: AnimationState>(
+        &mut self,
+    ) -> &mut Self {
+        self.add_systems(
+            Update,
+            update_animation_controllers::<S>
+                .in_set(AnimationControllerSystems)
+                .run_if(in_state(AssetState::Loaded)),
+        )
+    }
+}
+
+fn update_animation_controllers<S: AnimationState>(
+    mut commands: Commands,
+    mut q_controllers: Query<(
+        &mut AnimationGraphController<S>,
+        &NodeMap,
+        &AnimationTarget,
+        Entity,
+    )>,
+    mut q_animation_players: Query<(
+        &mut AnimationPlayer,
+        &mut AnimationTransitions,
+    )>,
+) {
+    for (mut controller, node_map, animation_target, entity) in
+        q_controllers.iter_mut()
+    {
+        let Ok((mut anim_player, mut anim_transitions)) =
+            q_animation_players.get_mut(animation_target.player)
+        else {
+            continue;
+        };
+
+        // Report the end of the clip and move on.
+        if let Some((state, Some(node))) = controller.playing.clone()
+        {
+            let finished = anim_player
+                .animation(node)
+                .is_none_or(|a| a.is_finished());
+
+            if finished && controller.finished == false {
+                controller.finished = true;
+
+                if controller.one_shot.as_ref() == Some(&state) {
+                    controller.one_shot = None;
+                } else if let Some(next) = state.next() {
+                    controller.state = Some(next);
+                }
+
+                commands.trigger_targets(
+                    AnimationStateFinished {
+                        state: state.clone(),
+                    },
+                    entity,
+                );
+            }
+        }
+
+        let Some(state) = controller.current().cloned() else {
+            continue;
+        };
+
+        if controller
+            .playing
+            .as_ref()
+            .is_some_and(|(playing, _)| *playing == state)
+        {
+            continue;
+        }
+
+        let clip = state.clip();
+        let Some(node) = clip.node(node_map) else {
+            warn!(
+                "No '{}' animation found for {state:?}!",
+                clip.name
+            );
+
+            // Don't try again every frame.
+            if controller.one_shot.as_ref() == Some(&state) {
+                controller.one_shot = None;
+            }
+            controller.playing = Some((state, None));
+            controller.finished = true;
+            continue;
+        };
+
+        let active_animation = anim_transitions
+            .play(&mut anim_player, node, clip.transition)
+            .set_speed(clip.speed);
+        if clip.repeat {
+            active_animation.repeat();
+        }
+
+        controller.playing = Some((state, Some(node)));
+        controller.finished = clip.repeat;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::animation::AnimationTargetId;
+    use bevy::platform::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TestAnimation {
+        Idle,
+        Hit,
+        Land,
+        /// Has no clip in the [`NodeMap`].
+        Missing,
+    }
+
+    impl AnimationState for TestAnimation {
+        fn clip(&self) -> StateClip {
+            match self {
+                TestAnimation::Idle => {
+                    StateClip::new("Idle").repeat()
+                }
+                TestAnimation::Hit => StateClip::new("Hit"),
+                TestAnimation::Land => StateClip::new("Land"),
+                TestAnimation::Missing => StateClip::new("Missing"),
+            }
+        }
+
+        fn next(&self) -> Option<Self> {
+            match self {
+                TestAnimation::Land => Some(TestAnimation::Idle),
+                _ => None,
+            }
+        }
+    }
+
+    /// States of the [`AnimationStateFinished`] triggered so far.
+    #[derive(Resource, Default)]
+    struct Finished(Vec<TestAnimation>);
+
+    const IDLE: usize = 1;
+    const HIT: usize = 2;
+    const LAND: usize = 3;
+
+    /// Returns the app, the owner and the animation player.
+    fn setup() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Finished>()
+            .add_systems(
+                Update,
+                update_animation_controllers::<TestAnimation>,
+            )
+            .add_observer(
+                |trigger: Trigger<
+                    AnimationStateFinished<TestAnimation>,
+                >,
+                 mut finished: ResMut<Finished>| {
+                    finished.0.push(trigger.state);
+                },
+            );
+
+        let player = app
+            .world_mut()
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationTransitions::new(),
+            ))
+            .id();
+        let owner = app
+            .world_mut()
+            .spawn((
+                AnimationGraphController::new(TestAnimation::Idle),
+                NodeMap::new(HashMap::from_iter([
+                    (
+                        "Idle".to_string(),
+                        AnimationNodeIndex::new(IDLE),
+                    ),
+                    ("Hit".to_string(), AnimationNodeIndex::new(HIT)),
+                    (
+                        "Land".to_string(),
+                        AnimationNodeIndex::new(LAND),
+                    ),
+                ])),
+                AnimationTarget {
+                    id: AnimationTargetId::from_name(&Name::new(
+                        "Owner",
+                    )),
+                    player,
+                },
+            ))
+            .id();
+
+        app.update();
+
+        (app, owner, player)
+    }
+
+    /// The clip reached its end.
+    fn finish_clip(app: &mut App, player: Entity, node: usize) {
+        app.world_mut()
+            .get_mut::<AnimationPlayer>(player)
+            .unwrap()
+            .stop(AnimationNodeIndex::new(node));
+    }
+
+    fn playing(app: &App, owner: Entity) -> Option<TestAnimation> {
+        app.world()
+            .get::<AnimationGraphController<TestAnimation>>(owner)
+            .and_then(|c| c.playing.as_ref())
+            .map(|(state, _)| *state)
+    }
+
+    fn controller(
+        app: &mut App,
+        owner: Entity,
+    ) -> Mut<'_, AnimationGraphController<TestAnimation>> {
+        app.world_mut()
+            .get_mut::<AnimationGraphController<TestAnimation>>(owner)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_one_shot_returns_to_state() {
+        let (mut app, owner, player) = setup();
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Idle));
+
+        controller(&mut app, owner).play_once(TestAnimation::Hit);
+        app.update();
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Hit));
+
+        finish_clip(&mut app, player, HIT);
+        app.update();
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Idle));
+        assert_eq!(
+            app.world().resource::<Finished>().0,
+            [TestAnimation::Hit]
+        );
+    }
+
+    #[test]
+    fn test_next_state() {
+        let (mut app, owner, player) = setup();
+
+        controller(&mut app, owner).set(TestAnimation::Land);
+        app.update();
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Land));
+
+        finish_clip(&mut app, player, LAND);
+        app.update();
+        assert_eq!(
+            controller(&mut app, owner).current(),
+            Some(&TestAnimation::Idle)
+        );
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Idle));
+    }
+
+    #[test]
+    fn test_finished_triggered_once() {
+        let (mut app, owner, player) = setup();
+
+        controller(&mut app, owner).set(TestAnimation::Hit);
+        app.update();
+        finish_clip(&mut app, player, HIT);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        // Stays on the last frame of the clip.
+        assert_eq!(playing(&app, owner), Some(TestAnimation::Hit));
+        assert_eq!(
+            app.world().resource::<Finished>().0,
+            [TestAnimation::Hit]
+        );
+
+        // Repeating clips never finish.
+        controller(&mut app, owner).set(TestAnimation::Idle);
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Finished>().0.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_clip() {
+        let (mut app, owner, _) = setup();
+
+        controller(&mut app, owner).play_once(TestAnimation::Missing);
+        app.update();
+
+        // Given up on the one-shot without reporting it.
+        assert_eq!(
+            controller(&mut app, owner).current(),
+            Some(&TestAnimation::Idle)
+        );
+        assert!(app.world().resource::<Finished>().0.is_empty());
+    }
+}
@@ -14,17 +14,56 @@ impl Plugin for AnimationPipelinePlugin {
             OnEnter(AssetState::LoadingAnimation),
             setup_prefab_animation_graphs,
         )
-        .add_observer(setup_animation_player_target);
+        .add_observer(setup_animation_player_target)
+        .add_observer(forward_animation_event);
 
         #[cfg(feature = "dev")]
         app.register_type::<AnimationPlayerTargets>();
     }
 }
 
+/// Animation events to be inserted into the prefab animation clips
+/// in the form of (prefab file name, animation name, times, kind).
+///
+/// Times are normalized based on the clip's duration (0 - 1).
+const ANIMATION_EVENTS: &[(
+    &str,
+    &str,
+    &[f32],
+    AnimationEventKind,
+)] = &[
+    (
+        "polo_bun",
+        "Walking",
+        &[0.25, 0.75],
+        AnimationEventKind::Footstep,
+    ),
+    (
+        "baguette",
+        "Walking",
+        &[0.25, 0.75],
+        AnimationEventKind::Footstep,
+    ),
+    (
+        "mouse_a",
+        "Walk",
+        &[0.25, 0.75],
+        AnimationEventKind::Footstep,
+    ),
+    ("mouse_a", "Eat", &[0.5], AnimationEventKind::AttackImpact),
+    (
+        "rotisserie",
+        "OnStart",
+        &[0.3],
+        AnimationEventKind::OvenDoorOpen,
+    ),
+];
+
 fn setup_prefab_animation_graphs(
     mut prefabs: ResMut<PrefabAssets>,
     gltfs: Res<Assets<Gltf>>,
     mut graphs: ResMut<Assets<AnimationGraph>>,
+    mut clips: ResMut<Assets<AnimationClip>>,
     mut state: ResMut<NextState<AssetState>>,
 ) -> Result {
     let mut named_graphs = Vec::new();
@@ -34,6 +73,7 @@ fn setup_prefab_animation_graphs(
             .get_gltf(PrefabName::Absolute(name), &gltfs)
             .ok_or("Prefab should have been loaded.")?;
 
+        let prefab_path = name.as_str();
         let mut graph = AnimationGraph::new();
         let mut node_map = HashMap::new();
 
@@ -47,6 +87,26 @@ fn setup_prefab_animation_graphs(
                 continue;
             };
 
+            for (_, _, times, kind) in ANIMATION_EVENTS.iter().filter(
+                |(prefab, anim, ..)| {
+                    *anim == node_name
+                        && PrefabName::FileName(prefab).cast()
+                            == prefab_path
+                },
+            ) {
+                let Some(clip_asset) = clips.get_mut(clip) else {
+                    continue;
+                };
+
+                let duration = clip_asset.duration();
+                for time in times.iter() {
+                    clip_asset.add_event(
+                        time * duration,
+                        AnimationClipEvent { kind: *kind },
+                    );
+                }
+            }
+
             node_map.insert(
                 node_name,
                 graph.add_clip(clip.clone(), 1.0, graph.root),
@@ -58,7 +118,7 @@ fn setup_prefab_animation_graphs(
             name.clone(),
             AnimationGraphMap {
                 graph: graph_handle,
-                node_map: NodeMap(Arc::new(node_map)),
+                node_map: NodeMap::new(node_map),
             },
         ));
     }
@@ -71,7 +131,7 @@ fn setup_prefab_animation_graphs(
         "Loading state '{:?}' is done",
         AssetState::LoadingAnimation
     );
-    state.set(AssetState::Loaded);
+    state.set(AssetState::LoadingRegistries);
 
     Ok(())
 }
@@ -99,6 +159,25 @@ fn setup_animation_player_target(
     commands.entity(scene_entity).insert(targets);
 }
 
+/// Forward [`AnimationClipEvent`]s from the [`AnimationPlayer`]
+/// to the [`AnimationOwner`] as an [`AnimationEvent`].
+fn forward_animation_event(
+    trigger: Trigger<AnimationClipEvent>,
+    mut commands: Commands,
+    q_owners: Query<&AnimationOwner>,
+) {
+    let Ok(owner) = q_owners.get(trigger.target()) else {
+        return;
+    };
+
+    commands.trigger_targets(
+        AnimationEvent {
+            kind: trigger.event().kind,
+        },
+        owner.0,
+    );
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "dev", derive(Reflect))]
 pub struct AnimationGraphMap {
@@ -110,6 +189,14 @@ pub struct AnimationGraphMap {
 #[cfg_attr(feature = "dev", derive(Reflect))]
 pub struct NodeMap(Arc<HashMap<String, AnimationNodeIndex>>);
 
+impl NodeMap {
+    pub fn new(
+        node_map: HashMap<String, AnimationNodeIndex>,
+    ) -> Self {
+        Self(Arc::new(node_map))
+    }
+}
+
 /// Map [`Name`] to their respective [`Entity`].
 #[derive(Component, Deref, Default, Debug)]
 #[cfg_attr(feature = "dev", derive(Reflect))]
@@ -118,3 +205,32 @@ pub struct AnimationPlayerTargets(HashMap<String, Entity>);
 
 #[derive(Component)]
 pub struct IsAnimatable;
+
+/// Points the [`AnimationPlayer`] to the gameplay entity that owns it
+/// so that [`AnimationEvent`]s can be forwarded.
+#[derive(Component, Deref, Debug, Clone, Copy)]
+pub struct AnimationOwner(pub Entity);
+
+/// Named events that happen at specific frames of an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnimationEventKind {
+    /// A foot touches the ground.
+    Footstep,
+    /// The frame where a melee attack should deal damage.
+    AttackImpact,
+    /// The oven door is fully opened.
+    OvenDoorOpen,
+}
+
+/// Triggered on the [`AnimationPlayer`] entity by the animation clip.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimationClipEvent {
+    pub kind: AnimationEventKind,
+}
+
+/// Triggered on the [`AnimationOwner`] entity when an
+/// [`AnimationClipEvent`] is reached.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimationEvent {
+    pub kind: AnimationEventKind,
+}
@@ -0,0 +1,111 @@
+use bevy::asset::UntypedAssetLoadFailedEvent;
+use bevy::prelude::*;
+
+use super::AssetState;
+
+pub(super) struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrackedAssets>()
+            .init_resource::<AssetLoadErrors>()
+            .init_resource::<LoadingProgress>()
+            .add_systems(
+                Update,
+                (
+                    collect_failed_loads,
+                    update_loading_progress,
+                    finish_loading.run_if(in_state(
+                        AssetState::LoadingRegistries,
+                    )),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Block loading when any asset fails to load.
+fn collect_failed_loads(
+    mut failed_events: EventReader<UntypedAssetLoadFailedEvent>,
+    mut errors: ResMut<AssetLoadErrors>,
+    state: Res<State<AssetState>>,
+    mut next_state: ResMut<NextState<AssetState>>,
+) {
+    for event in failed_events.read() {
+        error!("Failed to load '{}': {}", event.path, event.error);
+        errors.push(format!("{}: {}", event.path, event.error));
+    }
+
+    // Failures after loading (e.g. hot reloads) are only logged.
+    if errors.is_empty() == false
+        && matches!(
+            state.get(),
+            AssetState::LoadingGltf
+                | AssetState::LoadingAnimation
+                | AssetState::LoadingRegistries
+        )
+    {
+        next_state.set(AssetState::Failed);
+    }
+}
+
+fn update_loading_progress(
+    tracked_assets: Res<TrackedAssets>,
+    asset_server: Res<AssetServer>,
+    state: Res<State<AssetState>>,
+    mut progress: ResMut<LoadingProgress>,
+) {
+    let stages_done = match state.get() {
+        AssetState::LoadingGltf | AssetState::Failed => 0,
+        AssetState::LoadingAnimation => 1,
+        AssetState::LoadingRegistries | AssetState::Loaded => 2,
+    };
+
+    let loaded = tracked_assets.loaded_count(&asset_server);
+    let total = tracked_assets.len() + 2;
+
+    progress.set_if_neq(LoadingProgress(
+        (stages_done + loaded) as f32 / total as f32,
+    ));
+}
+
+pub(super) fn finish_loading(
+    progress: Res<LoadingProgress>,
+    errors: Res<AssetLoadErrors>,
+    mut next_state: ResMut<NextState<AssetState>>,
+) {
+    if **progress >= 1.0 && errors.is_empty() {
+        info!(
+            "Loading state '{:?}' is done",
+            AssetState::LoadingRegistries
+        );
+        next_state.set(AssetState::Loaded);
+    }
+}
+
+/// Assets that need to be loaded (with their dependencies)
+/// before leaving the loading screen.
+#[derive(Resource, Deref, Default, Debug)]
+pub struct TrackedAssets(Vec<UntypedHandle>);
+
+impl TrackedAssets {
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.0.push(handle.into());
+    }
+
+    pub fn loaded_count(&self, asset_server: &AssetServer) -> usize {
+        self.iter()
+            .filter(|h| {
+                asset_server.is_loaded_with_dependencies(h.id())
+            })
+            .count()
+    }
+}
+
+/// Errors of assets that failed to load.
+#[derive(Resource, Deref, DerefMut, Default, Debug)]
+pub struct AssetLoadErrors(Vec<String>);
+
+/// Overall loading progress from 0.0 to 1.0.
+#[derive(Resource, Deref, Default, Debug, PartialEq)]
+pub struct LoadingProgress(f32);
@@ -0,0 +1,102 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+
+use crate::tower::tower_attack::Health;
+
+use super::PrefabAssets;
+
+/// Re-instantiate placed prefabs in-place when their Gltf
+/// is modified on disk.
+///
+/// Components on the scene root (transforms, placement, etc.)
+/// are untouched, [`Health`] inside the scene is carried over
+/// by [`Name`].
+///
+/// *Note: Machines that are baked into the level scene are
+/// reloaded along with the level instead.*
+pub(super) struct PrefabReloadPlugin;
+
+impl Plugin for PrefabReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            reload_modified_prefabs
+                .run_if(resource_exists::<PrefabAssets>),
+        );
+    }
+}
+
+fn reload_modified_prefabs(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<Gltf>>,
+    prefabs: Res<PrefabAssets>,
+    gltfs: Res<Assets<Gltf>>,
+    q_scene_roots: Query<(&SceneRoot, Entity)>,
+    q_children: Query<&Children>,
+    q_healths: Query<(&Name, &Health)>,
+) {
+    for event in asset_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        let Some(prefab_name) = prefabs
+            .named_prefabs
+            .iter()
+            .find(|(_, handle)| handle.id() == *id)
+            .map(|(name, _)| name)
+        else {
+            continue;
+        };
+
+        let Some(gltf) = gltfs.get(*id) else {
+            continue;
+        };
+
+        let mut count = 0;
+        for (scene_root, entity) in q_scene_roots.iter() {
+            if gltf.scenes.contains(&scene_root.0) == false {
+                continue;
+            }
+
+            let healths = q_children
+                .iter_descendants(entity)
+                .filter_map(|e| q_healths.get(e).ok())
+                .map(|(name, health)| (name.clone(), health.0))
+                .collect::<HashMap<_, _>>();
+
+            // Changing the scene root respawns the scene instance.
+            commands
+                .entity(entity)
+                .insert(SceneRoot(scene_root.0.clone()))
+                .observe(
+                    move |trigger: Trigger<SceneInstanceReady>,
+                          mut commands: Commands,
+                          q_children: Query<&Children>,
+                          q_names: Query<&Name>| {
+                        for entity in q_children
+                            .iter_descendants(trigger.target())
+                        {
+                            if let Some(health) = q_names
+                                .get(entity)
+                                .ok()
+                                .and_then(|name| healths.get(name))
+                            {
+                                commands
+                                    .entity(entity)
+                                    .insert(Health(*health));
+                            }
+                        }
+
+                        // Only restore once.
+                        commands.entity(trigger.observer()).despawn();
+                    },
+                );
+
+            count += 1;
+        }
+
+        info!("Reloaded {count} instance(s) of {prefab_name}.");
+    }
+}
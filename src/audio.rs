@@ -1,12 +1,26 @@
+//! Music and sound effects, played through [`AudioCue`]s and
+//! machines operating.
+//!
+//! Only [`AudioCue`] exists in headless builds, which have no audio
+//! device to play it on.
+
 use bevy::prelude::*;
+#[cfg(not(feature = "headless"))]
 use bevy_seedling::prelude::*;
+#[cfg(not(feature = "headless"))]
 use bevy_seedling::sample::Sample;
 
-use crate::machine::{Machine, OperationTimer};
+use crate::footstep::Surface;
+#[cfg(not(feature = "headless"))]
+use crate::machine::{MachineKind, OperationTimer};
+use crate::player::player_ping::PingKind;
+#[cfg(not(feature = "headless"))]
 use crate::ui::Screen;
 
+#[cfg(not(feature = "headless"))]
 pub(super) struct AudioPlugin;
 
+#[cfg(not(feature = "headless"))]
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(SeedlingPlugin::default())
@@ -16,12 +30,15 @@ impl Plugin for AudioPlugin {
                 OnEnter(Screen::EnterLevel),
                 start_game_music,
             )
-            .add_systems(OnEnter(Screen::GameOver), game_over_music)
+            .add_systems(OnEnter(Screen::Victory), game_over_music)
+            .add_systems(OnEnter(Screen::Defeat), game_over_music)
             .add_observer(start_machine_audio)
-            .add_observer(stop_machine_audio);
+            .add_observer(stop_machine_audio)
+            .add_observer(play_audio_cue);
     }
 }
 
+#[cfg(not(feature = "headless"))]
 /// Start menu background music.
 fn start_menu_music(mut commands: Commands, audio: Res<GameAudio>) {
     commands.spawn((
@@ -32,6 +49,7 @@ fn start_menu_music(mut commands: Commands, audio: Res<GameAudio>) {
     ));
 }
 
+#[cfg(not(feature = "headless"))]
 /// Start in-game background music.
 fn start_game_music(mut commands: Commands, audio: Res<GameAudio>) {
     commands.spawn((
@@ -42,34 +60,97 @@ fn start_game_music(mut commands: Commands, audio: Res<GameAudio>) {
     ));
 }
 
+#[cfg(not(feature = "headless"))]
 /// Start game over background music.
-fn game_over_music(mut commands: Commands, audio: Res<GameAudio>) {
+fn game_over_music(
+    mut commands: Commands,
+    audio: Res<GameAudio>,
+    screen: Res<State<Screen>>,
+) {
     commands.spawn((
         SamplePlayer::new(audio.game_over_music.clone())
             .looping()
             .with_volume(Volume::Linear(0.4)),
-        StateScoped(Screen::GameOver),
+        StateScoped(screen.get().clone()),
     ));
 }
 
+#[cfg(not(feature = "headless"))]
+/// Play a one-shot [`AudioCue`].
+fn play_audio_cue(
+    trigger: Trigger<AudioCue>,
+    mut commands: Commands,
+    audio: Res<GameAudio>,
+) {
+    match trigger.event() {
+        AudioCue::VictoryStinger => {
+            commands.spawn((
+                SamplePlayer::new(audio.victory_stinger.clone())
+                    .with_volume(Volume::Linear(0.5)),
+                // Make way for the game over music.
+                StateScoped(Screen::EnterLevel),
+            ));
+        }
+        AudioCue::Footstep {
+            surface,
+            translation,
+        } => {
+            let step = match surface {
+                Surface::Stone => audio.footstep_stone.clone(),
+                Surface::Biscuit => audio.footstep_biscuit.clone(),
+                Surface::Bread => audio.footstep_bread.clone(),
+                Surface::Wood => audio.footstep_wood.clone(),
+                Surface::Metal => audio.footstep_metal.clone(),
+            };
+
+            commands.spawn((
+                SamplePlayer::new(step)
+                    .with_volume(Volume::Linear(0.15)),
+                GlobalTransform::from_translation(*translation),
+                SpatialBasicNode {
+                    panning_threshold: 0.4,
+                    volume: Volume::Linear(2.0),
+                    ..Default::default()
+                },
+                SpatialScale(Vec3::splat(0.1)),
+                StateScoped(Screen::EnterLevel),
+            ));
+        }
+        AudioCue::Ping(kind) => {
+            let bark = match kind {
+                PingKind::AttackHere => audio.ping_attack.clone(),
+                PingKind::NeedIngredient => audio.ping_need.clone(),
+                PingKind::Danger => audio.ping_danger.clone(),
+            };
+
+            commands.spawn((
+                SamplePlayer::new(bark)
+                    .with_volume(Volume::Linear(0.6)),
+                StateScoped(Screen::EnterLevel),
+            ));
+        }
+    }
+}
+
+#[cfg(not(feature = "headless"))]
 /// Start audio when machines start operating
 fn start_machine_audio(
     trigger: Trigger<OnAdd, OperationTimer>,
     mut commands: Commands,
-    q_machines: Query<(&Machine, &GlobalTransform)>,
+    q_machines: Query<(&MachineKind, &GlobalTransform)>,
     audio: Res<GameAudio>,
 ) {
     let machine_entity = trigger.target();
-    let Ok((machine, machine_transform)) =
+    let Ok((machine_kind, machine_transform)) =
         q_machines.get(machine_entity)
     else {
         return;
     };
 
-    let sound_handle = match machine.recipe_id.as_str() {
-        "rotisserie" => audio.rotisserie.clone(),
-        "wok" => audio.wok.clone(),
-        _ => return,
+    let sound_handle = match machine_kind {
+        MachineKind::Oven => audio.rotisserie.clone(),
+        MachineKind::Fryer => audio.wok.clone(),
+        MachineKind::Mixer => return,
     };
 
     // Spawn the sound player entity with spatial audio components
@@ -95,6 +176,7 @@ fn start_machine_audio(
         .insert(PlayingAudio(sound_entity));
 }
 
+#[cfg(not(feature = "headless"))]
 /// Stop audio when machines finish operating
 fn stop_machine_audio(
     trigger: Trigger<OnRemove, OperationTimer>,
@@ -111,22 +193,50 @@ fn stop_machine_audio(
     commands.entity(machine_entity).remove::<PlayingAudio>();
 }
 
+/// One-shot audio cues, trigger these instead of spawning sample
+/// players directly so that effects can start on the same frame
+/// as the sound.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum AudioCue {
+    /// Played when the final wave is cleared.
+    VictoryStinger,
+    /// Voice bark of a player's ping.
+    Ping(PingKind),
+    /// A character or enemy stepped on the surface.
+    Footstep { surface: Surface, translation: Vec3 },
+}
+
+#[cfg(not(feature = "headless"))]
 /// Component that stores the entity ID of the playing audio
 #[derive(Component)]
 struct PlayingAudio(Entity);
 
+#[cfg(not(feature = "headless"))]
 /// Resource containing all game audio handles
 #[derive(Resource)]
 pub struct GameAudio {
     // Machine sounds
     pub rotisserie: Handle<Sample>,
     pub wok: Handle<Sample>,
+    // Ping barks
+    pub ping_attack: Handle<Sample>,
+    pub ping_need: Handle<Sample>,
+    pub ping_danger: Handle<Sample>,
+    // Footsteps
+    pub footstep_stone: Handle<Sample>,
+    pub footstep_biscuit: Handle<Sample>,
+    pub footstep_bread: Handle<Sample>,
+    pub footstep_wood: Handle<Sample>,
+    pub footstep_metal: Handle<Sample>,
+    // Stingers
+    pub victory_stinger: Handle<Sample>,
     // Background music
     pub menu_music: Handle<Sample>,
     pub game_music: Handle<Sample>,
     pub game_over_music: Handle<Sample>,
 }
 
+#[cfg(not(feature = "headless"))]
 impl FromWorld for GameAudio {
     fn from_world(world: &mut World) -> Self {
         let asset_server = world.resource::<AssetServer>();
@@ -134,6 +244,21 @@ impl FromWorld for GameAudio {
             rotisserie: asset_server
                 .load("audios/machine/rotisserie.ogg"),
             wok: asset_server.load("audios/machine/wok.ogg"),
+            ping_attack: asset_server.load("audios/ping/attack.wav"),
+            ping_need: asset_server.load("audios/ping/need.wav"),
+            ping_danger: asset_server.load("audios/ping/danger.wav"),
+            footstep_stone: asset_server
+                .load("audios/footstep/stone.wav"),
+            footstep_biscuit: asset_server
+                .load("audios/footstep/biscuit.wav"),
+            footstep_bread: asset_server
+                .load("audios/footstep/bread.wav"),
+            footstep_wood: asset_server
+                .load("audios/footstep/wood.wav"),
+            footstep_metal: asset_server
+                .load("audios/footstep/metal.wav"),
+            victory_stinger: asset_server
+                .load("audios/stinger/victory.wav"),
             menu_music: asset_server
                 .load("audios/music/menu_bgm.ogg"),
             game_music: asset_server
@@ -0,0 +1,363 @@
+use avian3d::prelude::*;
+use bevy::color::palettes::tailwind::{RED_500, ZINC_900};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::asset_pipeline::CurrentScene;
+use crate::character_controller::CharacterController;
+use crate::damage::{DamageEvent, DamageKind};
+use crate::enemy::Enemy;
+use crate::interaction::grab::{Grabbable, Held};
+use crate::interaction::{
+    Interactable, InteractionPlayer, InteractionPrompt, MarkerOf,
+};
+use crate::machine::CarryableCrafted;
+use crate::tile::{PlacedOn, TileMap};
+
+/// Item id of the bomb in the item registry.
+const BOMB_ITEM_ID: &str = "bomb";
+/// Radius of the bomb's collider and mesh.
+const BOMB_RADIUS: f32 = 0.4;
+
+/// Carryable bombs that clear destructible obstacles.
+pub(super) struct BombPlugin;
+
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_obstacle_tile,
+                place_bombs,
+                hold_to_arm,
+                tick_fuses,
+                draw_danger_radius,
+            )
+                .chain(),
+        )
+        .add_observer(spawn_crafted_bomb);
+
+        app.register_type::<Bomb>().register_type::<Obstacle>();
+    }
+}
+
+/// Spawn the bomb beside the machine that crafted it.
+fn spawn_crafted_bomb(
+    trigger: Trigger<CarryableCrafted>,
+    mut commands: Commands,
+    q_global_transforms: Query<&GlobalTransform>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    current_scene: Res<CurrentScene>,
+) -> Result {
+    if trigger.event().item_id != BOMB_ITEM_ID {
+        return Ok(());
+    }
+
+    let Some(current_scene) = current_scene.get() else {
+        return Ok(());
+    };
+
+    let machine_tf = q_global_transforms.get(trigger.target())?;
+
+    commands.spawn((
+        Name::new("Bomb"),
+        Bomb::default(),
+        Transform::from_translation(
+            machine_tf.translation()
+                + machine_tf.forward() * 2.0
+                + Vec3::Y,
+        ),
+        Mesh3d(meshes.add(Sphere::new(BOMB_RADIUS))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: ZINC_900.into(),
+            ..default()
+        })),
+        RigidBody::Dynamic,
+        Collider::sphere(BOMB_RADIUS),
+        ChildOf(current_scene),
+    ));
+
+    Ok(())
+}
+
+/// Occupy the tile beneath each obstacle.
+fn setup_obstacle_tile(
+    mut commands: Commands,
+    q_obstacles: Query<
+        (&GlobalTransform, Entity),
+        (With<Obstacle>, Without<PlacedOn>),
+    >,
+    tile_map: Res<TileMap>,
+) {
+    for (global_transform, entity) in q_obstacles.iter() {
+        // The tiles might not be set up yet.
+        let Some(tile) = tile_map
+            .translation_to_tile_coord(
+                &global_transform.translation(),
+            )
+            .and_then(|coord| tile_map.get_tile(&coord.as_ivec3()))
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert(PlacedOn(tile.target()));
+    }
+}
+
+/// Dropped bombs next to an [`Obstacle`] get stuck onto it
+/// and can no longer be picked up.
+fn place_bombs(
+    mut commands: Commands,
+    q_bombs: Query<
+        (&Bomb, &GlobalTransform, Entity),
+        (Without<PlacedBomb>, Without<Held>),
+    >,
+    q_obstacles: Query<(&GlobalTransform, Entity), With<Obstacle>>,
+) {
+    for (bomb, bomb_tf, entity) in q_bombs.iter() {
+        let Some((_, obstacle)) = q_obstacles
+            .iter()
+            .map(|(obstacle_tf, obstacle)| {
+                (
+                    obstacle_tf
+                        .translation()
+                        .distance_squared(bomb_tf.translation()),
+                    obstacle,
+                )
+            })
+            .filter(|(dist, _)| {
+                *dist <= bomb.place_range * bomb.place_range
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        else {
+            continue;
+        };
+
+        commands
+            .entity(entity)
+            .insert((
+                PlacedBomb(obstacle),
+                InteractionPrompt("Arm".to_string()),
+            ))
+            .remove::<Grabbable>();
+    }
+}
+
+/// Arm placed bombs or defuse armed bombs by holding the
+/// interact action.
+fn hold_to_arm(
+    mut commands: Commands,
+    q_players: Query<
+        (&TargetAction, &MarkerOf, Entity),
+        With<InteractionPlayer>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_bombs: Query<
+        (&Bomb, &mut BombHold, Has<ArmedBomb>, Entity),
+        With<PlacedBomb>,
+    >,
+    time: Res<Time>,
+) {
+    for (target_action, marker_of, player) in q_players.iter() {
+        let Ok((_, mut hold, ..)) =
+            q_bombs.get_mut(marker_of.entity())
+        else {
+            continue;
+        };
+
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.pressed(&PlayerAction::Interact))
+        {
+            hold.holding = Some(player);
+        }
+    }
+
+    for (bomb, mut hold, armed, entity) in q_bombs.iter_mut() {
+        let Some(holder) = hold.holding.take() else {
+            hold.progress = 0.0;
+            hold.finished = false;
+            continue;
+        };
+
+        // Wait for the action to be released before toggling again.
+        if hold.finished {
+            continue;
+        }
+
+        hold.progress += time.delta_secs();
+        if hold.progress < bomb.arm_duration {
+            continue;
+        }
+
+        hold.progress = 0.0;
+        hold.finished = true;
+
+        if armed {
+            commands
+                .entity(entity)
+                .remove::<ArmedBomb>()
+                .insert(InteractionPrompt("Arm".to_string()));
+        } else {
+            commands.entity(entity).insert((
+                ArmedBomb {
+                    fuse: Timer::from_seconds(
+                        bomb.fuse_duration,
+                        TimerMode::Once,
+                    ),
+                    armed_by: holder,
+                },
+                InteractionPrompt("Defuse".to_string()),
+            ));
+        }
+    }
+}
+
+/// Explode bombs once their fuse runs out, clearing the
+/// obstacle, damaging enemies and knocking back players.
+fn tick_fuses(
+    mut commands: Commands,
+    mut q_bombs: Query<(
+        &Bomb,
+        &PlacedBomb,
+        &mut ArmedBomb,
+        &GlobalTransform,
+        Entity,
+    )>,
+    q_enemies: Query<(&GlobalTransform, Entity), With<Enemy>>,
+    mut q_players: Query<
+        (&GlobalTransform, &mut LinearVelocity),
+        With<CharacterController>,
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (bomb, placed_bomb, mut armed_bomb, bomb_tf, entity) in
+        q_bombs.iter_mut()
+    {
+        if armed_bomb.fuse.tick(time.delta()).finished() == false {
+            continue;
+        }
+
+        let origin = bomb_tf.translation();
+        let radius_squared = bomb.blast_radius * bomb.blast_radius;
+
+        for (enemy_tf, enemy) in q_enemies.iter() {
+            let offset = enemy_tf.translation() - origin;
+            if offset.length_squared() > radius_squared {
+                continue;
+            }
+
+            damage_events.write(DamageEvent {
+                source: armed_bomb.armed_by,
+                target: enemy,
+                amount: bomb.damage,
+                kind: DamageKind::Blast,
+                knockback: offset.with_y(0.0).normalize_or_zero()
+                    * bomb.knockback,
+            });
+        }
+
+        for (player_tf, mut linear_velocity) in q_players.iter_mut() {
+            let offset = player_tf.translation() - origin;
+            if offset.length_squared() > radius_squared {
+                continue;
+            }
+
+            let direction =
+                offset.with_y(0.0).normalize_or_zero() + Vec3::Y;
+            linear_velocity.0 = direction * bomb.knockback;
+        }
+
+        // Clearing the obstacle frees up its tile. Other bombs
+        // placed on the same obstacle might have cleared it already.
+        commands.entity(placed_bomb.0).try_despawn();
+        commands.entity(entity).try_despawn();
+    }
+}
+
+/// Show the danger radius of armed bombs.
+fn draw_danger_radius(
+    q_bombs: Query<(&Bomb, &ArmedBomb, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+) {
+    for (bomb, armed_bomb, bomb_tf) in q_bombs.iter() {
+        // Flash faster as the fuse runs out.
+        let flash =
+            (armed_bomb.fuse.fraction() * 20.0).sin() * 0.5 + 0.5;
+
+        gizmos.circle(
+            Isometry3d::new(
+                bomb_tf.translation().with_y(
+                    bomb_tf.translation().y - BOMB_RADIUS + 0.05,
+                ),
+                Quat::from_rotation_x(core::f32::consts::FRAC_PI_2),
+            ),
+            bomb.blast_radius,
+            RED_500.with_alpha(0.4 + flash * 0.6),
+        );
+    }
+}
+
+/// A carryable bomb that clears the [`Obstacle`] it is placed
+/// next to.
+#[derive(Component, Reflect)]
+#[require(Grabbable, Interactable, BombHold)]
+#[reflect(Component, Default)]
+pub struct Bomb {
+    /// Duration in seconds before an armed bomb explodes.
+    pub fuse_duration: f32,
+    /// Duration in seconds to hold interact to arm or defuse.
+    pub arm_duration: f32,
+    /// How close to an obstacle the bomb needs to be dropped.
+    pub place_range: f32,
+    pub blast_radius: f32,
+    /// Damage dealt to enemies within the blast radius.
+    pub damage: f32,
+    /// Speed at which players within the blast radius are
+    /// knocked back.
+    pub knockback: f32,
+}
+
+impl Default for Bomb {
+    fn default() -> Self {
+        Self {
+            fuse_duration: 4.0,
+            arm_duration: 1.0,
+            place_range: 2.5,
+            blast_radius: 4.0,
+            damage: 80.0,
+            knockback: 12.0,
+        }
+    }
+}
+
+/// Hold progress of the interact action on a [`Bomb`].
+#[derive(Component, Default, Debug)]
+pub struct BombHold {
+    progress: f32,
+    /// The player holding interact this frame.
+    holding: Option<Entity>,
+    /// Whether the hold has already toggled the bomb.
+    finished: bool,
+}
+
+/// Attached to a [`Bomb`] that is placed on an [`Obstacle`].
+#[derive(Component, Deref, Debug)]
+pub struct PlacedBomb(pub Entity);
+
+/// Attached to an armed [`Bomb`].
+#[derive(Component, Debug)]
+pub struct ArmedBomb {
+    pub fuse: Timer,
+    /// The player that armed the bomb, credited with its damage.
+    pub armed_by: Entity,
+}
+
+/// A destructible obstacle that blocks the tile beneath it
+/// until it's cleared by a [`Bomb`].
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Obstacle;
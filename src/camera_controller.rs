@@ -3,26 +3,41 @@ use std::f32::consts::{FRAC_PI_2, TAU};
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
+use cinematic::VictoryCinematic;
 use leafwing_input_manager::prelude::*;
-use split_screen::{CameraType, QueryCameras};
+use split_screen::{CameraType, QueryCameras, Spectate};
 
 use crate::action::{PlayerAction, RequireAction, TargetAction};
 use crate::asset_pipeline::CurrentScene;
+use crate::character_controller::ledge::Traversal;
 use crate::physics::GameLayer;
-use crate::player::PlayerType;
+use crate::player::{PlayerType, QueryPlayers};
 use crate::tower::Projectile;
 
+pub mod cinematic;
 pub mod split_screen;
+pub mod viewport_style;
 
 pub const UI_RENDER_LAYER: RenderLayers = RenderLayers::layer(1);
 pub const A_RENDER_LAYER: RenderLayers = RenderLayers::layer(2);
 pub const B_RENDER_LAYER: RenderLayers = RenderLayers::layer(3);
 
+/// Minimum camera pitch (radians) while the player is
+/// traversing a ledge.
+const TRAVERSAL_MIN_PITCH: f32 = 0.6;
+/// Zoom speed of held gamepad inputs, relative to a scroll step
+/// per second.
+const GAMEPAD_ZOOM_SPEED: f32 = 10.0;
+
 pub(super) struct CameraControllerPlugin;
 
 impl Plugin for CameraControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(split_screen::SplitScreenPlugin);
+        app.add_plugins((
+            split_screen::SplitScreenPlugin,
+            cinematic::CinematicPlugin,
+            viewport_style::ViewportStylePlugin,
+        ));
 
         app.add_systems(Update, setup_third_person_camera)
             .add_systems(
@@ -33,6 +48,7 @@ impl Plugin for CameraControllerPlugin {
                     snap_camera,
                 )
                     .chain()
+                    .run_if(not(resource_exists::<VictoryCinematic>))
                     .after(TransformSystem::TransformPropagate),
             )
             .add_observer(setup_directional_light);
@@ -47,7 +63,7 @@ impl Plugin for CameraControllerPlugin {
 /// main target's view.
 fn obstacle_snap_front(
     q_camera_targets: Query<
-        (&PlayerType, &GlobalTransform),
+        (&PlayerType, &GlobalTransform, Option<&Spectate>),
         With<CameraTarget>,
     >,
     mut q_cameras: QueryCameras<&mut Transform, With<CameraSnap>>,
@@ -55,13 +71,23 @@ fn obstacle_snap_front(
     q_is_projectile: Query<(), With<Projectile>>,
     cast_shape: Local<ViewCastShape>,
 ) -> Result {
-    for (camera_type, target_transform) in q_camera_targets.iter() {
+    let targets = q_camera_targets
+        .iter()
+        .map(|(player_type, transform, _)| {
+            (*player_type, transform.translation())
+        })
+        .collect::<Vec<_>>();
+
+    for (camera_type, target_transform, spectate) in
+        q_camera_targets.iter()
+    {
         let mut camera_transform = match camera_type {
             PlayerType::A => q_cameras.get_mut(CameraType::A),
             PlayerType::B => q_cameras.get_mut(CameraType::B),
         }?;
 
-        let target_translation = target_transform.translation();
+        let target_translation =
+            camera_focus(target_transform, spectate, &targets);
         let camera_translation = camera_transform.translation;
         let diff = camera_translation - target_translation;
 
@@ -107,11 +133,17 @@ fn obstacle_snap_front(
 
 fn third_person_camera(
     q_camera_targets: Query<
-        (&PlayerType, &GlobalTransform, &TargetAction),
+        (
+            &PlayerType,
+            &GlobalTransform,
+            &TargetAction,
+            Option<&Spectate>,
+        ),
         With<CameraTarget>,
     >,
+    q_traversals: QueryPlayers<(), With<Traversal>>,
     mut q_cameras: QueryCameras<
-        (&ThirdPersonCamera, &mut OrbitAngle, &mut Transform),
+        (&mut ThirdPersonCamera, &mut OrbitAngle, &mut Transform),
         With<CameraSnap>,
     >,
     q_actions: Query<(
@@ -121,11 +153,17 @@ fn third_person_camera(
     time: Res<Time>,
 ) -> Result {
     let dt = time.delta_secs();
-
-    for (camera_type, target_transform, target_action) in
+    let targets = q_camera_targets
+        .iter()
+        .map(|(player_type, transform, ..)| {
+            (*player_type, transform.translation())
+        })
+        .collect::<Vec<_>>();
+
+    for (camera_type, target_transform, target_action, spectate) in
         q_camera_targets.iter()
     {
-        let (config, mut angle, mut camera_transform) =
+        let (mut config, mut angle, mut camera_transform) =
             match camera_type {
                 PlayerType::A => q_cameras.get_mut(CameraType::A),
                 PlayerType::B => q_cameras.get_mut(CameraType::B),
@@ -135,7 +173,13 @@ fn third_person_camera(
             q_actions.get(target_action.get())?;
 
         let is_gamepad = input_map.gamepad().is_some();
-        let aim = action.axis_pair(&PlayerAction::Aim);
+        let zoom = action.value(&PlayerAction::Zoom);
+        let mut aim = action.axis_pair(&PlayerAction::Aim);
+        // The right stick zooms instead of pitching while `Select`
+        // is held on a gamepad.
+        if is_gamepad && zoom != 0.0 {
+            aim.y = 0.0;
+        }
 
         // Gamepad gets a boost in sensitivity.
         let device_sensitivity = if is_gamepad { 10.0 } else { 1.0 };
@@ -151,6 +195,14 @@ fn third_person_camera(
             aim.x * config.yaw_sensitivity * device_sensitivity * dt;
         angle.pitch += aim_y;
 
+        // Look down at the player from above while climbing
+        // so that the wall doesn't block the view.
+        if q_traversals.get(*camera_type).is_ok() {
+            angle.pitch = angle
+                .pitch
+                .lerp(angle.pitch.max(TRAVERSAL_MIN_PITCH), dt * 4.0);
+        }
+
         // Clamp pitch to prevent camera flipping overhead or underfoot.
         angle.pitch = angle.pitch.clamp(
             FRAC_PI_2 * config.min_pitch,
@@ -162,11 +214,44 @@ fn third_person_camera(
         // functions handling periodicity.
         angle.yaw = angle.yaw.rem_euclid(TAU);
 
-        let focus = target_transform.translation();
+        let zoom = if is_gamepad {
+            zoom * GAMEPAD_ZOOM_SPEED * dt
+        } else {
+            zoom
+        };
+        if zoom != 0.0 {
+            config.distance = (config.distance
+                - zoom * config.zoom_sensitivity)
+                .clamp(config.min_distance, config.max_distance);
+        }
+
+        // Move in over the shoulder while attacking.
+        let aim_target = match action.pressed(&PlayerAction::Attack)
+            && spectate.is_none()
+        {
+            true => 1.0,
+            false => 0.0,
+        };
+        angle.aim =
+            angle.aim.lerp(aim_target, dt * config.follow_speed);
+
+        // Towards the right of the camera.
+        let shoulder =
+            Vec3::new(angle.yaw.cos(), 0.0, -angle.yaw.sin())
+                * config.shoulder_offset
+                * angle.aim;
+        let focus =
+            camera_focus(target_transform, spectate, &targets)
+                + shoulder;
+        let target_distance = config.distance.lerp(
+            config.aim_distance.min(config.distance),
+            angle.aim,
+        );
+
         let current_distance =
             focus.distance(camera_transform.translation);
         let distance = current_distance
-            .lerp(config.distance, dt * config.follow_speed);
+            .lerp(target_distance, dt * config.follow_speed);
 
         // Calculate camera position using spherical coordinates logic
         let cam_x =
@@ -182,6 +267,23 @@ fn third_person_camera(
     Ok(())
 }
 
+/// The point the camera orbits around, the spectated player in
+/// `targets` if any, falling back to the player itself.
+fn camera_focus(
+    target_transform: &GlobalTransform,
+    spectate: Option<&Spectate>,
+    targets: &[(PlayerType, Vec3)],
+) -> Vec3 {
+    spectate
+        .and_then(|spectate| {
+            targets
+                .iter()
+                .find(|(player_type, _)| *player_type == **spectate)
+        })
+        .map(|(_, translation)| *translation)
+        .unwrap_or(target_transform.translation())
+}
+
 fn snap_camera(
     mut q_cameras: QueryCameras<&mut Transform>,
     q_camera_snaps: Query<
@@ -273,8 +375,24 @@ pub struct ThirdPersonCamera {
     /// The pitch angle sensitivity.
     pub pitch_sensitivity: f32,
     /// The distance between the
-    /// camera and the [`CameraTarget`].
+    /// camera and the [`CameraTarget`], changed by zooming.
     pub distance: f32,
+    /// The closest the camera can zoom in.
+    #[reflect(default = "default_min_distance")]
+    pub min_distance: f32,
+    /// The furthest the camera can zoom out.
+    #[reflect(default = "default_max_distance")]
+    pub max_distance: f32,
+    /// Distance zoomed per scroll step.
+    #[reflect(default = "default_zoom_sensitivity")]
+    pub zoom_sensitivity: f32,
+    /// The distance while aiming over the shoulder.
+    #[reflect(default = "default_aim_distance")]
+    pub aim_distance: f32,
+    /// Sideway offset of the camera while aiming over the
+    /// shoulder.
+    #[reflect(default = "default_shoulder_offset")]
+    pub shoulder_offset: f32,
     /// The follow speed.
     pub follow_speed: f32,
     /// Max pitch angle in percentage from 0 - 1.
@@ -291,6 +409,11 @@ impl Default for ThirdPersonCamera {
             yaw_sensitivity: 0.4,
             pitch_sensitivity: 0.4,
             distance: 4.0,
+            min_distance: default_min_distance(),
+            max_distance: default_max_distance(),
+            zoom_sensitivity: default_zoom_sensitivity(),
+            aim_distance: default_aim_distance(),
+            shoulder_offset: default_shoulder_offset(),
             follow_speed: 10.0,
             max_pitch: 0.8,
             min_pitch: 0.5,
@@ -298,10 +421,35 @@ impl Default for ThirdPersonCamera {
     }
 }
 
+// Defaults of the fields that scenes exported before zooming
+// don't have.
+
+fn default_min_distance() -> f32 {
+    2.0
+}
+
+fn default_max_distance() -> f32 {
+    8.0
+}
+
+fn default_zoom_sensitivity() -> f32 {
+    0.5
+}
+
+fn default_aim_distance() -> f32 {
+    2.0
+}
+
+fn default_shoulder_offset() -> f32 {
+    0.6
+}
+
 #[derive(Component, Default, Debug)]
 pub struct OrbitAngle {
     pub yaw: f32,
     pub pitch: f32,
+    /// Blend towards the over the shoulder aim from 0 - 1.
+    pub aim: f32,
 }
 
 #[derive(Deref)]
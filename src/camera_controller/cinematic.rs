@@ -0,0 +1,277 @@
+//! Victory cinematic that blends both split cameras into a single
+//! orbit shot of the [`FinalTarget`] before the victory screen.
+
+use bevy::core_pipeline::Skybox;
+use bevy::prelude::*;
+use bevy::render::view::Layer;
+use bevy::window::PrimaryWindow;
+
+use crate::enemy::FinalTarget;
+use crate::ui::Screen;
+
+use super::UI_RENDER_LAYER;
+use super::split_screen::{
+    CameraType, QueryCameras, SplitOrientation, ViewportLayout,
+};
+
+/// Seconds to blend the split cameras into the orbit shot.
+const BLEND_DURATION: f32 = 1.5;
+/// Seconds to orbit around the final target.
+const ORBIT_DURATION: f32 = 5.0;
+const ORBIT_RADIUS: f32 = 8.0;
+const ORBIT_HEIGHT: f32 = 4.0;
+/// Orbit speed in radians per second.
+const ORBIT_SPEED: f32 = 0.6;
+
+pub(super) struct CinematicPlugin;
+
+impl Plugin for CinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                blend_split_cameras.run_if(
+                    |c: Res<VictoryCinematic>| c.is_blending(),
+                ),
+                orbit_final_target.run_if(
+                    |c: Res<VictoryCinematic>| {
+                        c.is_blending() == false
+                    },
+                ),
+            )
+                .chain()
+                .run_if(resource_exists::<VictoryCinematic>)
+                .after(TransformSystem::TransformPropagate),
+        )
+        .add_systems(
+            OnExit(Screen::EnterLevel),
+            end_victory_cinematic
+                .run_if(resource_exists::<VictoryCinematic>),
+        )
+        .add_observer(start_victory_cinematic);
+    }
+}
+
+fn start_victory_cinematic(
+    _: Trigger<VictoryReached>,
+    mut commands: Commands,
+    q_final_targets: Query<&GlobalTransform, With<FinalTarget>>,
+    q_cameras: QueryCameras<&Transform>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) -> Result {
+    let Ok(target) = q_final_targets.single() else {
+        // Nothing to celebrate around.
+        next_screen.set(Screen::Victory);
+        return Ok(());
+    };
+
+    let target = target.translation();
+    let from = [
+        *q_cameras.get(CameraType::A)?,
+        *q_cameras.get(CameraType::B)?,
+    ];
+
+    // Start orbiting from in between the 2 cameras.
+    let offset =
+        (from[0].translation + from[1].translation) * 0.5 - target;
+    let yaw = offset.x.atan2(offset.z);
+
+    commands.insert_resource(VictoryCinematic {
+        target,
+        yaw,
+        from,
+        blend: Timer::from_seconds(BLEND_DURATION, TimerMode::Once),
+        orbit: Timer::from_seconds(ORBIT_DURATION, TimerMode::Once),
+    });
+
+    Ok(())
+}
+
+/// Move both split cameras towards the orbit shot while the
+/// viewport of camera A takes over the window.
+fn blend_split_cameras(
+    mut commands: Commands,
+    mut cinematic: ResMut<VictoryCinematic>,
+    mut q_cameras: QueryCameras<(
+        &mut Transform,
+        &mut Camera,
+        Option<&Skybox>,
+        Option<&EnvironmentMapLight>,
+        Entity,
+    )>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    layout: Res<ViewportLayout>,
+    time: Res<Time>,
+) -> Result {
+    let t = cinematic.blend.tick(time.delta()).fraction();
+    let t = t * t * (3.0 - 2.0 * t);
+    let pose = cinematic.orbit_pose(0.0);
+
+    let window_size = q_windows.single()?.physical_size();
+    let [viewport_a, viewport_b] = layout.viewports(window_size);
+    // Camera B slides off the far edge of the window.
+    let far_edge = match layout.orientation {
+        SplitOrientation::Vertical => UVec2::new(window_size.x, 0),
+        SplitOrientation::Horizontal => UVec2::new(0, window_size.y),
+    };
+
+    for (i, camera_type) in
+        [CameraType::A, CameraType::B].into_iter().enumerate()
+    {
+        let (mut transform, mut camera, ..) =
+            q_cameras.get_mut(camera_type)?;
+        let from = cinematic.from[i];
+
+        transform.translation =
+            from.translation.lerp(pose.translation, t);
+        transform.rotation = from.rotation.slerp(pose.rotation, t);
+
+        let Some(viewport) = camera.viewport.as_mut() else {
+            continue;
+        };
+
+        match camera_type {
+            CameraType::A => {
+                viewport.physical_size = viewport_a
+                    .physical_size
+                    .as_vec2()
+                    .lerp(window_size.as_vec2(), t)
+                    .as_uvec2();
+            }
+            _ => {
+                let position = viewport_b
+                    .physical_position
+                    .as_vec2()
+                    .lerp(far_edge.as_vec2(), t)
+                    .as_uvec2();
+                viewport.physical_position = position
+                    .min(window_size.saturating_sub(UVec2::ONE));
+                viewport.physical_size = window_size
+                    .saturating_sub(position)
+                    .max(UVec2::ONE);
+            }
+        }
+    }
+
+    if cinematic.blend.just_finished() == false {
+        return Ok(());
+    }
+
+    // Cut to the full camera.
+    let (_, _, skybox, environment_map, _) =
+        q_cameras.get(CameraType::A)?;
+    let (skybox, environment_map) =
+        (skybox.cloned(), environment_map.cloned());
+
+    for camera_type in [CameraType::A, CameraType::B] {
+        q_cameras.get_mut(camera_type)?.1.is_active = false;
+    }
+
+    let (mut transform, .., full_camera) =
+        q_cameras.get_mut(CameraType::Full)?;
+    *transform = pose;
+
+    let mut full_camera = commands.entity(full_camera);
+    full_camera.insert(UI_RENDER_LAYER.with(Layer::default()));
+    if let Some(skybox) = skybox {
+        full_camera.insert(skybox);
+    }
+    if let Some(environment_map) = environment_map {
+        full_camera.insert(environment_map);
+    }
+
+    Ok(())
+}
+
+/// Orbit around the final target and then cut to the victory
+/// screen.
+fn orbit_final_target(
+    mut cinematic: ResMut<VictoryCinematic>,
+    mut q_cameras: QueryCameras<&mut Transform>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    time: Res<Time>,
+) -> Result {
+    let elapsed = cinematic.orbit.tick(time.delta()).elapsed_secs();
+
+    *q_cameras.get_mut(CameraType::Full)? =
+        cinematic.orbit_pose(elapsed * ORBIT_SPEED);
+
+    if cinematic.orbit.just_finished() {
+        next_screen.set(Screen::Victory);
+    }
+
+    Ok(())
+}
+
+/// Restore the split cameras once the level is left.
+fn end_victory_cinematic(
+    mut commands: Commands,
+    mut q_cameras: QueryCameras<(
+        &mut Transform,
+        &mut Camera,
+        Entity,
+    )>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    layout: Res<ViewportLayout>,
+) -> Result {
+    let (mut transform, _, full_camera) =
+        q_cameras.get_mut(CameraType::Full)?;
+    *transform = Transform::IDENTITY;
+    commands
+        .entity(full_camera)
+        .insert(UI_RENDER_LAYER)
+        .remove::<(Skybox, EnvironmentMapLight)>();
+
+    let window_size = q_windows.single()?.physical_size();
+    let viewports = layout.viewports(window_size);
+
+    for (camera_type, viewport) in
+        [CameraType::A, CameraType::B].into_iter().zip(viewports)
+    {
+        let (_, mut camera, _) = q_cameras.get_mut(camera_type)?;
+        camera.is_active =
+            camera_type == CameraType::A || layout.solo == false;
+        camera.viewport = Some(viewport);
+    }
+
+    commands.remove_resource::<VictoryCinematic>();
+
+    Ok(())
+}
+
+/// Triggered when the final wave is cleared.
+#[derive(Event, Debug)]
+pub struct VictoryReached;
+
+/// The victory cinematic that is currently playing, the player
+/// cameras stop following their targets while it exists.
+#[derive(Resource, Debug)]
+pub struct VictoryCinematic {
+    target: Vec3,
+    /// Starting yaw of the orbit.
+    yaw: f32,
+    /// Transforms of camera A and B when the cinematic starts.
+    from: [Transform; 2],
+    blend: Timer,
+    orbit: Timer,
+}
+
+impl VictoryCinematic {
+    fn is_blending(&self) -> bool {
+        self.blend.finished() == false
+    }
+
+    fn orbit_pose(&self, angle: f32) -> Transform {
+        let yaw = self.yaw + angle;
+
+        Transform::from_translation(
+            self.target
+                + Vec3::new(
+                    yaw.sin() * ORBIT_RADIUS,
+                    ORBIT_HEIGHT,
+                    yaw.cos() * ORBIT_RADIUS,
+                ),
+        )
+        .looking_at(self.target, Vec3::Y)
+    }
+}
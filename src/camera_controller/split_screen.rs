@@ -1,8 +1,6 @@
 use bevy::color::palettes::css::SKY_BLUE;
 use bevy::core_pipeline::Skybox;
-use bevy::core_pipeline::bloom::Bloom;
 use bevy::core_pipeline::core_3d::Camera3dDepthLoadOp;
-use bevy::core_pipeline::smaa::Smaa;
 use bevy::core_pipeline::tonemapping::{DebandDither, Tonemapping};
 use bevy::ecs::component::{ComponentHooks, Immutable, StorageType};
 use bevy::ecs::query::{
@@ -12,56 +10,112 @@ use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::render::camera::{CameraOutputMode, Viewport};
 use bevy::render::view::{Layer, RenderLayers};
-use bevy::window::WindowResized;
+use bevy::window::{PrimaryWindow, WindowResized};
 
+use crate::player::PlayerType;
+use crate::player::companion::Companion;
 use crate::util::PropagateComponentAppExt;
 
+use super::cinematic::VictoryCinematic;
 use super::{A_RENDER_LAYER, B_RENDER_LAYER, UI_RENDER_LAYER};
 
+/// Brightness of the skybox and its environment lighting in
+/// daylight.
+pub const ENVIRONMENT_BRIGHTNESS: f32 = 1000.0;
+
 pub(super) struct SplitScreenPlugin;
 
 impl Plugin for SplitScreenPlugin {
     fn build(&self, app: &mut App) {
-        app.propagate_component::<CameraType, Children>()
+        app.init_resource::<ViewportLayout>()
+            .propagate_component::<CameraType, Children>()
             .add_systems(PreStartup, setup_camera_and_environment)
-            .add_systems(Update, set_camera_split_viewports);
+            .add_systems(
+                Update,
+                (
+                    update_solo_layout,
+                    set_camera_split_viewports.run_if(not(
+                        resource_exists::<VictoryCinematic>,
+                    )),
+                    fit_viewport_nodes,
+                )
+                    .chain(),
+            );
+
+        app.register_type::<CameraType>()
+            .register_type::<Spectate>()
+            .register_type::<ViewportLayout>()
+            .register_type::<ViewportNode>();
+    }
+}
 
-        app.register_type::<CameraType>();
+/// Only Player A needs a viewport when Player B is played by the
+/// [`Companion`].
+fn update_solo_layout(
+    q_companions: Query<(), With<Companion>>,
+    mut layout: ResMut<ViewportLayout>,
+) {
+    let solo = q_companions.is_empty() == false;
+    if layout.solo != solo {
+        layout.solo = solo;
     }
 }
 
 fn set_camera_split_viewports(
-    windows: Query<&Window>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
     mut resize_events: EventReader<WindowResized>,
     mut q_cameras: QueryCameras<&mut Camera>,
+    layout: Res<ViewportLayout>,
 ) -> Result {
     // We need to dynamically resize the camera's viewports whenever the
-    // window size changes so then each camera always takes up half the screen.
+    // window size or the layout changes.
     // A resize_event is sent when the window is first created,
     // allowing us to reuse this system for initial setup.
+    let resized = resize_events.read().count() > 0;
+    if resized == false && layout.is_changed() == false {
+        return Ok(());
+    }
 
-    for resize_event in resize_events.read() {
-        let window_size =
-            windows.get(resize_event.window).unwrap().physical_size();
-        let additional_pixel = window_size.x % 2;
-        let split_size = UVec2::new(window_size.x / 2, window_size.y);
+    let Ok(window) = q_windows.single() else {
+        return Ok(());
+    };
+    let [viewport_a, viewport_b] =
+        layout.viewports(window.physical_size());
 
-        q_cameras.get_mut(CameraType::A)?.viewport = Some(Viewport {
-            physical_position: UVec2::ZERO,
-            physical_size: split_size,
-            ..default()
-        });
-        q_cameras.get_mut(CameraType::B)?.viewport = Some(Viewport {
-            physical_position: UVec2::new(split_size.x, 0),
-            physical_size: split_size
-                + UVec2::new(additional_pixel, 0),
-            ..default()
-        });
-    }
+    q_cameras.get_mut(CameraType::A)?.viewport = Some(viewport_a);
+
+    let mut camera_b = q_cameras.get_mut(CameraType::B)?;
+    camera_b.viewport = Some(viewport_b);
+    camera_b.is_active = layout.solo == false;
 
     Ok(())
 }
 
+/// Fit the [`ViewportNode`]s to the viewports of their players.
+fn fit_viewport_nodes(
+    mut q_nodes: Query<(&ViewportNode, &mut Node)>,
+    q_added: Query<(), Added<ViewportNode>>,
+    layout: Res<ViewportLayout>,
+) {
+    if layout.is_changed() == false && q_added.is_empty() {
+        return;
+    }
+
+    for (viewport_node, mut node) in q_nodes.iter_mut() {
+        let Some(rect) = layout.rect(viewport_node.0) else {
+            node.display = Display::None;
+            continue;
+        };
+
+        node.display = Display::Flex;
+        node.position_type = PositionType::Absolute;
+        node.left = Val::Percent(rect.min.x * 100.0);
+        node.top = Val::Percent(rect.min.y * 100.0);
+        node.width = Val::Percent(rect.width() * 100.0);
+        node.height = Val::Percent(rect.height() * 100.0);
+    }
+}
+
 fn setup_camera_and_environment(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -130,13 +184,13 @@ fn game_camera_bundle(
         Msaa::Off,
         Skybox {
             image: diffuse_map.clone(),
-            brightness: 1000.0,
+            brightness: ENVIRONMENT_BRIGHTNESS,
             ..default()
         },
         EnvironmentMapLight {
             diffuse_map: diffuse_map.clone(),
             specular_map: specular_map.clone(),
-            intensity: 1000.0,
+            intensity: ENVIRONMENT_BRIGHTNESS,
             ..default()
         },
     )
@@ -155,9 +209,9 @@ fn ui_camera_bundle(order: isize) -> impl Bundle {
             ..default()
         },
         Msaa::Off,
-        Smaa::default(),
+        // Anti-aliasing and bloom are applied based on the
+        // `GraphicsPreset`.
         Tonemapping::None,
-        Bloom::NATURAL,
         DebandDither::Enabled,
         IsDefaultUiCamera,
     )
@@ -287,3 +341,128 @@ pub struct CameraA;
 /// Usually used to render the POV of [`crate::player::PlayerB`]
 #[derive(Component, Debug)]
 pub struct CameraB;
+
+/// How the window is shared between [`CameraType::A`] and
+/// [`CameraType::B`].
+#[derive(
+    Resource, Reflect, Default, Debug, Clone, Copy, PartialEq,
+)]
+#[reflect(Resource)]
+pub struct ViewportLayout {
+    /// Can be changed from the menu.
+    pub orientation: SplitOrientation,
+    /// Camera A takes up the whole window and camera B is
+    /// disabled, set while a [`Companion`] plays Player B.
+    pub solo: bool,
+}
+
+impl ViewportLayout {
+    /// Viewports of [`CameraType::A`] and [`CameraType::B`].
+    ///
+    /// In [`Self::solo`], both cover the whole window (camera B
+    /// is disabled anyway).
+    pub fn viewports(&self, window_size: UVec2) -> [Viewport; 2] {
+        let full = Viewport {
+            physical_position: UVec2::ZERO,
+            physical_size: window_size,
+            ..default()
+        };
+
+        if self.solo {
+            return [full.clone(), full];
+        }
+
+        // The second viewport gets the additional pixel.
+        let (size_a, position_b) = match self.orientation {
+            SplitOrientation::Vertical => {
+                let half = window_size.x / 2;
+                (UVec2::new(half, window_size.y), UVec2::new(half, 0))
+            }
+            SplitOrientation::Horizontal => {
+                let half = window_size.y / 2;
+                (UVec2::new(window_size.x, half), UVec2::new(0, half))
+            }
+        };
+
+        [
+            Viewport {
+                physical_position: UVec2::ZERO,
+                physical_size: size_a,
+                ..default()
+            },
+            Viewport {
+                physical_position: position_b,
+                physical_size: window_size - position_b,
+                ..default()
+            },
+        ]
+    }
+
+    /// Normalized rect of the player's viewport in the window,
+    /// [`None`] if it's not shown.
+    pub fn rect(&self, player_type: PlayerType) -> Option<Rect> {
+        let rect = match (self.solo, self.orientation, player_type) {
+            (true, _, PlayerType::A) => Rect::new(0.0, 0.0, 1.0, 1.0),
+            (true, _, PlayerType::B) => return None,
+            (false, SplitOrientation::Vertical, PlayerType::A) => {
+                Rect::new(0.0, 0.0, 0.5, 1.0)
+            }
+            (false, SplitOrientation::Vertical, PlayerType::B) => {
+                Rect::new(0.5, 0.0, 1.0, 1.0)
+            }
+            (false, SplitOrientation::Horizontal, PlayerType::A) => {
+                Rect::new(0.0, 0.0, 1.0, 0.5)
+            }
+            (false, SplitOrientation::Horizontal, PlayerType::B) => {
+                Rect::new(0.0, 0.5, 1.0, 1.0)
+            }
+        };
+
+        Some(rect)
+    }
+}
+
+/// Direction of the line splitting the two viewports.
+#[derive(Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitOrientation {
+    /// Side by side.
+    #[default]
+    Vertical,
+    /// Stacked on top of each other.
+    Horizontal,
+}
+
+impl SplitOrientation {
+    pub fn toggled(self) -> Self {
+        match self {
+            SplitOrientation::Vertical => {
+                SplitOrientation::Horizontal
+            }
+            SplitOrientation::Horizontal => {
+                SplitOrientation::Vertical
+            }
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SplitOrientation::Vertical => "Split: Side by side",
+            SplitOrientation::Horizontal => "Split: Stacked",
+        }
+    }
+}
+
+/// Ui [`Node`] that covers the viewport of the player, hidden
+/// when the viewport isn't shown.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct ViewportNode(pub PlayerType);
+
+/// Temporarily point the camera of this player at the
+/// [`CameraTarget`](super::CameraTarget) of another player,
+/// e.g. to spectate the partner while downed.
+///
+/// The player keeps orbiting the camera with their own inputs.
+#[derive(Component, Reflect, Deref, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Spectate(pub PlayerType);
@@ -0,0 +1,239 @@
+//! Subtle per-player styles (color grading and vignette) so that
+//! both halves of the screen are distinguishable at a glance.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{
+    Extent3d, TextureDimension, TextureFormat,
+};
+use bevy::render::view::{
+    ColorGrading, ColorGradingGlobal, ColorGradingSection,
+};
+use bevy::ui::FocusPolicy;
+
+use crate::player::PlayerType;
+
+use super::UI_RENDER_LAYER;
+use super::cinematic::VictoryCinematic;
+use super::split_screen::{QueryCameras, ViewportNode};
+
+/// Resolution of the generated vignette texture.
+const VIGNETTE_SIZE: u32 = 64;
+
+pub(super) struct ViewportStylePlugin;
+
+impl Plugin for ViewportStylePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewportStyles>()
+            .add_systems(Startup, spawn_vignettes)
+            .add_systems(
+                PostUpdate,
+                (
+                    apply_viewport_styles
+                        .run_if(resource_changed::<ViewportStyles>),
+                    set_vignette_visibility(Visibility::Hidden)
+                        .run_if(resource_added::<VictoryCinematic>),
+                    set_vignette_visibility(Visibility::Inherited)
+                        .run_if(resource_removed::<VictoryCinematic>),
+                ),
+            );
+
+        app.register_type::<ViewportStyles>();
+    }
+}
+
+fn spawn_vignettes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let image = images.add(vignette_image());
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            UI_RENDER_LAYER,
+            ViewportVignette(player_type),
+            ViewportNode(player_type),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            ImageNode::new(image.clone()),
+            // Behind every other ui.
+            ZIndex(-1),
+            FocusPolicy::Pass,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+/// A black texture that gets more opaque towards the edges.
+fn vignette_image() -> Image {
+    let mut data = Vec::with_capacity(
+        (VIGNETTE_SIZE * VIGNETTE_SIZE * 4) as usize,
+    );
+
+    for y in 0..VIGNETTE_SIZE {
+        for x in 0..VIGNETTE_SIZE {
+            let uv = (UVec2::new(x, y).as_vec2() + 0.5)
+                / VIGNETTE_SIZE as f32;
+            let distance = (uv - 0.5).length() * 2.0;
+            let t = ((distance - 0.6) / 0.6).clamp(0.0, 1.0);
+            let alpha = t * t * (3.0 - 2.0 * t);
+
+            data.extend_from_slice(&[0, 0, 0, (alpha * 255.0) as u8]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: VIGNETTE_SIZE,
+            height: VIGNETTE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+fn apply_viewport_styles(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+    mut q_vignettes: Query<(&ViewportVignette, &mut ImageNode)>,
+    styles: Res<ViewportStyles>,
+) -> Result {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands
+            .entity(q_cameras.get(player_type.camera_type())?)
+            .insert(styles.get(player_type).color_grading());
+    }
+
+    for (vignette, mut image) in q_vignettes.iter_mut() {
+        image.color = Color::WHITE
+            .with_alpha(styles.get(vignette.0).vignette());
+    }
+
+    Ok(())
+}
+
+fn set_vignette_visibility(
+    visibility: Visibility,
+) -> impl Fn(Query<'_, '_, &mut Visibility, With<ViewportVignette>>) {
+    move |mut q_vignettes: Query<
+        &mut Visibility,
+        With<ViewportVignette>,
+    >| {
+        for mut viz in q_vignettes.iter_mut() {
+            *viz = visibility;
+        }
+    }
+}
+
+/// The [`ViewportStyle`] of each player.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct ViewportStyles {
+    pub a: ViewportStyle,
+    pub b: ViewportStyle,
+}
+
+impl ViewportStyles {
+    pub fn get(&self, player_type: PlayerType) -> ViewportStyle {
+        match player_type {
+            PlayerType::A => self.a,
+            PlayerType::B => self.b,
+        }
+    }
+
+    pub fn get_mut(
+        &mut self,
+        player_type: PlayerType,
+    ) -> &mut ViewportStyle {
+        match player_type {
+            PlayerType::A => &mut self.a,
+            PlayerType::B => &mut self.b,
+        }
+    }
+}
+
+impl Default for ViewportStyles {
+    fn default() -> Self {
+        Self {
+            a: ViewportStyle::Warm,
+            b: ViewportStyle::Cool,
+        }
+    }
+}
+
+/// Color grade and vignette of a player's viewport.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewportStyle {
+    Neutral,
+    Warm,
+    Cool,
+    Vivid,
+    Faded,
+}
+
+impl ViewportStyle {
+    pub const ALL: [Self; 5] = [
+        Self::Neutral,
+        Self::Warm,
+        Self::Cool,
+        Self::Vivid,
+        Self::Faded,
+    ];
+
+    /// The next style in [`Self::ALL`].
+    pub fn next(self) -> Self {
+        let index =
+            Self::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ViewportStyle::Neutral => "Neutral",
+            ViewportStyle::Warm => "Warm",
+            ViewportStyle::Cool => "Cool",
+            ViewportStyle::Vivid => "Vivid",
+            ViewportStyle::Faded => "Faded",
+        }
+    }
+
+    pub fn color_grading(&self) -> ColorGrading {
+        let (temperature, saturation, contrast) = match self {
+            ViewportStyle::Neutral => (0.0, 1.0, 1.0),
+            ViewportStyle::Warm => (0.15, 1.05, 1.0),
+            ViewportStyle::Cool => (-0.15, 1.0, 1.0),
+            ViewportStyle::Vivid => (0.0, 1.25, 1.05),
+            ViewportStyle::Faded => (0.05, 0.75, 0.9),
+        };
+
+        ColorGrading::with_identical_sections(
+            ColorGradingGlobal {
+                temperature,
+                ..default()
+            },
+            ColorGradingSection {
+                saturation,
+                contrast,
+                ..default()
+            },
+        )
+    }
+
+    /// Opacity of the vignette.
+    pub fn vignette(&self) -> f32 {
+        match self {
+            ViewportStyle::Neutral => 0.0,
+            ViewportStyle::Faded => 0.5,
+            _ => 0.3,
+        }
+    }
+}
+
+/// Darkens the edges of a player's viewport.
+#[derive(Component)]
+struct ViewportVignette(PlayerType);
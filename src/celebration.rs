@@ -0,0 +1,332 @@
+//! Confetti bursts, flour clouds and a banner drop over the
+//! [`FinalTarget`] to celebrate cleared waves and the victory.
+//!
+//! Particles are pooled and recycled from the oldest one, so
+//! back to back celebrations never spawn new entities.
+
+use std::f32::consts::TAU;
+
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+
+use crate::audio::AudioCue;
+use crate::camera_controller::cinematic::VictoryReached;
+use crate::enemy::FinalTarget;
+use crate::ui::Screen;
+
+const CONFETTI_POOL_SIZE: usize = 192;
+const FLOUR_POOL_SIZE: usize = 24;
+const GRAVITY: f32 = 9.8;
+/// Air drag of the confetti, makes them flutter down.
+const CONFETTI_DRAG: f32 = 2.5;
+/// Height above the [`FinalTarget`] to burst from.
+const BURST_HEIGHT: f32 = 3.0;
+const BANNER_HEIGHT: f32 = 4.0;
+/// Seconds for the banner to drop into place.
+const BANNER_DROP_DURATION: f32 = 0.8;
+
+pub(super) struct CelebrationPlugin;
+
+impl Plugin for CelebrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_particle_pool)
+            .add_systems(Update, (update_particles, drop_banners))
+            .add_observer(celebrate)
+            .add_observer(celebrate_victory);
+    }
+}
+
+fn spawn_particle_pool(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let confetti_mesh = meshes.add(Rectangle::new(0.12, 0.2));
+    let confetti_materials =
+        [AMBER_400, ROSE_400, SKY_400, EMERALD_400, VIOLET_400].map(
+            |color| {
+                materials.add(StandardMaterial {
+                    base_color: color.into(),
+                    unlit: true,
+                    // Visible from both sides while spinning.
+                    cull_mode: None,
+                    double_sided: true,
+                    ..default()
+                })
+            },
+        );
+
+    let flour_mesh = meshes.add(Sphere::new(0.5));
+    let flour_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE.with_alpha(0.6),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+
+    let confetti = (0..CONFETTI_POOL_SIZE)
+        .map(|i| {
+            commands
+                .spawn((
+                    Mesh3d(confetti_mesh.clone()),
+                    MeshMaterial3d(
+                        confetti_materials
+                            [i % confetti_materials.len()]
+                        .clone(),
+                    ),
+                    Particle::default(),
+                    Transform::default(),
+                    Visibility::Hidden,
+                ))
+                .id()
+        })
+        .collect();
+
+    let flour = (0..FLOUR_POOL_SIZE)
+        .map(|_| {
+            commands
+                .spawn((
+                    Mesh3d(flour_mesh.clone()),
+                    MeshMaterial3d(flour_material.clone()),
+                    Particle::default(),
+                    Transform::default(),
+                    Visibility::Hidden,
+                ))
+                .id()
+        })
+        .collect();
+
+    commands.insert_resource(ParticlePool {
+        confetti,
+        flour,
+        next_confetti: 0,
+        next_flour: 0,
+    });
+
+    commands.insert_resource(BannerAssets {
+        mesh: meshes.add(Cuboid::new(3.0, 1.2, 0.05)),
+        material: materials.add(StandardMaterial {
+            base_color: RED_500.into(),
+            ..default()
+        }),
+    });
+}
+
+fn celebrate_victory(
+    _: Trigger<VictoryReached>,
+    mut commands: Commands,
+) {
+    commands.trigger(Celebrate::Victory);
+}
+
+/// Burst the effects over the [`FinalTarget`] and send the matching
+/// [`AudioCue`] on the same frame.
+fn celebrate(
+    trigger: Trigger<Celebrate>,
+    mut commands: Commands,
+    q_final_targets: Query<&GlobalTransform, With<FinalTarget>>,
+    mut q_particles: Query<(
+        &mut Particle,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    mut pool: ResMut<ParticlePool>,
+    banner_assets: Res<BannerAssets>,
+) -> Result {
+    let Ok(target) = q_final_targets.single() else {
+        return Ok(());
+    };
+
+    let origin = target.translation() + Vec3::Y * BURST_HEIGHT;
+    let celebration = *trigger.event();
+
+    let (confetti_count, flour_count) = match celebration {
+        Celebrate::WaveCleared => (48, 4),
+        Celebrate::Victory => (CONFETTI_POOL_SIZE, 12),
+    };
+
+    for i in 0..confetti_count {
+        let (mut particle, mut transform, mut viz) =
+            q_particles.get_mut(pool.take_confetti())?;
+
+        // Spread evenly on a cone with the golden angle.
+        let angle = i as f32 * 2.399_963;
+        let spread = (i as f32 * 0.618_034).fract();
+        let direction = Vec3::new(
+            angle.cos() * (0.3 + spread),
+            2.0,
+            angle.sin() * (0.3 + spread),
+        )
+        .normalize();
+
+        *particle = Particle {
+            velocity: direction * (7.0 + spread * 3.0),
+            spin: Vec3::new(spread * 8.0, 6.0, (1.0 - spread) * 8.0),
+            growth: 0.0,
+            drag: CONFETTI_DRAG,
+            gravity: GRAVITY,
+            lifetime: Timer::from_seconds(
+                3.0 + spread,
+                TimerMode::Once,
+            ),
+        };
+        *transform = Transform::from_translation(origin)
+            .with_rotation(Quat::from_rotation_y(angle));
+        *viz = Visibility::Inherited;
+    }
+
+    for i in 0..flour_count {
+        let (mut particle, mut transform, mut viz) =
+            q_particles.get_mut(pool.take_flour())?;
+
+        let angle = i as f32 / flour_count as f32 * TAU;
+        let offset = Vec3::new(angle.cos(), 0.0, angle.sin());
+
+        *particle = Particle {
+            velocity: offset * 1.5 + Vec3::Y * 0.5,
+            spin: Vec3::ZERO,
+            growth: 1.2,
+            drag: 1.0,
+            gravity: 0.0,
+            lifetime: Timer::from_seconds(1.5, TimerMode::Once),
+        };
+        *transform = Transform::from_translation(
+            target.translation() + offset * 0.5,
+        )
+        .with_scale(Vec3::splat(0.3));
+        *viz = Visibility::Inherited;
+    }
+
+    if celebration == Celebrate::Victory {
+        commands.trigger(AudioCue::VictoryStinger);
+
+        let to = target.translation() + Vec3::Y * BANNER_HEIGHT;
+        commands.spawn((
+            Mesh3d(banner_assets.mesh.clone()),
+            MeshMaterial3d(banner_assets.material.clone()),
+            Transform::from_translation(to + Vec3::Y * 6.0),
+            BannerDrop {
+                to,
+                timer: Timer::from_seconds(
+                    BANNER_DROP_DURATION,
+                    TimerMode::Once,
+                ),
+            },
+            StateScoped(Screen::EnterLevel),
+        ));
+    }
+
+    Ok(())
+}
+
+fn update_particles(
+    mut q_particles: Query<(
+        &mut Particle,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut particle, mut transform, mut viz) in
+        q_particles.iter_mut()
+    {
+        if *viz == Visibility::Hidden {
+            continue;
+        }
+
+        if particle.lifetime.tick(time.delta()).finished() {
+            *viz = Visibility::Hidden;
+            continue;
+        }
+
+        let drag = particle.drag;
+        particle.velocity.y -= particle.gravity * dt;
+        particle.velocity *= 1.0 - (drag * dt).min(1.0);
+
+        transform.translation += particle.velocity * dt;
+        transform.rotate_local_x(particle.spin.x * dt);
+        transform.rotate_local_y(particle.spin.y * dt);
+        transform.rotate_local_z(particle.spin.z * dt);
+        transform.scale += Vec3::splat(particle.growth * dt);
+    }
+}
+
+/// Drop the banner in with a small bounce at the end.
+fn drop_banners(
+    mut q_banners: Query<(&mut BannerDrop, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (mut banner, mut transform) in q_banners.iter_mut() {
+        if banner.timer.finished() {
+            continue;
+        }
+
+        let t = banner.timer.tick(time.delta()).fraction();
+        // Ease out back.
+        let c = 1.70158;
+        let eased = 1.0
+            + (c + 1.0) * (t - 1.0).powi(3)
+            + c * (t - 1.0).powi(2);
+
+        transform.translation.y = banner.to.y + (1.0 - eased) * 6.0;
+    }
+}
+
+/// Trigger this event to celebrate with confetti and flour.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Celebrate {
+    WaveCleared,
+    /// Also drops a banner and plays the victory stinger.
+    Victory,
+}
+
+#[derive(Resource)]
+struct ParticlePool {
+    confetti: Vec<Entity>,
+    flour: Vec<Entity>,
+    next_confetti: usize,
+    next_flour: usize,
+}
+
+impl ParticlePool {
+    /// Take the next confetti, recycling the oldest one.
+    fn take_confetti(&mut self) -> Entity {
+        let entity = self.confetti[self.next_confetti];
+        self.next_confetti =
+            (self.next_confetti + 1) % self.confetti.len();
+        entity
+    }
+
+    /// Take the next flour cloud, recycling the oldest one.
+    fn take_flour(&mut self) -> Entity {
+        let entity = self.flour[self.next_flour];
+        self.next_flour = (self.next_flour + 1) % self.flour.len();
+        entity
+    }
+}
+
+#[derive(Component, Default)]
+struct Particle {
+    velocity: Vec3,
+    /// Angular velocity around the local axes.
+    spin: Vec3,
+    /// Scale growth per second.
+    growth: f32,
+    drag: f32,
+    gravity: f32,
+    lifetime: Timer,
+}
+
+#[derive(Resource)]
+struct BannerAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+struct BannerDrop {
+    to: Vec3,
+    timer: Timer,
+}
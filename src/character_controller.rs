@@ -1,24 +1,34 @@
 use avian3d::prelude::*;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy_seedling::prelude::*;
 use leafwing_input_manager::prelude::*;
 
 use crate::action::{PlayerAction, RequireAction, TargetAction};
-use crate::camera_controller::split_screen::{
-    CameraType, QueryCameras,
-};
-use crate::inventory::Inventory;
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::interaction::grab::CarryingHeavy;
+use crate::interaction::push_pull::PushingOf;
+use crate::inventory::{Inventory, ItemMagnet};
 use crate::physics::GameLayer;
 use crate::player::PlayerType;
+use crate::player::player_melee::Melee;
+use crate::squash_stretch::SquashStretch;
+use crate::tower::tower_attack::Invulnerable;
+use crate::water::{Breath, InWater};
+use ledge::{LedgeGrab, Traversal};
 
 mod animation;
+pub mod ledge;
 
 /// Plugin that sets up kinematic character movement
 pub(super) struct CharacterControllerPlugin;
 
 impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(animation::CharacterAnimationPlugin);
+        app.add_plugins((
+            animation::CharacterAnimationPlugin::default(),
+            ledge::LedgePlugin,
+        ));
 
         app.add_systems(
             FixedUpdate,
@@ -26,7 +36,11 @@ impl Plugin for CharacterControllerPlugin {
                 check_grounded,
                 apply_gravity,
                 movement,
+                update_stamina,
                 jump,
+                update_dash_cooldowns,
+                start_dash,
+                apply_dash,
                 rotate_to_velocity,
                 movement_damping,
             )
@@ -38,7 +52,9 @@ impl Plugin for CharacterControllerPlugin {
                 .in_set(NarrowPhaseSet::Last),
         );
 
-        app.register_type::<CharacterController>();
+        app.register_type::<CharacterController>()
+            .register_type::<Dash>()
+            .register_type::<Stamina>();
     }
 }
 
@@ -52,7 +68,7 @@ impl Default for GroundCastShape {
 }
 
 /// Check grounded state by raycasting downwards.
-fn check_grounded(
+pub(crate) fn check_grounded(
     mut q_characters: Query<(
         &GlobalTransform,
         &CharacterController,
@@ -106,12 +122,21 @@ fn check_grounded(
 }
 
 fn jump(
-    mut q_characters: Query<(
-        &mut LinearVelocity,
-        &mut IsGrounded,
-        &CharacterController,
-        &TargetAction,
-    )>,
+    mut q_characters: Query<
+        (
+            &mut LinearVelocity,
+            &mut IsGrounded,
+            &CharacterController,
+            &TargetAction,
+            Option<&InWater>,
+        ),
+        // Heavy items can't be carried mid-air.
+        (
+            Without<CarryingHeavy>,
+            Without<Traversal>,
+            Without<PushingOf>,
+        ),
+    >,
     q_actions: Query<&ActionState<PlayerAction>>,
 ) {
     for (
@@ -119,13 +144,17 @@ fn jump(
         mut is_grounded,
         character,
         target_action,
+        in_water,
     ) in q_characters.iter_mut()
     {
         let Ok(action) = q_actions.get(target_action.get()) else {
             continue;
         };
 
-        if is_grounded.0 && action.just_pressed(&PlayerAction::Jump) {
+        // Swim up while submerged.
+        let can_jump =
+            is_grounded.0 || in_water.is_some_and(|w| w.submerged);
+        if can_jump && action.just_pressed(&PlayerAction::Jump) {
             linear_velocity.0.y = character.jump_impulse;
             is_grounded.set_if_neq(IsGrounded(false));
         }
@@ -134,19 +163,24 @@ fn jump(
 
 fn rotate_to_velocity(
     mut q_characters: Query<
-        (&mut Rotation, &LinearVelocity, &IsMoving),
-        With<CharacterController>,
+        (&mut Rotation, &mut TurnRate, &LinearVelocity, &IsMoving),
+        (
+            With<CharacterController>,
+            Without<Traversal>,
+            Without<PushingOf>,
+        ),
     >,
     time: Res<Time>,
 ) {
     const ROTATION_RATE: f32 = 10.0;
     let dt = time.delta_secs();
 
-    for (mut rotation, linear_velocity, is_moving) in
+    for (mut rotation, mut turn_rate, linear_velocity, is_moving) in
         q_characters.iter_mut()
     {
         // Rotate during movement only.
         if is_moving.0 == false {
+            turn_rate.0 = 0.0;
             continue;
         }
 
@@ -162,26 +196,40 @@ fn rotate_to_velocity(
             -direction.y,
         ));
 
-        rotation.0 =
+        let new_rotation =
             rotation.0.slerp(target_rotation, dt * ROTATION_RATE);
+
+        // Signed yaw difference, positive when turning left.
+        let (yaw_delta, ..) = (new_rotation * rotation.0.inverse())
+            .to_euler(EulerRot::YXZ);
+        turn_rate.0 = yaw_delta / dt;
+
+        rotation.0 = new_rotation;
     }
 }
 
 /// Applies gravity to vertical velocity
 fn apply_gravity(
-    mut q_characters: Query<(
-        &mut LinearVelocity,
-        &CharacterController,
-        &IsGrounded,
-    )>,
+    mut q_characters: Query<
+        (
+            &mut LinearVelocity,
+            &CharacterController,
+            &IsGrounded,
+            Option<&InWater>,
+        ),
+        Without<Traversal>,
+    >,
     time: Res<Time>,
 ) {
     let dt = time.delta_secs();
-    for (mut linear_velocity, character, is_grounded) in
+    for (mut linear_velocity, character, is_grounded, in_water) in
         q_characters.iter_mut()
     {
         if is_grounded.0 == false {
-            linear_velocity.0 += character.gravity * dt;
+            let gravity_scale =
+                in_water.map_or(1.0, |w| w.volume.gravity_scale);
+            linear_velocity.0 +=
+                character.gravity * gravity_scale * dt;
         }
     }
 }
@@ -191,13 +239,21 @@ fn movement(
     time: Res<Time>,
     q_cameras: QueryCameras<&GlobalTransform>,
     q_actions: Query<&ActionState<PlayerAction>>,
-    mut q_characters: Query<(
-        &CharacterController,
-        &mut LinearVelocity,
-        &mut IsMoving,
-        &TargetAction,
-        &PlayerType,
-    )>,
+    mut q_characters: Query<
+        (
+            &CharacterController,
+            &mut LinearVelocity,
+            &mut IsMoving,
+            &mut IsSprinting,
+            &IsGrounded,
+            &Stamina,
+            &TargetAction,
+            &PlayerType,
+            Option<&CarryingHeavy>,
+            Option<&InWater>,
+        ),
+        (Without<Dashing>, Without<Traversal>, Without<PushingOf>),
+    >,
 ) {
     let dt = time.delta_secs_f64() as f32;
 
@@ -205,27 +261,22 @@ fn movement(
         character,
         mut linear_velocity,
         mut is_moving,
+        mut is_sprinting,
+        is_grounded,
+        stamina,
         target_action,
         player_type,
+        carrying_heavy,
+        in_water,
     ) in q_characters.iter_mut()
     {
         // Get camera transform.
         let Ok(cam_global_transform) =
-            q_cameras.get(match player_type {
-                PlayerType::A => CameraType::A,
-                PlayerType::B => CameraType::B,
-            })
+            q_cameras.get(player_type.camera_type())
         else {
             return;
         };
 
-        let cam_forward = cam_global_transform.forward();
-        let cam_forward = Vec2::new(cam_forward.x, cam_forward.z)
-            .normalize_or_zero();
-        let cam_left = cam_global_transform.left();
-        let cam_left =
-            Vec2::new(cam_left.x, cam_left.z).normalize_or_zero();
-
         let Ok(action) = q_actions.get(target_action.get()) else {
             warn!("No `InputMap` found for player: {player_type:?}");
             continue;
@@ -237,30 +288,38 @@ fn movement(
         if movement.length_squared() <= f32::EPSILON {
             // Ignore movement when it's negligible.
             is_moving.set_if_neq(IsMoving(false));
+            is_sprinting.set_if_neq(IsSprinting(false));
             continue;
         }
 
         is_moving.set_if_neq(IsMoving(true));
 
         let world_move =
-            (cam_forward * movement.y) - (cam_left * movement.x);
-        let world_move = Vec3::new(world_move.x, 0.0, world_move.y);
+            camera_relative_direction(cam_global_transform, movement);
 
-        // Only allow sprinting if grounded
-        // let can_sprint = *sprint && is_grounded.0;
-        let is_sprinting = false;
+        // Only allow sprinting if grounded, has stamina left
+        // and is not carrying anything heavy.
+        let sprinting = action.pressed(&PlayerAction::Sprint)
+            && is_grounded.0
+            && stamina.current > 0.0
+            && carrying_heavy.is_none();
+        is_sprinting.set_if_neq(IsSprinting(sprinting));
 
         // Apply acceleration * sprint factor
-        let factor = if is_sprinting { 2.0 } else { 1.0 };
+        let factor = if sprinting { 2.0 } else { 1.0 };
         let acceleration = character.acceleration;
         linear_velocity.0 +=
             world_move * (acceleration * dt * factor);
 
         // Clamp horizontal speed (only sprint speed if grounded)
-        let max_speed = match is_sprinting {
-            true => character.max_sprint,
-            false => character.max_walk,
-        };
+        let max_speed = match (sprinting, carrying_heavy) {
+            (true, _) => character.max_sprint,
+            (false, Some(carrying_heavy)) => {
+                character.max_walk * carrying_heavy.0
+            }
+            (false, None) => character.max_walk,
+        } * in_water
+            .map_or(1.0, |w| w.volume.speed_scale);
 
         let horiz =
             Vec2::new(linear_velocity.0.x, linear_velocity.0.z);
@@ -272,19 +331,203 @@ fn movement(
     }
 }
 
-/// Applies damping to horizontal movement
-fn movement_damping(
+/// Drain stamina while sprinting and regenerate it otherwise.
+fn update_stamina(
+    mut q_characters: Query<(&mut Stamina, &IsSprinting)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut stamina, is_sprinting) in q_characters.iter_mut() {
+        if is_sprinting.0 {
+            stamina.current =
+                (stamina.current - stamina.drain_rate * dt).max(0.0);
+        } else if stamina.current < stamina.max {
+            stamina.current = (stamina.current
+                + stamina.regen_rate * dt)
+                .min(stamina.max);
+        }
+    }
+}
+
+/// Tick down dash cooldowns.
+fn update_dash_cooldowns(
+    mut q_cooldowns: Query<&mut DashCooldown>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for mut cooldown in q_cooldowns.iter_mut() {
+        cooldown.0 = (cooldown.0 - dt).max(0.0);
+    }
+}
+
+/// Start dashing along the input direction (or forward
+/// direction if there is no input) when the dash action
+/// is pressed.
+fn start_dash(
+    mut commands: Commands,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_characters: Query<
+        (
+            &Dash,
+            &mut DashCooldown,
+            &mut LinearVelocity,
+            &Rotation,
+            &TargetAction,
+            &PlayerType,
+            Entity,
+        ),
+        (
+            Without<Dashing>,
+            Without<CarryingHeavy>,
+            Without<Traversal>,
+            Without<PushingOf>,
+        ),
+    >,
+) {
+    for (
+        dash,
+        mut cooldown,
+        mut linear_velocity,
+        rotation,
+        target_action,
+        player_type,
+        entity,
+    ) in q_characters.iter_mut()
+    {
+        if cooldown.0 > 0.0 {
+            continue;
+        }
+
+        let Ok(action) = q_actions.get(target_action.get()) else {
+            continue;
+        };
+
+        if action.just_pressed(&PlayerAction::Dash) == false {
+            continue;
+        }
+
+        let movement = action
+            .clamped_axis_pair(&PlayerAction::Move)
+            .clamp_length_max(1.0);
+
+        let direction = match q_cameras.get(player_type.camera_type())
+        {
+            Ok(cam_global_transform)
+                if movement.length_squared() > f32::EPSILON =>
+            {
+                camera_relative_direction(
+                    cam_global_transform,
+                    movement,
+                )
+            }
+            // Dash forward when there is no input.
+            _ => (rotation.0 * Vec3::NEG_Z)
+                .with_y(0.0)
+                .normalize_or_zero(),
+        };
+
+        let Ok(direction) = Dir3::new(direction) else {
+            continue;
+        };
+
+        linear_velocity.x = direction.x * dash.speed;
+        linear_velocity.z = direction.z * dash.speed;
+
+        cooldown.0 = dash.cooldown;
+        commands.entity(entity).insert((
+            Dashing {
+                direction,
+                timer: Timer::from_seconds(
+                    dash.duration,
+                    TimerMode::Once,
+                ),
+            },
+            Invulnerable::from_seconds(dash.invulnerability),
+        ));
+    }
+}
+
+/// Maintain dash velocity until the dash ends.
+fn apply_dash(
+    mut commands: Commands,
     mut q_characters: Query<(
+        &Dash,
+        &mut Dashing,
         &mut LinearVelocity,
-        &CharacterController,
+        Entity,
     )>,
+    time: Res<Time>,
 ) {
-    for (mut linear_velocity, character) in q_characters.iter_mut() {
+    for (dash, mut dashing, mut linear_velocity, entity) in
+        q_characters.iter_mut()
+    {
+        if dashing.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Dashing>();
+            continue;
+        }
+
+        linear_velocity.x = dashing.direction.x * dash.speed;
+        linear_velocity.z = dashing.direction.z * dash.speed;
+    }
+}
+
+/// Convert a 2d input into a world space direction on the
+/// XZ plane relative to the camera.
+pub(crate) fn camera_relative_direction(
+    cam_global_transform: &GlobalTransform,
+    input: Vec2,
+) -> Vec3 {
+    let cam_forward = cam_global_transform.forward();
+    let cam_forward =
+        Vec2::new(cam_forward.x, cam_forward.z).normalize_or_zero();
+    let cam_left = cam_global_transform.left();
+    let cam_left =
+        Vec2::new(cam_left.x, cam_left.z).normalize_or_zero();
+
+    let world_move = (cam_forward * input.y) - (cam_left * input.x);
+    Vec3::new(world_move.x, 0.0, world_move.y)
+}
+
+/// Inverse of [`camera_relative_direction`], the movement input
+/// that moves towards a world space direction.
+pub(crate) fn camera_relative_input(
+    cam_global_transform: &GlobalTransform,
+    direction: Vec3,
+) -> Vec2 {
+    let cam_forward = cam_global_transform.forward();
+    let cam_forward =
+        Vec2::new(cam_forward.x, cam_forward.z).normalize_or_zero();
+    let cam_left = cam_global_transform.left();
+    let cam_left =
+        Vec2::new(cam_left.x, cam_left.z).normalize_or_zero();
+
+    let direction = direction.xz();
+    Vec2::new(-direction.dot(cam_left), direction.dot(cam_forward))
+}
+
+/// Applies damping to horizontal movement
+fn movement_damping(
+    mut q_characters: Query<
+        (&mut LinearVelocity, &CharacterController, Option<&InWater>),
+        (Without<Dashing>, Without<Traversal>),
+    >,
+) {
+    for (mut linear_velocity, character, in_water) in
+        q_characters.iter_mut()
+    {
         // Damping cannot go above 1.0.
         let damping = character.damping.min(1.0);
         // Apply damping directly to physics velocity, except gravity.
         linear_velocity.x *= damping;
         linear_velocity.z *= damping;
+
+        // Water slows down sinking as well.
+        if let Some(in_water) = in_water {
+            let damping = in_water.volume.damping.min(1.0);
+            linear_velocity.0 *= damping;
+        }
     }
 }
 
@@ -293,6 +536,11 @@ fn kinematic_controller_collisions(
     collisions: Collisions,
     bodies: Query<&RigidBody>,
     collider_rbs: Query<&ColliderOf, Without<Sensor>>,
+    q_colliders: Query<
+        (&Collider, &Position),
+        Without<CharacterController>,
+    >,
+    spatial_query: SpatialQuery,
     mut q_characters: Query<
         (
             &mut Position,
@@ -304,7 +552,12 @@ fn kinematic_controller_collisions(
     >,
     time: Res<Time>,
 ) {
+    // Contacts with normals flatter than this are walls that might
+    // be stepped over.
+    const STEP_WALL_NORMAL: f32 = 0.3;
     let dt = time.delta_secs();
+    // Characters that already stepped up a ledge this frame.
+    let mut stepped = HashSet::<Entity>::new();
 
     for contacts in collisions.iter() {
         // Pull out the two bodies
@@ -337,6 +590,11 @@ fn kinematic_controller_collisions(
         let other_dynamic =
             bodies.get(other).is_ok_and(|rb| rb.is_dynamic());
 
+        let collider = match is_first {
+            true => contacts.collider1,
+            false => contacts.collider2,
+        };
+
         for manifold in &contacts.manifolds {
             let normal = if is_first {
                 -manifold.normal
@@ -344,6 +602,40 @@ fn kinematic_controller_collisions(
                 manifold.normal
             };
 
+            // Climb small ledges instead of sliding along them.
+            if is_grounded.0
+                && other_dynamic == false
+                && stepped.contains(&entity) == false
+                && normal.y.abs() < STEP_WALL_NORMAL
+                && linear_velocity.dot(normal) < 0.0
+            {
+                if let Ok((shape, collider_pos)) =
+                    q_colliders.get(collider)
+                {
+                    let mut mask = LayerMask::ALL;
+                    mask.remove(GameLayer::Player);
+                    let filter = SpatialQueryFilter::from_mask(mask)
+                        .with_excluded_entities([entity, collider]);
+
+                    if let Some(lift) = step_up_height(
+                        &spatial_query,
+                        shape,
+                        collider_pos.0,
+                        normal,
+                        ctl,
+                        &filter,
+                    ) {
+                        pos.y += lift;
+                        linear_velocity.y =
+                            linear_velocity.y.max(0.0);
+                        // The remaining contacts are outdated after
+                        // the lift.
+                        stepped.insert(entity);
+                        break;
+                    }
+                }
+            }
+
             // Push out of penetration and handle velocity
             let mut deepest = 0.0;
             for pt in &manifold.points {
@@ -407,19 +699,84 @@ fn kinematic_controller_collisions(
     }
 }
 
+/// Height the character needs to be lifted by to stand on the
+/// ledge in front of the wall, [`None`] if the ledge is too high
+/// or too steep to step onto.
+fn step_up_height(
+    spatial_query: &SpatialQuery,
+    shape: &Collider,
+    origin: Vec3,
+    wall_normal: Vec3,
+    ctl: &CharacterController,
+    filter: &SpatialQueryFilter,
+) -> Option<f32> {
+    // How far past the wall the top of the ledge is searched at.
+    const STEP_PROBE: f32 = 0.05;
+
+    if ctl.step_height <= 0.0 {
+        return None;
+    }
+
+    let forward = -wall_normal
+        .reject_from_normalized(Vec3::Y)
+        .try_normalize()?;
+
+    // Cast down onto the ledge from above the step height.
+    let hit = spatial_query.cast_shape(
+        shape,
+        origin + Vec3::Y * ctl.step_height + forward * STEP_PROBE,
+        Quat::IDENTITY,
+        Dir3::NEG_Y,
+        &ShapeCastConfig {
+            max_distance: ctl.step_height,
+            ..ShapeCastConfig::DEFAULT
+        },
+        filter,
+    )?;
+
+    // Blocked even at the step height, it's a wall.
+    if hit.distance <= 0.0 {
+        return None;
+    }
+
+    if hit.normal1.angle_between(Vec3::Y) > ctl.max_slope_angle {
+        return None;
+    }
+
+    let lift = ctl.step_height - hit.distance;
+    (lift > 0.01).then_some(lift)
+}
+
 #[derive(Component, Deref, DerefMut, Default, PartialEq, Eq)]
 pub struct IsGrounded(pub bool);
 
 #[derive(Component, Deref, DerefMut, Default, PartialEq, Eq)]
 pub struct IsMoving(pub bool);
 
+#[derive(Component, Deref, DerefMut, Default, PartialEq, Eq)]
+pub struct IsSprinting(pub bool);
+
+/// Angular velocity around the up axis in radians per second,
+/// positive when turning left.
+#[derive(Component, Deref, DerefMut, Default, Debug)]
+pub struct TurnRate(pub f32);
+
 /// Marker for kinematic character bodies
 #[derive(Component, Reflect)]
 #[require(
     IsGrounded,
     IsMoving,
+    IsSprinting,
+    TurnRate,
+    Stamina,
+    Breath,
+    LedgeGrab,
+    Dash,
+    Melee,
+    SquashStretch,
     RequireAction,
     Inventory,
+    ItemMagnet,
     TransformInterpolation,
     CollisionEventsEnabled,
     CollisionLayers::new(GameLayer::Player, LayerMask::ALL,),
@@ -437,9 +794,17 @@ pub struct CharacterController {
     pub damping: f32,
     pub jump_impulse: f32,
     pub max_slope_angle: f32,
+    /// Height of the ledges (e.g. curbs and stairs) that are
+    /// stepped onto instead of blocking the movement.
+    #[reflect(default = "default_step_height")]
+    pub step_height: f32,
     pub gravity: Vec3,
 }
 
+fn default_step_height() -> f32 {
+    0.25
+}
+
 impl Default for CharacterController {
     fn default() -> Self {
         Self {
@@ -449,7 +814,149 @@ impl Default for CharacterController {
             damping: 0.8,
             jump_impulse: 4.0,
             max_slope_angle: 1.41,
+            step_height: default_step_height(),
             gravity: Vec3::new(0.0, -20.0, 0.0),
         }
     }
 }
+
+/// Dash ability configuration for a [`CharacterController`].
+#[derive(Component, Reflect)]
+#[require(DashCooldown)]
+#[reflect(Component, Default)]
+pub struct Dash {
+    /// Horizontal velocity during the dash.
+    pub speed: f32,
+    /// How long the dash lasts in seconds.
+    pub duration: f32,
+    /// Time in seconds before the next dash is allowed.
+    pub cooldown: f32,
+    /// Duration of the invulnerability granted on dash.
+    pub invulnerability: f32,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Self {
+            speed: 18.0,
+            duration: 0.2,
+            cooldown: 1.0,
+            invulnerability: 0.3,
+        }
+    }
+}
+
+/// Time left before the character can dash again.
+#[derive(Component, Deref, DerefMut, Default, Debug)]
+pub struct DashCooldown(pub f32);
+
+/// Inserted while the character is dashing.
+#[derive(Component, Debug)]
+pub struct Dashing {
+    pub direction: Dir3,
+    pub timer: Timer,
+}
+
+/// Stamina that drains while sprinting.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// Stamina drained per second while sprinting.
+    pub drain_rate: f32,
+    /// Stamina regenerated per second while not sprinting.
+    pub regen_rate: f32,
+}
+
+impl Stamina {
+    /// Remaining stamina in percentage from 0 - 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+
+        self.current / self.max
+    }
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+            drain_rate: 30.0,
+            regen_rate: 20.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    /// Distance of the character's origin from its feet.
+    const HALF_HEIGHT: f32 = 0.8;
+
+    fn physics_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(
+            TimeUpdateStrategy::ManualDuration(
+                Duration::from_secs_f32(1.0 / 60.0),
+            ),
+        );
+
+        app
+    }
+
+    /// Step height for a character walking into a box of the given
+    /// height along -Z.
+    fn step_up_onto(height: f32) -> Option<f32> {
+        let mut app = physics_app();
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(2.0, height, 2.0),
+            Transform::from_xyz(0.0, height * 0.5, -1.5),
+        ));
+
+        // Let the spatial query pick up the collider.
+        for _ in 0..4 {
+            app.update();
+        }
+
+        app.world_mut()
+            .run_system_once(move |spatial_query: SpatialQuery| {
+                step_up_height(
+                    &spatial_query,
+                    &Collider::capsule(0.3, 1.0),
+                    Vec3::new(0.0, HALF_HEIGHT, -0.25),
+                    Vec3::Z,
+                    &CharacterController::default(),
+                    &SpatialQueryFilter::default(),
+                )
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_step_up_height() {
+        let lift =
+            step_up_onto(0.15).expect("Should step onto a curb.");
+        assert!((lift - 0.15).abs() < 0.01, "lift: {lift}");
+
+        // Taller than the step height.
+        assert_eq!(step_up_onto(1.0), None);
+    }
+}
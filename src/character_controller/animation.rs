@@ -1,138 +1,443 @@
-use core::time::Duration;
-
-use bevy::animation::AnimationTarget;
+use avian3d::prelude::*;
+use bevy::animation::transition::advance_transitions;
+use bevy::animation::{AnimationTarget, animate_targets};
+use bevy::app::Animation;
 use bevy::prelude::*;
 
+use crate::asset_pipeline::animation_controller::{
+    AnimationControllerAppExt, AnimationControllerSystems,
+    AnimationGraphController, AnimationState, StateClip,
+};
 use crate::asset_pipeline::animation_pipeline::{
-    AnimationGraphMap, NodeMap,
+    AnimationGraphMap, AnimationOwner, NodeMap,
 };
 use crate::asset_pipeline::{AssetState, PrefabAssets};
-use crate::player::PlayerType;
+use crate::damage::Hit;
+use crate::interaction::grab::CarryingHeavy;
+use crate::player::CharacterId;
+use crate::player::player_melee::Swinging;
+use crate::squash_stretch::SquashStretchBone;
+
+use super::ledge::Traversal;
+use super::{
+    CharacterController, Dashing, IsGrounded, IsMoving, TurnRate,
+};
 
-use super::{CharacterController, IsGrounded, IsMoving};
+/// Angular speed (radians per second) needed to be considered
+/// as turning.
+const MIN_TURN_RATE: f32 = 1.0;
 
-pub(super) struct CharacterAnimationPlugin;
+pub(super) struct CharacterAnimationPlugin {
+    /// Characters slower than this will turn in place.
+    pub turn_in_place_threshold: f32,
+    /// Speed at which the walk animation is fully weighted.
+    pub walk_speed: f32,
+    /// Speed at which the run animation is fully weighted.
+    pub run_speed: f32,
+    /// Maximum lean angle (radians) when turning.
+    pub max_lean: f32,
+    /// Lean angle per unit of speed and turn rate.
+    pub lean_factor: f32,
+    /// How fast the lean angle catches up to its target.
+    pub lean_rate: f32,
+}
+
+impl Default for CharacterAnimationPlugin {
+    fn default() -> Self {
+        Self {
+            turn_in_place_threshold: 0.5,
+            walk_speed: 3.0,
+            run_speed: 6.0,
+            max_lean: 0.25,
+            lean_factor: 0.02,
+            lean_rate: 8.0,
+        }
+    }
+}
 
 impl Plugin for CharacterAnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.insert_resource(MotionBlending {
+            turn_in_place_threshold: self.turn_in_place_threshold,
+            walk_speed: self.walk_speed,
+            run_speed: self.run_speed,
+            max_lean: self.max_lean,
+            lean_factor: self.lean_factor,
+            lean_rate: self.lean_rate,
+        })
+        .add_animation_controller::<CharacterAnimation>()
+        .add_systems(
             Update,
-            (setup_animation_graph, movement_animation)
+            (
+                (setup_animation_graph, movement_animation)
+                    .chain()
+                    .before(AnimationControllerSystems),
+                update_lean,
+            )
                 .run_if(in_state(AssetState::Loaded)),
+        )
+        .add_observer(hit_animation)
+        .add_systems(
+            PostUpdate,
+            (
+                blend_locomotion
+                    .after(advance_transitions)
+                    .before(animate_targets),
+                restore_lean.before(Animation),
+                apply_lean
+                    .after(Animation)
+                    .before(TransformSystem::TransformPropagate),
+            ),
         );
+
+        app.register_type::<MotionBlending>();
     }
 }
 
+/// Decide on the animation from the movement, optional
+/// animations fall back to the next one if they're not available.
 fn movement_animation(
-    q_characters: Query<
+    mut q_characters: Query<
         (
             &NodeMap,
             &IsMoving,
             &IsGrounded,
-            &AnimationTarget,
-            &PlayerType,
+            &LinearVelocity,
+            &TurnRate,
+            &mut AnimationGraphController<CharacterAnimation>,
+            Has<Dashing>,
+            Has<CarryingHeavy>,
+            Option<&Traversal>,
+            Option<&Swinging>,
         ),
         With<CharacterController>,
     >,
-    mut q_animation_players: Query<(
-        &mut AnimationPlayer,
-        &mut AnimationTransitions,
-    )>,
-) -> Result {
+    motion_blending: Res<MotionBlending>,
+) {
     for (
         node_map,
         is_moving,
         is_grounded,
-        animation_target,
-        player_type,
-    ) in q_characters.iter()
+        linear_velocity,
+        turn_rate,
+        mut controller,
+        is_dashing,
+        carrying_heavy,
+        traversal,
+        swinging,
+    ) in q_characters.iter_mut()
     {
-        let (mut anim_player, mut anim_transitions) =
-            q_animation_players.get_mut(animation_target.player)?;
-
-        if is_grounded.0 == false {
-            let jump_node =
-                *node_map.get("JumpUp").ok_or(format!(
-                    "No idle animation found for {:?}!",
-                    player_type
-                ))?;
-
-            if anim_player.is_playing_animation(jump_node) == false {
-                anim_transitions
-                    .play(
-                        &mut anim_player,
-                        jump_node,
-                        Duration::from_millis(100),
-                    )
-                    .set_speed(2.0);
-            }
+        let is_turning = is_moving.0
+            && linear_velocity.xz().length()
+                < motion_blending.turn_in_place_threshold
+            && turn_rate.abs() > MIN_TURN_RATE;
+
+        let candidates = [
+            traversal.map(|traversal| match traversal {
+                Traversal::Vaulting(_) => CharacterAnimation::Vault,
+                Traversal::Hanging(_) => CharacterAnimation::Hang,
+                Traversal::Climbing(_) => CharacterAnimation::Climb,
+            }),
+            is_dashing.then_some(CharacterAnimation::Dash),
+            swinging.map(|swinging| {
+                CharacterAnimation::Melee(swinging.step)
+            }),
+            (is_grounded.0 == false)
+                .then_some(CharacterAnimation::Jump),
+            carrying_heavy.then_some(match is_moving.0 {
+                true => CharacterAnimation::CarryWalking,
+                false => CharacterAnimation::CarryIdle,
+            }),
+            is_turning.then_some(match turn_rate.0 > 0.0 {
+                true => CharacterAnimation::TurnLeft,
+                false => CharacterAnimation::TurnRight,
+            }),
+        ];
+
+        let state = candidates
+            .into_iter()
+            .flatten()
+            .find(|state| state.is_available(node_map))
+            .unwrap_or(match is_moving.0 {
+                true => CharacterAnimation::Walking,
+                false => CharacterAnimation::Idle,
+            });
+
+        controller.set(state);
+    }
+}
+
+/// Flinch if the character has a "Hit" animation.
+fn hit_animation(
+    trigger: Trigger<Hit>,
+    mut q_characters: Query<
+        (&NodeMap, &mut AnimationGraphController<CharacterAnimation>),
+        With<CharacterController>,
+    >,
+) {
+    let Ok((node_map, mut controller)) =
+        q_characters.get_mut(trigger.target())
+    else {
+        return;
+    };
+
+    if CharacterAnimation::Hit.is_available(node_map) {
+        controller.play_once(CharacterAnimation::Hit);
+    }
+}
+
+/// Blend between the walk and run animations based on the
+/// character's speed while walking is the main animation.
+fn blend_locomotion(
+    q_characters: Query<
+        (&NodeMap, &LinearVelocity, &AnimationTarget),
+        With<CharacterController>,
+    >,
+    mut q_animation_players: Query<(
+        &mut AnimationPlayer,
+        &AnimationTransitions,
+    )>,
+    motion_blending: Res<MotionBlending>,
+) {
+    for (node_map, linear_velocity, animation_target) in
+        q_characters.iter()
+    {
+        let Ok((mut anim_player, anim_transitions)) =
+            q_animation_players.get_mut(animation_target.player)
+        else {
+            continue;
+        };
+
+        let (Some(&walking_node), running_node) =
+            (node_map.get("Walking"), node_map.get("Running"))
+        else {
+            continue;
+        };
+
+        let is_walking = anim_transitions.get_main_animation()
+            == Some(walking_node);
+
+        let Some(&running_node) = running_node else {
+            continue;
+        };
 
+        if is_walking == false {
+            anim_player.stop(running_node);
             continue;
         }
 
-        if is_moving.0 {
-            let walking_node =
-                *node_map.get("Walking").ok_or(format!(
-                    "No walking animation found for {:?}!",
-                    player_type
-                ))?;
-
-            if anim_player.is_playing_animation(walking_node) == false
-            {
-                anim_transitions
-                    .play(
-                        &mut anim_player,
-                        walking_node,
-                        Duration::from_millis(200),
-                    )
-                    .set_speed(1.5)
-                    .repeat();
-            }
-        } else {
-            let idle_node = *node_map.get("Idle").ok_or(format!(
-                "No idle animation found for {:?}!",
-                player_type
-            ))?;
+        let speed = linear_velocity.xz().length();
+        let run_weight = ((speed - motion_blending.walk_speed)
+            / (motion_blending.run_speed
+                - motion_blending.walk_speed))
+            .clamp(0.0, 1.0);
 
-            if anim_player.is_playing_animation(idle_node) == false {
-                anim_transitions
-                    .play(
-                        &mut anim_player,
-                        idle_node,
-                        Duration::from_millis(200),
-                    )
-                    .repeat();
-            }
+        // Weight given to the main animation by the transitions.
+        let Some(total_weight) =
+            anim_player.animation(walking_node).map(|a| a.weight)
+        else {
+            continue;
+        };
+
+        if let Some(walking) = anim_player.animation_mut(walking_node)
+        {
+            walking.set_weight(total_weight * (1.0 - run_weight));
         }
+        anim_player
+            .play(running_node)
+            .repeat()
+            .set_weight(total_weight * run_weight)
+            .set_speed(
+                (speed / motion_blending.run_speed).clamp(0.5, 1.5),
+            );
     }
+}
 
-    Ok(())
+/// Lean towards the direction of the turn.
+fn update_lean(
+    mut q_characters: Query<
+        (&mut Lean, &LinearVelocity, &TurnRate),
+        With<CharacterController>,
+    >,
+    motion_blending: Res<MotionBlending>,
+    time: Res<Time>,
+) {
+    for (mut lean, linear_velocity, turn_rate) in
+        q_characters.iter_mut()
+    {
+        let target = (turn_rate.0
+            * linear_velocity.xz().length()
+            * motion_blending.lean_factor)
+            .clamp(
+                -motion_blending.max_lean,
+                motion_blending.max_lean,
+            );
+
+        lean.angle = lean.angle.lerp(
+            target,
+            (time.delta_secs() * motion_blending.lean_rate).min(1.0),
+        );
+    }
+}
+
+/// Undo the lean from the last frame so that it does
+/// not accumulate on bones that are not animated.
+fn restore_lean(
+    q_characters: Query<(&Lean, &SquashStretchBone)>,
+    mut q_transforms: Query<&mut Transform>,
+) {
+    for (lean, bone) in q_characters.iter() {
+        if let Ok(mut transform) = q_transforms.get_mut(bone.bone()) {
+            transform.rotation = Quat::from_rotation_z(-lean.applied)
+                * transform.rotation;
+        }
+    }
+}
+
+/// Lean the root bone after the animation has been applied.
+fn apply_lean(
+    mut q_characters: Query<(&mut Lean, &SquashStretchBone)>,
+    mut q_transforms: Query<&mut Transform>,
+) {
+    for (mut lean, bone) in q_characters.iter_mut() {
+        let Ok(mut transform) = q_transforms.get_mut(bone.bone())
+        else {
+            continue;
+        };
+
+        // Roll around the forward axis.
+        transform.rotation =
+            Quat::from_rotation_z(lean.angle) * transform.rotation;
+        lean.applied = lean.angle;
+    }
 }
 
 fn setup_animation_graph(
     mut commands: Commands,
     q_characters: Query<
-        (&PlayerType, &AnimationTarget, Entity),
+        (&CharacterId, &AnimationTarget, Entity),
         (With<CharacterController>, Without<NodeMap>),
     >,
     prefabs: Res<PrefabAssets>,
 ) -> Result {
-    for (player_type, animation_target, entity) in q_characters.iter()
+    for (character_id, animation_target, entity) in
+        q_characters.iter()
     {
         let AnimationGraphMap { graph, node_map } = prefabs
-            .get_animation(player_type.prefab_name())
+            .get_animation(character_id.prefab_name())
             .ok_or(format!(
-                "Unable to get animation for {player_type:?}!"
+                "Unable to get animation for {character_id:?}!"
             ))?;
 
-        commands.entity(entity).insert(node_map.clone());
+        commands.entity(entity).insert((
+            node_map.clone(),
+            AnimationGraphController::<CharacterAnimation>::default(),
+            Lean::default(),
+        ));
         commands.entity(animation_target.player).insert((
             AnimationGraphHandle(graph.clone()),
             AnimationTransitions::new(),
+            AnimationOwner(entity),
         ));
 
-        info!("Setup animation graph for {player_type:?}.");
+        info!("Setup animation graph for {character_id:?}.");
     }
 
     Ok(())
 }
+
+/// Parameters for turning in place, blending between walk
+/// and run, and leaning into turns.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct MotionBlending {
+    pub turn_in_place_threshold: f32,
+    pub walk_speed: f32,
+    pub run_speed: f32,
+    pub max_lean: f32,
+    pub lean_factor: f32,
+    pub lean_rate: f32,
+}
+
+/// Current lean angle (radians) of a character.
+#[derive(Component, Default, Debug)]
+pub struct Lean {
+    pub angle: f32,
+    applied: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterAnimation {
+    Idle,
+    Walking,
+    Jump,
+    Vault,
+    Hang,
+    Climb,
+    Dash,
+    /// The swing in the combo, starting from 1.
+    Melee(usize),
+    CarryIdle,
+    CarryWalking,
+    TurnLeft,
+    TurnRight,
+    Hit,
+}
+
+impl AnimationState for CharacterAnimation {
+    fn clip(&self) -> StateClip {
+        match self {
+            CharacterAnimation::Idle => {
+                StateClip::new("Idle").with_transition(200).repeat()
+            }
+            CharacterAnimation::Walking => StateClip::new("Walking")
+                .with_transition(200)
+                .with_speed(1.5)
+                .repeat(),
+            CharacterAnimation::Jump => StateClip::new("JumpUp")
+                .with_transition(100)
+                .with_speed(2.0),
+            // Ledge animations fallback to the jump animation.
+            CharacterAnimation::Vault => StateClip::new("Vault")
+                .with_fallback("JumpUp")
+                .with_transition(100),
+            CharacterAnimation::Hang => StateClip::new("Hang")
+                .with_fallback("JumpUp")
+                .with_transition(100)
+                .repeat(),
+            CharacterAnimation::Climb => StateClip::new("Climb")
+                .with_fallback("JumpUp")
+                .with_transition(100),
+            CharacterAnimation::Dash => StateClip::new("Dash")
+                .with_transition(50)
+                .with_speed(2.0),
+            CharacterAnimation::Melee(step) => {
+                StateClip::new(format!("Melee{step}"))
+                    .with_transition(50)
+            }
+            CharacterAnimation::CarryIdle => {
+                StateClip::new("CarryIdle")
+                    .with_transition(200)
+                    .repeat()
+            }
+            CharacterAnimation::CarryWalking => {
+                StateClip::new("CarryWalking")
+                    .with_transition(200)
+                    .repeat()
+            }
+            CharacterAnimation::TurnLeft => {
+                StateClip::new("TurnLeft")
+                    .with_transition(100)
+                    .repeat()
+            }
+            CharacterAnimation::TurnRight => {
+                StateClip::new("TurnRight")
+                    .with_transition(100)
+                    .repeat()
+            }
+            CharacterAnimation::Hit => {
+                StateClip::new("Hit").with_transition(50)
+            }
+        }
+    }
+}
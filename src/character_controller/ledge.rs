@@ -0,0 +1,373 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::interaction::grab::CarryingHeavy;
+use crate::physics::GameLayer;
+
+use super::{Dashing, IsGrounded, check_grounded};
+
+/// Radius of the sphere used for ledge detection.
+const CAST_RADIUS: f32 = 0.2;
+
+pub(super) struct LedgePlugin;
+
+impl Plugin for LedgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (detect_ledge, start_traversal, update_traversal)
+                .chain()
+                .after(check_grounded)
+                .before(super::apply_gravity),
+        );
+
+        app.register_type::<LedgeGrab>();
+    }
+}
+
+/// Detect a ledge in front of the character using a forward
+/// shape cast against the wall followed by a downward shape
+/// cast onto the top of the wall.
+fn detect_ledge(
+    mut q_characters: Query<(
+        &LedgeGrab,
+        &mut DetectedLedge,
+        &Position,
+        &Rotation,
+    )>,
+    spatial_query: SpatialQuery,
+    cast_shape: Local<LedgeCastShape>,
+) {
+    /// Height from the feet to start the forward cast.
+    const FORWARD_CAST_HEIGHT: f32 = 0.5;
+    /// How far into the wall to start the downward cast.
+    const WALL_DEPTH: f32 = 0.3;
+    /// Minimum height of a ledge, anything lower is a step.
+    const MIN_LEDGE_HEIGHT: f32 = 0.3;
+
+    let mut mask = LayerMask::ALL;
+    mask.remove([
+        GameLayer::Player,
+        GameLayer::Enemy,
+        GameLayer::Interactable,
+        GameLayer::InventoryItem,
+        GameLayer::Projectile,
+    ]);
+    let filter = SpatialQueryFilter::default().with_mask(mask);
+
+    for (ledge_grab, mut detected_ledge, position, rotation) in
+        q_characters.iter_mut()
+    {
+        detected_ledge.0 = None;
+
+        let Ok(forward) =
+            Dir3::new((rotation.0 * Vec3::NEG_Z).with_y(0.0))
+        else {
+            continue;
+        };
+
+        let Some(wall_hit) = spatial_query.cast_shape(
+            &cast_shape,
+            position.0 + Vec3::Y * FORWARD_CAST_HEIGHT,
+            Quat::IDENTITY,
+            forward,
+            &ShapeCastConfig {
+                max_distance: ledge_grab.reach,
+                ..ShapeCastConfig::DEFAULT
+            },
+            &filter,
+        ) else {
+            continue;
+        };
+
+        // Only vertical-ish walls are considered.
+        let wall_normal = wall_hit.normal1.with_y(0.0);
+        let Ok(wall_normal) = Dir3::new(wall_normal) else {
+            continue;
+        };
+
+        // Cast down from above the highest climbable ledge.
+        let top_origin = (wall_hit.point1 - wall_normal * WALL_DEPTH)
+            .with_y(
+                position.y
+                    + ledge_grab.max_climb_height
+                    + CAST_RADIUS,
+            );
+
+        let Some(top_hit) = spatial_query.cast_shape(
+            &cast_shape,
+            top_origin,
+            Quat::IDENTITY,
+            Dir3::NEG_Y,
+            &ShapeCastConfig {
+                max_distance: ledge_grab.max_climb_height,
+                ..ShapeCastConfig::DEFAULT
+            },
+            &filter,
+        ) else {
+            continue;
+        };
+
+        // The top has to be walkable.
+        if top_hit.normal1.angle_between(Vec3::Y) > 0.5 {
+            continue;
+        }
+
+        let height = top_hit.point1.y - position.y;
+        if height < MIN_LEDGE_HEIGHT
+            || height > ledge_grab.max_climb_height
+        {
+            continue;
+        }
+
+        detected_ledge.0 = Some(Ledge {
+            point: top_hit.point1,
+            wall_normal,
+            height,
+        });
+    }
+}
+
+/// Vault over low walls or hang on higher ledges when the
+/// climb action is pressed, and climb up or drop down while
+/// hanging.
+fn start_traversal(
+    mut commands: Commands,
+    mut q_characters: Query<
+        (
+            &LedgeGrab,
+            &DetectedLedge,
+            &IsGrounded,
+            &Position,
+            &mut LinearVelocity,
+            Option<&Traversal>,
+            &TargetAction,
+            Entity,
+        ),
+        (Without<Dashing>, Without<CarryingHeavy>),
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+) {
+    for (
+        ledge_grab,
+        detected_ledge,
+        is_grounded,
+        position,
+        mut linear_velocity,
+        traversal,
+        target_action,
+        entity,
+    ) in q_characters.iter_mut()
+    {
+        let Ok(action) = q_actions.get(target_action.get()) else {
+            continue;
+        };
+
+        match traversal {
+            Some(Traversal::Hanging(ledge)) => {
+                if action.just_pressed(&PlayerAction::Cancel) {
+                    // Let go of the ledge.
+                    commands.entity(entity).remove::<Traversal>();
+                } else if action.just_pressed(&PlayerAction::Climb)
+                    || action.just_pressed(&PlayerAction::Jump)
+                {
+                    commands.entity(entity).insert(
+                        Traversal::Climbing(TraversalPath::new(
+                            position.0,
+                            ledge.point - ledge.wall_normal * 0.5,
+                            0.3,
+                            ledge_grab.climb_duration,
+                        )),
+                    );
+                }
+            }
+            // Already vaulting or climbing.
+            Some(_) => {}
+            None => {
+                let Some(ledge) = detected_ledge.0 else {
+                    continue;
+                };
+
+                if action.just_pressed(&PlayerAction::Climb) == false
+                {
+                    continue;
+                }
+
+                linear_velocity.0 = Vec3::ZERO;
+
+                if is_grounded.0
+                    && ledge.height <= ledge_grab.max_vault_height
+                {
+                    // Land on the other side of the wall.
+                    let end = (ledge.point
+                        - ledge.wall_normal
+                            * ledge_grab.vault_distance)
+                        .with_y(position.y);
+
+                    commands.entity(entity).insert(
+                        Traversal::Vaulting(TraversalPath::new(
+                            position.0,
+                            end,
+                            ledge.height + 0.3,
+                            ledge_grab.vault_duration,
+                        )),
+                    );
+                } else {
+                    commands.entity(entity).insert((
+                        Traversal::Hanging(ledge),
+                        Position(
+                            ledge.point
+                                + ledge.wall_normal
+                                    * ledge_grab.hang_distance
+                                - Vec3::Y * ledge_grab.hang_depth,
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Move the character along the traversal path.
+fn update_traversal(
+    mut commands: Commands,
+    mut q_characters: Query<(
+        &mut Traversal,
+        &mut Position,
+        &mut LinearVelocity,
+        Entity,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut traversal, mut position, mut linear_velocity, entity) in
+        q_characters.iter_mut()
+    {
+        linear_velocity.0 = Vec3::ZERO;
+
+        let path = match traversal.as_mut() {
+            Traversal::Vaulting(path) | Traversal::Climbing(path) => {
+                path
+            }
+            Traversal::Hanging(_) => continue,
+        };
+
+        path.timer.tick(time.delta());
+        position.0 = path.sample();
+
+        if path.timer.finished() {
+            commands.entity(entity).remove::<Traversal>();
+        }
+    }
+}
+
+#[derive(Deref)]
+struct LedgeCastShape(Collider);
+
+impl Default for LedgeCastShape {
+    fn default() -> Self {
+        Self(Collider::sphere(CAST_RADIUS))
+    }
+}
+
+/// Ledge grabbing and vaulting configuration.
+#[derive(Component, Reflect)]
+#[require(DetectedLedge)]
+#[reflect(Component, Default)]
+pub struct LedgeGrab {
+    /// How far in front of the character to look for walls.
+    pub reach: f32,
+    /// Ledges up to this height will be vaulted over.
+    pub max_vault_height: f32,
+    /// Ledges up to this height can be grabbed.
+    pub max_climb_height: f32,
+    /// How far past the wall to land after vaulting.
+    pub vault_distance: f32,
+    pub vault_duration: f32,
+    pub climb_duration: f32,
+    /// Distance from the wall while hanging.
+    pub hang_distance: f32,
+    /// Distance below the ledge while hanging.
+    pub hang_depth: f32,
+}
+
+impl Default for LedgeGrab {
+    fn default() -> Self {
+        Self {
+            reach: 0.8,
+            max_vault_height: 1.2,
+            max_climb_height: 2.5,
+            vault_distance: 1.0,
+            vault_duration: 0.4,
+            climb_duration: 0.6,
+            hang_distance: 0.4,
+            hang_depth: 1.4,
+        }
+    }
+}
+
+/// The ledge currently in front of the character.
+#[derive(Component, Default, Debug)]
+pub struct DetectedLedge(pub Option<Ledge>);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ledge {
+    /// Point on top of the ledge.
+    pub point: Vec3,
+    /// Normal of the wall facing the character.
+    pub wall_normal: Dir3,
+    /// Height of the ledge from the character's feet.
+    pub height: f32,
+}
+
+/// Inserted while the character is traversing a ledge,
+/// regular movement is disabled until it's removed.
+#[derive(Component, Debug)]
+pub enum Traversal {
+    Vaulting(TraversalPath),
+    Hanging(Ledge),
+    Climbing(TraversalPath),
+}
+
+#[derive(Debug)]
+pub struct TraversalPath {
+    start: Vec3,
+    end: Vec3,
+    /// Height of the arc above the highest point.
+    arc_height: f32,
+    timer: Timer,
+}
+
+impl TraversalPath {
+    pub fn new(
+        start: Vec3,
+        end: Vec3,
+        arc_height: f32,
+        duration: f32,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            arc_height,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+
+    /// Position along the path at the current time.
+    fn sample(&self) -> Vec3 {
+        let t = self.timer.fraction();
+        // Rise first then move forward.
+        let rise = (t * 2.0).min(1.0);
+        let base_y = self.start.y.max(self.end.y);
+
+        let mut position = self.start.lerp(self.end, t);
+        position.y = self.start.y.lerp(base_y, rise)
+            + self.arc_height * (t * core::f32::consts::PI).sin();
+        if t >= 1.0 {
+            position.y = self.end.y;
+        }
+
+        position
+    }
+}
@@ -0,0 +1,485 @@
+//! In-game dev console, toggled with [`CONSOLE_KEY`].
+//!
+//! Other plugins can add their own commands:
+//!
+//! ```ignore
+//! app.add_console_command(
+//!     "heal",
+//!     "heal <amount>: Heal every tower.",
+//!     |In(args): In<ConsoleArgs>, mut q_healths: Query<&mut Health>| {
+//!         let amount = args.parse::<f32>(0, "amount")?;
+//!         // ...
+//!         Ok(format!("Healed by {amount}."))
+//!     },
+//! );
+//! ```
+
+use core::fmt::Display;
+use core::str::FromStr;
+use std::collections::BTreeMap;
+
+use avian3d::prelude::*;
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContextPass, EguiContexts};
+use bevy_inspector_egui::egui;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{InputLock, InputLocks, PlayerAction};
+use crate::asset_pipeline::{CurrentScene, PrefabAssets, PrefabId};
+use crate::character_controller::CharacterController;
+use crate::debug_flags::DebugFlags;
+use crate::enemy::spawner::{EnemySpawner, SpawnWave};
+use crate::inventory::Inventory;
+use crate::inventory::item::{ItemMetaAsset, ItemRegistry};
+use crate::machine::recipe::RecipeMetaAsset;
+use crate::player::{PlayerType, QueryPlayers};
+use crate::tuning::Tuning;
+
+/// Key to toggle the console (`~` on most layouts).
+const CONSOLE_KEY: KeyCode = KeyCode::Backquote;
+/// Number of log lines kept.
+const MAX_LOG_LINES: usize = 200;
+
+pub(super) struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .add_systems(
+                Update,
+                (toggle_console, run_console_commands),
+            )
+            .add_systems(EguiContextPass, console_panel);
+
+        app.add_console_command(
+            "help",
+            "help: List every command.",
+            help,
+        )
+        .add_console_command("clear", "clear: Clear the log.", clear)
+        .add_console_command(
+            "spawn_enemy",
+            "spawn_enemy [count]: Spawn enemies at the spawner.",
+            spawn_enemy,
+        )
+        .add_console_command(
+            "give",
+            "give <item> [quantity] [a|b]: Give items to a player.",
+            give,
+        )
+        .add_console_command(
+            "wave",
+            "wave <1|2|3>: Skip to the wave.",
+            set_wave,
+        )
+        .add_console_command(
+            "god",
+            "god: Toggle losing marks to enemies.",
+            toggle_god_mode,
+        )
+        .add_console_command(
+            "tp",
+            "tp <x> <y> <z> [a|b]: Teleport a player.",
+            teleport,
+        )
+        .add_console_command(
+            "reload",
+            "reload: Reload the item, recipe and tuning registries.",
+            reload_registries,
+        );
+    }
+}
+
+/// Toggle the console, pausing the player actions while typing.
+fn toggle_console(
+    mut console: ResMut<Console>,
+    mut q_actions: Query<(
+        &mut ActionState<PlayerAction>,
+        &mut InputLocks,
+    )>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+) {
+    if kbd_inputs.just_pressed(CONSOLE_KEY) == false {
+        return;
+    }
+
+    console.open = !console.open;
+    console.focus_input = console.open;
+
+    for (mut action, mut locks) in q_actions.iter_mut() {
+        match console.open {
+            true => locks.lock(InputLock::Console, &mut action),
+            false => locks.release(InputLock::Console, &mut action),
+        }
+    }
+}
+
+fn console_panel(
+    mut contexts: EguiContexts,
+    mut console: ResMut<Console>,
+) {
+    if console.open == false {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let console = console.bypass_change_detection();
+
+    egui::Window::new("Console").default_width(480.0).show(
+        ctx,
+        |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in console.log.iter() {
+                        ui.monospace(line);
+                    }
+                });
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .desired_width(f32::INFINITY)
+                    .font(egui::TextStyle::Monospace),
+            );
+            // The toggle key shouldn't end up in the input.
+            console.input.retain(|c| c != '`' && c != '~');
+
+            if response.lost_focus()
+                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+            {
+                let line = core::mem::take(&mut console.input);
+                if line.trim().is_empty() == false {
+                    console.submitted.push(line);
+                }
+                // Keep typing the next command.
+                console.focus_input = true;
+            }
+
+            // Only grab the focus when needed, so that other
+            // widgets can still be clicked.
+            if console.focus_input {
+                response.request_focus();
+                console.focus_input = false;
+            }
+        },
+    );
+}
+
+/// Run the submitted lines and log their output.
+fn run_console_commands(world: &mut World) {
+    let lines = core::mem::take(
+        &mut world.resource_mut::<Console>().submitted,
+    );
+
+    for line in lines {
+        let output = run_command(world, &line);
+
+        let mut console = world.resource_mut::<Console>();
+        console.push(format!("> {line}"));
+        match output {
+            Ok(message) => {
+                for message in message.lines() {
+                    console.push(message.to_string());
+                }
+            }
+            Err(err) => console.push(format!("Error: {err}")),
+        }
+    }
+}
+
+fn run_command(world: &mut World, line: &str) -> Result<String> {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Ok(String::new());
+    };
+
+    let system = world
+        .resource::<ConsoleCommands>()
+        .get(name)
+        .ok_or(format!("Unknown command `{name}`, try `help`."))?
+        .system;
+
+    world.run_system_with(
+        system,
+        ConsoleArgs(words.map(String::from).collect()),
+    )?
+}
+
+fn help(
+    _: In<ConsoleArgs>,
+    console_commands: Res<ConsoleCommands>,
+) -> Result<String> {
+    Ok(console_commands
+        .values()
+        .map(|c| c.help)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn clear(
+    _: In<ConsoleArgs>,
+    mut console: ResMut<Console>,
+) -> Result<String> {
+    console.log.clear();
+    Ok(String::new())
+}
+
+fn spawn_enemy(
+    In(args): In<ConsoleArgs>,
+    mut commands: Commands,
+    q_spawner: Query<&GlobalTransform, With<EnemySpawner>>,
+    current_scene: Res<CurrentScene>,
+    prefabs: Res<PrefabAssets>,
+    gltfs: Res<Assets<Gltf>>,
+) -> Result<String> {
+    let count = args.parse_or(0, "count", 1u32)?;
+    let transform = q_spawner.iter().next().ok_or("No spawner!")?;
+    let current_scene =
+        current_scene.get().ok_or("No level is loaded!")?;
+
+    let scene = prefabs
+        .get_gltf(PrefabId::MouseA.into(), &gltfs)
+        .ok_or("Can't find mouse prefab!")?
+        .default_scene
+        .clone()
+        .ok_or("Mouse prefab should have a default scene.")?;
+
+    for _ in 0..count {
+        commands.spawn((
+            SceneRoot(scene.clone()),
+            transform.compute_transform(),
+            ChildOf(current_scene),
+        ));
+    }
+
+    Ok(format!("Spawned {count} enemies."))
+}
+
+fn give(
+    In(args): In<ConsoleArgs>,
+    mut q_inventories: QueryPlayers<&mut Inventory>,
+    item_registry: ItemRegistry,
+) -> Result<String> {
+    let item_id = args.parse::<String>(0, "item")?;
+    let quantity = args.parse_or(1, "quantity", 1u32)?;
+    let player_type = args.parse_player(2)?;
+
+    let item_meta = item_registry
+        .get_item(&item_id)
+        .ok_or(format!("Unknown item `{item_id}`!"))?;
+    let mut inventory = q_inventories
+        .get_mut(player_type)
+        .map_err(|_| format!("{player_type:?} has no inventory!"))?;
+
+    if inventory.add(&item_id, item_meta, quantity) == false {
+        return Err("Not enough space in the inventory!".into());
+    }
+
+    Ok(format!("Gave {quantity} {item_id} to {player_type:?}."))
+}
+
+fn set_wave(
+    In(args): In<ConsoleArgs>,
+    spawn_wave: Option<Res<State<SpawnWave>>>,
+    mut next_wave: ResMut<NextState<SpawnWave>>,
+) -> Result<String> {
+    if spawn_wave.is_none() {
+        return Err("Not in a level!".into());
+    }
+
+    let wave = match args.parse::<u32>(0, "wave")? {
+        1 => SpawnWave::One,
+        2 => SpawnWave::Two,
+        3 => SpawnWave::Three,
+        wave => {
+            return Err(format!("There is no wave {wave}!").into());
+        }
+    };
+
+    next_wave.set(wave);
+    Ok(format!("Skipping to {wave:?}."))
+}
+
+fn toggle_god_mode(
+    _: In<ConsoleArgs>,
+    mut flags: ResMut<DebugFlags>,
+) -> Result<String> {
+    flags.god_mode = !flags.god_mode;
+
+    Ok(match flags.god_mode {
+        true => "God mode on.".to_string(),
+        false => "God mode off.".to_string(),
+    })
+}
+
+fn teleport(
+    In(args): In<ConsoleArgs>,
+    mut q_players: QueryPlayers<
+        (&mut Position, &mut LinearVelocity),
+        With<CharacterController>,
+    >,
+) -> Result<String> {
+    let target = Vec3::new(
+        args.parse(0, "x")?,
+        args.parse(1, "y")?,
+        args.parse(2, "z")?,
+    );
+    let player_type = args.parse_player(3)?;
+
+    let (mut position, mut linear_velocity) = q_players
+        .get_mut(player_type)
+        .map_err(|_| format!("{player_type:?} is not spawned!"))?;
+
+    position.0 = target;
+    linear_velocity.0 = Vec3::ZERO;
+
+    Ok(format!("Teleported {player_type:?} to {target}."))
+}
+
+/// Reload every loaded registry asset from disk.
+fn reload_registries(
+    _: In<ConsoleArgs>,
+    asset_server: Res<AssetServer>,
+    items: Res<Assets<ItemMetaAsset>>,
+    recipes: Res<Assets<RecipeMetaAsset>>,
+    tunings: Res<Assets<Tuning>>,
+) -> Result<String> {
+    let ids = items
+        .ids()
+        .map(|id| id.untyped())
+        .chain(recipes.ids().map(|id| id.untyped()))
+        .chain(tunings.ids().map(|id| id.untyped()));
+
+    let mut count = 0;
+    for path in ids.filter_map(|id| asset_server.get_path(id)) {
+        asset_server.reload(path.into_owned());
+        count += 1;
+    }
+
+    Ok(format!("Reloading {count} registries."))
+}
+
+/// Extension for registering [`ConsoleCommands`].
+pub trait ConsoleCommandAppExt {
+    /// Register a one-shot system as a console command.
+    ///
+    /// The system receives the words after the command name and
+    /// its output is logged to the console.
+    fn add_console_command<M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<ConsoleArgs>, Result<String>, M>
+        + 'static,
+    ) -> &mut Self;
+}
+
+impl ConsoleCommandAppExt for App {
+    fn add_console_command<M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<ConsoleArgs>, Result<String>, M>
+        + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+
+        self.world_mut()
+            .get_resource_or_init::<ConsoleCommands>()
+            .insert(name, ConsoleCommand { help, system });
+
+        self
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+struct Console {
+    open: bool,
+    /// Focus the input on the next frame.
+    focus_input: bool,
+    input: String,
+    /// Lines waiting to be run.
+    submitted: Vec<String>,
+    log: Vec<String>,
+}
+
+impl Console {
+    fn push(&mut self, line: String) {
+        self.log.push(line);
+
+        let overflow = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..overflow);
+    }
+}
+
+/// Every registered command by name.
+#[derive(Resource, Deref, DerefMut, Default, Debug)]
+pub struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+#[derive(Debug)]
+pub struct ConsoleCommand {
+    /// Usage and description of the command.
+    pub help: &'static str,
+    pub system: SystemId<In<ConsoleArgs>, Result<String>>,
+}
+
+/// The words following the command name.
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleArgs(Vec<String>);
+
+impl ConsoleArgs {
+    /// Parse the argument at `index`, `name` is used for
+    /// the error message.
+    pub fn parse<T>(&self, index: usize, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let arg =
+            self.0.get(index).ok_or(format!("Missing <{name}>."))?;
+
+        match arg.parse() {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                Err(format!("Invalid <{name}> `{arg}`: {err}").into())
+            }
+        }
+    }
+
+    /// Like [`Self::parse`] but falls back to `default` if the
+    /// argument is missing.
+    pub fn parse_or<T>(
+        &self,
+        index: usize,
+        name: &str,
+        default: T,
+    ) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.0.get(index) {
+            Some(_) => self.parse(index, name),
+            None => Ok(default),
+        }
+    }
+
+    /// Parse `a` or `b` at `index`, defaulting to [`PlayerType::A`].
+    pub fn parse_player(&self, index: usize) -> Result<PlayerType> {
+        match self.0.get(index).map(|arg| arg.to_lowercase()) {
+            None => Ok(PlayerType::A),
+            Some(arg) if arg == "a" => Ok(PlayerType::A),
+            Some(arg) if arg == "b" => Ok(PlayerType::B),
+            Some(arg) => {
+                Err(format!("Invalid player `{arg}`, use a or b.")
+                    .into())
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! All damage is dealt by sending a [`DamageEvent`], which
+//! follows the [`DamageRules`] of who can hurt who.
+
+use avian3d::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::death::Dying;
+use crate::physics::GameLayer;
+use crate::tower::tower_attack::{Health, Invulnerable};
+
+pub(super) struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_systems(Update, apply_damage);
+
+        app.init_resource::<DamageRules>()
+            .register_type::<DamageRules>()
+            .register_type::<Armor>();
+    }
+}
+
+/// Who can hurt who, and by how much.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct DamageRules {
+    /// Player attacks hurt towers.
+    pub players_hit_towers: bool,
+    /// Player attacks hurt the other player.
+    pub players_hit_players: bool,
+    /// Area damage from enemies hurts other enemies.
+    pub enemy_area_hits_enemies: bool,
+    /// Damage multipliers of (source, target) layer pairs, pairs
+    /// that are not listed deal full damage.
+    pub multipliers: HashMap<(GameLayer, GameLayer), f32>,
+}
+
+impl DamageRules {
+    /// Multiplier of the damage from `source` to `target`,
+    /// [`None`] if it's not allowed at all.
+    pub fn multiplier(
+        &self,
+        source: GameLayer,
+        target: GameLayer,
+        area: bool,
+    ) -> Option<f32> {
+        let allowed = match (source, target) {
+            (GameLayer::Player, GameLayer::Tower) => {
+                self.players_hit_towers
+            }
+            (GameLayer::Player, GameLayer::Player) => {
+                self.players_hit_players
+            }
+            (GameLayer::Enemy, GameLayer::Enemy) => {
+                area && self.enemy_area_hits_enemies
+            }
+            _ => true,
+        };
+
+        if allowed == false {
+            return None;
+        }
+
+        Some(
+            self.multipliers
+                .get(&(source, target))
+                .copied()
+                .unwrap_or(1.0),
+        )
+    }
+
+    /// Layers that projectiles fired from `source` collide with.
+    pub fn projectile_filters(&self, source: GameLayer) -> LayerMask {
+        let mut filters = LayerMask::from(GameLayer::Enemy);
+
+        if source == GameLayer::Player {
+            if self.players_hit_towers {
+                filters.add(GameLayer::Tower);
+            }
+            if self.players_hit_players {
+                filters.add(GameLayer::Player);
+            }
+        }
+
+        filters
+    }
+}
+
+/// Deal damage to the target, processed by [`apply_damage`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    /// Whatever dealt the damage.
+    pub source: Entity,
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    /// Velocity the target gets knocked back with, zero for none.
+    pub knockback: Vec3,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageKind {
+    Projectile,
+    Melee,
+    /// Damage over an area.
+    Blast,
+}
+
+impl DamageKind {
+    pub fn is_area(&self) -> bool {
+        *self == Self::Blast
+    }
+}
+
+/// Triggered on the target after it took damage, for on-hit
+/// effects.
+#[derive(Event, Debug)]
+pub struct Hit {
+    pub source: Entity,
+    /// Damage dealt after the [`DamageRules`] and [`Armor`].
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub knockback: Vec3,
+}
+
+/// Triggered on the target when its [`Health`] runs out, right
+/// before it starts [`Dying`].
+#[derive(Event, Debug)]
+pub struct Killed {
+    pub source: Entity,
+    pub kind: DamageKind,
+}
+
+/// Fraction of the damage absorbed, from 0 to 1.
+#[derive(Component, Reflect, Deref, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct Armor(pub f32);
+
+/// The single place where [`Health`] is taken away.
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut q_healths: Query<
+        (&mut Health, Option<&Armor>),
+        Without<Invulnerable>,
+    >,
+    q_layers: Query<&CollisionLayers>,
+    q_child_ofs: Query<&ChildOf>,
+    rules: Res<DamageRules>,
+) {
+    for event in damage_events.read() {
+        let Some(multiplier) = rules.multiplier(
+            layer_of(event.source, &q_layers, &q_child_ofs),
+            layer_of(event.target, &q_layers, &q_child_ofs),
+            event.kind.is_area(),
+        ) else {
+            continue;
+        };
+
+        let Ok((mut health, armor)) = q_healths.get_mut(event.target)
+        else {
+            continue;
+        };
+
+        // Already killed, waiting to be despawned.
+        if health.0 <= 0.0 {
+            continue;
+        }
+
+        let absorbed = armor.map_or(0.0, |a| a.0.clamp(0.0, 1.0));
+        let amount = event.amount * multiplier * (1.0 - absorbed);
+        health.0 -= amount;
+
+        commands.trigger_targets(
+            Hit {
+                source: event.source,
+                amount,
+                kind: event.kind,
+                knockback: event.knockback,
+            },
+            event.target,
+        );
+
+        if health.0 <= 0.0 {
+            commands.trigger_targets(
+                Killed {
+                    source: event.source,
+                    kind: event.kind,
+                },
+                event.target,
+            );
+            commands.entity(event.target).insert(Dying::default());
+        }
+    }
+}
+
+/// The [`GameLayer`] of the entity, the layers might be on one of
+/// its ancestors.
+fn layer_of(
+    entity: Entity,
+    q_layers: &Query<&CollisionLayers>,
+    q_child_ofs: &Query<&ChildOf>,
+) -> GameLayer {
+    let Some(layers) = core::iter::once(entity)
+        .chain(q_child_ofs.iter_ancestors(entity))
+        .find_map(|e| q_layers.get(e).ok())
+    else {
+        return GameLayer::Default;
+    };
+
+    [GameLayer::Player, GameLayer::Enemy, GameLayer::Tower]
+        .into_iter()
+        .find(|layer| layers.memberships.has_all(*layer))
+        .unwrap_or_default()
+}
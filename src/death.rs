@@ -0,0 +1,137 @@
+//! Killed entities are [`Dying`] for a while before they get
+//! despawned, giving them time to play out their death.
+//!
+//! Bodies either freeze in place or, if the [`GraphicsPreset`]
+//! allows it, ragdoll by tumbling over with physics.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::enemy::{Enemy, IsEnemy};
+use crate::performance::GraphicsPreset;
+use crate::physics::GameLayer;
+use crate::tower::tower_attack::Tower;
+
+/// Seconds from being killed until despawning.
+const DYING_DURATION: f32 = 1.5;
+/// Fraction of the [`DYING_DURATION`] after which the corpse
+/// starts shrinking away.
+const SHRINK_START: f32 = 0.6;
+/// Upward speed ragdolls are launched with.
+const RAGDOLL_LIFT: f32 = 3.0;
+/// Horizontal speed ragdolls are pushed away with.
+const RAGDOLL_PUSH: f32 = 2.0;
+/// Angular speed ragdolls tumble over with.
+const RAGDOLL_SPIN: f32 = 6.0;
+
+pub(super) struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, tick_dying).add_observer(on_dying);
+    }
+}
+
+/// Corpses no longer get controlled by anything, they either
+/// ragdoll or stop colliding and moving altogether.
+fn on_dying(
+    trigger: Trigger<OnAdd, Dying>,
+    mut commands: Commands,
+    q_bodies: Query<
+        (&LinearVelocity, &GlobalTransform),
+        With<RigidBody>,
+    >,
+    q_children: Query<&Children>,
+    preset: Res<GraphicsPreset>,
+) {
+    let entity = trigger.target();
+
+    // Corpses are no longer aimed at, the tag was propagated
+    // down to the colliders.
+    for descendant in q_children.iter_descendants(entity) {
+        commands.entity(descendant).remove::<IsEnemy>();
+    }
+
+    let mut entity_cmd = commands.entity(entity);
+    entity_cmd.remove::<(Enemy, IsEnemy, Tower)>();
+
+    if preset.ragdolls() {
+        if let Ok((velocity, global_transform)) = q_bodies.get(entity)
+        {
+            // Keep flying in the direction it was knocked back to,
+            // or fall over backwards.
+            let push = velocity
+                .with_y(0.0)
+                .try_normalize()
+                .unwrap_or(global_transform.back().as_vec3());
+
+            entity_cmd.insert((
+                RigidBody::Dynamic,
+                CollisionLayers::new(
+                    GameLayer::Corpse,
+                    GameLayer::Default,
+                ),
+                LinearVelocity(
+                    push * RAGDOLL_PUSH + Vec3::Y * RAGDOLL_LIFT,
+                ),
+                // Tip over towards the push.
+                AngularVelocity(Vec3::Y.cross(push) * RAGDOLL_SPIN),
+            ));
+            return;
+        }
+    }
+
+    entity_cmd.insert((CollisionLayers::NONE, RigidBodyDisabled));
+}
+
+/// Shrink the corpses away and despawn them once the time is up.
+fn tick_dying(
+    mut commands: Commands,
+    mut q_dying: Query<(&mut Dying, &mut Transform, Entity)>,
+    q_is_dying: Query<(), With<Dying>>,
+    q_child_ofs: Query<&ChildOf>,
+    time: Res<Time>,
+) {
+    for (mut dying, mut transform, entity) in q_dying.iter_mut() {
+        if dying.timer.tick(time.delta()).finished() {
+            // Might be despawned along with its parent already.
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+
+        // Already shrinking along with a dying ancestor.
+        if q_child_ofs
+            .iter_ancestors(entity)
+            .any(|e| q_is_dying.contains(e))
+        {
+            continue;
+        }
+
+        let scale = *dying.scale.get_or_insert(transform.scale);
+        let shrink = ((dying.timer.fraction() - SHRINK_START)
+            / (1.0 - SHRINK_START))
+            .clamp(0.0, 1.0);
+        transform.scale = scale * (1.0 - shrink).max(0.01);
+    }
+}
+
+/// Inserted when an entity gets killed, despawns it after the
+/// [`DYING_DURATION`].
+#[derive(Component, Debug)]
+pub struct Dying {
+    timer: Timer,
+    /// Scale before shrinking away.
+    scale: Option<Vec3>,
+}
+
+impl Default for Dying {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(
+                DYING_DURATION,
+                TimerMode::Once,
+            ),
+            scale: None,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Cheats for playtesting specific scenarios.
+//!
+//! The [`DebugFlags`] resource only exists in dev builds, so every
+//! flag reads as disabled otherwise.
+
+use bevy::prelude::*;
+
+#[cfg(feature = "dev")]
+pub(super) struct DebugFlagsPlugin;
+
+#[cfg(feature = "dev")]
+impl Plugin for DebugFlagsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugFlags>()
+            .add_systems(
+                bevy_inspector_egui::bevy_egui::EguiContextPass,
+                debug_flags_panel,
+            )
+            .add_systems(
+                Update,
+                sync_hitboxes.run_if(resource_changed::<DebugFlags>),
+            );
+
+        app.register_type::<DebugFlags>();
+    }
+}
+
+#[cfg(feature = "dev")]
+fn debug_flags_panel(
+    mut contexts: bevy_inspector_egui::bevy_egui::EguiContexts,
+    mut flags: ResMut<DebugFlags>,
+) {
+    use bevy_inspector_egui::egui;
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Only trigger change detection on actual changes.
+    let mut edited = flags.clone();
+
+    egui::Window::new("Debug Flags").default_open(false).show(
+        ctx,
+        |ui| {
+            ui.checkbox(&mut edited.god_mode, "God mode");
+            ui.checkbox(
+                &mut edited.infinite_ingredients,
+                "Infinite ingredients",
+            );
+            ui.checkbox(&mut edited.instant_cook, "Instant cook");
+            ui.checkbox(&mut edited.freeze_waves, "Freeze waves");
+            ui.checkbox(&mut edited.show_hitboxes, "Show hitboxes");
+        },
+    );
+
+    flags.set_if_neq(edited);
+}
+
+#[cfg(feature = "dev")]
+fn sync_hitboxes(
+    flags: Res<DebugFlags>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    config_store
+        .config_mut::<avian3d::prelude::PhysicsGizmos>()
+        .0
+        .enabled = flags.show_hitboxes;
+}
+
+/// Run condition that checks a [`DebugFlags`] flag.
+///
+/// ```ignore
+/// spawn_enemy.run_if(not(debug_flag(|f| f.freeze_waves)))
+/// ```
+pub fn debug_flag(
+    flag: fn(&DebugFlags) -> bool,
+) -> impl Fn(Option<Res<DebugFlags>>) -> bool + Clone {
+    move |flags: Option<Res<DebugFlags>>| {
+        flags.is_some_and(|f| flag(&f))
+    }
+}
+
+#[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct DebugFlags {
+    /// Enemies reaching the final target leave without eating
+    /// away at it.
+    pub god_mode: bool,
+    /// Cook recipes without having or using the ingredients
+    /// and fuel.
+    pub infinite_ingredients: bool,
+    /// Finish cooking as soon as it starts.
+    pub instant_cook: bool,
+    /// Pause the wave countdown and enemy spawning.
+    pub freeze_waves: bool,
+    /// Draw the physics colliders.
+    pub show_hitboxes: bool,
+}
+
+impl Default for DebugFlags {
+    fn default() -> Self {
+        Self {
+            god_mode: false,
+            infinite_ingredients: false,
+            instant_cook: false,
+            freeze_waves: false,
+            // Colliders were always drawn in dev builds.
+            show_hitboxes: true,
+        }
+    }
+}
@@ -0,0 +1,477 @@
+//! Purely cosmetic decorations bought with leftover corn.
+//!
+//! Decorations can only be placed on cells of the tile grid that are
+//! not a [`Tile`](crate::tile::Tile), so they never get in the way of
+//! towers or enemies. The layout of each level is kept in the
+//! [`DecorationProfile`] and restored when the level is loaded again.
+
+use avian3d::prelude::*;
+use bevy::color::palettes::tailwind::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::asset_pipeline::{AssetState, CurrentScene};
+use crate::character_controller::CharacterController;
+use crate::inventory::Inventory;
+use crate::physics::GameLayer;
+use crate::player::{PlayerType, QueryPlayers};
+use crate::storage::Profile;
+use crate::tile::TileMap;
+use crate::tower::{InPlacementMode, Preview};
+use crate::ui::toast_ui::Toast;
+
+/// Item spent on decorations.
+const CURRENCY: &str = "corn";
+
+pub(super) struct DecorationPlugin;
+
+impl Plugin for DecorationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DecorationProfile::load())
+            .add_systems(Startup, setup_decoration_assets)
+            .add_systems(
+                Update,
+                (toggle_decoration_mode, place_decoration)
+                    .chain()
+                    .run_if(in_state(AssetState::Loaded)),
+            )
+            .add_observer(restore_decorations);
+    }
+}
+
+fn setup_decoration_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut material = |color: Srgba| {
+        materials.add(StandardMaterial {
+            base_color: color.into(),
+            perceptual_roughness: 0.8,
+            ..default()
+        })
+    };
+
+    let pole = material(AMBER_900);
+    let flags =
+        [material(RED_400), material(AMBER_300), material(SKY_400)];
+    let pot = material(ORANGE_700);
+    let foliage = material(GREEN_500);
+
+    let pole_mesh = meshes.add(Cylinder::new(0.04, 1.6));
+    let string_mesh = meshes.add(Cuboid::new(1.6, 0.02, 0.02));
+    let flag_mesh = meshes.add(Cone::new(0.12, 0.25));
+    let pot_mesh = meshes.add(Cylinder::new(0.25, 0.35));
+    let foliage_mesh = meshes.add(Sphere::new(0.3));
+
+    let mut bunting = vec![
+        DecorationPart {
+            mesh: pole_mesh.clone(),
+            material: pole.clone(),
+            transform: Transform::from_xyz(-0.8, 0.8, 0.0),
+        },
+        DecorationPart {
+            mesh: pole_mesh,
+            material: pole.clone(),
+            transform: Transform::from_xyz(0.8, 0.8, 0.0),
+        },
+        DecorationPart {
+            mesh: string_mesh,
+            material: pole,
+            transform: Transform::from_xyz(0.0, 1.5, 0.0),
+        },
+    ];
+    // Hang the flags upside down along the string.
+    bunting.extend((0..4).map(|i| {
+        DecorationPart {
+            mesh: flag_mesh.clone(),
+            material: flags[i % flags.len()].clone(),
+            transform: Transform::from_xyz(
+                -0.6 + i as f32 * 0.4,
+                1.37,
+                0.0,
+            )
+            .with_rotation(Quat::from_rotation_x(
+                core::f32::consts::PI,
+            )),
+        }
+    }));
+
+    let potted_plant = vec![
+        DecorationPart {
+            mesh: pot_mesh,
+            material: pot,
+            transform: Transform::from_xyz(0.0, 0.175, 0.0),
+        },
+        DecorationPart {
+            mesh: foliage_mesh,
+            material: foliage,
+            transform: Transform::from_xyz(0.0, 0.6, 0.0),
+        },
+    ];
+
+    commands.insert_resource(DecorationAssets {
+        bunting,
+        potted_plant,
+    });
+}
+
+/// Enter decoration mode, pressing again cycles through
+/// the [`DecorationKind`]s.
+fn toggle_decoration_mode(
+    mut commands: Commands,
+    mut q_players: Query<
+        (
+            &PlayerType,
+            &TargetAction,
+            Option<&mut DecorationPlacement>,
+            Entity,
+        ),
+        (With<CharacterController>, Without<InPlacementMode>),
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_previews: QueryPlayers<&mut Visibility, With<Preview>>,
+) -> Result {
+    for (player_type, target_action, placement, entity) in
+        q_players.iter_mut()
+    {
+        let action = q_actions.get(target_action.get())?;
+
+        let kind = match placement {
+            Some(_) if action.just_pressed(&PlayerAction::Cancel) => {
+                commands
+                    .entity(entity)
+                    .remove::<DecorationPlacement>();
+                *q_previews.get_mut(*player_type)? =
+                    Visibility::Hidden;
+                continue;
+            }
+            Some(mut placement)
+                if action.just_pressed(&PlayerAction::Decorate) =>
+            {
+                placement.kind = placement.kind.next();
+                placement.kind
+            }
+            None if action.just_pressed(&PlayerAction::Decorate) => {
+                commands
+                    .entity(entity)
+                    .insert(DecorationPlacement::default());
+                DecorationKind::default()
+            }
+            _ => continue,
+        };
+
+        commands.trigger(Toast {
+            player_type: *player_type,
+            message: format!(
+                "{} ({} {CURRENCY})",
+                kind.name(),
+                kind.cost()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+fn place_decoration(
+    mut commands: Commands,
+    mut q_players: Query<
+        (
+            &GlobalTransform,
+            &PlayerType,
+            &DecorationPlacement,
+            &mut Inventory,
+            &TargetAction,
+            Entity,
+        ),
+        With<CharacterController>,
+    >,
+    q_decorations: Query<&GlobalTransform, With<Decoration>>,
+    mut q_previews: QueryPlayers<
+        (&mut Transform, &mut Visibility),
+        With<Preview>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_scene_roots: Query<&SceneRoot>,
+    tile_map: Res<TileMap>,
+    spatial_query: SpatialQuery,
+    current_scene: Res<CurrentScene>,
+    asset_server: Res<AssetServer>,
+    decoration_assets: Res<DecorationAssets>,
+    mut profile: ResMut<DecorationProfile>,
+) -> Result {
+    for (
+        global_transform,
+        player_type,
+        placement,
+        mut inventory,
+        target_action,
+        player_entity,
+    ) in q_players.iter_mut()
+    {
+        let (mut preview_transform, mut preview_viz) =
+            q_previews.get_mut(*player_type)?;
+
+        let Some(translation) = free_decoration_spot(
+            global_transform,
+            &q_decorations,
+            &tile_map,
+            &spatial_query,
+        ) else {
+            *preview_viz = Visibility::Hidden;
+            continue;
+        };
+
+        preview_transform.translation = translation + Vec3::Y * 0.25;
+        *preview_viz = Visibility::Inherited;
+
+        if q_actions
+            .get(target_action.get())?
+            .just_pressed(&PlayerAction::Placement)
+            == false
+        {
+            continue;
+        }
+
+        let kind = placement.kind;
+        if inventory.remove(CURRENCY, kind.cost()) == false {
+            commands.trigger(Toast {
+                player_type: *player_type,
+                message: format!(
+                    "Need {} {CURRENCY} for a {}",
+                    kind.cost(),
+                    kind.name()
+                ),
+            });
+            continue;
+        }
+
+        let scene = current_scene.get().ok_or("No level loaded.")?;
+        let decoration = PlacedDecoration {
+            kind,
+            translation: translation.to_array(),
+            // Same heading as the player.
+            yaw: global_transform
+                .rotation()
+                .to_euler(EulerRot::YXZ)
+                .0,
+        };
+
+        decoration.spawn(&mut commands, &decoration_assets, scene);
+
+        if let Some(level) =
+            level_key(scene, &q_scene_roots, &asset_server)
+        {
+            profile.levels.entry(level).or_default().push(decoration);
+            profile.save();
+        }
+
+        commands
+            .entity(player_entity)
+            .remove::<DecorationPlacement>();
+        *preview_viz = Visibility::Hidden;
+    }
+
+    Ok(())
+}
+
+/// Find the ground of the grid cell in front of the player,
+/// only cells without a tile and decoration are free.
+fn free_decoration_spot(
+    global_transform: &GlobalTransform,
+    q_decorations: &Query<&GlobalTransform, With<Decoration>>,
+    tile_map: &TileMap,
+    spatial_query: &SpatialQuery,
+) -> Option<Vec3> {
+    let front = global_transform.translation()
+        + global_transform.forward() * 2.0;
+
+    let coordinate =
+        tile_map.translation_to_tile_coord(&front)?.as_ivec3();
+    if tile_map.get_tile(&coordinate).is_some() {
+        return None;
+    }
+
+    let center = tile_map.tile_coord_to_world_space(&coordinate);
+    let origin = Vec3::new(center.x, front.y + 2.0, center.z);
+
+    let hit = spatial_query.cast_ray(
+        origin,
+        Dir3::NEG_Y,
+        6.0,
+        true,
+        &SpatialQueryFilter::from_mask(GameLayer::Default),
+    )?;
+
+    let translation = origin + Vec3::NEG_Y * hit.distance;
+    let tile_size = tile_map.config().tile_size;
+
+    q_decorations
+        .iter()
+        .all(|t| {
+            t.translation().xz().distance(translation.xz())
+                > tile_size * 0.5
+        })
+        .then_some(translation)
+}
+
+/// Spawn the saved decorations once the level is loaded.
+fn restore_decorations(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    q_scene_roots: Query<&SceneRoot>,
+    current_scene: Res<CurrentScene>,
+    asset_server: Res<AssetServer>,
+    decoration_assets: Res<DecorationAssets>,
+    profile: Res<DecorationProfile>,
+) {
+    let scene = trigger.target();
+    if current_scene.get() != Some(scene) {
+        return;
+    }
+
+    let Some(decorations) =
+        level_key(scene, &q_scene_roots, &asset_server)
+            .and_then(|level| profile.levels.get(&level))
+    else {
+        return;
+    };
+
+    for decoration in decorations.iter() {
+        decoration.spawn(&mut commands, &decoration_assets, scene);
+    }
+}
+
+/// Asset path of the level scene, used to tell levels apart.
+fn level_key(
+    scene: Entity,
+    q_scene_roots: &Query<&SceneRoot>,
+    asset_server: &AssetServer,
+) -> Option<String> {
+    let scene_root = q_scene_roots.get(scene).ok()?;
+    asset_server
+        .get_path(scene_root.id())
+        .map(|p| p.to_string())
+}
+
+/// Attached to players that are placing a decoration.
+#[derive(Component, Default, Debug)]
+pub struct DecorationPlacement {
+    pub kind: DecorationKind,
+}
+
+/// A placed decoration.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Decoration(pub DecorationKind);
+
+#[derive(
+    Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum DecorationKind {
+    #[default]
+    Bunting,
+    PottedPlant,
+}
+
+impl DecorationKind {
+    pub fn next(self) -> Self {
+        match self {
+            DecorationKind::Bunting => DecorationKind::PottedPlant,
+            DecorationKind::PottedPlant => DecorationKind::Bunting,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DecorationKind::Bunting => "Bunting",
+            DecorationKind::PottedPlant => "Potted plant",
+        }
+    }
+
+    /// Price in [`CURRENCY`].
+    pub fn cost(&self) -> u32 {
+        match self {
+            DecorationKind::Bunting => 5,
+            DecorationKind::PottedPlant => 3,
+        }
+    }
+}
+
+/// Decorations placed in each level, keyed by the level's
+/// scene path.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct DecorationProfile {
+    levels: HashMap<String, Vec<PlacedDecoration>>,
+}
+
+impl Profile for DecorationProfile {
+    const KEY: &'static str = "decorations.profile.ron";
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct PlacedDecoration {
+    kind: DecorationKind,
+    translation: [f32; 3],
+    /// Rotation around the y axis.
+    yaw: f32,
+}
+
+impl PlacedDecoration {
+    fn spawn(
+        &self,
+        commands: &mut Commands,
+        assets: &DecorationAssets,
+        scene: Entity,
+    ) {
+        let parts = assets
+            .parts(self.kind)
+            .iter()
+            .map(|part| {
+                (
+                    Mesh3d(part.mesh.clone()),
+                    MeshMaterial3d(part.material.clone()),
+                    part.transform,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        commands.spawn((
+            Name::new(self.kind.name()),
+            Decoration(self.kind),
+            Transform::from_translation(Vec3::from_array(
+                self.translation,
+            ))
+            .with_rotation(Quat::from_rotation_y(self.yaw)),
+            Visibility::Inherited,
+            ChildOf(scene),
+            Children::spawn(SpawnIter(parts.into_iter())),
+        ));
+    }
+}
+
+#[derive(Resource)]
+struct DecorationAssets {
+    bunting: Vec<DecorationPart>,
+    potted_plant: Vec<DecorationPart>,
+}
+
+impl DecorationAssets {
+    fn parts(&self, kind: DecorationKind) -> &[DecorationPart] {
+        match kind {
+            DecorationKind::Bunting => &self.bunting,
+            DecorationKind::PottedPlant => &self.potted_plant,
+        }
+    }
+}
+
+struct DecorationPart {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    transform: Transform,
+}
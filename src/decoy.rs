@@ -0,0 +1,145 @@
+use avian3d::prelude::*;
+use bevy::color::palettes::tailwind::AMBER_700;
+use bevy::prelude::*;
+
+use crate::asset_pipeline::CurrentScene;
+use crate::enemy::aggro::Threat;
+use crate::interaction::Interactable;
+use crate::interaction::grab::{GrabState, Grabbable, ReleaseEvent};
+use crate::machine::CarryableCrafted;
+use crate::tower::tower_attack::MaxHealth;
+
+/// Item id of the decoy in the item registry.
+const DECOY_ITEM_ID: &str = "gingerbread_man";
+const DECOY_RADIUS: f32 = 0.3;
+const DECOY_LENGTH: f32 = 0.6;
+
+/// Deployable decoys that lure enemies away from the defenses.
+pub(super) struct DecoyPlugin;
+
+impl Plugin for DecoyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, expire_decoys)
+            .add_observer(spawn_crafted_decoy)
+            .add_observer(deploy_decoy);
+
+        app.register_type::<Decoy>();
+    }
+}
+
+/// Spawn the decoy beside the machine that crafted it.
+fn spawn_crafted_decoy(
+    trigger: Trigger<CarryableCrafted>,
+    mut commands: Commands,
+    q_global_transforms: Query<&GlobalTransform>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    current_scene: Res<CurrentScene>,
+) -> Result {
+    if trigger.event().item_id != DECOY_ITEM_ID {
+        return Ok(());
+    }
+
+    let Some(current_scene) = current_scene.get() else {
+        return Ok(());
+    };
+
+    let machine_tf = q_global_transforms.get(trigger.target())?;
+
+    commands.spawn((
+        Name::new("Decoy"),
+        Decoy::default(),
+        Transform::from_translation(
+            machine_tf.translation()
+                + machine_tf.forward() * 2.0
+                + Vec3::Y,
+        ),
+        Mesh3d(
+            meshes.add(Capsule3d::new(DECOY_RADIUS, DECOY_LENGTH)),
+        ),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: AMBER_700.into(),
+            ..default()
+        })),
+        RigidBody::Dynamic,
+        Collider::capsule(DECOY_RADIUS, DECOY_LENGTH),
+        LockedAxes::ROTATION_LOCKED,
+        ChildOf(current_scene),
+    ));
+
+    Ok(())
+}
+
+/// Deploy the decoy once it's put down by a player.
+fn deploy_decoy(
+    trigger: Trigger<ReleaseEvent>,
+    mut commands: Commands,
+    q_grab_states: Query<&GrabState>,
+    q_decoys: Query<&Decoy, Without<DecoyLifetime>>,
+) {
+    let Some((decoy, entity)) = q_grab_states
+        .get(trigger.event().player)
+        .ok()
+        .and_then(|g| g.held)
+        .and_then(|e| Some((q_decoys.get(e).ok()?, e)))
+    else {
+        return;
+    };
+
+    commands
+        .entity(entity)
+        .insert((
+            Threat {
+                radius: decoy.radius,
+                // Always more attractive than the other threats.
+                priority: i32::MAX,
+            },
+            MaxHealth(decoy.health),
+            DecoyLifetime(Timer::from_seconds(
+                decoy.lifetime,
+                TimerMode::Once,
+            )),
+        ))
+        // Deployed decoys stay where they are.
+        .remove::<Grabbable>();
+}
+
+/// Despawn deployed decoys when their lifetime runs out.
+fn expire_decoys(
+    mut commands: Commands,
+    mut q_decoys: Query<(&mut DecoyLifetime, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut lifetime, entity) in q_decoys.iter_mut() {
+        if lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// A carryable gingerbread man that attracts nearby enemies
+/// once deployed.
+#[derive(Component, Reflect)]
+#[require(Grabbable, Interactable)]
+#[reflect(Component, Default)]
+pub struct Decoy {
+    /// Radius within which enemies are attracted.
+    pub radius: f32,
+    pub health: f32,
+    /// Duration in seconds before the decoy crumbles.
+    pub lifetime: f32,
+}
+
+impl Default for Decoy {
+    fn default() -> Self {
+        Self {
+            radius: 10.0,
+            health: 60.0,
+            lifetime: 20.0,
+        }
+    }
+}
+
+/// Remaining lifetime of a deployed [`Decoy`].
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct DecoyLifetime(Timer);
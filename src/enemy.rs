@@ -1,62 +1,175 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
+use crate::ai::{Brain, Decision};
+use crate::asset_pipeline::animation_pipeline::{
+    AnimationEvent, AnimationEventKind,
+};
+use crate::damage::{DamageEvent, DamageKind, Hit};
+use crate::death::Dying;
+use crate::debug_flags::DebugFlags;
+use crate::environment::Environment;
 use crate::physics::GameLayer;
 use crate::player::player_attack::AttackCooldown;
-use crate::player::player_mark::PlayerMark;
+use crate::squash_stretch::{SquashStretch, SquashStretchState};
 use crate::tile::{PlacedBy, TileMap};
-use crate::tower::tower_attack::{Health, Tower};
+use crate::tower::tower_attack::{
+    Health, Invulnerable, MaxHealth, Tower,
+};
 use crate::ui::Screen;
 use crate::util::PropagateComponentAppExt;
+use crate::water::InWater;
+use aggro::AggroOf;
+use hibernation::Hibernating;
+use time_slice::{AiBudget, TimeSlicer};
 
+pub mod aggro;
 mod animation;
+mod behavior;
+mod crowd;
+pub mod hibernation;
+pub mod ready_up;
+pub mod spatial_grid;
 pub mod spawner;
+pub mod time_slice;
+
+/// Seconds enemies are knocked back for.
+const KNOCKBACK_DURATION: f32 = 0.2;
+/// [`MaxHealth`] of the [`FinalTarget`].
+pub const BASE_HEALTH: f32 = 100.0;
 
 pub(super) struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            aggro::AggroPlugin,
             animation::EnemyAnimationPlugin,
+            behavior::EnemyBehaviorPlugin,
+            crowd::CrowdPlugin,
+            hibernation::HibernationPlugin,
+            ready_up::ReadyUpPlugin,
+            spatial_grid::EnemyGridPlugin,
             spawner::EnemySpawnerPlugin,
+            time_slice::TimeSlicePlugin,
         ));
 
         app.propagate_component::<IsEnemy, Children>()
             .add_systems(
                 PostUpdate,
-                pathfind.after(TransformSystem::TransformPropagate),
+                (
+                    mark_stale_paths
+                        .run_if(resource_changed::<TileMap>),
+                    pathfind,
+                )
+                    .chain()
+                    .after(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                FixedUpdate,
+                (tick_knockback, enemy_movement).chain(),
             )
-            .add_systems(FixedUpdate, enemy_movement)
             .add_systems(
                 Update,
                 (
                     rotate_to_velocity,
-                    (target_reach_respond, attack_tower).chain(),
+                    (
+                        target_reach_respond,
+                        validate_target_tower,
+                        attack_tower_hibernating,
+                    )
+                        .chain(),
                 )
                     .run_if(in_state(Screen::EnterLevel)),
             )
-            .add_observer(on_path_changed);
+            .add_observer(on_path_changed)
+            .add_observer(attack_tower)
+            .add_observer(knock_back_on_hit);
 
         app.register_type::<FinalTarget>().register_type::<Enemy>();
     }
 }
 
+/// Mark every path as stale so that they get recalculated over
+/// the next few frames.
+fn mark_stale_paths(
+    mut commands: Commands,
+    q_enemies: Query<Entity, With<Path>>,
+) {
+    for entity in q_enemies.iter() {
+        commands.entity(entity).insert(StalePath);
+    }
+}
+
 fn pathfind(
     mut commands: Commands,
-    q_enemies: Query<(&Path, &GlobalTransform, Entity)>,
+    q_enemies: Query<(
+        &Path,
+        &GlobalTransform,
+        Option<&AggroOf>,
+        Option<&Decision>,
+        Has<StalePath>,
+        Entity,
+    )>,
     q_final_target: Query<&GlobalTransform, With<FinalTarget>>,
+    q_global_transforms: Query<&GlobalTransform>,
     tile_map: Res<TileMap>,
+    budget: Res<AiBudget>,
+    mut slicer: Local<TimeSlicer>,
 ) {
     let Ok(final_target) = q_final_target.single() else {
         return;
     };
 
-    for (enemy_path, transform, entity) in q_enemies.iter() {
-        // Pathfind if it's just newly added or the tile map has been updated.
-        if enemy_path.is_empty() || tile_map.is_changed() {
+    // Pathfind if it's just newly added or the tile map has been updated.
+    let enemies = q_enemies
+        .iter()
+        .filter(|(path, .., stale, _)| path.is_empty() || *stale);
+
+    slicer.run(
+        enemies,
+        budget.0,
+        |(_, transform, aggro_of, decision, _, entity)| {
+            commands.entity(entity).remove::<StalePath>();
+
             let start_translation = transform.translation();
             let end_translation = final_target.translation();
 
+            // Threats take over the final target.
+            if let Some(path_to_threat) = aggro_of
+                .and_then(|a| q_global_transforms.get(a.0).ok())
+                .and_then(|threat| {
+                    tile_map.pathfind_to(
+                        &start_translation,
+                        &threat.translation(),
+                        false,
+                    )
+                })
+            {
+                debug!("To threat: {:?}", path_to_threat);
+                commands.entity(entity).insert((
+                    Path(path_to_threat),
+                    TargetType::Threat,
+                ));
+                return;
+            }
+
+            // Head for the closest tower instead of avoiding it.
+            if decision.is_some_and(|d| d.is("siege")) {
+                if let Some(path_to_tower) = tile_map.pathfind_to(
+                    &start_translation,
+                    &end_translation,
+                    true,
+                ) {
+                    debug!("Siege: {:?}", path_to_tower);
+                    commands.entity(entity).insert((
+                        Path(path_to_tower),
+                        TargetType::Tower,
+                    ));
+                    return;
+                }
+            }
+
             debug!(
                 "pathfind: {start_translation}, {end_translation}"
             );
@@ -81,8 +194,8 @@ fn pathfind(
             } else {
                 warn!("Can't find path for enemy {entity}!");
             }
-        }
-    }
+        },
+    );
 }
 
 fn on_path_changed(
@@ -95,6 +208,49 @@ fn on_path_changed(
         .remove::<(TargetReached, TargetTower)>();
 }
 
+/// Knock the enemy back, which overrides its path following for
+/// the [`KNOCKBACK_DURATION`].
+fn knock_back_on_hit(
+    trigger: Trigger<Hit>,
+    mut commands: Commands,
+    mut q_enemies: Query<&mut LinearVelocity, With<Enemy>>,
+) {
+    let knockback = trigger.event().knockback;
+    if knockback == Vec3::ZERO {
+        return;
+    }
+
+    let Ok(mut linear_velocity) = q_enemies.get_mut(trigger.target())
+    else {
+        return;
+    };
+
+    linear_velocity.0 += knockback;
+    commands.entity(trigger.target()).insert(Knockback(
+        Timer::from_seconds(KNOCKBACK_DURATION, TimerMode::Once),
+    ));
+}
+
+/// Stop and remove [`Knockback`] once it runs out.
+fn tick_knockback(
+    mut commands: Commands,
+    mut q_knockbacks: Query<(
+        &mut Knockback,
+        &mut LinearVelocity,
+        Entity,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut knockback, mut linear_velocity, entity) in
+        q_knockbacks.iter_mut()
+    {
+        if knockback.tick(time.delta()).finished() {
+            linear_velocity.0 = Vec3::ZERO;
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
 fn enemy_movement(
     mut commands: Commands,
     mut q_enemies: Query<
@@ -104,21 +260,72 @@ fn enemy_movement(
             &mut PathIndex,
             &mut LinearVelocity,
             &Position,
+            Option<&Decision>,
+            Option<&InWater>,
             Entity,
         ),
-        Without<TargetReached>,
+        (
+            Without<TargetReached>,
+            Without<Hibernating>,
+            Without<Knockback>,
+        ),
     >,
+    tile_map: Res<TileMap>,
+    environment: Res<Environment>,
 ) {
+    /// Speed multiplier while waiting for allies.
+    const GROUP_UP_SPEED: f32 = 0.4;
+
     for (
         enemy,
         path,
         mut path_index,
         mut linear_velocity,
         position,
+        decision,
+        in_water,
         entity,
     ) in q_enemies.iter_mut()
     {
-        let Some(target_position) = path.get_target(&path_index)
+        // Wading through water slows every movement down.
+        let speed_scale = environment.enemy_speed
+            * in_water.map_or(1.0, |w| w.volume.speed_scale);
+
+        // Head back the way it came.
+        if decision.is_some_and(|d| d.is("flee")) {
+            let previous_index =
+                PathIndex(path_index.saturating_sub(1));
+            let Some(target_position) = path
+                .get_target(&previous_index, &tile_map)
+                .map(|t| t.xz())
+            else {
+                linear_velocity.0 = Vec3::ZERO;
+                continue;
+            };
+
+            let offset = target_position - position.xz();
+            if offset.length() < 0.1 {
+                // Escaped back to where it spawned, leave the wave
+                // instead of idling there forever.
+                if path_index.0 == 0 {
+                    info!("Enemy {entity} fled the level.");
+                    commands.entity(entity).despawn();
+                    continue;
+                }
+
+                *path_index = previous_index;
+            }
+
+            let target_velocity = offset.normalize_or_zero()
+                * enemy.movement_speed
+                * speed_scale;
+            linear_velocity.0 =
+                Vec3::new(target_velocity.x, 0.0, target_velocity.y);
+            continue;
+        }
+
+        let Some(target_position) =
+            path.get_target(&path_index, &tile_map).map(|t| t.xz())
         else {
             linear_velocity.0 = Vec3::ZERO;
             commands.entity(entity).insert(TargetReached);
@@ -131,9 +338,12 @@ fn enemy_movement(
             path_index.increment();
         }
 
-        let target_velocity = (target_position - current_position)
-            .normalize()
-            * enemy.movement_speed;
+        let speed = match decision.is_some_and(|d| d.is("group_up")) {
+            true => enemy.movement_speed * GROUP_UP_SPEED,
+            false => enemy.movement_speed,
+        } * speed_scale;
+        let target_velocity =
+            (target_position - current_position).normalize() * speed;
 
         linear_velocity.0 =
             Vec3::new(target_velocity.x, 0.0, target_velocity.y);
@@ -143,25 +353,55 @@ fn enemy_movement(
 fn target_reach_respond(
     mut commands: Commands,
     q_enemies: Query<
-        (&TargetType, &Path, Entity),
-        (With<TargetReached>, Without<TargetTower>),
+        (&TargetType, &Path, Option<&AggroOf>, Entity),
+        (
+            With<TargetReached>,
+            Without<TargetTower>,
+            Without<SiegingBase>,
+        ),
+    >,
+    q_final_targets: Query<
+        Entity,
+        (With<FinalTarget>, Without<Dying>),
     >,
     q_is_tower: Query<(), With<Tower>>,
     q_children: Query<&Children>,
     q_placed_by: Query<&PlacedBy>,
     tile_map: Res<TileMap>,
-    mut player_mark: ResMut<PlayerMark>,
+    debug_flags: Option<Res<DebugFlags>>,
 ) {
-    for (target_type, path, entity) in q_enemies.iter() {
+    for (target_type, path, aggro_of, entity) in q_enemies.iter() {
+        if *target_type == TargetType::Threat {
+            // Attack the threat until it's gone.
+            if let Some(threat) = aggro_of.map(|a| a.0) {
+                commands.entity(entity).try_insert(TargetTower {
+                    root: threat,
+                    target: threat,
+                });
+            }
+            continue;
+        }
+
         if *target_type != TargetType::Tower {
-            // Decrease mark.
-            player_mark.0 = player_mark.saturating_sub(1);
+            // God mode keeps the base untouched.
+            if debug_flags.is_some_and(|f| f.god_mode) {
+                commands.entity(entity).despawn();
+                continue;
+            }
 
-            info!(
-                "Enemy reached destination, mark decreased {}!",
-                player_mark.0
-            );
-            commands.entity(entity).despawn();
+            let Ok(base) = q_final_targets.single() else {
+                continue;
+            };
+
+            // Eat away at the base until it's gone.
+            info!("Enemy reached the base!");
+            commands.entity(entity).try_insert((
+                SiegingBase,
+                TargetTower {
+                    root: base,
+                    target: base,
+                },
+            ));
             continue;
         }
 
@@ -179,13 +419,12 @@ fn target_reach_respond(
             .map(move |m| tile_coord + m)
             .filter_map(|coord| {
                 // Must be a valid coordinate.
-                if TileMap::within_map_range(&coord) == false {
+                if tile_map.within_map_range(&coord) == false {
                     return None;
                 }
 
-                let index = TileMap::tile_coord_to_tile_idx(
-                    &coord.as_uvec2(),
-                );
+                let index = tile_map
+                    .tile_coord_to_tile_idx(&coord.as_uvec3());
                 let tile_meta = tile_map[index]?;
 
                 // Must be occupied for a tower to exist.
@@ -220,35 +459,114 @@ fn target_reach_respond(
     }
 }
 
-fn attack_tower(
+/// Remove [`TargetTower`] when the tower no longer exists.
+fn validate_target_tower(
     mut commands: Commands,
+    q_enemies: Query<(&TargetTower, Entity), With<TargetReached>>,
+    q_healths: Query<(), (With<Health>, Without<Dying>)>,
+) {
+    for (target_tower, entity) in q_enemies.iter() {
+        if q_healths.contains(target_tower.target) == false {
+            // No more target, find another one.
+            commands.entity(entity).remove::<TargetTower>();
+        }
+    }
+}
+
+/// Deal damage to the [`TargetTower`] on the impact frame of the
+/// attack animation, squashing the enemy.
+fn attack_tower(
+    trigger: Trigger<AnimationEvent>,
     mut q_enemies: Query<
-        (&TargetTower, &Enemy, &mut AttackCooldown, Entity),
+        (
+            &TargetTower,
+            &Enemy,
+            &mut AttackCooldown,
+            &SquashStretch,
+            &mut SquashStretchState,
+        ),
         With<TargetReached>,
     >,
-    mut q_healths: Query<&mut Health>,
+    q_invulnerables: Query<(), With<Invulnerable>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    if trigger.event().kind != AnimationEventKind::AttackImpact {
+        return;
+    }
+
+    let entity = trigger.target();
+    let Ok((
+        target_tower,
+        enemy,
+        mut cooldown,
+        squash_stretch,
+        mut state,
+    )) = q_enemies.get_mut(entity)
+    else {
+        return;
+    };
+
+    if strike_tower(
+        entity,
+        target_tower,
+        enemy,
+        &mut cooldown,
+        &q_invulnerables,
+        &mut damage_events,
+    ) {
+        state.displacement -= squash_stretch.landing_squash;
+    }
+}
+
+/// Hibernating enemies don't animate, deal their damage whenever
+/// the attack is off cooldown instead.
+fn attack_tower_hibernating(
+    mut q_enemies: Query<
+        (&TargetTower, &Enemy, &mut AttackCooldown, Entity),
+        (With<TargetReached>, With<Hibernating>),
+    >,
+    q_invulnerables: Query<(), With<Invulnerable>>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
     for (target_tower, enemy, mut cooldown, entity) in
         q_enemies.iter_mut()
     {
-        if let Ok(mut health) = q_healths.get_mut(target_tower.target)
-        {
-            if cooldown.0 > 0.0 {
-                continue;
-            }
-
-            health.0 -= enemy.damage;
-            cooldown.0 = enemy.attack_cooldown;
+        strike_tower(
+            entity,
+            target_tower,
+            enemy,
+            &mut cooldown,
+            &q_invulnerables,
+            &mut damage_events,
+        );
+    }
+}
 
-            if health.0 <= 0.0 {
-                commands.entity(target_tower.root).despawn();
-            }
-            info!("attacking {}", health.0);
-        } else {
-            // No more target, find another one.
-            commands.entity(entity).remove::<TargetTower>();
-        }
+/// Damage the [`TargetTower`] if the attack is off cooldown,
+/// returns `true` if it was damaged.
+fn strike_tower(
+    entity: Entity,
+    target_tower: &TargetTower,
+    enemy: &Enemy,
+    cooldown: &mut AttackCooldown,
+    q_invulnerables: &Query<(), With<Invulnerable>>,
+    damage_events: &mut EventWriter<DamageEvent>,
+) -> bool {
+    if cooldown.0 > 0.0
+        || q_invulnerables.contains(target_tower.target)
+    {
+        return false;
     }
+
+    damage_events.write(DamageEvent {
+        source: entity,
+        target: target_tower.target,
+        amount: enemy.damage,
+        kind: DamageKind::Melee,
+        knockback: Vec3::ZERO,
+    });
+    cooldown.0 = enemy.attack_cooldown;
+    true
 }
 
 fn rotate_to_velocity(
@@ -284,10 +602,18 @@ fn rotate_to_velocity(
     }
 }
 
+/// The base defended by the players, the level is lost once its
+/// [`Health`] runs out.
 #[derive(Component, Reflect)]
+#[require(MaxHealth(BASE_HEALTH))]
 #[reflect(Component)]
 pub struct FinalTarget;
 
+/// Enemies that reached the [`FinalTarget`] and are eating away at
+/// it.
+#[derive(Component, Debug)]
+pub struct SiegingBase;
+
 /// Configuration for the enemy unit.
 #[derive(Component, Reflect)]
 #[component(immutable)]
@@ -296,7 +622,9 @@ pub struct FinalTarget;
     Path,
     CollisionEventsEnabled,
     CollisionLayers::new(GameLayer::Enemy, LayerMask::ALL),
-    AttackCooldown
+    AttackCooldown,
+    SquashStretch,
+    Brain::new("enemy")
 )]
 #[reflect(Component)]
 pub struct Enemy {
@@ -305,6 +633,11 @@ pub struct Enemy {
     pub attack_cooldown: f32,
 }
 
+/// Enemies stop following their path while being knocked back,
+/// keeping the velocity they were hit with.
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct Knockback(pub Timer);
+
 /// Tag component for enemy units.
 /// Will be propagated down the hierarchy.
 #[derive(Component, Default, Clone, Copy)]
@@ -314,11 +647,24 @@ pub struct IsEnemy;
 #[derive(Component, Deref, Default)]
 #[require(PathIndex)]
 #[component(immutable)]
-pub struct Path(Vec<IVec2>);
+pub struct Path(Vec<IVec3>);
 
 impl Path {
-    pub fn get_target(&self, index: &PathIndex) -> Option<Vec2> {
-        self.0.get(index.0).map(TileMap::tile_coord_to_world_space)
+    /// World space position of the target tile surface.
+    pub fn get_target(
+        &self,
+        index: &PathIndex,
+        tile_map: &TileMap,
+    ) -> Option<Vec3> {
+        self.0.get(index.0).map(|coord| {
+            let mut target =
+                tile_map.tile_coord_to_world_space(coord);
+            // Ramps sit in between layers.
+            if let Some(tile) = tile_map.get_tile(coord) {
+                target.y = tile.height();
+            }
+            target
+        })
     }
 }
 
@@ -331,10 +677,17 @@ impl PathIndex {
     }
 }
 
+/// The [`Path`] was calculated on an outdated [`TileMap`] and is
+/// waiting for its turn to be recalculated.
+#[derive(Component)]
+pub struct StalePath;
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TargetType {
     Tower,
     Final,
+    /// Lured by a [`aggro::Threat`].
+    Threat,
 }
 
 #[derive(Component)]
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use super::hibernation::Hibernating;
+use super::{Enemy, Path};
+
+/// Lets [`Threat`]s draw the attention of nearby enemies.
+pub(super) struct AggroPlugin;
+
+impl Plugin for AggroPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, acquire_threats)
+            .add_observer(on_aggro_inserted)
+            .add_observer(on_aggro_removed);
+
+        app.register_type::<Threat>();
+    }
+}
+
+/// Aggro idle enemies onto the most threatening [`Threat`]
+/// within its radius.
+fn acquire_threats(
+    mut commands: Commands,
+    q_enemies: Query<
+        (&GlobalTransform, Entity),
+        (With<Enemy>, Without<AggroOf>, Without<Hibernating>),
+    >,
+    q_threats: Query<(&Threat, &GlobalTransform, Entity)>,
+) {
+    if q_threats.is_empty() {
+        return;
+    }
+
+    for (enemy_tf, entity) in q_enemies.iter() {
+        let enemy_translation = enemy_tf.translation();
+
+        let Some((.., threat)) =
+            q_threats
+                .iter()
+                .filter_map(|(threat, threat_tf, threat_entity)| {
+                    let dist = threat_tf
+                        .translation()
+                        .distance_squared(enemy_translation);
+
+                    (dist <= threat.radius * threat.radius).then_some(
+                        (threat.priority, -dist, threat_entity),
+                    )
+                })
+                // Highest priority then closest.
+                .max_by(|(pa, da, _), (pb, db, _)| {
+                    pa.cmp(pb).then(da.total_cmp(db))
+                })
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert(AggroOf(threat));
+    }
+}
+
+/// Clear the path so that it gets recalculated towards the threat.
+fn on_aggro_inserted(
+    trigger: Trigger<OnInsert, AggroOf>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(trigger.target())
+        .try_insert(Path::default());
+}
+
+/// Clear the path so that it gets recalculated towards the
+/// final target.
+fn on_aggro_removed(
+    trigger: Trigger<OnRemove, AggroOf>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(trigger.target())
+        .try_insert(Path::default());
+}
+
+/// Attracts enemies within the radius, overriding their path
+/// towards it until it's gone.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct Threat {
+    pub radius: f32,
+    /// Enemies prefer higher priority threats over closer ones.
+    pub priority: i32,
+}
+
+impl Default for Threat {
+    fn default() -> Self {
+        Self {
+            radius: 8.0,
+            priority: 0,
+        }
+    }
+}
+
+/// Attached to enemies that are aggroed onto a [`Threat`].
+#[derive(Component, Deref, Debug)]
+#[relationship(relationship_target = AggroedBy)]
+pub struct AggroOf(pub Entity);
+
+/// Attached to the [`Threat`] with the enemies aggroed onto it.
+#[derive(Component, Deref, Default, Debug)]
+#[relationship_target(relationship = AggroOf)]
+pub struct AggroedBy(Vec<Entity>);
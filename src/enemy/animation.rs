@@ -1,76 +1,91 @@
-use core::time::Duration;
-
 use bevy::animation::AnimationTarget;
 use bevy::prelude::*;
 
+use crate::asset_pipeline::animation_controller::{
+    AnimationControllerAppExt, AnimationControllerSystems,
+    AnimationGraphController, AnimationState, StateClip,
+};
 use crate::asset_pipeline::animation_pipeline::{
-    AnimationGraphMap, NodeMap,
+    AnimationGraphMap, AnimationOwner, NodeMap,
 };
-use crate::asset_pipeline::{AssetState, PrefabAssets, PrefabName};
+use crate::asset_pipeline::{AssetState, PrefabAssets, PrefabId};
+use crate::damage::Hit;
+use crate::death::Dying;
 
+use super::hibernation::Hibernating;
 use super::{Enemy, TargetReached};
 
 pub(super) struct EnemyAnimationPlugin;
 
 impl Plugin for EnemyAnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (setup_animation_graph, movement_animation)
-                .run_if(in_state(AssetState::Loaded)),
-        );
+        app.add_animation_controller::<EnemyAnimation>()
+            .add_systems(
+                Update,
+                (setup_animation_graph, movement_animation)
+                    .chain()
+                    .before(AnimationControllerSystems)
+                    .run_if(in_state(AssetState::Loaded)),
+            )
+            .add_observer(hit_animation)
+            .add_observer(death_animation);
     }
 }
 
-fn movement_animation(
-    q_enemies: Query<
-        (&NodeMap, &AnimationTarget, Has<TargetReached>),
-        With<Enemy>,
+/// Flinch if the enemy has a "Hit" animation.
+fn hit_animation(
+    trigger: Trigger<Hit>,
+    mut q_enemies: Query<
+        (&NodeMap, &mut AnimationGraphController<EnemyAnimation>),
+        Without<Dying>,
     >,
-    mut q_animation_players: Query<(
-        &mut AnimationPlayer,
-        &mut AnimationTransitions,
-    )>,
-) -> Result {
-    for (node_map, animation_target, reached_target) in
-        q_enemies.iter()
-    {
-        let (mut anim_player, mut anim_transitions) =
-            q_animation_players.get_mut(animation_target.player)?;
+) {
+    let Ok((node_map, mut controller)) =
+        q_enemies.get_mut(trigger.target())
+    else {
+        return;
+    };
 
-        if reached_target {
-            let eat_node = *node_map
-                .get("Eat")
-                .ok_or("No idle animation found for enemy!")?;
+    if EnemyAnimation::Hit.is_available(node_map) {
+        controller.play_once(EnemyAnimation::Hit);
+    }
+}
 
-            if anim_player.is_playing_animation(eat_node) == false {
-                anim_transitions
-                    .play(
-                        &mut anim_player,
-                        eat_node,
-                        Duration::from_millis(200),
-                    )
-                    .repeat();
-            }
-        } else {
-            let walk_node = *node_map
-                .get("Walk")
-                .ok_or("No walking animation found for enemy!")?;
+/// Play the "Death" animation if the enemy has one, otherwise it
+/// keeps its last animation.
+fn death_animation(
+    trigger: Trigger<OnAdd, Dying>,
+    mut q_enemies: Query<(
+        &NodeMap,
+        &mut AnimationGraphController<EnemyAnimation>,
+    )>,
+) {
+    let Ok((node_map, mut controller)) =
+        q_enemies.get_mut(trigger.target())
+    else {
+        return;
+    };
 
-            if anim_player.is_playing_animation(walk_node) == false {
-                anim_transitions
-                    .play(
-                        &mut anim_player,
-                        walk_node,
-                        Duration::from_millis(200),
-                    )
-                    .set_speed(1.5)
-                    .repeat();
-            }
-        }
+    if EnemyAnimation::Death.is_available(node_map) {
+        controller.interrupt(EnemyAnimation::Death);
     }
+}
 
-    Ok(())
+fn movement_animation(
+    mut q_enemies: Query<
+        (
+            &mut AnimationGraphController<EnemyAnimation>,
+            Has<TargetReached>,
+        ),
+        (With<Enemy>, Without<Hibernating>),
+    >,
+) {
+    for (mut controller, reached_target) in q_enemies.iter_mut() {
+        controller.set(match reached_target {
+            true => EnemyAnimation::Eat,
+            false => EnemyAnimation::Walk,
+        });
+    }
 }
 
 fn setup_animation_graph(
@@ -83,13 +98,17 @@ fn setup_animation_graph(
 ) -> Result {
     for (animation_target, entity) in q_enemies.iter() {
         let AnimationGraphMap { graph, node_map } = prefabs
-            .get_animation(PrefabName::FileName("mouse_a"))
+            .get_animation(PrefabId::MouseA.into())
             .ok_or("Unable to get animation for enemy!")?;
 
-        commands.entity(entity).insert(node_map.clone());
+        commands.entity(entity).insert((
+            node_map.clone(),
+            AnimationGraphController::<EnemyAnimation>::default(),
+        ));
         commands.entity(animation_target.player).insert((
             AnimationGraphHandle(graph.clone()),
             AnimationTransitions::new(),
+            AnimationOwner(entity),
         ));
 
         info!("Setup animation graph for enemy.");
@@ -97,3 +116,32 @@ fn setup_animation_graph(
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyAnimation {
+    Walk,
+    /// Attacking the [`FinalTarget`](super::FinalTarget).
+    Eat,
+    Hit,
+    Death,
+}
+
+impl AnimationState for EnemyAnimation {
+    fn clip(&self) -> StateClip {
+        match self {
+            EnemyAnimation::Walk => StateClip::new("Walk")
+                .with_transition(200)
+                .with_speed(1.5)
+                .repeat(),
+            EnemyAnimation::Eat => {
+                StateClip::new("Eat").with_transition(200).repeat()
+            }
+            EnemyAnimation::Hit => {
+                StateClip::new("Hit").with_transition(50)
+            }
+            EnemyAnimation::Death => {
+                StateClip::new("Death").with_transition(100)
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Senses the inputs of the enemy [`Brain`] ("enemy" in
+//! "brains.ai.ron"), which decides between:
+//!
+//! - `advance` along the path.
+//! - `flee` back along its path at low health, leaving the level
+//!   once it's back where it spawned.
+//! - `siege` the closest tower instead of walking around it.
+//! - `group_up` by slowing down until allies catch up.
+//!
+//! The decisions are carried out by the movement and pathfinding.
+
+use bevy::prelude::*;
+
+use crate::ai::{AiInputs, AiSet, Decision};
+use crate::tower::tower_attack::{Health, MaxHealth, Tower};
+
+use super::hibernation::Hibernating;
+use super::spatial_grid::EnemyGrid;
+use super::time_slice::{AiBudget, TimeSlicer};
+use super::{Enemy, StalePath, TargetType};
+
+/// Towers closer than this can be sieged.
+const SIEGE_RADIUS: f32 = 6.0;
+/// Enemies closer than this count as a group.
+const GROUP_RADIUS: f32 = 4.0;
+/// Allies needed to feel safe.
+const GROUP_SIZE: f32 = 3.0;
+
+pub(super) struct EnemyBehaviorPlugin;
+
+impl Plugin for EnemyBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            sense_enemies.in_set(AiSet::Sense),
+        )
+        .add_systems(Update, repath_on_decision);
+    }
+}
+
+fn sense_enemies(
+    mut q_enemies: Query<
+        (
+            &mut AiInputs,
+            &GlobalTransform,
+            Option<&Health>,
+            Option<&MaxHealth>,
+        ),
+        (With<Enemy>, Without<Hibernating>),
+    >,
+    q_towers: Query<&GlobalTransform, With<Tower>>,
+    enemy_grid: Res<EnemyGrid>,
+    budget: Res<AiBudget>,
+    mut slicer: Local<TimeSlicer>,
+) {
+    slicer.run(
+        q_enemies.iter_mut(),
+        budget.0,
+        |(mut inputs, transform, health, max_health)| {
+            let position = transform.translation();
+
+            let health = match (health, max_health) {
+                (Some(health), Some(max_health)) => {
+                    health.0 / max_health.0
+                }
+                _ => 1.0,
+            };
+            inputs.set("health", health);
+
+            // Excluding itself.
+            let allies = enemy_grid
+                .within_radius(position, GROUP_RADIUS)
+                .count()
+                .saturating_sub(1);
+            inputs.set("allies_nearby", allies as f32 / GROUP_SIZE);
+
+            let tower_distance = q_towers
+                .iter()
+                .map(|t| t.translation().distance(position))
+                .min_by(f32::total_cmp);
+            inputs.set(
+                "tower_proximity",
+                tower_distance
+                    .map_or(0.0, |d| 1.0 - d / SIEGE_RADIUS),
+            );
+        },
+    );
+}
+
+/// Sieging paths towards the closest tower instead of the final
+/// target, recalculate the path when that changes.
+fn repath_on_decision(
+    mut commands: Commands,
+    q_enemies: Query<
+        (&Decision, &TargetType, Entity),
+        (With<Enemy>, Changed<Decision>),
+    >,
+) {
+    for (decision, target_type, entity) in q_enemies.iter() {
+        if decision.is("siege") != (*target_type == TargetType::Tower)
+        {
+            commands.entity(entity).insert(StalePath);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Enemies push each other apart so that large waves spread out
+//! instead of stacking into a single column along the path.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::hibernation::Hibernating;
+use super::spatial_grid::EnemyGrid;
+use super::{Enemy, Knockback, TargetReached};
+
+/// Enemies closer than this push each other apart.
+const SEPARATION_RADIUS: f32 = 1.2;
+/// Speed of the push between two enemies on top of each other.
+const SEPARATION_SPEED: f32 = 2.0;
+
+pub(super) struct CrowdPlugin;
+
+impl Plugin for CrowdPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            separate_enemies.after(super::enemy_movement),
+        );
+    }
+}
+
+/// Add a soft push away from nearby enemies on top of the path
+/// following velocity, stronger the closer they are.
+fn separate_enemies(
+    mut q_enemies: Query<
+        (&mut LinearVelocity, &Position, Entity),
+        (
+            With<Enemy>,
+            Without<TargetReached>,
+            Without<Hibernating>,
+            Without<Knockback>,
+        ),
+    >,
+    enemy_grid: Res<EnemyGrid>,
+) {
+    for (mut linear_velocity, position, entity) in
+        q_enemies.iter_mut()
+    {
+        let mut push = Vec3::ZERO;
+
+        for (other, other_position) in
+            enemy_grid.within_radius(position.0, SEPARATION_RADIUS)
+        {
+            if other == entity {
+                continue;
+            }
+
+            let offset = (position.0 - other_position).with_y(0.0);
+            let distance = offset.length();
+            let weight = 1.0 - distance / SEPARATION_RADIUS;
+            // Split enemies on the exact same spot by their ids.
+            let direction = match distance > f32::EPSILON {
+                true => offset / distance,
+                false => match entity < other {
+                    true => Vec3::X,
+                    false => Vec3::NEG_X,
+                },
+            };
+
+            push += direction * weight;
+        }
+
+        linear_velocity.0 += push * SEPARATION_SPEED;
+    }
+}
@@ -0,0 +1,253 @@
+use core::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::animation::AnimationTarget;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+
+use crate::camera_controller::split_screen::{
+    CameraType, QueryCameras,
+};
+use crate::character_controller::CharacterController;
+use crate::environment::Environment;
+use crate::tile::TileMap;
+use crate::tower::Projectile;
+use crate::ui::Screen;
+
+use super::{Enemy, FinalTarget, Path, PathIndex, TargetReached};
+
+/// How often (in seconds) the activity check runs.
+const ACTIVITY_INTERVAL: f32 = 0.25;
+/// How often (in seconds) hibernating enemies progress
+/// along their path.
+const COARSE_STEP: f32 = 0.5;
+
+pub(super) struct HibernationPlugin;
+
+impl Plugin for HibernationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HibernationConfig>()
+            .add_systems(
+                Update,
+                update_activity.run_if(
+                    in_state(Screen::EnterLevel).and(on_timer(
+                        Duration::from_secs_f32(ACTIVITY_INTERVAL),
+                    )),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                simulate_hibernating_enemies.run_if(
+                    in_state(Screen::EnterLevel).and(on_timer(
+                        Duration::from_secs_f32(COARSE_STEP),
+                    )),
+                ),
+            )
+            .add_observer(on_hibernate)
+            .add_observer(on_wake);
+
+        app.register_type::<HibernationConfig>();
+    }
+}
+
+/// Put enemies and projectiles that are far away from every
+/// anchor (players, cameras and the [`FinalTarget`]) into
+/// hibernation and wake them up once they are close again.
+fn update_activity(
+    mut commands: Commands,
+    q_anchors: Query<
+        &GlobalTransform,
+        Or<(With<CharacterController>, With<FinalTarget>)>,
+    >,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_simulated: Query<
+        (&GlobalTransform, Has<Hibernating>, Entity),
+        Or<(With<Enemy>, With<Projectile>)>,
+    >,
+    config: Res<HibernationConfig>,
+) {
+    let mut anchors = q_anchors
+        .iter()
+        .map(|t| t.translation())
+        .collect::<Vec<_>>();
+
+    for camera_type in [CameraType::A, CameraType::B] {
+        if let Ok(transform) = q_cameras.get(camera_type) {
+            anchors.push(transform.translation());
+        }
+    }
+
+    // Nothing to compare against.
+    if anchors.is_empty() {
+        return;
+    }
+
+    let hibernate_dist_sq = config.hibernate_distance.powi(2);
+    let wake_dist_sq = config.wake_distance.powi(2);
+
+    for (transform, is_hibernating, entity) in q_simulated.iter() {
+        let translation = transform.translation();
+        let closest_dist_sq = anchors
+            .iter()
+            .map(|anchor| anchor.distance_squared(translation))
+            .fold(f32::MAX, f32::min);
+
+        if is_hibernating {
+            if closest_dist_sq < wake_dist_sq {
+                commands.entity(entity).remove::<Hibernating>();
+            }
+        } else if closest_dist_sq > hibernate_dist_sq {
+            commands.entity(entity).insert(Hibernating);
+        }
+    }
+}
+
+/// Progress hibernating enemies along their path in coarse
+/// steps by teleporting them, bypassing physics entirely.
+fn simulate_hibernating_enemies(
+    mut commands: Commands,
+    mut q_enemies: Query<
+        (
+            &Enemy,
+            &Path,
+            &mut PathIndex,
+            &mut Position,
+            &mut Transform,
+            Entity,
+        ),
+        (With<Hibernating>, Without<TargetReached>),
+    >,
+    tile_map: Res<TileMap>,
+    environment: Res<Environment>,
+) {
+    for (
+        enemy,
+        path,
+        mut path_index,
+        mut position,
+        mut transform,
+        entity,
+    ) in q_enemies.iter_mut()
+    {
+        let mut travel = enemy.movement_speed
+            * environment.enemy_speed
+            * COARSE_STEP;
+        let mut current = position.xz();
+        // Surface height of the tile the enemy is leaving.
+        let mut surface = path
+            .get_target(
+                &PathIndex(path_index.saturating_sub(1)),
+                &tile_map,
+            )
+            .map(|t| t.y);
+
+        loop {
+            let Some(target) =
+                path.get_target(&path_index, &tile_map)
+            else {
+                commands.entity(entity).insert(TargetReached);
+                break;
+            };
+
+            let distance = current.distance(target.xz());
+            if distance > travel {
+                current +=
+                    (target.xz() - current) / distance * travel;
+                break;
+            }
+
+            travel -= distance;
+            current = target.xz();
+            path_index.increment();
+
+            // Follow the height changes of ramps and platforms.
+            if let Some(surface) = surface.replace(target.y) {
+                position.y += target.y - surface;
+            }
+        }
+
+        position.x = current.x;
+        position.z = current.y;
+        transform.translation.x = current.x;
+        transform.translation.y = position.y;
+        transform.translation.z = current.y;
+    }
+}
+
+fn on_hibernate(
+    trigger: Trigger<OnAdd, Hibernating>,
+    mut commands: Commands,
+    mut q_velocities: Query<&mut LinearVelocity, Without<Projectile>>,
+    q_animation_targets: Query<&AnimationTarget>,
+    mut q_animation_players: Query<&mut AnimationPlayer>,
+) {
+    let entity = trigger.target();
+
+    commands
+        .entity(entity)
+        .insert((RigidBodyDisabled, Visibility::Hidden));
+
+    if let Ok(mut linear_velocity) = q_velocities.get_mut(entity) {
+        linear_velocity.0 = Vec3::ZERO;
+    }
+
+    if let Some(mut anim_player) = q_animation_targets
+        .get(entity)
+        .ok()
+        .and_then(|t| q_animation_players.get_mut(t.player).ok())
+    {
+        anim_player.pause_all();
+    }
+}
+
+fn on_wake(
+    trigger: Trigger<OnRemove, Hibernating>,
+    mut commands: Commands,
+    q_animation_targets: Query<&AnimationTarget>,
+    mut q_animation_players: Query<&mut AnimationPlayer>,
+) {
+    let entity = trigger.target();
+
+    // The entity might be despawning.
+    commands
+        .entity(entity)
+        .try_remove::<RigidBodyDisabled>()
+        .try_insert(Visibility::Inherited);
+
+    if let Some(mut anim_player) = q_animation_targets
+        .get(entity)
+        .ok()
+        .and_then(|t| q_animation_players.get_mut(t.player).ok())
+    {
+        anim_player.resume_all();
+    }
+}
+
+/// Distances used to decide when to hibernate or wake up.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct HibernationConfig {
+    /// Entities further than this distance from every
+    /// anchor will be put into hibernation.
+    pub hibernate_distance: f32,
+    /// Hibernating entities closer than this distance to
+    /// any anchor will be woken up.
+    ///
+    /// *Note: This should be smaller than
+    /// [`Self::hibernate_distance`] to prevent flickering.*
+    pub wake_distance: f32,
+}
+
+impl Default for HibernationConfig {
+    fn default() -> Self {
+        Self {
+            hibernate_distance: 40.0,
+            wake_distance: 32.0,
+        }
+    }
+}
+
+/// Tag component for enemies and projectiles that are
+/// simulated cheaply without physics and animation.
+#[derive(Component, Debug)]
+pub struct Hibernating;
@@ -0,0 +1,181 @@
+//! The wave countdown is a build phase, which both players can
+//! end early by readying up, either by pressing
+//! [`PlayerAction::Ready`] or by standing on a [`ReadyPlate`].
+//! A [`Companion`] is always ready.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::character_controller::CharacterController;
+use crate::player::companion::Companion;
+use crate::player::{PlayerType, QueryPlayers};
+use crate::ui::Screen;
+
+use super::spawner::{SpawnSchedule, SpawnWave, WaveCountdown};
+
+pub(super) struct ReadyUpPlugin;
+
+impl Plugin for ReadyUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReadyUp>().add_systems(
+            Update,
+            (
+                reset_ready_up.run_if(state_changed::<SpawnWave>),
+                (update_ready_up, start_wave_when_ready)
+                    .chain()
+                    .run_if(in_build_phase),
+            )
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
+        );
+
+        app.register_type::<ReadyPlate>();
+    }
+}
+
+/// Waiting for the wave to start.
+pub fn is_build_phase(
+    countdown: &WaveCountdown,
+    wave: SpawnWave,
+) -> bool {
+    countdown.finished() == false && wave != SpawnWave::None
+}
+
+fn in_build_phase(
+    countdown: Res<WaveCountdown>,
+    wave: Option<Res<State<SpawnWave>>>,
+) -> bool {
+    wave.is_some_and(|w| is_build_phase(&countdown, *w.get()))
+}
+
+fn reset_ready_up(mut ready_up: ResMut<ReadyUp>) {
+    *ready_up = ReadyUp::default();
+}
+
+fn update_ready_up(
+    mut ready_up: ResMut<ReadyUp>,
+    q_players: QueryPlayers<
+        (&GlobalTransform, &TargetAction),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_companions: Query<(), With<Companion>>,
+    q_plates: Query<(&ReadyPlate, &GlobalTransform)>,
+) {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        let Ok((transform, target_action)) =
+            q_players.get(player_type)
+        else {
+            continue;
+        };
+
+        let position = transform.translation().xz();
+        let on_plate =
+            q_plates.iter().any(|(plate, plate_transform)| {
+                plate_transform.translation().xz().distance(position)
+                    <= plate.radius
+            });
+
+        let toggled = q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::Ready));
+        let companion = q_companions.contains(target_action.get());
+
+        // Avoid triggering change detection every frame.
+        let ready = ready_up.get(player_type);
+        if ready.on_plate != on_plate
+            || toggled
+            || (companion && ready.pressed == false)
+        {
+            let ready = ready_up.get_mut(player_type);
+            ready.on_plate = on_plate;
+            ready.pressed = match companion {
+                true => true,
+                false => ready.pressed ^ toggled,
+            };
+        }
+    }
+}
+
+/// Skip the rest of the countdowns once everyone is ready.
+fn start_wave_when_ready(
+    ready_up: Res<ReadyUp>,
+    mut q_schedules: Query<&mut SpawnSchedule>,
+) {
+    if ready_up.all_ready() == false {
+        return;
+    }
+
+    info!("Both players are ready, starting the wave.");
+    for mut schedule in q_schedules.iter_mut() {
+        schedule.skip_countdown();
+    }
+}
+
+/// Players standing within the radius are ready for the next
+/// wave.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct ReadyPlate {
+    /// Horizontal radius from the plate's origin.
+    pub radius: f32,
+}
+
+impl Default for ReadyPlate {
+    fn default() -> Self {
+        Self { radius: 1.5 }
+    }
+}
+
+/// Readiness of each player during the build phase.
+#[derive(Resource, Default, Debug)]
+pub struct ReadyUp {
+    pub a: PlayerReady,
+    pub b: PlayerReady,
+}
+
+impl ReadyUp {
+    pub fn get(&self, player_type: PlayerType) -> &PlayerReady {
+        match player_type {
+            PlayerType::A => &self.a,
+            PlayerType::B => &self.b,
+        }
+    }
+
+    fn get_mut(
+        &mut self,
+        player_type: PlayerType,
+    ) -> &mut PlayerReady {
+        match player_type {
+            PlayerType::A => &mut self.a,
+            PlayerType::B => &mut self.b,
+        }
+    }
+
+    /// Number of players that are ready.
+    pub fn count(&self) -> usize {
+        [&self.a, &self.b]
+            .into_iter()
+            .filter(|r| r.is_ready())
+            .count()
+    }
+
+    pub fn all_ready(&self) -> bool {
+        self.a.is_ready() && self.b.is_ready()
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct PlayerReady {
+    /// Toggled with [`PlayerAction::Ready`].
+    pub pressed: bool,
+    /// Standing on a [`ReadyPlate`].
+    pub on_plate: bool,
+}
+
+impl PlayerReady {
+    pub fn is_ready(&self) -> bool {
+        self.pressed || self.on_plate
+    }
+}
@@ -0,0 +1,123 @@
+//! A uniform grid of enemy positions for cheap radius queries,
+//! instead of intersecting shapes against the whole physics world.
+
+use avian3d::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use super::Enemy;
+
+pub(super) struct EnemyGridPlugin;
+
+impl Plugin for EnemyGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemyGrid>()
+            .add_systems(PreUpdate, update_enemy_grid);
+    }
+}
+
+/// Rebuild the grid from scratch, keeping the cell allocations.
+fn update_enemy_grid(
+    q_enemies: Query<(&Position, Entity), With<Enemy>>,
+    mut grid: ResMut<EnemyGrid>,
+) {
+    grid.clear();
+
+    for (position, entity) in q_enemies.iter() {
+        grid.insert(entity, position.0);
+    }
+}
+
+/// Positions of every [`Enemy`] bucketed into square cells on the
+/// xz plane, rebuilt every frame in [`PreUpdate`].
+#[derive(Resource, Debug)]
+pub struct EnemyGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec3)>>,
+}
+
+impl EnemyGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    fn cell(&self, position: Vec3) -> IVec2 {
+        (position.xz() / self.cell_size).floor().as_ivec2()
+    }
+
+    fn clear(&mut self) {
+        for enemies in self.cells.values_mut() {
+            enemies.clear();
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// Enemies (and their positions) within `radius` of `center`.
+    pub fn within_radius(
+        &self,
+        center: Vec3,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        let min = self.cell(center - radius);
+        let max = self.cell(center + radius);
+        let radius_sq = radius * radius;
+
+        (min.x..=max.x)
+            .flat_map(move |x| {
+                (min.y..=max.y).map(move |y| IVec2::new(x, y))
+            })
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, position)| {
+                position.distance_squared(center) <= radius_sq
+            })
+    }
+}
+
+impl Default for EnemyGrid {
+    fn default() -> Self {
+        Self::new(4.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_radius() {
+        let mut grid = EnemyGrid::default();
+        let near = Entity::from_raw(1);
+        let edge = Entity::from_raw(2);
+        let far = Entity::from_raw(3);
+
+        grid.insert(near, Vec3::new(1.0, 0.0, 1.0));
+        // Right on the radius, in a neighbouring cell.
+        grid.insert(edge, Vec3::new(-5.0, 0.0, 0.0));
+        grid.insert(far, Vec3::new(20.0, 0.0, -20.0));
+
+        let mut found = grid
+            .within_radius(Vec3::ZERO, 5.0)
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, vec![near, edge]);
+
+        // Height counts towards the distance too.
+        assert_eq!(
+            grid.within_radius(Vec3::Y * 10.0, 5.0).count(),
+            0
+        );
+
+        grid.clear();
+        assert_eq!(grid.within_radius(Vec3::ZERO, 50.0).count(), 0);
+    }
+}
@@ -1,10 +1,24 @@
 use bevy::prelude::*;
 
-use crate::asset_pipeline::{CurrentScene, PrefabAssets, PrefabName};
+use crate::asset_pipeline::{CurrentScene, PrefabAssets, PrefabId};
+use crate::camera_controller::cinematic::VictoryReached;
+use crate::celebration::Celebrate;
+use crate::debug_flags::debug_flag;
+use crate::level::GameMode;
+use crate::performance::GraphicsPreset;
+use crate::tower::tower_attack::{Health, MaxHealth};
 use crate::ui::Screen;
 
 use super::Enemy;
 
+/// Enemy count multiplier of each endless wave.
+const ENDLESS_COUNT_GROWTH: f32 = 1.25;
+/// Spawn interval multiplier of each endless wave.
+const ENDLESS_INTERVAL_DECAY: f32 = 0.9;
+const MIN_SPAWN_INTERVAL: f32 = 0.3;
+/// Enemy health multiplier of each endless wave.
+const ENDLESS_HEALTH_GROWTH: f32 = 1.15;
+
 pub(super) struct EnemySpawnerPlugin;
 
 impl Plugin for EnemySpawnerPlugin {
@@ -13,15 +27,20 @@ impl Plugin for EnemySpawnerPlugin {
 
         app.add_sub_state::<SpawnWave>()
             .init_resource::<WaveCountdown>()
-            .init_resource::<SpawnCount>()
-            .init_resource::<SpawnTimer>()
             .add_systems(
                 Update,
                 (
-                    (set_wave_countdown, set_spawn_count_and_timer)
+                    reset_spawn_schedules
                         .run_if(state_changed::<SpawnWave>),
-                    ((wave_countdown, spawn_timer), spawn_enemy)
-                        .chain(),
+                    (
+                        tick_spawn_schedules,
+                        spawn_enemies,
+                        advance_wave,
+                    )
+                        .chain()
+                        .run_if(not(debug_flag(|f| f.freeze_waves))),
+                    sync_wave_countdown,
+                    scale_endless_enemies,
                 )
                     .chain()
                     .run_if(in_state(Screen::EnterLevel)),
@@ -38,170 +57,170 @@ fn on_add_spawner(
     next_wave.set(SpawnWave::One);
 }
 
-fn spawn_enemy(
+/// Start the schedule of the current wave on every spawner.
+pub(crate) fn reset_spawn_schedules(
+    mut q_spawners: Query<(&EnemySpawner, &mut SpawnSchedule)>,
+    current_wave: Res<State<SpawnWave>>,
+) {
+    for (spawner, mut schedule) in q_spawners.iter_mut() {
+        *schedule = spawner
+            .wave(*current_wave.get())
+            .map(|config| SpawnSchedule::new(&config))
+            .unwrap_or_default();
+    }
+}
+
+/// Tick the spawn timer after the countdown is reached.
+fn tick_spawn_schedules(
+    mut q_schedules: Query<&mut SpawnSchedule>,
+    time: Res<Time>,
+) {
+    for mut schedule in q_schedules.iter_mut() {
+        match schedule.countdown.finished() {
+            true => {
+                schedule.timer.tick(time.delta());
+            }
+            false => {
+                schedule.countdown.tick(time.delta());
+            }
+        }
+    }
+}
+
+fn spawn_enemies(
     mut commands: Commands,
-    q_spawner: Query<&GlobalTransform, With<EnemySpawner>>,
+    mut q_spawners: Query<(&mut SpawnSchedule, &GlobalTransform)>,
     q_enemies: Query<(), With<Enemy>>,
-    countdown: Res<WaveCountdown>,
-    timer: Res<SpawnTimer>,
-    mut spawn_count: ResMut<SpawnCount>,
     current_scene: Res<CurrentScene>,
     prefabs: Res<PrefabAssets>,
     gltfs: Res<Assets<Gltf>>,
-    curr_wave: Res<State<SpawnWave>>,
-    mut next_wave: ResMut<NextState<SpawnWave>>,
-    mut next_screen: ResMut<NextState<Screen>>,
+    preset: Res<GraphicsPreset>,
 ) -> Result {
-    let Ok(transform) = q_spawner.single() else {
-        return Ok(());
-    };
-
     let Some(current_scene) = current_scene.get() else {
         return Ok(());
     };
 
-    if countdown.finished() == false {
-        return Ok(());
-    }
+    let mut enemy_count = q_enemies.iter().len();
 
-    if timer.just_finished() == false {
-        return Ok(());
-    }
+    for (mut schedule, transform) in q_spawners.iter_mut() {
+        if schedule.count == 0
+            || schedule.countdown.finished() == false
+            || schedule.timer.just_finished() == false
+        {
+            continue;
+        }
 
-    if spawn_count.0 == 0 {
-        match curr_wave.get() {
-            SpawnWave::One => {
-                next_wave.set(SpawnWave::Two);
-                info!("Entering wave 2.")
-            }
-            SpawnWave::Two => {
-                next_wave.set(SpawnWave::Three);
-                info!("Entering wave 3.")
-            }
-            SpawnWave::Three => {
-                if q_enemies.iter().len() == 0 {
-                    next_wave.set(SpawnWave::None);
-                    next_screen.set(Screen::GameOver);
-                    info!("Game over!")
-                }
-            }
-            SpawnWave::None => {}
+        // Hold the spawn until enemies are killed.
+        if preset.enemy_cap().is_some_and(|cap| enemy_count >= cap) {
+            continue;
         }
-        return Ok(());
-    }
 
-    spawn_count.0 -= 1;
+        schedule.count -= 1;
+        enemy_count += 1;
 
-    commands.spawn((
-        SceneRoot(
-            prefabs
-                .get_gltf(PrefabName::FileName("mouse_a"), &gltfs)
-                .ok_or("Can't find mouse prefab!")?
-                .default_scene
-                .clone()
-                .ok_or("Mouse prefab should have a default scene.")?,
-        ),
-        transform.compute_transform(),
-        ChildOf(current_scene),
-    ));
+        commands.spawn((
+            SceneRoot(
+                prefabs
+                    .get_gltf(schedule.enemy.into(), &gltfs)
+                    .ok_or("Can't find enemy prefab!")?
+                    .default_scene
+                    .clone()
+                    .ok_or(
+                        "Enemy prefab should have a default scene.",
+                    )?,
+            ),
+            transform.compute_transform(),
+            ChildOf(current_scene),
+        ));
+    }
 
     Ok(())
 }
 
-fn set_wave_countdown(
-    current_wave: Res<State<SpawnWave>>,
-    mut countdown: ResMut<WaveCountdown>,
-    q_spawner: Query<&EnemySpawner>,
+/// Move on once every spawner is done with the current wave.
+fn advance_wave(
+    mut commands: Commands,
+    q_schedules: Query<&SpawnSchedule>,
+    q_enemies: Query<(), With<Enemy>>,
+    curr_wave: Res<State<SpawnWave>>,
+    mut next_wave: ResMut<NextState<SpawnWave>>,
+    game_mode: Res<GameMode>,
 ) {
-    let Ok(spawner) = q_spawner.single() else {
+    if q_schedules.is_empty()
+        || q_schedules.iter().any(|s| s.count > 0)
+    {
         return;
-    };
+    }
 
-    let countdown_time = match current_wave.get() {
+    match *curr_wave.get() {
         SpawnWave::One => {
-            info!("Setting wave 1 countdown.");
-            spawner.wave_1.countdown
+            next_wave.set(SpawnWave::Two);
+            commands.trigger(Celebrate::WaveCleared);
+            info!("Entering wave 2.")
         }
         SpawnWave::Two => {
-            info!("Setting wave 2 countdown.");
-            spawner.wave_2.countdown
+            next_wave.set(SpawnWave::Three);
+            commands.trigger(Celebrate::WaveCleared);
+            info!("Entering wave 3.")
+        }
+        SpawnWave::Three if *game_mode == GameMode::Endless => {
+            next_wave.set(SpawnWave::Endless(4));
+            commands.trigger(Celebrate::WaveCleared);
+            info!("Entering endless wave 4.")
         }
         SpawnWave::Three => {
-            info!("Setting wave 3 countdown.");
-            spawner.wave_3.countdown
+            if q_enemies.iter().len() == 0 {
+                next_wave.set(SpawnWave::None);
+                // Celebrate before the victory screen.
+                commands.trigger(VictoryReached);
+                info!("Game over!")
+            }
         }
-        SpawnWave::None => {
-            return;
+        SpawnWave::Endless(number) => {
+            next_wave.set(SpawnWave::Endless(number + 1));
+            commands.trigger(Celebrate::WaveCleared);
+            info!("Entering endless wave {}.", number + 1)
         }
-    };
-
-    countdown.0 =
-        Timer::from_seconds(countdown_time, TimerMode::Once);
+        SpawnWave::None => {}
+    }
 }
 
-fn set_spawn_count_and_timer(
-    q_spawner: Query<&EnemySpawner>,
+/// Enemies of the endless waves get tougher every wave.
+fn scale_endless_enemies(
+    mut q_enemies: Query<(&mut Health, &mut MaxHealth), Added<Enemy>>,
     current_wave: Res<State<SpawnWave>>,
-    mut timer: ResMut<SpawnTimer>,
-    mut spawn_count: ResMut<SpawnCount>,
 ) {
-    let Ok(spawner) = q_spawner.single() else {
+    let SpawnWave::Endless(number) = *current_wave.get() else {
         return;
     };
 
-    let (interval, count) = match current_wave.get() {
-        SpawnWave::One => {
-            info!("Setting wave 1 interval and count.");
-            (
-                spawner.wave_1.spawn_interval,
-                spawner.wave_1.enemy_count,
-            )
-        }
-        SpawnWave::Two => {
-            info!("Setting wave 2 interval and count.");
-            (
-                spawner.wave_2.spawn_interval,
-                spawner.wave_2.enemy_count,
-            )
-        }
-        SpawnWave::Three => {
-            info!("Setting wave 3 interval and count.");
-            (
-                spawner.wave_3.spawn_interval,
-                spawner.wave_3.enemy_count,
-            )
-        }
-        SpawnWave::None => {
-            return;
-        }
-    };
-
-    timer.0 = Timer::from_seconds(interval, TimerMode::Repeating);
-    spawn_count.0 = count;
+    let multiplier = ENDLESS_HEALTH_GROWTH.powi(number as i32 - 3);
+    for (mut health, mut max_health) in q_enemies.iter_mut() {
+        health.0 *= multiplier;
+        max_health.0 *= multiplier;
+    }
 }
 
-/// Tick every frame.
-fn wave_countdown(
+/// Mirror the countdown of the first spawner to start.
+fn sync_wave_countdown(
+    q_schedules: Query<&SpawnSchedule>,
     mut countdown: ResMut<WaveCountdown>,
-    time: Res<Time>,
 ) {
-    if countdown.finished() == false {
-        countdown.tick(time.delta());
-    }
-}
+    let Some(first) = q_schedules
+        .iter()
+        .filter(|s| s.count > 0)
+        .min_by_key(|s| s.countdown.remaining())
+    else {
+        return;
+    };
 
-fn spawn_timer(
-    countdown: Res<WaveCountdown>,
-    mut timer: ResMut<SpawnTimer>,
-    time: Res<Time>,
-) {
-    // Only tick after countdown is reached.
-    if countdown.finished() {
-        timer.tick(time.delta());
+    if countdown.0 != first.countdown {
+        countdown.0 = first.countdown.clone();
     }
 }
 
 #[derive(Component, Reflect)]
+#[require(SpawnSchedule)]
 #[reflect(Component)]
 pub struct EnemySpawner {
     pub wave_1: WaveConfig,
@@ -209,7 +228,21 @@ pub struct EnemySpawner {
     pub wave_3: WaveConfig,
 }
 
-#[derive(Reflect)]
+impl EnemySpawner {
+    pub fn wave(&self, wave: SpawnWave) -> Option<WaveConfig> {
+        match wave {
+            SpawnWave::One => Some(self.wave_1.clone()),
+            SpawnWave::Two => Some(self.wave_2.clone()),
+            SpawnWave::Three => Some(self.wave_3.clone()),
+            SpawnWave::Endless(number) => {
+                Some(self.wave_3.endless(number))
+            }
+            SpawnWave::None => None,
+        }
+    }
+}
+
+#[derive(Reflect, Clone)]
 pub struct WaveConfig {
     /// How long before the wave starts.
     pub countdown: f32,
@@ -217,6 +250,23 @@ pub struct WaveConfig {
     pub spawn_interval: f32,
 }
 
+impl WaveConfig {
+    /// Scale up the last authored wave into an endless wave.
+    fn endless(&self, number: u32) -> Self {
+        let step = number.saturating_sub(3) as i32;
+
+        Self {
+            countdown: self.countdown,
+            enemy_count: (self.enemy_count as f32
+                * ENDLESS_COUNT_GROWTH.powi(step))
+            .ceil() as usize,
+            spawn_interval: (self.spawn_interval
+                * ENDLESS_INTERVAL_DECAY.powi(step))
+            .max(MIN_SPAWN_INTERVAL),
+        }
+    }
+}
+
 #[derive(
     SubStates, Default, Debug, Hash, Clone, Copy, Eq, PartialEq,
 )]
@@ -227,16 +277,90 @@ pub enum SpawnWave {
     One,
     Two,
     Three,
+    /// Generated waves after [`SpawnWave::Three`] in
+    /// [`GameMode::Endless`], numbered from 4.
+    Endless(u32),
+}
+
+impl SpawnWave {
+    /// Number of the wave starting from 1.
+    pub fn number(&self) -> Option<u32> {
+        match self {
+            SpawnWave::None => None,
+            SpawnWave::One => Some(1),
+            SpawnWave::Two => Some(2),
+            SpawnWave::Three => Some(3),
+            SpawnWave::Endless(number) => Some(*number),
+        }
+    }
 }
 
-/// Countdown timer until enemies start to spawn.
-#[derive(Resource, Deref, DerefMut, Default)]
+/// Countdown timer until the first [`EnemySpawner`] starts to
+/// spawn, mirrored from their [`SpawnSchedule`]s.
+#[derive(Resource, Deref, Default)]
 pub struct WaveCountdown(Timer);
 
-/// Number of enemies to spawn left.
-#[derive(Resource, Deref, DerefMut, Default)]
-pub struct SpawnCount(usize);
+/// Progress of an [`EnemySpawner`] through the current wave.
+#[derive(Component, Debug)]
+pub struct SpawnSchedule {
+    /// Prefab of the enemies to spawn.
+    enemy: PrefabId,
+    /// Countdown until enemies start to spawn.
+    countdown: Timer,
+    /// Time left before the next spawn.
+    timer: Timer,
+    /// Number of enemies to spawn left.
+    count: usize,
+}
+
+impl Default for SpawnSchedule {
+    fn default() -> Self {
+        Self {
+            enemy: PrefabId::MouseA,
+            countdown: Timer::default(),
+            timer: Timer::default(),
+            count: 0,
+        }
+    }
+}
 
-/// Time left before the next spawn.
-#[derive(Resource, Deref, DerefMut, Default)]
-pub struct SpawnTimer(Timer);
+impl SpawnSchedule {
+    fn new(config: &WaveConfig) -> Self {
+        Self {
+            enemy: PrefabId::MouseA,
+            countdown: Timer::from_seconds(
+                config.countdown,
+                TimerMode::Once,
+            ),
+            timer: Timer::from_seconds(
+                config.spawn_interval,
+                TimerMode::Repeating,
+            ),
+            count: config.enemy_count,
+        }
+    }
+
+    pub fn enemy(&self) -> PrefabId {
+        self.enemy
+    }
+
+    /// Number of enemies to spawn left.
+    pub fn remaining(&self) -> usize {
+        self.count
+    }
+
+    /// Enemies spawned per second.
+    pub fn spawn_rate(&self) -> f32 {
+        let interval = self.timer.duration().as_secs_f32();
+        match interval > 0.0 {
+            true => 1.0 / interval,
+            false => 0.0,
+        }
+    }
+
+    /// Finish the countdown, spawning starts right away.
+    pub fn skip_countdown(&mut self) {
+        let remaining = self.countdown.remaining();
+        self.countdown.tick(remaining);
+    }
+}
@@ -0,0 +1,123 @@
+//! Spreads expensive per-entity AI work (pathfinding, sensing and
+//! decisions) across frames so that large waves don't spike the
+//! frame time.
+
+use core::time::Duration;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+pub(super) struct TimeSlicePlugin;
+
+impl Plugin for TimeSlicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiBudget>();
+
+        app.register_type::<AiBudget>();
+    }
+}
+
+/// Time each time-sliced system may spend per frame.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct AiBudget(pub Duration);
+
+impl Default for AiBudget {
+    fn default() -> Self {
+        Self(Duration::from_millis(2))
+    }
+}
+
+/// Round-robin cursor of a time-sliced system, meant to be used
+/// as a [`Local`].
+///
+/// Every frame continues from where the last one ran out of
+/// budget, so every item gets its turn eventually.
+#[derive(Default, Debug)]
+pub struct TimeSlicer {
+    cursor: usize,
+}
+
+impl TimeSlicer {
+    /// Run `f` over `items` starting from the cursor until the
+    /// `budget` runs out. At least one item is processed per call
+    /// so that progress is always made.
+    ///
+    /// Returns the number of items processed.
+    pub fn run<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+        budget: Duration,
+        f: impl FnMut(T),
+    ) -> usize {
+        let start = Instant::now();
+        self.run_while(items, f, || start.elapsed() < budget)
+    }
+
+    fn run_while<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+        mut f: impl FnMut(T),
+        mut within_budget: impl FnMut() -> bool,
+    ) -> usize {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        if items.is_empty() {
+            self.cursor = 0;
+            return 0;
+        }
+
+        let len = items.len();
+        // The item count may have shrunk since the last frame.
+        let offset = self.cursor % len;
+        let mut rotated = items.split_off(offset);
+        rotated.append(&mut items);
+
+        let mut processed = 0;
+        for item in rotated {
+            if processed > 0 && within_budget() == false {
+                break;
+            }
+
+            f(item);
+            processed += 1;
+        }
+
+        self.cursor = (offset + processed) % len;
+        processed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Budget that allows `count` items per call.
+    fn items_budget(count: usize) -> impl FnMut() -> bool {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            calls < count
+        }
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut slicer = TimeSlicer::default();
+        let mut seen = Vec::new();
+
+        for _ in 0..3 {
+            slicer.run_while(0..5, |i| seen.push(i), items_budget(2));
+        }
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 0]);
+
+        // Always makes progress, even without budget.
+        seen.clear();
+        slicer.run_while(0..5, |i| seen.push(i), || false);
+        assert_eq!(seen, vec![1]);
+
+        // Wraps around when items got removed.
+        seen.clear();
+        slicer.run_while(0..2, |i| seen.push(i), items_budget(5));
+        assert_eq!(seen, vec![0, 1]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Day/night and weather cycle of a level, driven by the
+//! [`EnvironmentCycle`] authored in the level's scene.
+//!
+//! Besides the lighting and fog, enemies move faster at night and
+//! the [`EnvironmentCycle::rain_towers`] deal more damage in the
+//! rain.
+
+use bevy::core_pipeline::Skybox;
+use bevy::pbr::{DistanceFog, FogFalloff};
+use bevy::prelude::*;
+
+use crate::camera_controller::split_screen::ENVIRONMENT_BRIGHTNESS;
+use crate::ui::Screen;
+
+/// Fraction of the day spent fading between day and night.
+const TWILIGHT: f32 = 0.05;
+/// Seconds to blend in and out of the rain.
+const RAIN_BLEND_DURATION: f32 = 4.0;
+/// Fraction of the sunlight blocked by the clouds at full rain.
+const RAIN_DIMMING: f32 = 0.5;
+
+pub(super) struct EnvironmentPlugin;
+
+impl Plugin for EnvironmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Environment>()
+            .add_systems(
+                OnEnter(Screen::EnterLevel),
+                reset_environment,
+            )
+            .add_systems(
+                Update,
+                (
+                    advance_environment
+                        .run_if(in_state(Screen::EnterLevel)),
+                    apply_environment
+                        .run_if(resource_changed::<Environment>),
+                )
+                    .chain(),
+            );
+
+        app.register_type::<EnvironmentCycle>();
+    }
+}
+
+fn reset_environment(mut environment: ResMut<Environment>) {
+    *environment = Environment::default();
+}
+
+/// Move the cycle forward and update the gameplay multipliers.
+fn advance_environment(
+    q_cycles: Query<Ref<EnvironmentCycle>>,
+    mut environment: ResMut<Environment>,
+    time: Res<Time>,
+) {
+    // Levels without a cycle stay at clear daylight.
+    let Ok(cycle) = q_cycles.single() else {
+        return;
+    };
+
+    if cycle.is_changed() {
+        environment.rain_towers = cycle.rain_towers.clone();
+    }
+
+    let dt = time.delta_secs();
+    environment.elapsed += dt;
+    environment.daylight = cycle.daylight(environment.elapsed);
+
+    let rain_target = match cycle.is_raining(environment.elapsed) {
+        true => 1.0,
+        false => 0.0,
+    };
+    environment.rain = environment.rain
+        + (rain_target - environment.rain).clamp(
+            -dt / RAIN_BLEND_DURATION,
+            dt / RAIN_BLEND_DURATION,
+        );
+
+    // Blend the multipliers along with the visuals.
+    environment.enemy_speed = 1.0_f32
+        .lerp(cycle.night_enemy_speed, 1.0 - environment.daylight);
+    environment.rain_tower_damage =
+        1.0_f32.lerp(cycle.rain_tower_damage, environment.rain);
+}
+
+/// Light the level and fog up the cameras based on the
+/// [`Environment`].
+fn apply_environment(
+    mut commands: Commands,
+    environment: Res<Environment>,
+    q_cycles: Query<&EnvironmentCycle>,
+    mut q_lights: Query<&mut DirectionalLight>,
+    mut q_cameras: Query<(
+        &mut Skybox,
+        &mut EnvironmentMapLight,
+        Option<&mut DistanceFog>,
+        Entity,
+    )>,
+) {
+    let Ok(cycle) = q_cycles.single() else {
+        // Back to the camera defaults.
+        for (mut skybox, mut environment_light, _, entity) in
+            q_cameras.iter_mut()
+        {
+            skybox.brightness = ENVIRONMENT_BRIGHTNESS;
+            environment_light.intensity = ENVIRONMENT_BRIGHTNESS;
+            commands.entity(entity).remove::<DistanceFog>();
+        }
+        return;
+    };
+
+    let daylight = environment.daylight;
+    let sunlight = 1.0 - environment.rain * RAIN_DIMMING;
+
+    for mut light in q_lights.iter_mut() {
+        light.illuminance = cycle
+            .night_illuminance
+            .lerp(cycle.day_illuminance, daylight)
+            * sunlight;
+    }
+
+    let brightness = cycle
+        .night_sky_brightness
+        .lerp(ENVIRONMENT_BRIGHTNESS, daylight)
+        * sunlight;
+    let fog = DistanceFog {
+        color: cycle
+            .night_fog_color
+            .mix(&cycle.day_fog_color, daylight),
+        falloff: FogFalloff::from_visibility(
+            cycle
+                .clear_fog_distance
+                .lerp(cycle.rain_fog_distance, environment.rain),
+        ),
+        ..default()
+    };
+
+    for (mut skybox, mut environment_light, distance_fog, entity) in
+        q_cameras.iter_mut()
+    {
+        skybox.brightness = brightness;
+        environment_light.intensity = brightness;
+
+        match distance_fog {
+            Some(mut distance_fog) => *distance_fog = fog.clone(),
+            None => {
+                commands.entity(entity).insert(fog.clone());
+            }
+        }
+    }
+}
+
+/// Day/night and rain cycle of the level, place one in the
+/// level's scene.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct EnvironmentCycle {
+    /// Seconds for a full day and night, 0 to stay at daylight.
+    pub day_duration: f32,
+    /// Fraction of the [`Self::day_duration`] that is night, the
+    /// level starts in the morning.
+    pub night_fraction: f32,
+    /// Seconds of clear weather between showers.
+    pub rain_interval: f32,
+    /// Seconds each shower lasts, 0 for no rain.
+    pub rain_duration: f32,
+    /// Illuminance of the directional lights in daylight.
+    pub day_illuminance: f32,
+    pub night_illuminance: f32,
+    /// Brightness of the skybox and environment lighting at night.
+    pub night_sky_brightness: f32,
+    pub day_fog_color: Color,
+    pub night_fog_color: Color,
+    /// Visibility distance of the fog in clear weather.
+    pub clear_fog_distance: f32,
+    /// Visibility distance of the fog at full rain.
+    pub rain_fog_distance: f32,
+    /// Multiplier of the enemy movement speed at night.
+    pub night_enemy_speed: f32,
+    /// Multiplier of the damage of the [`Self::rain_towers`] at
+    /// full rain.
+    pub rain_tower_damage: f32,
+    /// Prefab names of the towers that are stronger in the rain.
+    pub rain_towers: Vec<String>,
+}
+
+impl EnvironmentCycle {
+    /// 1 at daylight, 0 at night.
+    pub fn daylight(&self, elapsed: f32) -> f32 {
+        if self.day_duration <= 0.0 {
+            return 1.0;
+        }
+
+        let phase = (elapsed / self.day_duration).fract();
+        let dusk = 1.0 - self.night_fraction;
+
+        // Fade out before dusk and back in before the next dawn.
+        let fade_out = ((dusk - phase) / TWILIGHT).clamp(0.0, 1.0);
+        let fade_in =
+            ((phase - (1.0 - TWILIGHT)) / TWILIGHT).clamp(0.0, 1.0);
+
+        fade_out.max(fade_in)
+    }
+
+    pub fn is_raining(&self, elapsed: f32) -> bool {
+        if self.rain_duration <= 0.0 {
+            return false;
+        }
+
+        elapsed % (self.rain_interval + self.rain_duration)
+            >= self.rain_interval
+    }
+}
+
+impl Default for EnvironmentCycle {
+    fn default() -> Self {
+        Self {
+            day_duration: 240.0,
+            night_fraction: 0.35,
+            rain_interval: 120.0,
+            rain_duration: 40.0,
+            day_illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            night_illuminance: 400.0,
+            night_sky_brightness: 150.0,
+            day_fog_color: Color::srgb(0.62, 0.68, 0.74),
+            night_fog_color: Color::srgb(0.04, 0.05, 0.1),
+            clear_fog_distance: 200.0,
+            rain_fog_distance: 40.0,
+            night_enemy_speed: 1.25,
+            rain_tower_damage: 1.3,
+            rain_towers: vec!["gun_tower".to_string()],
+        }
+    }
+}
+
+/// Current state of the [`EnvironmentCycle`] and its effect on
+/// the gameplay.
+#[derive(Resource, Debug)]
+pub struct Environment {
+    /// Seconds since the level started.
+    elapsed: f32,
+    /// 1 at daylight, 0 at night.
+    pub daylight: f32,
+    /// 1 at full rain, blends in and out.
+    pub rain: f32,
+    /// Multiplier of the enemy movement speed.
+    pub enemy_speed: f32,
+    /// Multiplier of the damage of the [`Self::rain_towers`].
+    rain_tower_damage: f32,
+    rain_towers: Vec<String>,
+}
+
+impl Environment {
+    /// Multiplier of the damage dealt by the tower.
+    pub fn tower_damage(&self, prefab_name: &str) -> f32 {
+        match self.rain_towers.iter().any(|name| name == prefab_name)
+        {
+            true => self.rain_tower_damage,
+            false => 1.0,
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            daylight: 1.0,
+            rain: 0.0,
+            enemy_speed: 1.0,
+            rain_tower_damage: 1.0,
+            rain_towers: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,523 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::character_controller::{CharacterController, IsGrounded};
+use crate::death::Dying;
+use crate::enemy::Enemy;
+use crate::physics::GameLayer;
+use crate::player::CharacterId;
+
+/// How fast the body leans towards the slope beneath it.
+const TILT_RATE: f32 = 8.0;
+
+/// Two-bone leg inverse kinematics that plants the feet
+/// on slopes and steps, and leans the body into the slope.
+pub(super) struct FootIkPlugin;
+
+impl Plugin for FootIkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_character_foot_ik,
+                setup_enemy_foot_ik,
+                setup_foot_ik_bones,
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            solve_foot_ik.after(TransformSystem::TransformPropagate),
+        );
+
+        app.register_type::<FootIk>();
+    }
+}
+
+/// Player characters get their leg bones by default.
+fn setup_character_foot_ik(
+    mut commands: Commands,
+    q_characters: Query<
+        (&CharacterId, Entity),
+        (With<CharacterController>, Without<FootIk>),
+    >,
+) {
+    for (character_id, entity) in q_characters.iter() {
+        // The rigs of the characters name their bones differently.
+        let legs = ["L", "R"].map(|side| match character_id {
+            CharacterId::PoloBun => IkLeg {
+                upper: format!("thigh.{side}"),
+                lower: format!("shin.{side}"),
+                foot: Some(format!("foot.{side}")),
+                ..default()
+            },
+            CharacterId::Baguette => IkLeg {
+                upper: format!("thigh.{side}"),
+                lower: format!("shin.{side}.003"),
+                foot: Some(format!("shin.{side}.001")),
+                ..default()
+            },
+        });
+
+        commands.entity(entity).insert(FootIk {
+            legs: legs.into(),
+            root: Some("spine".to_string()),
+            slope_alignment: 0.3,
+            ..default()
+        });
+    }
+}
+
+/// Enemy legs end at the shin, which has no foot bone.
+fn setup_enemy_foot_ik(
+    mut commands: Commands,
+    q_enemies: Query<Entity, (With<Enemy>, Without<FootIk>)>,
+) {
+    for entity in q_enemies.iter() {
+        let legs = ["L", "R"].map(|side| IkLeg {
+            upper: format!("thigh.{side}"),
+            lower: format!("shin.{side}"),
+            foot: None,
+            // About as long as the thigh of the mouse rig.
+            lower_length: 0.25,
+        });
+
+        commands.entity(entity).insert(FootIk {
+            legs: legs.into(),
+            root: Some("spine".to_string()),
+            slope_alignment: 0.8,
+            ..default()
+        });
+    }
+}
+
+/// Resolve the leg bone names into entities.
+fn setup_foot_ik_bones(
+    mut commands: Commands,
+    q_foot_iks: Query<(&FootIk, Entity), Without<FootIkBones>>,
+    q_children: Query<&Children>,
+    q_names: Query<&Name>,
+) {
+    for (foot_ik, entity) in q_foot_iks.iter() {
+        let find_bone = |name: &str| {
+            q_children.iter_descendants(entity).find(|e| {
+                q_names.get(*e).is_ok_and(|n| n.as_str() == name)
+            })
+        };
+
+        let legs = foot_ik
+            .legs
+            .iter()
+            .map(|leg| {
+                Some(LegBones {
+                    upper: find_bone(&leg.upper)?,
+                    lower: find_bone(&leg.lower)?,
+                    foot: match &leg.foot {
+                        Some(foot) => Some(find_bone(foot)?),
+                        None => None,
+                    },
+                })
+            })
+            .collect::<Option<Vec<_>>>();
+        let root = match &foot_ik.root {
+            Some(root) => find_bone(root).map(Some),
+            None => Some(None),
+        };
+
+        // The bones might not be spawned yet.
+        if let (Some(legs), Some(root)) = (legs, root) {
+            commands.entity(entity).insert((
+                FootIkBones { legs, root },
+                RootTilt::default(),
+            ));
+        }
+    }
+}
+
+/// Adjust the root and leg bones after the animation has been
+/// applied so that the body leans into the slope and each foot
+/// rests on the ground beneath it.
+fn solve_foot_ik(
+    mut q_foot_iks: Query<
+        (
+            &FootIk,
+            &FootIkBones,
+            &mut RootTilt,
+            &GlobalTransform,
+            Option<&IsGrounded>,
+            Entity,
+        ),
+        Without<Dying>,
+    >,
+    mut q_transforms: Query<
+        (&mut Transform, &mut GlobalTransform),
+        Without<FootIk>,
+    >,
+    q_parents: Query<&ChildOf>,
+    q_children: Query<&Children>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+) {
+    for (
+        foot_ik,
+        bones,
+        mut tilt,
+        global_transform,
+        is_grounded,
+        entity,
+    ) in q_foot_iks.iter_mut()
+    {
+        // Let the animation take over while airborne.
+        if is_grounded.is_some_and(|g| g.0 == false) {
+            continue;
+        }
+
+        let filter =
+            SpatialQueryFilter::from_mask(GameLayer::Default)
+                .with_excluded_entities([entity]);
+        let max_distance = foot_ik.ray_height + foot_ik.max_adjust;
+        let ray_ground = |origin: Vec3| {
+            spatial_query
+                .cast_ray(
+                    origin + Vec3::Y * foot_ik.ray_height,
+                    Dir3::NEG_Y,
+                    max_distance,
+                    true,
+                    &filter,
+                )
+                .map(|hit| {
+                    (
+                        origin.y + foot_ik.ray_height - hit.distance,
+                        hit.normal,
+                    )
+                })
+        };
+
+        // Ground level beneath the body which the animation
+        // has been authored against.
+        let translation = global_transform.translation();
+        let Some((base_height, base_normal)) =
+            ray_ground(translation)
+        else {
+            continue;
+        };
+
+        if let Some(root) = bones.root {
+            let (axis, angle) =
+                Quat::from_rotation_arc(Vec3::Y, base_normal)
+                    .to_axis_angle();
+            let target_tilt = Quat::from_axis_angle(
+                axis,
+                (angle * foot_ik.slope_alignment)
+                    .min(foot_ik.max_tilt),
+            );
+            tilt.0 = tilt.0.slerp(
+                target_tilt,
+                (time.delta_secs() * TILT_RATE).min(1.0),
+            );
+
+            // Lean around the point the body stands on.
+            if let Ok((_, root_gt)) = q_transforms.get(root) {
+                let root_gt = *root_gt;
+                let pivot = translation.with_y(base_height);
+
+                set_global_pose(
+                    root,
+                    pivot + tilt.0 * (root_gt.translation() - pivot),
+                    tilt.0 * root_gt.rotation(),
+                    &mut q_transforms,
+                    &q_parents,
+                    &q_children,
+                );
+            }
+        }
+
+        for (leg, &LegBones { upper, lower, foot }) in
+            foot_ik.legs.iter().zip(bones.legs.iter())
+        {
+            let Ok([(_, upper_gt), (_, lower_gt)]) =
+                q_transforms.get_many([upper, lower])
+            else {
+                continue;
+            };
+            let foot_gt = match foot {
+                Some(foot) => match q_transforms.get(foot) {
+                    Ok((_, foot_gt)) => Some(*foot_gt),
+                    Err(_) => continue,
+                },
+                None => None,
+            };
+
+            let (upper_gt, lower_gt) = (*upper_gt, *lower_gt);
+            let hip = upper_gt.translation();
+            let knee = lower_gt.translation();
+            // Without a foot bone, the ankle is at the tail of the
+            // lower bone, which points along its local y axis.
+            let ankle = match foot_gt {
+                Some(foot_gt) => foot_gt.translation(),
+                None => lower_gt
+                    .transform_point(Vec3::Y * leg.lower_length),
+            };
+
+            let Some((ground_height, normal)) = ray_ground(ankle)
+            else {
+                continue;
+            };
+
+            let adjust = (ground_height - base_height)
+                .clamp(-foot_ik.max_adjust, foot_ik.max_adjust);
+            let target = ankle + Vec3::Y * adjust;
+
+            let Some((new_knee, new_ankle)) =
+                solve_two_bone(hip, knee, ankle, target)
+            else {
+                continue;
+            };
+
+            // Rotate the upper bone to point towards the new knee.
+            let upper_delta = Quat::from_rotation_arc(
+                (knee - hip).normalize(),
+                (new_knee - hip).normalize(),
+            );
+            // Rotate the lower bone to point towards the new ankle.
+            let lower_delta = Quat::from_rotation_arc(
+                (upper_delta * (ankle - knee)).normalize(),
+                (new_ankle - new_knee).normalize(),
+            ) * upper_delta;
+
+            set_global_rotation(
+                upper,
+                upper_delta * upper_gt.rotation(),
+                &mut q_transforms,
+                &q_parents,
+                &q_children,
+            );
+            set_global_rotation(
+                lower,
+                lower_delta * lower_gt.rotation(),
+                &mut q_transforms,
+                &q_parents,
+                &q_children,
+            );
+
+            // Keep the foot aligned to the ground.
+            if let (Some(foot), Some(foot_gt)) = (foot, foot_gt) {
+                set_global_rotation(
+                    foot,
+                    Quat::from_rotation_arc(Vec3::Y, normal)
+                        * foot_gt.rotation(),
+                    &mut q_transforms,
+                    &q_parents,
+                    &q_children,
+                );
+            }
+        }
+    }
+}
+
+/// Analytical two-bone IK solve, returning the new knee and
+/// ankle positions. The bending plane is preserved from the
+/// current pose.
+fn solve_two_bone(
+    hip: Vec3,
+    knee: Vec3,
+    ankle: Vec3,
+    target: Vec3,
+) -> Option<(Vec3, Vec3)> {
+    let upper_len = hip.distance(knee);
+    let lower_len = knee.distance(ankle);
+
+    let to_target = target - hip;
+    let direction = to_target.try_normalize()?;
+    let distance = to_target.length().clamp(
+        (upper_len - lower_len).abs() + 0.001,
+        upper_len + lower_len - 0.001,
+    );
+
+    let bend_axis = to_target
+        .cross(knee - hip)
+        .try_normalize()
+        .unwrap_or_else(|| direction.any_orthonormal_vector());
+
+    // Law of cosines for the angle at the hip.
+    let cos_hip = ((upper_len * upper_len + distance * distance
+        - lower_len * lower_len)
+        / (2.0 * upper_len * distance))
+        .clamp(-1.0, 1.0);
+    let hip_angle = cos_hip.acos();
+
+    let new_knee = hip
+        + Quat::from_axis_angle(bend_axis, hip_angle)
+            * direction
+            * upper_len;
+    let new_ankle = hip + direction * distance;
+
+    Some((new_knee, new_ankle))
+}
+
+/// Set the world space translation and rotation of an entity and
+/// update the [`GlobalTransform`]s of its hierarchy as transform
+/// propagation has already happened.
+fn set_global_pose(
+    entity: Entity,
+    translation: Vec3,
+    rotation: Quat,
+    q_transforms: &mut Query<
+        (&mut Transform, &mut GlobalTransform),
+        Without<FootIk>,
+    >,
+    q_parents: &Query<&ChildOf>,
+    q_children: &Query<&Children>,
+) {
+    if let Ok((mut transform, mut global_transform)) =
+        q_transforms.get_mut(entity)
+    {
+        // The parent might be the entity with the `FootIk`, derive
+        // its transform from the entity's own instead.
+        let parent_gt = GlobalTransform::from(
+            global_transform.affine()
+                * transform.compute_affine().inverse(),
+        );
+
+        *global_transform = GlobalTransform::from(Transform {
+            translation,
+            rotation,
+            scale: global_transform.scale(),
+        });
+        *transform = global_transform.reparented_to(&parent_gt);
+    }
+
+    propagate_global_transform(
+        entity,
+        q_transforms,
+        q_parents,
+        q_children,
+    );
+}
+
+/// Set the world space rotation of an entity and update the
+/// [`GlobalTransform`]s of its hierarchy as transform
+/// propagation has already happened.
+fn set_global_rotation(
+    entity: Entity,
+    rotation: Quat,
+    q_transforms: &mut Query<
+        (&mut Transform, &mut GlobalTransform),
+        Without<FootIk>,
+    >,
+    q_parents: &Query<&ChildOf>,
+    q_children: &Query<&Children>,
+) {
+    let parent_rotation = q_parents
+        .get(entity)
+        .ok()
+        .and_then(|c| q_transforms.get(c.parent()).ok())
+        .map(|(_, gt)| gt.rotation())
+        .unwrap_or_default();
+
+    if let Ok((mut transform, _)) = q_transforms.get_mut(entity) {
+        transform.rotation = parent_rotation.inverse() * rotation;
+    }
+
+    propagate_global_transform(
+        entity,
+        q_transforms,
+        q_parents,
+        q_children,
+    );
+}
+
+/// Recompute the [`GlobalTransform`] of an entity
+/// and all of its descendants.
+fn propagate_global_transform(
+    entity: Entity,
+    q_transforms: &mut Query<
+        (&mut Transform, &mut GlobalTransform),
+        Without<FootIk>,
+    >,
+    q_parents: &Query<&ChildOf>,
+    q_children: &Query<&Children>,
+) {
+    // Descendants are iterated breadth first,
+    // parents will always be updated first.
+    for entity in core::iter::once(entity)
+        .chain(q_children.iter_descendants(entity))
+    {
+        let Some(parent_gt) = q_parents
+            .get(entity)
+            .ok()
+            .and_then(|c| q_transforms.get(c.parent()).ok())
+            .map(|(_, gt)| *gt)
+        else {
+            continue;
+        };
+
+        if let Ok((transform, mut global_transform)) =
+            q_transforms.get_mut(entity)
+        {
+            *global_transform = parent_gt * *transform;
+        }
+    }
+}
+
+/// Per prefab leg configuration for foot placement.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct FootIk {
+    pub legs: Vec<IkLeg>,
+    /// Bone that leans the whole body into the slope, [`None`]
+    /// to keep the body upright.
+    pub root: Option<String>,
+    /// Fraction of the slope angle the body leans with.
+    pub slope_alignment: f32,
+    /// Maximum angle in radians the body leans with.
+    pub max_tilt: f32,
+    /// Maximum height a foot can be raised or lowered.
+    pub max_adjust: f32,
+    /// Height above the foot to start the ground raycast from.
+    pub ray_height: f32,
+}
+
+impl Default for FootIk {
+    fn default() -> Self {
+        Self {
+            legs: Vec::new(),
+            root: None,
+            slope_alignment: 0.0,
+            max_tilt: 0.35,
+            max_adjust: 0.4,
+            ray_height: 0.5,
+        }
+    }
+}
+
+/// Bone names of a two-bone leg chain.
+#[derive(Reflect, Default, Debug, Clone)]
+pub struct IkLeg {
+    pub upper: String,
+    pub lower: String,
+    /// [`None`] for rigs whose legs end at the lower bone.
+    pub foot: Option<String>,
+    /// Length of the lower bone, only used to locate the ankle
+    /// when there is no foot bone.
+    pub lower_length: f32,
+}
+
+/// Resolved bone entities of the [`FootIk`].
+#[derive(Component, Debug)]
+pub struct FootIkBones {
+    /// Bones of each of the [`FootIk::legs`].
+    legs: Vec<LegBones>,
+    root: Option<Entity>,
+}
+
+/// Resolved bone entities of an [`IkLeg`].
+#[derive(Debug, Clone, Copy)]
+struct LegBones {
+    upper: Entity,
+    lower: Entity,
+    foot: Option<Entity>,
+}
+
+/// Smoothed lean currently applied to the [`FootIk::root`].
+#[derive(Component, Deref, Default, Debug)]
+pub struct RootTilt(Quat);
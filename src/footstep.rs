@@ -0,0 +1,131 @@
+//! Footstep sounds and particles of characters and enemies, played
+//! on the [`AnimationEventKind::Footstep`]s of their walk cycles
+//! based on the [`Surface`] underfoot.
+
+use avian3d::prelude::*;
+use bevy::diagnostic::FrameCount;
+use bevy::prelude::*;
+
+use crate::asset_pipeline::animation_pipeline::{
+    AnimationEvent, AnimationEventKind,
+};
+use crate::audio::AudioCue;
+use crate::enemy::IsEnemy;
+use crate::performance::GraphicsPreset;
+use crate::physics::GameLayer;
+use crate::vfx::{Vfx, VfxPreset};
+
+/// How far above the origin the ground is searched from.
+const RAY_HEIGHT: f32 = 0.5;
+/// How far below the origin the ground can be, the origin isn't
+/// always at the feet.
+const MAX_GROUND_DISTANCE: f32 = 1.5;
+
+pub(super) struct FootstepPlugin;
+
+impl Plugin for FootstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(play_footstep);
+
+        app.register_type::<Surface>();
+    }
+}
+
+fn play_footstep(
+    trigger: Trigger<AnimationEvent>,
+    mut commands: Commands,
+    q_transforms: Query<&GlobalTransform>,
+    q_surfaces: Query<&Surface>,
+    q_child_ofs: Query<&ChildOf>,
+    q_is_enemy: Query<(), With<IsEnemy>>,
+    spatial_query: SpatialQuery,
+    preset: Res<GraphicsPreset>,
+    frame_count: Res<FrameCount>,
+    // Enemy footsteps played so far in the frame.
+    mut enemy_steps: Local<(u32, usize)>,
+) {
+    if trigger.kind != AnimationEventKind::Footstep {
+        return;
+    }
+
+    let entity = trigger.target();
+
+    // A horde stomping in sync is just noise.
+    if q_is_enemy.contains(entity) {
+        if enemy_steps.0 != frame_count.0 {
+            *enemy_steps = (frame_count.0, 0);
+        }
+
+        if enemy_steps.1 >= preset.enemy_footstep_cap() {
+            return;
+        }
+        enemy_steps.1 += 1;
+    }
+    let Ok(global_transform) = q_transforms.get(entity) else {
+        return;
+    };
+    let translation = global_transform.translation();
+
+    let Some(hit) = spatial_query.cast_ray(
+        translation + Vec3::Y * RAY_HEIGHT,
+        Dir3::NEG_Y,
+        RAY_HEIGHT + MAX_GROUND_DISTANCE,
+        true,
+        &SpatialQueryFilter::from_mask(GameLayer::Default)
+            .with_excluded_entities([entity]),
+    ) else {
+        // Mid-air.
+        return;
+    };
+
+    // The surface might be tagged on one of the collider's
+    // ancestors.
+    let surface = [hit.entity]
+        .into_iter()
+        .chain(q_child_ofs.iter_ancestors(hit.entity))
+        .find_map(|e| q_surfaces.get(e).ok())
+        .copied()
+        .unwrap_or_default();
+
+    commands.trigger(AudioCue::Footstep {
+        surface,
+        translation,
+    });
+
+    if let Some(preset) = surface.vfx_preset() {
+        commands.trigger(Vfx {
+            preset,
+            translation,
+        });
+    }
+}
+
+/// Material of the level geometry, tag the colliders (or their
+/// ancestors) in the level's scene.
+#[derive(
+    Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[reflect(Component, Default)]
+pub enum Surface {
+    #[default]
+    Stone,
+    Biscuit,
+    Bread,
+    Wood,
+    Metal,
+}
+
+impl Surface {
+    /// Particles kicked up by a footstep, if any.
+    pub fn vfx_preset(&self) -> Option<VfxPreset> {
+        match self {
+            Surface::Stone | Surface::Wood => {
+                Some(VfxPreset::StepDust)
+            }
+            Surface::Biscuit | Surface::Bread => {
+                Some(VfxPreset::StepCrumbs)
+            }
+            Surface::Metal => None,
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! A typed stream of notable [`GameplayEvent`]s, so that stats and
+//! achievements can be tracked without hooking into every gameplay
+//! system.
+//!
+//! ```ignore
+//! app.add_gameplay_subscriber(
+//!     |mut events: EventReader<GameplayEvent>| {
+//!         for event in events.read() {
+//!             info!("{event:?}");
+//!         }
+//!     },
+//! );
+//! ```
+
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+
+use crate::camera_controller::cinematic::VictoryReached;
+use crate::damage::{Hit, Killed};
+use crate::enemy::Enemy;
+use crate::enemy::spawner::SpawnWave;
+use crate::inventory::{Item, ItemCollectionEvent};
+use crate::machine::overcook::CookedOutput;
+use crate::machine::{OperatedBy, OperationTimer, SelectedRecipe};
+use crate::player::PlayerType;
+use crate::tower::Projectile;
+use crate::tower::ownership::PlacedByPlayer;
+use crate::tower::tower_attack::Tower;
+use crate::ui::Screen;
+
+pub(super) struct GameplayEventPlugin;
+
+impl Plugin for GameplayEventPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameplayEvent>()
+            .configure_sets(
+                Update,
+                (
+                    GameplayEventSystems::Emit,
+                    GameplayEventSystems::Subscribe,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                emit_wave_started
+                    .run_if(
+                        in_state(Screen::EnterLevel)
+                            .and(state_changed::<SpawnWave>),
+                    )
+                    .in_set(GameplayEventSystems::Emit),
+            )
+            .add_observer(emit_killed)
+            .add_observer(emit_damage_dealt)
+            .add_observer(emit_projectile_fired)
+            .add_observer(emit_cooking_started)
+            .add_observer(emit_recipe_cooked)
+            .add_observer(emit_item_collected)
+            .add_observer(emit_tower_placed)
+            .add_observer(emit_level_cleared);
+    }
+}
+
+fn emit_wave_started(
+    current_wave: Res<State<SpawnWave>>,
+    mut events: EventWriter<GameplayEvent>,
+) {
+    if let Some(number) = current_wave.number() {
+        events.write(GameplayEvent::WaveStarted { number });
+    }
+}
+
+fn emit_killed(
+    trigger: Trigger<Killed>,
+    q_enemies: Query<(), With<Enemy>>,
+    q_towers: Query<(), With<Tower>>,
+    mut events: EventWriter<GameplayEvent>,
+) {
+    let target = trigger.target();
+    if q_enemies.contains(target) {
+        events.write(GameplayEvent::EnemyKilled {
+            enemy: target,
+            source: trigger.source,
+        });
+    } else if q_towers.contains(target) {
+        events.write(GameplayEvent::TowerDestroyed { tower: target });
+    }
+}
+
+fn emit_damage_dealt(
+    trigger: Trigger<Hit>,
+    mut events: EventWriter<GameplayEvent>,
+) {
+    events.write(GameplayEvent::DamageDealt {
+        target: trigger.target(),
+        source: trigger.source,
+        amount: trigger.amount,
+    });
+}
+
+fn emit_projectile_fired(
+    trigger: Trigger<OnAdd, Projectile>,
+    q_projectiles: Query<&Projectile>,
+    mut events: EventWriter<GameplayEvent>,
+) -> Result {
+    let projectile = trigger.target();
+
+    events.write(GameplayEvent::ProjectileFired {
+        projectile,
+        shooter: q_projectiles.get(projectile)?.shooter,
+    });
+
+    Ok(())
+}
+
+fn emit_cooking_started(
+    trigger: Trigger<OnAdd, OperationTimer>,
+    q_machines: Query<(&SelectedRecipe, Option<&OperatedBy>)>,
+    q_player_types: Query<&PlayerType>,
+    mut events: EventWriter<GameplayEvent>,
+) -> Result {
+    let machine = trigger.target();
+    let (selected_recipe, operated_by) = q_machines.get(machine)?;
+
+    events.write(GameplayEvent::CookingStarted {
+        machine,
+        recipe_id: selected_recipe.to_string(),
+        player: operated_by
+            .and_then(|o| q_player_types.get(**o).ok())
+            .copied(),
+    });
+
+    Ok(())
+}
+
+fn emit_recipe_cooked(
+    trigger: Trigger<OnAdd, CookedOutput>,
+    q_outputs: Query<&CookedOutput>,
+    mut events: EventWriter<GameplayEvent>,
+) -> Result {
+    let machine = trigger.target();
+    let output = q_outputs.get(machine)?;
+
+    events.write(GameplayEvent::RecipeCooked {
+        machine,
+        item_id: output.item_id.clone(),
+        quantity: output.quantity,
+    });
+
+    Ok(())
+}
+
+fn emit_item_collected(
+    trigger: Trigger<ItemCollectionEvent>,
+    q_items: Query<&Item>,
+    q_player_types: Query<&PlayerType>,
+    mut events: EventWriter<GameplayEvent>,
+) {
+    let Ok(item) = q_items.get(trigger.item) else {
+        return;
+    };
+
+    events.write(GameplayEvent::ItemCollected {
+        item_id: item.id.clone(),
+        quantity: item.quantity,
+        player: q_player_types.get(trigger.target()).ok().copied(),
+    });
+}
+
+fn emit_tower_placed(
+    trigger: Trigger<OnAdd, PlacedByPlayer>,
+    q_owners: Query<&PlacedByPlayer>,
+    mut events: EventWriter<GameplayEvent>,
+) -> Result {
+    let tower = trigger.target();
+
+    events.write(GameplayEvent::TowerPlaced {
+        tower,
+        player: **q_owners.get(tower)?,
+    });
+
+    Ok(())
+}
+
+fn emit_level_cleared(
+    _: Trigger<VictoryReached>,
+    mut events: EventWriter<GameplayEvent>,
+) {
+    events.write(GameplayEvent::LevelCleared);
+}
+
+#[derive(Event, Debug, Clone)]
+pub enum GameplayEvent {
+    WaveStarted {
+        /// Starting from 1.
+        number: u32,
+    },
+    EnemyKilled {
+        enemy: Entity,
+        /// Whatever dealt the killing blow.
+        source: Entity,
+    },
+    /// Damage that got through to the target.
+    DamageDealt {
+        target: Entity,
+        source: Entity,
+        amount: f32,
+    },
+    ProjectileFired {
+        projectile: Entity,
+        shooter: Entity,
+    },
+    CookingStarted {
+        machine: Entity,
+        recipe_id: String,
+        /// The player that started the machine.
+        player: Option<PlayerType>,
+    },
+    /// A machine finished cooking.
+    RecipeCooked {
+        machine: Entity,
+        item_id: String,
+        quantity: u32,
+    },
+    ItemCollected {
+        item_id: String,
+        quantity: u32,
+        player: Option<PlayerType>,
+    },
+    TowerPlaced {
+        tower: Entity,
+        player: PlayerType,
+    },
+    TowerDestroyed {
+        tower: Entity,
+    },
+    /// The last wave got defeated.
+    LevelCleared,
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameplayEventSystems {
+    /// Systems that write [`GameplayEvent`]s.
+    Emit,
+    /// Systems that read [`GameplayEvent`]s.
+    Subscribe,
+}
+
+pub trait GameplayEventAppExt {
+    /// Run the system every frame after the [`GameplayEvent`]s of
+    /// systems are written.
+    fn add_gameplay_subscriber<M>(
+        &mut self,
+        system: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+}
+
+impl GameplayEventAppExt for App {
+    fn add_gameplay_subscriber<M>(
+        &mut self,
+        system: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.add_systems(
+            Update,
+            system.in_set(GameplayEventSystems::Subscribe),
+        )
+    }
+}
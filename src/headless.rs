@@ -0,0 +1,297 @@
+//! Runs the game without a window, renderer or audio device for
+//! scripted simulations, e.g. catching balance regressions in
+//! tests.
+//!
+//! ```ignore
+//! let report = Scenario::new(SpawnWave::Two)
+//!     .with_tower(IVec3::new(4, 0, 6), "gun_tower")
+//!     .run_for(60.0)?;
+//! assert_eq!(report.leaks, 0);
+//! ```
+//!
+//! Enable with `--no-default-features --features headless`, the
+//! default `dev_native` features expect a window and renderer:
+//!
+//! ```sh
+//! cargo test --no-default-features --features headless
+//! ```
+
+use core::time::Duration;
+
+use bevy::asset::AssetMetaCheck;
+use bevy::ecs::error::{GLOBAL_ERROR_HANDLER, error};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+
+use crate::AppPlugin;
+use crate::asset_pipeline::AssetState;
+use crate::enemy::spawner::{EnemySpawner, SpawnWave};
+use crate::enemy::{Enemy, SiegingBase};
+use crate::player::player_mark::PlayerMark;
+use crate::rng::GameRng;
+use crate::tile::TileMap;
+use crate::tower::blueprint::TowerBlueprint;
+use crate::tower::tower_attack::Health;
+use crate::ui::Screen;
+
+/// Simulated seconds per frame.
+const TIMESTEP: f32 = 1.0 / 60.0;
+/// Maximum real seconds to wait for the assets and level to load.
+const LOAD_TIMEOUT: f32 = 120.0;
+
+/// Build the [`AppPlugin`] on top of the default plugins, minus
+/// windowing, rendering and audio. Time advances by a fixed
+/// [`TIMESTEP`] on every update.
+pub fn headless_app() -> App {
+    // Log errors instead of panicking, like the game does.
+    // Ignored if a previous app already set it.
+    let _ = GLOBAL_ERROR_HANDLER.set(error);
+
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .build()
+            .disable::<WinitPlugin>()
+            .disable::<bevy::audio::AudioPlugin>()
+            .set(AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+                ..default()
+            })
+            .set(RenderPlugin {
+                // No backend, the render world is never created.
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            }),
+    )
+    .insert_resource(TimeUpdateStrategy::ManualDuration(
+        Duration::from_secs_f32(TIMESTEP),
+    ))
+    .add_plugins((AppPlugin, SimulationPlugin));
+
+    app
+}
+
+struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationReport>()
+            .add_systems(OnEnter(Screen::EnterLevel), reset_report)
+            .add_observer(count_spawn)
+            .add_observer(count_defeat_or_leak)
+            .add_observer(count_siege);
+    }
+}
+
+fn reset_report(mut report: ResMut<SimulationReport>) {
+    *report = SimulationReport::default();
+}
+
+fn count_spawn(
+    _: Trigger<OnAdd, Enemy>,
+    mut report: ResMut<SimulationReport>,
+) {
+    report.spawned += 1;
+}
+
+/// Enemies removed without health left were killed, the others
+/// leaked through unless the level is being torn down. Sieging
+/// enemies were already counted as leaks.
+fn count_defeat_or_leak(
+    trigger: Trigger<OnRemove, Enemy>,
+    q_healths: Query<&Health>,
+    q_sieging: Query<(), With<SiegingBase>>,
+    mut report: ResMut<SimulationReport>,
+    screen: Option<Res<State<Screen>>>,
+) {
+    if q_sieging.contains(trigger.target()) {
+        return;
+    }
+
+    if q_healths.get(trigger.target()).is_ok_and(|h| h.0 <= 0.0) {
+        report.kills += 1;
+    } else if screen.is_some_and(|s| *s.get() == Screen::EnterLevel) {
+        report.leaks += 1;
+    }
+}
+
+fn count_siege(
+    _: Trigger<OnAdd, SiegingBase>,
+    mut report: ResMut<SimulationReport>,
+) {
+    report.leaks += 1;
+}
+
+/// A wave played against a tower layout for a fixed duration.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    wave: SpawnWave,
+    /// Tile coordinates and prefab names of the towers.
+    towers: Vec<(IVec3, String)>,
+    seed: u64,
+}
+
+impl Scenario {
+    /// Start the level from the given wave, with no towers.
+    pub fn new(wave: SpawnWave) -> Self {
+        Self {
+            wave,
+            towers: Vec::new(),
+            seed: 0,
+        }
+    }
+
+    /// Place a tower (the file name in `prefabs/`) on the tile
+    /// before the wave starts.
+    pub fn with_tower(
+        mut self,
+        tile_coord: IVec3,
+        prefab_name: impl Into<String>,
+    ) -> Self {
+        self.towers.push((tile_coord, prefab_name.into()));
+        self
+    }
+
+    /// Seed the [`GameRng`] with this instead of `0`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Load the level in a [`headless_app`], set up the scenario
+    /// and simulate it for `seconds`.
+    pub fn run_for(self, seconds: f32) -> Result<SimulationReport> {
+        let mut app = headless_app();
+        app.world_mut().resource_mut::<GameRng>().pinned =
+            Some(self.seed);
+
+        load_level(&mut app)?;
+
+        let world = app.world_mut();
+        for (tile_coord, prefab_name) in self.towers {
+            let tile = world
+                .resource::<TileMap>()
+                .get_tile(&tile_coord)
+                .ok_or(format!("No tile at {tile_coord}!"))?
+                .target();
+
+            TowerBlueprint::from_prefab(prefab_name)
+                .spawn_at(tile)
+                .apply(world)?;
+        }
+
+        world.resource_mut::<NextState<SpawnWave>>().set(self.wave);
+
+        for _ in 0..(seconds / TIMESTEP).ceil() as u32 {
+            app.update();
+        }
+
+        let world = app.world_mut();
+        let mut report = world.resource::<SimulationReport>().clone();
+        report.wave = *world.resource::<State<SpawnWave>>().get();
+        report.mark = world.resource::<PlayerMark>().0;
+        report.alive = world
+            .query_filtered::<(), (With<Enemy>, Without<SiegingBase>)>()
+            .iter(world)
+            .len() as u32;
+
+        Ok(report)
+    }
+}
+
+/// Update until the assets are loaded and the level is ready.
+fn load_level(app: &mut App) -> Result {
+    let start = Instant::now();
+
+    loop {
+        app.update();
+
+        let world = app.world_mut();
+        match world.resource::<State<AssetState>>().get() {
+            AssetState::Failed => {
+                return Err("Assets failed to load.".into());
+            }
+            AssetState::Loaded => {
+                if world
+                    .get_resource::<State<Screen>>()
+                    .is_some_and(|s| *s.get() == Screen::Menu)
+                {
+                    world
+                        .resource_mut::<NextState<Screen>>()
+                        .set(Screen::EnterLevel);
+                }
+
+                let spawner_ready = world
+                    .query_filtered::<(), With<EnemySpawner>>()
+                    .iter(world)
+                    .next()
+                    .is_some();
+                let tiles_ready = world
+                    .resource::<TileMap>()
+                    .iter()
+                    .any(Option::is_some);
+
+                if spawner_ready && tiles_ready {
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+
+        if start.elapsed().as_secs_f32() > LOAD_TIMEOUT {
+            return Err("Level took too long to load.".into());
+        }
+    }
+}
+
+/// Outcome of a [`Scenario`].
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SimulationReport {
+    /// Enemies spawned since entering the level.
+    pub spawned: u32,
+    /// Enemies killed by towers (or players).
+    pub kills: u32,
+    /// Enemies that reached the final target, sieging it or
+    /// leaving.
+    pub leaks: u32,
+    /// Enemies still alive and on their way at the end.
+    pub alive: u32,
+    /// The wave at the end.
+    pub wave: SpawnWave,
+    /// [`PlayerMark`] left at the end.
+    pub mark: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undefended_wave_only_leaks() -> Result {
+        let report = Scenario::new(SpawnWave::One).run_for(60.0)?;
+
+        assert!(report.spawned > 0);
+        assert_eq!(report.kills, 0);
+        assert_eq!(
+            report.spawned,
+            report.kills + report.leaks + report.alive
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+//! Notable moments of a run (biggest multi-kill, closest call and
+//! fastest craft), each with a short top-down recording of what
+//! happened around it for the highlight reel on the results screen.
+
+use std::collections::VecDeque;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::character_controller::CharacterController;
+use crate::enemy::{Enemy, FinalTarget};
+use crate::machine::OperationTimer;
+use crate::machine::overcook::CookedOutput;
+use crate::player::PlayerType;
+use crate::tower::tower_attack::Health;
+use crate::ui::Screen;
+
+/// Seconds between recorded frames.
+pub const SAMPLE_INTERVAL: f32 = 0.1;
+/// Seconds of recording kept before each highlight.
+const SNIPPET_DURATION: f32 = 3.0;
+/// Kills within this many seconds count as one multi-kill.
+const MULTI_KILL_WINDOW: f32 = 2.0;
+
+pub(super) struct HighlightsPlugin;
+
+impl Plugin for HighlightsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Highlights>()
+            .init_resource::<SnippetRecorder>()
+            .add_systems(
+                OnEnter(Screen::EnterLevel),
+                reset_highlights,
+            )
+            .add_systems(
+                Update,
+                record_snippet.run_if(in_state(Screen::EnterLevel)),
+            )
+            .add_observer(track_enemy_defeat)
+            .add_observer(track_craft_start)
+            .add_observer(track_craft_collect);
+    }
+}
+
+fn reset_highlights(
+    mut highlights: ResMut<Highlights>,
+    mut recorder: ResMut<SnippetRecorder>,
+) {
+    *highlights = Highlights::default();
+    *recorder = SnippetRecorder::default();
+}
+
+fn record_snippet(
+    q_players: Query<
+        (&PlayerType, &GlobalTransform),
+        With<CharacterController>,
+    >,
+    q_enemies: Query<&GlobalTransform, With<Enemy>>,
+    q_final_targets: Query<&GlobalTransform, With<FinalTarget>>,
+    mut recorder: ResMut<SnippetRecorder>,
+    time: Res<Time>,
+) {
+    if recorder.timer.tick(time.delta()).just_finished() == false {
+        return;
+    }
+
+    let point = |transform: &GlobalTransform, kind| SnippetPoint {
+        position: transform.translation().xz(),
+        kind,
+    };
+
+    let frame = q_final_targets
+        .iter()
+        .map(|t| point(t, SnippetKind::Target))
+        .chain(q_enemies.iter().map(|t| point(t, SnippetKind::Enemy)))
+        .chain(q_players.iter().map(|(player_type, t)| {
+            point(t, SnippetKind::Player(*player_type))
+        }))
+        .collect();
+
+    recorder.frames.push_back(frame);
+    while recorder.frames.len()
+        > (SNIPPET_DURATION / SAMPLE_INTERVAL) as usize
+    {
+        recorder.frames.pop_front();
+    }
+}
+
+/// Enemies removed without health left were defeated, the others
+/// reached the final target or got cleaned up with the level.
+fn track_enemy_defeat(
+    trigger: Trigger<OnRemove, Enemy>,
+    q_enemies: Query<(&Health, &GlobalTransform)>,
+    q_final_targets: Query<&GlobalTransform, With<FinalTarget>>,
+    mut highlights: ResMut<Highlights>,
+    recorder: Res<SnippetRecorder>,
+    time: Res<Time>,
+) {
+    let Ok((health, transform)) = q_enemies.get(trigger.target())
+    else {
+        return;
+    };
+
+    if health.0 > 0.0 {
+        return;
+    }
+
+    let now = time.elapsed_secs();
+    highlights.recent_kills.push_back(now);
+    while highlights
+        .recent_kills
+        .front()
+        .is_some_and(|t| now - t > MULTI_KILL_WINDOW)
+    {
+        highlights.recent_kills.pop_front();
+    }
+
+    let kills = highlights.recent_kills.len() as f32;
+    if kills >= 2.0
+        && highlights
+            .multi_kill
+            .as_ref()
+            .is_none_or(|h| kills > h.value)
+    {
+        highlights.multi_kill = Some(recorder.highlight(kills));
+    }
+
+    let Ok(final_target) = q_final_targets.single() else {
+        return;
+    };
+
+    let distance = final_target
+        .translation()
+        .xz()
+        .distance(transform.translation().xz());
+    if highlights
+        .closest_call
+        .as_ref()
+        .is_none_or(|h| distance < h.value)
+    {
+        highlights.closest_call = Some(recorder.highlight(distance));
+    }
+}
+
+fn track_craft_start(
+    trigger: Trigger<OnAdd, OperationTimer>,
+    mut highlights: ResMut<Highlights>,
+    time: Res<Time>,
+) {
+    highlights
+        .craft_starts
+        .insert(trigger.target(), time.elapsed_secs());
+}
+
+/// A craft is done once its output gets collected.
+fn track_craft_collect(
+    trigger: Trigger<OnRemove, CookedOutput>,
+    q_outputs: Query<&CookedOutput>,
+    mut highlights: ResMut<Highlights>,
+    recorder: Res<SnippetRecorder>,
+    screen: Option<Res<State<Screen>>>,
+    time: Res<Time>,
+) {
+    let entity = trigger.target();
+    let Some(start) = highlights.craft_starts.remove(&entity) else {
+        return;
+    };
+
+    // Ignore the level being torn down and burnt outputs.
+    if screen.is_none_or(|s| *s.get() != Screen::EnterLevel)
+        || q_outputs.get(entity).is_ok_and(|o| o.burnt)
+    {
+        return;
+    }
+
+    let duration = time.elapsed_secs() - start;
+    if highlights
+        .fastest_craft
+        .as_ref()
+        .is_none_or(|h| duration < h.value)
+    {
+        highlights.fastest_craft = Some(recorder.highlight(duration));
+    }
+}
+
+/// The best moments of the current run.
+#[derive(Resource, Default, Debug)]
+pub struct Highlights {
+    /// Most enemies defeated within [`MULTI_KILL_WINDOW`].
+    pub multi_kill: Option<Highlight>,
+    /// Shortest distance of a defeated enemy to the
+    /// [`FinalTarget`].
+    pub closest_call: Option<Highlight>,
+    /// Shortest time from starting a craft to collecting it.
+    pub fastest_craft: Option<Highlight>,
+    /// Time of the kills within [`MULTI_KILL_WINDOW`].
+    recent_kills: VecDeque<f32>,
+    /// Start time of the crafts in progress by machine.
+    craft_starts: HashMap<Entity, f32>,
+}
+
+impl Highlights {
+    /// Title, value and highlight of every recorded moment.
+    pub fn reel(&self) -> Vec<(&'static str, String, &Highlight)> {
+        let mut reel = Vec::new();
+
+        if let Some(h) = &self.multi_kill {
+            reel.push((
+                "Biggest multi-kill",
+                format!("{} enemies", h.value as u32),
+                h,
+            ));
+        }
+        if let Some(h) = &self.closest_call {
+            reel.push((
+                "Closest call",
+                format!("{:.1}m away", h.value),
+                h,
+            ));
+        }
+        if let Some(h) = &self.fastest_craft {
+            reel.push((
+                "Fastest craft",
+                format!("{:.1}s", h.value),
+                h,
+            ));
+        }
+
+        reel
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Highlight {
+    pub value: f32,
+    /// Frames recorded every [`SAMPLE_INTERVAL`] leading up to
+    /// the moment.
+    pub snippet: Vec<Vec<SnippetPoint>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SnippetPoint {
+    /// Position on the xz plane.
+    pub position: Vec2,
+    pub kind: SnippetKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnippetKind {
+    Player(PlayerType),
+    Enemy,
+    Target,
+}
+
+/// Rolling recording of the last [`SNIPPET_DURATION`] seconds.
+#[derive(Resource, Debug)]
+struct SnippetRecorder {
+    timer: Timer,
+    frames: VecDeque<Vec<SnippetPoint>>,
+}
+
+impl SnippetRecorder {
+    fn highlight(&self, value: f32) -> Highlight {
+        Highlight {
+            value,
+            snippet: self.frames.iter().cloned().collect(),
+        }
+    }
+}
+
+impl Default for SnippetRecorder {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(
+                SAMPLE_INTERVAL,
+                TimerMode::Repeating,
+            ),
+            frames: VecDeque::new(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! World inspector with click-to-inspect support.
+//!
+//! Free the cursor and click on an entity in either viewport to
+//! select it in the world inspector. Press [`PANEL_KEY`] to toggle
+//! a panel showing the gameplay state of the selected entity.
+
+use avian3d::picking::PhysicsPickingPlugin;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::DefaultInspectorConfigPlugin;
+use bevy_inspector_egui::bevy_egui::{
+    EguiContext, EguiContextPass, EguiContexts,
+};
+use bevy_inspector_egui::bevy_inspector::hierarchy::{
+    SelectedEntities, hierarchy_ui,
+};
+use bevy_inspector_egui::bevy_inspector::{
+    ui_for_entities_shared_components, ui_for_entity,
+};
+use bevy_inspector_egui::egui;
+
+use crate::enemy::{Path, PathIndex};
+use crate::inventory::Inventory;
+use crate::tile::TileMap;
+use crate::tower::tower_attack::{Health, MaxHealth};
+
+/// Key to toggle the gameplay panel.
+const PANEL_KEY: KeyCode = KeyCode::F3;
+
+pub(super) struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            DefaultInspectorConfigPlugin,
+            PhysicsPickingPlugin,
+        ))
+        .init_resource::<InspectorSelection>()
+        .add_systems(Update, toggle_gameplay_panel)
+        .add_systems(
+            EguiContextPass,
+            (world_inspector, gameplay_panel).chain(),
+        )
+        .add_observer(pick_entity);
+    }
+}
+
+/// Select the clicked entity (or the rigid body it belongs to).
+fn pick_entity(
+    mut trigger: Trigger<Pointer<Click>>,
+    mut selection: ResMut<InspectorSelection>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_nodes: Query<(), With<Node>>,
+    q_collider_ofs: Query<&ColliderOf>,
+) {
+    let entity = trigger.target();
+    // Ignore ui clicks and clicks while the cursor is locked, and
+    // let them bubble up to their own observers.
+    if trigger.button != PointerButton::Primary
+        || q_nodes.contains(entity)
+        || q_windows.single().is_ok_and(|w| w.cursor_options.visible)
+            == false
+    {
+        return;
+    }
+
+    let entity = q_collider_ofs
+        .get(entity)
+        .map(|collider_of| collider_of.body)
+        .unwrap_or(entity);
+
+    selection.entities.select_replace(entity);
+    // Only the first entity in the bubbling chain is selected.
+    trigger.propagate(false);
+}
+
+fn toggle_gameplay_panel(
+    mut selection: ResMut<InspectorSelection>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+) {
+    if kbd_inputs.just_pressed(PANEL_KEY) {
+        selection.show_panel = !selection.show_panel;
+    }
+}
+
+/// A world inspector that shares its selection with
+/// [`InspectorSelection`].
+fn world_inspector(world: &mut World) {
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .single(world)
+    else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+
+    world.resource_scope(
+        |world, mut selection: Mut<InspectorSelection>| {
+            egui::Window::new("World Inspector")
+                .default_size((320.0, 160.0))
+                .show(egui_context.get_mut(), |ui| {
+                    egui::ScrollArea::both().show(ui, |ui| {
+                        hierarchy_ui(
+                            world,
+                            ui,
+                            &mut selection.entities,
+                        );
+
+                        ui.separator();
+
+                        match selection.entities.as_slice() {
+                            &[] => {
+                                ui.label(
+                                    "Click an entity to inspect it.",
+                                );
+                            }
+                            &[entity] => {
+                                ui_for_entity(world, entity, ui)
+                            }
+                            entities => {
+                                ui_for_entities_shared_components(
+                                    world, entities, ui,
+                                )
+                            }
+                        }
+                    });
+                });
+        },
+    );
+}
+
+/// Readable overview of the gameplay components of the
+/// selected entity.
+fn gameplay_panel(
+    mut contexts: EguiContexts,
+    mut selection: ResMut<InspectorSelection>,
+    q_entities: Query<(
+        NameOrEntity,
+        Option<(&Health, &MaxHealth)>,
+        Option<(&Path, &PathIndex)>,
+        Option<&Inventory>,
+    )>,
+    tile_map: Res<TileMap>,
+) {
+    if selection.show_panel == false {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let selection = selection.bypass_change_detection();
+
+    egui::Window::new("Gameplay")
+        .open(&mut selection.show_panel)
+        .show(ctx, |ui| {
+            let Some((name, health, path, inventory)) = selection
+                .entities
+                .as_slice()
+                .first()
+                .and_then(|e| q_entities.get(*e).ok())
+            else {
+                ui.label("No entity selected.");
+                return;
+            };
+
+            ui.heading(name.to_string());
+
+            if let Some((health, max_health)) = health {
+                ui.add(
+                    egui::ProgressBar::new(
+                        (health.0 / max_health.0).clamp(0.0, 1.0),
+                    )
+                    .text(format!(
+                        "Health: {:.1} / {:.1}",
+                        health.0, max_health.0
+                    )),
+                );
+            }
+
+            if let Some((path, path_index)) = path {
+                ui.separator();
+                ui.label(format!(
+                    "Path: waypoint {} / {}",
+                    **path_index,
+                    path.len()
+                ));
+                if let Some(target) =
+                    path.get_target(path_index, &tile_map)
+                {
+                    ui.label(format!(
+                        "Next target: ({:.1}, {:.1}, {:.1})",
+                        target.x, target.y, target.z
+                    ));
+                }
+            }
+
+            if let Some(inventory) = inventory {
+                ui.separator();
+                ui.label("Inventory");
+                egui::Grid::new("gameplay_inventory")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, slot) in
+                            inventory.slots().iter().enumerate()
+                        {
+                            let selected = inventory.selected_index()
+                                == Some(index);
+                            ui.label(match selected {
+                                true => format!("> {index}"),
+                                false => index.to_string(),
+                            });
+                            match slot {
+                                Some(stack) => {
+                                    ui.label(&stack.item_id);
+                                    ui.label(format!(
+                                        "x{}",
+                                        stack.quantity
+                                    ));
+                                }
+                                None => {
+                                    ui.label("-");
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+}
+
+#[derive(Resource, Default)]
+pub struct InspectorSelection {
+    entities: SelectedEntities,
+    /// Show the gameplay panel.
+    show_panel: bool,
+}
@@ -1,14 +1,23 @@
 use avian3d::prelude::*;
 use bevy::color::palettes::tailwind::SKY_300;
+use bevy::ecs::query::{QueryFilter, ReadOnlyQueryData};
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy_mod_outline::{
-    InheritOutline, OutlineMode, OutlineStencil, OutlineVolume,
+    InheritOutline, OutlineMode, OutlineRenderLayers, OutlineStencil,
+    OutlineVolume,
 };
 
-mod grab;
+pub mod coop_carry;
+pub mod grab;
+pub mod push_pull;
 
+use crate::camera_controller::{A_RENDER_LAYER, B_RENDER_LAYER};
 use crate::physics::GameLayer;
+use crate::player::PlayerType;
+use crate::tower::ownership::owner_color;
 
+/// Outline of items marked by both players.
 const MARK_COLOR: Color = Color::Srgba(SKY_300);
 // const GRABBED_COLOR: Color = Color::Srgba(EMERALD_500);
 
@@ -19,28 +28,53 @@ impl Plugin for InteractionPlugin {
         app.add_plugins((
             bevy_mod_outline::OutlinePlugin,
             grab::GrabPlugin,
+            coop_carry::CoopCarryPlugin,
+            push_pull::PushPullPlugin,
         ));
 
-        app.add_systems(
+        app.configure_sets(
             Update,
-            (setup_interactable_outline, detect_interactables),
+            InteractionFilterSet.before(detect_interactables),
+        )
+        .add_systems(
+            Update,
+            (
+                setup_interactable_outline,
+                enable_interactables.before(InteractionFilterSet),
+                detect_interactables,
+                mark_items.after(detect_interactables),
+            ),
         )
-        .add_observer(mark_item)
         .add_observer(unmark_item);
 
         app.register_type::<Interactable>()
+            .register_type::<InteractionPriority>()
+            .register_type::<InteractionPrompt>()
             .register_type::<InteractionPlayer>();
     }
 }
 
+/// Re-enable all interactables before the filters are evaluated.
+fn enable_interactables(
+    mut q_enabled: Query<&mut InteractionEnabled>,
+) {
+    for mut enabled in q_enabled.iter_mut() {
+        enabled.set_if_neq(InteractionEnabled(true));
+    }
+}
+
 fn detect_interactables(
     mut commands: Commands,
     mut q_players: Query<
         (&InteractionPlayer, Entity),
-        (Changed<GlobalTransform>, Without<Occupied>),
+        Without<Occupied>,
     >,
     q_global_transforms: Query<&GlobalTransform>,
     q_collider_ofs: Query<&ColliderOf>,
+    q_interactables: Query<(
+        &InteractionEnabled,
+        Option<&InteractionPriority>,
+    )>,
     spatial_query: SpatialQuery,
 ) -> Result {
     for (player, entity) in q_players.iter_mut() {
@@ -51,13 +85,44 @@ fn detect_interactables(
 
         let player_translation = player_transform.translation();
 
-        let item_entities = spatial_query.shape_intersections(
+        let hit_entities = spatial_query.shape_intersections(
             &Collider::sphere(player.range),
             player_translation,
             Quat::IDENTITY,
             &SpatialQueryFilter::from_mask(GameLayer::Interactable),
         );
 
+        // Use the rigidbody's entity as the reference point.
+        let mut item_entities = Vec::new();
+        let mut visited = HashSet::new();
+        for hit_entity in hit_entities {
+            let item_entity = q_collider_ofs
+                .get(hit_entity)
+                .map(|c| c.body)
+                .unwrap_or(hit_entity);
+
+            if visited.insert(item_entity) {
+                item_entities.push(item_entity);
+            }
+        }
+
+        // Only keep enabled items with the highest priority.
+        let priority = |item_entity: Entity| {
+            q_interactables
+                .get(item_entity)
+                .ok()
+                .filter(|(enabled, _)| enabled.0)
+                .map(|(_, priority)| {
+                    priority.map(|p| p.0).unwrap_or_default()
+                })
+        };
+        let highest_priority =
+            item_entities.iter().filter_map(|e| priority(*e)).max();
+        item_entities.retain(|e| {
+            highest_priority.is_some()
+                && priority(*e) == highest_priority
+        });
+
         // No items around.
         if item_entities.is_empty() {
             commands.entity(entity).remove::<MarkerOf>();
@@ -111,29 +176,43 @@ fn detect_interactables(
             }
         }
 
-        let mut marked_entity = item_entities[closest_idx];
-        // Use the rigidbody's entity as the reference point.
-        marked_entity = q_collider_ofs
-            .get(marked_entity)
-            .map(|c| c.body)
-            .unwrap_or(marked_entity);
-
+        let marked_entity = item_entities[closest_idx];
         commands.entity(entity).insert(MarkerOf(marked_entity));
     }
 
     Ok(())
 }
 
-fn mark_item(
-    trigger: Trigger<OnAdd, MarkerPlayers>,
-    mut q_outlines: Query<&mut OutlineVolume>,
+/// Outline the marked items in the color of the marking player,
+/// only on the player's own viewport.
+fn mark_items(
+    mut commands: Commands,
+    mut q_outlines: Query<
+        (&MarkerPlayers, &mut OutlineVolume, Entity),
+        Changed<MarkerPlayers>,
+    >,
+    q_player_types: Query<&PlayerType>,
 ) {
-    let Ok(mut outline) = q_outlines.get_mut(trigger.target()) else {
-        return;
-    };
+    for (marker_players, mut outline, entity) in q_outlines.iter_mut()
+    {
+        let player_types = marker_players
+            .iter()
+            .filter_map(|e| q_player_types.get(*e).ok())
+            .collect::<Vec<_>>();
+
+        let (colour, layers) = match player_types.as_slice() {
+            [player_type] => (
+                owner_color(**player_type),
+                player_type.render_layer(),
+            ),
+            // Marked by both players.
+            _ => (MARK_COLOR, A_RENDER_LAYER.union(&B_RENDER_LAYER)),
+        };
 
-    outline.visible = true;
-    outline.colour = MARK_COLOR;
+        outline.visible = true;
+        outline.colour = colour;
+        commands.entity(entity).insert(OutlineRenderLayers(layers));
+    }
 }
 
 fn unmark_item(
@@ -178,15 +257,83 @@ fn setup_interactable_outline(
     }
 }
 
+pub trait InteractionFilterAppExt {
+    /// Disable interaction for [`Interactable`]s matching the
+    /// query when the predicate returns `true`.
+    fn disable_interaction_if<D, F>(
+        &mut self,
+        predicate: impl Fn(D::Item<'_>) -> bool + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        D: ReadOnlyQueryData + 'static,
+        F: QueryFilter + 'static;
+}
+
+impl InteractionFilterAppExt for App {
+    fn disable_interaction_if<D, F>(
+        &mut self,
+        predicate: impl Fn(D::Item<'_>) -> bool + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        D: ReadOnlyQueryData + 'static,
+        F: QueryFilter + 'static,
+    {
+        self.add_systems(
+            Update,
+            (move |mut q_interactables: Query<
+                (&mut InteractionEnabled, D),
+                (With<Interactable>, F),
+            >| {
+                for (mut enabled, data) in q_interactables.iter_mut()
+                {
+                    if predicate(data) {
+                        enabled.0 = false;
+                    }
+                }
+            })
+            .in_set(InteractionFilterSet),
+        )
+    }
+}
+
+/// Systems that disable [`Interactable`]s, runs
+/// before interactables are detected.
+#[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct InteractionFilterSet;
+
 /// An entity that can be interacted.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-#[require(CollisionLayers::new(
-    GameLayer::Interactable,
-    LayerMask::ALL,
-))]
+#[require(
+    CollisionLayers::new(GameLayer::Interactable, LayerMask::ALL),
+    InteractionEnabled
+)]
 pub struct Interactable;
 
+/// Overlapping [`Interactable`]s with higher priority will be
+/// marked over lower priority ones regardless of distance.
+#[derive(Component, Reflect, Deref, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct InteractionPriority(pub i32);
+
+/// Whether the [`Interactable`] can be marked this frame.
+/// Evaluated by the filters registered through
+/// [`InteractionFilterAppExt::disable_interaction_if`].
+#[derive(Component, Deref, Debug, PartialEq, Eq)]
+pub struct InteractionEnabled(pub bool);
+
+impl Default for InteractionEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Overrides the action label shown in the interaction prompt
+/// (e.g. "Upgrade").
+#[derive(Component, Reflect, Deref, Default, Debug)]
+#[reflect(Component)]
+pub struct InteractionPrompt(pub String);
+
 /// Stores a list of player entities that is marking this entity.
 #[derive(Component, Deref, Default, Debug)]
 #[relationship_target(relationship = MarkerOf)]
@@ -0,0 +1,256 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::Occupied;
+use super::grab::{
+    CarryingHeavy, GrabEvent, GrabState, Grabbable, Heavy,
+    ReleaseEvent,
+};
+
+/// Plugin that sets up cooperative carrying of oversized items.
+pub(super) struct CoopCarryPlugin;
+
+impl Plugin for CoopCarryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, constrain_coop_carriers)
+            .add_observer(handle_coop_grab)
+            .add_observer(handle_coop_release);
+
+        app.register_type::<CoopCarry>();
+    }
+}
+
+/// Occupy the closest free anchor of a [`CoopCarry`] item.
+fn handle_coop_grab(
+    trigger: Trigger<GrabEvent>,
+    mut commands: Commands,
+    q_coop_carries: Query<(
+        &CoopCarry,
+        &GlobalTransform,
+        Option<&CoopCarriers>,
+        Option<&Heavy>,
+    )>,
+    q_carriers: Query<&CoopCarrierOf>,
+    q_player_tf: Query<&GlobalTransform>,
+) {
+    let GrabEvent { target, player } = *trigger.event();
+
+    let Ok((coop_carry, item_tf, carriers, heavy)) =
+        q_coop_carries.get(target)
+    else {
+        return;
+    };
+    let Ok(player_tf) = q_player_tf.get(player) else {
+        return;
+    };
+
+    let taken_anchors = carriers
+        .into_iter()
+        .flat_map(|c| c.iter())
+        .filter_map(|e| q_carriers.get(e).ok())
+        .map(|c| c.anchor)
+        .collect::<Vec<_>>();
+
+    // Pick the closest anchor that is not taken yet.
+    let Some(anchor) = [CoopAnchor::Left, CoopAnchor::Right]
+        .into_iter()
+        .filter(|anchor| taken_anchors.contains(anchor) == false)
+        .min_by(|a, b| {
+            let dist = |anchor: &CoopAnchor| {
+                coop_carry
+                    .anchor_position(item_tf, *anchor)
+                    .distance_squared(player_tf.translation())
+            };
+            dist(a).total_cmp(&dist(b))
+        })
+    else {
+        return;
+    };
+
+    commands.entity(player).insert((
+        Occupied,
+        GrabState { held: Some(target) },
+        CoopCarrierOf {
+            object: target,
+            anchor,
+        },
+    ));
+
+    if let Some(heavy) = heavy {
+        commands
+            .entity(player)
+            .insert(CarryingHeavy(heavy.walk_multiplier));
+    }
+
+    // Keep the item still while waiting for the other player.
+    commands.entity(target).insert(RigidBodyDisabled);
+}
+
+/// Drop the item for every carrier once either of them releases it.
+fn handle_coop_release(
+    trigger: Trigger<ReleaseEvent>,
+    mut commands: Commands,
+    q_carriers: Query<&CoopCarrierOf>,
+    q_coop_carriers: Query<&CoopCarriers>,
+) {
+    let Ok(carrier_of) = q_carriers.get(trigger.event().player)
+    else {
+        return;
+    };
+
+    let object = carrier_of.object;
+    if let Ok(carriers) = q_coop_carriers.get(object) {
+        for carrier in carriers.iter() {
+            commands.entity(carrier).remove::<(
+                Occupied,
+                GrabState,
+                CarryingHeavy,
+                CoopCarrierOf,
+            )>();
+        }
+    }
+
+    commands.entity(object).remove::<RigidBodyDisabled>();
+}
+
+/// Keep the carriers at their anchors and move the item
+/// along with them once both anchors are occupied.
+fn constrain_coop_carriers(
+    q_coop_carries: Query<(&CoopCarry, &CoopCarriers, Entity)>,
+    q_carriers: Query<&CoopCarrierOf>,
+    mut q_positions: Query<&mut Position>,
+    mut q_transforms: Query<(&mut Transform, &GlobalTransform)>,
+) {
+    const HEIGHT_OFFSET: f32 = 1.0;
+
+    for (coop_carry, carriers, entity) in q_coop_carries.iter() {
+        let Ok((mut item_tf, item_global_tf)) =
+            q_transforms.get_mut(entity)
+        else {
+            continue;
+        };
+
+        let mut left = None;
+        let mut right = None;
+        for carrier in carriers.iter() {
+            match q_carriers.get(carrier).map(|c| c.anchor) {
+                Ok(CoopAnchor::Left) => left = Some(carrier),
+                Ok(CoopAnchor::Right) => right = Some(carrier),
+                Err(_) => {}
+            }
+        }
+
+        match (left, right) {
+            (Some(left), Some(right)) => {
+                let Ok([mut left_pos, mut right_pos]) =
+                    q_positions.get_many_mut([left, right])
+                else {
+                    continue;
+                };
+
+                let center = (left_pos.0 + right_pos.0) * 0.5;
+                let Ok(direction) =
+                    Dir3::new((right_pos.0 - left_pos.0).with_y(0.0))
+                else {
+                    continue;
+                };
+
+                // Hold the carriers at a fixed distance apart.
+                let offset = direction * coop_carry.half_length;
+                left_pos.0 = (center - offset).with_y(left_pos.0.y);
+                right_pos.0 = (center + offset).with_y(right_pos.0.y);
+
+                item_tf.translation =
+                    center + Vec3::Y * HEIGHT_OFFSET;
+                item_tf.rotation = Quat::from_rotation_y(f32::atan2(
+                    -direction.z,
+                    direction.x,
+                ));
+            }
+            (Some(carrier), None) | (None, Some(carrier)) => {
+                let anchor = match left.is_some() {
+                    true => CoopAnchor::Left,
+                    false => CoopAnchor::Right,
+                };
+                let anchor_position = coop_carry
+                    .anchor_position(item_global_tf, anchor);
+
+                let Ok(mut position) = q_positions.get_mut(carrier)
+                else {
+                    continue;
+                };
+
+                // Leash the carrier to the anchor as the item
+                // can't be moved alone.
+                let offset = (position.0 - anchor_position)
+                    .with_y(0.0)
+                    .clamp_length_max(coop_carry.leash);
+                position.0 =
+                    (anchor_position + offset).with_y(position.0.y);
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Marks a [`Grabbable`] as too large to be carried alone.
+/// Both players need to grab the opposite anchors (along the
+/// local X axis) before the item moves.
+#[derive(Component, Reflect)]
+#[require(Grabbable)]
+#[reflect(Component, Default)]
+pub struct CoopCarry {
+    /// Distance from the center of the item to each anchor.
+    pub half_length: f32,
+    /// How far a lone carrier can wander from its anchor.
+    pub leash: f32,
+}
+
+impl CoopCarry {
+    /// World space position of an anchor.
+    pub fn anchor_position(
+        &self,
+        global_transform: &GlobalTransform,
+        anchor: CoopAnchor,
+    ) -> Vec3 {
+        let side = match anchor {
+            CoopAnchor::Left => -1.0,
+            CoopAnchor::Right => 1.0,
+        };
+
+        global_transform.translation()
+            + global_transform.right() * side * self.half_length
+    }
+}
+
+impl Default for CoopCarry {
+    fn default() -> Self {
+        Self {
+            half_length: 1.5,
+            leash: 0.5,
+        }
+    }
+}
+
+/// The two opposite anchors of a [`CoopCarry`] item.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoopAnchor {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Attached to the players carrying a [`CoopCarry`] item.
+#[derive(Component, Debug)]
+#[component(immutable)]
+#[relationship(relationship_target = CoopCarriers)]
+pub struct CoopCarrierOf {
+    #[relationship]
+    pub object: Entity,
+    pub anchor: CoopAnchor,
+}
+
+/// Attached to the [`CoopCarry`] item being carried.
+#[derive(Component, Deref, Default, Debug)]
+#[relationship_target(relationship = CoopCarrierOf)]
+pub struct CoopCarriers(Vec<Entity>);
@@ -1,11 +1,24 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy::reflect::Reflect;
+use leafwing_input_manager::prelude::*;
 
+use crate::action::{PlayerAction, TargetAction};
+use crate::character_controller::IsGrounded;
+use crate::physics::GameLayer;
+
+use super::coop_carry::CoopCarry;
 use super::{
-    InteractionPlayer, MarkerOf, Occupied, detect_interactables,
+    InteractionFilterAppExt, InteractionPlayer, MarkerOf, Occupied,
+    detect_interactables,
 };
 
+/// Vertical speed above which items are considered airborne.
+const AIRBORNE_SPEED: f32 = 0.5;
+/// Default local position of the [`HoldAnchor`] when the
+/// character prefab doesn't have one.
+const DEFAULT_HOLD_OFFSET: Vec3 = Vec3::new(0.0, 1.5, -0.8);
+
 /// Plugin that sets up grabbing logic for interactable items.
 pub(super) struct GrabPlugin;
 
@@ -14,143 +27,292 @@ impl Plugin for GrabPlugin {
         app.add_systems(
             Update,
             (
+                setup_hold_anchor,
                 grab_input_system.after(detect_interactables),
-                update_snapping,
             ),
         )
+        .add_systems(FixedUpdate, follow_hold_anchor)
         .add_observer(handle_grab)
-        .add_observer(handle_release);
+        .add_observer(handle_release)
+        // Items can't be grabbed while airborne.
+        .disable_interaction_if::<&LinearVelocity, (
+            With<Grabbable>,
+            Without<RigidBodyDisabled>,
+            Without<Held>,
+        )>(|velocity| velocity.y.abs() > AIRBORNE_SPEED)
+        // Items can't be taken from another player's hands.
+        .disable_interaction_if::<(), With<Held>>(|_| true);
 
-        app.register_type::<Grabbable>().register_type::<Occupied>();
+        app.register_type::<Grabbable>()
+            .register_type::<Heavy>()
+            .register_type::<HoldAnchor>()
+            .register_type::<Occupied>();
+    }
+}
+
+/// Find the [`HoldAnchor`] of each player, or spawn a default
+/// one if the prefab doesn't have any.
+fn setup_hold_anchor(
+    mut commands: Commands,
+    q_players: Query<
+        Entity,
+        (With<InteractionPlayer>, Without<PlayerHoldAnchor>),
+    >,
+    q_children: Query<&Children>,
+    q_anchors: Query<(), With<HoldAnchor>>,
+) {
+    for entity in q_players.iter() {
+        let anchor = q_children
+            .iter_descendants(entity)
+            .find(|e| q_anchors.contains(*e))
+            .unwrap_or_else(|| {
+                commands
+                    .spawn((
+                        Name::new("HoldAnchor"),
+                        HoldAnchor::default(),
+                        Transform::from_translation(
+                            DEFAULT_HOLD_OFFSET,
+                        ),
+                        ChildOf(entity),
+                    ))
+                    .id()
+            });
+
+        commands.entity(entity).insert(PlayerHoldAnchor(anchor));
     }
 }
 
 fn grab_input_system(
     mut commands: Commands,
-    keys: Res<ButtonInput<KeyCode>>,
     q_players: Query<
-        (Entity, Option<&MarkerOf>, Option<&GrabState>),
+        (
+            Entity,
+            &TargetAction,
+            Option<&MarkerOf>,
+            Option<&GrabState>,
+            Option<&IsGrounded>,
+            Has<CarryingHeavy>,
+        ),
         With<InteractionPlayer>,
     >,
+    q_actions: Query<&ActionState<PlayerAction>>,
     q_grabbable: Query<&Grabbable>,
 ) {
-    if keys.just_pressed(KeyCode::KeyE) {
-        // Handle input for each player separately
-        for (player_entity, marked, grab_state) in q_players.iter() {
-            let currently_holding =
-                grab_state.is_some_and(|gs| gs.held.is_some());
+    // Handle input for each player separately
+    for (
+        player_entity,
+        target_action,
+        marked,
+        grab_state,
+        is_grounded,
+        carrying_heavy,
+    ) in q_players.iter()
+    {
+        let Ok(action) = q_actions.get(target_action.get()) else {
+            continue;
+        };
+
+        if action.just_pressed(&PlayerAction::Interact) == false {
+            continue;
+        }
+
+        let currently_holding =
+            grab_state.is_some_and(|gs| gs.held.is_some());
+
+        if currently_holding {
+            // Heavy items can only be put down on the ground.
+            if carrying_heavy
+                && is_grounded.is_some_and(|g| g.0) == false
+            {
+                continue;
+            }
 
-            if currently_holding {
+            commands.trigger_targets(
+                ReleaseEvent {
+                    player: player_entity,
+                },
+                player_entity,
+            );
+        } else if let Some(target) = marked.map(|m| m.entity()) {
+            if q_grabbable.get(target).is_ok() {
                 commands.trigger_targets(
-                    ReleaseEvent {
+                    GrabEvent {
+                        target,
                         player: player_entity,
                     },
                     player_entity,
                 );
-            } else if let Some(target) = marked.map(|m| m.entity()) {
-                if q_grabbable.get(target).is_ok() {
-                    commands.trigger_targets(
-                        GrabEvent {
-                            target,
-                            player: player_entity,
-                        },
-                        player_entity,
-                    );
-                }
             }
         }
     }
 }
 
-/// Attaches the grabbed entity to the player and marks the player occupied.
+/// Makes the grabbed entity follow the player's [`HoldAnchor`]
+/// and marks the player occupied.
 fn handle_grab(
     trigger: Trigger<GrabEvent>,
     mut commands: Commands,
     q_grab_state: Query<&GrabState>,
+    q_heavies: Query<&Heavy>,
+    q_coop_carries: Query<(), With<CoopCarry>>,
+    q_hold_anchors: Query<&PlayerHoldAnchor>,
+    q_layers: Query<&CollisionLayers>,
+    q_body_colliders: Query<&RigidBodyColliders>,
 ) {
     let grab_event = trigger.event();
     let player_entity = grab_event.player;
     let target_entity = grab_event.target;
 
+    // Handled by the coop carry logic.
+    if q_coop_carries.contains(target_entity) {
+        return;
+    }
+
     // Check if this player is already holding something
     let already_holding = q_grab_state
         .get(player_entity)
         .is_ok_and(|grab_state| grab_state.held.is_some());
 
+    let Ok(anchor) = q_hold_anchors.get(player_entity) else {
+        return;
+    };
+
     if !already_holding {
-        commands
-            .entity(player_entity)
-            .add_child(target_entity)
-            .insert(Occupied)
-            .insert(GrabState {
+        commands.entity(player_entity).insert(Occupied).insert(
+            GrabState {
                 held: Some(target_entity),
-            });
+            },
+        );
+
+        // Stop colliding with the players so that the item
+        // doesn't push its holder around.
+        let layers =
+            q_layers.get(target_entity).copied().unwrap_or_default();
+        let mut held_layers = layers;
+        held_layers.filters.remove(GameLayer::Player);
+        set_body_layers(
+            &mut commands,
+            target_entity,
+            held_layers,
+            &q_body_colliders,
+        );
 
-        // Disable physics on the grabbed item
-        commands.entity(target_entity).insert(RigidBodyDisabled);
+        commands.entity(target_entity).insert((
+            Held {
+                anchor: anchor.0,
+                layers,
+            },
+            GravityScale(0.0),
+        ));
+
+        if let Ok(heavy) = q_heavies.get(target_entity) {
+            commands
+                .entity(player_entity)
+                .insert(CarryingHeavy(heavy.walk_multiplier));
+        }
     }
 }
 
-/// Detaches the held entity from the specific player and places it in front of them
+/// Lets go of the held entity from the specific player, dropping
+/// it where the [`HoldAnchor`] is.
 fn handle_release(
     trigger: Trigger<ReleaseEvent>,
     mut commands: Commands,
-    q_player_tf: Query<&GlobalTransform, With<InteractionPlayer>>,
     q_grab_state: Query<&GrabState>,
-    mut q_tf: Query<&mut Transform>,
+    q_helds: Query<&Held>,
+    q_coop_carries: Query<(), With<CoopCarry>>,
+    q_body_colliders: Query<&RigidBodyColliders>,
 ) {
-    const RELEASE_DISTANCE: f32 = 2.0;
-
     let player_entity = trigger.event().player;
 
     // Get the player's current grab state
     if let Ok(grab_state) = q_grab_state.get(player_entity) {
-        if let Some(held_entity) = grab_state.held {
-            // Remove child relationship
-            commands
-                .entity(player_entity)
-                .remove_children(&[held_entity]);
-
+        if let Some(held_entity) = grab_state
+            .held
+            // Handled by the coop carry logic.
+            .filter(|e| q_coop_carries.contains(*e) == false)
+        {
             // Clear player state
-            commands
-                .entity(player_entity)
-                .remove::<Occupied>()
-                .remove::<GrabState>();
+            commands.entity(player_entity).remove::<(
+                Occupied,
+                GrabState,
+                CarryingHeavy,
+            )>();
+
+            // Restore the original collision layers.
+            if let Ok(held) = q_helds.get(held_entity) {
+                set_body_layers(
+                    &mut commands,
+                    held_entity,
+                    held.layers,
+                    &q_body_colliders,
+                );
+            }
 
-            // Re-enable physics on the released item
             commands
                 .entity(held_entity)
-                .remove::<RigidBodyDisabled>();
-
-            // Position the released item in front of the player
-            if let (Ok(player_tf), Ok(mut item_tf)) = (
-                q_player_tf.get(player_entity),
-                q_tf.get_mut(held_entity),
-            ) {
-                let forward = player_tf.forward();
-                item_tf.translation = player_tf.translation()
-                    + forward * RELEASE_DISTANCE;
-                item_tf.rotation = player_tf.rotation();
-            }
+                .remove::<(Held, GravityScale)>();
         }
     }
 }
 
-/// Ensure the held entity stays snapped on top of the player.
-fn update_snapping(
-    q_players: Query<(Entity, &GrabState), With<InteractionPlayer>>,
-    mut q_tf: Query<&mut Transform>,
+/// Drive the held entities towards their [`HoldAnchor`] using
+/// velocities so that they still collide with the world.
+fn follow_hold_anchor(
+    mut q_helds: Query<(
+        &Held,
+        &Position,
+        &Rotation,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+    q_anchors: Query<(&HoldAnchor, &GlobalTransform)>,
 ) {
-    const HEIGHT_OFFSET: f32 = 1.5;
-
-    for (_player_entity, grab_state) in q_players.iter() {
-        if let Some(held_entity) = grab_state.held {
-            if let Ok(mut item_tf) = q_tf.get_mut(held_entity) {
-                // Place item at player's head height
-                item_tf.translation = Vec3::Y * HEIGHT_OFFSET;
-                item_tf.rotation = Quat::IDENTITY;
-            }
+    for (
+        held,
+        position,
+        rotation,
+        mut linear_velocity,
+        mut angular_velocity,
+    ) in q_helds.iter_mut()
+    {
+        let Ok((anchor, anchor_tf)) = q_anchors.get(held.anchor)
+        else {
+            continue;
+        };
+
+        let (_, anchor_rotation, anchor_translation) =
+            anchor_tf.to_scale_rotation_translation();
+
+        linear_velocity.0 = ((anchor_translation - position.0)
+            * anchor.stiffness)
+            .clamp_length_max(anchor.max_speed);
+
+        // Shortest rotation towards the anchor's rotation.
+        let mut delta = anchor_rotation * rotation.0.inverse();
+        if delta.w < 0.0 {
+            delta = -delta;
         }
+        angular_velocity.0 =
+            delta.to_scaled_axis() * anchor.stiffness;
+    }
+}
+
+/// Set the [`CollisionLayers`] of a rigid body and its colliders.
+fn set_body_layers(
+    commands: &mut Commands,
+    entity: Entity,
+    layers: CollisionLayers,
+    q_body_colliders: &Query<&RigidBodyColliders>,
+) {
+    commands.entity(entity).insert(layers);
+
+    for collider in q_body_colliders
+        .get(entity)
+        .into_iter()
+        .flat_map(|c| c.iter())
+    {
+        commands.entity(collider).insert(layers);
     }
 }
 
@@ -159,6 +321,61 @@ fn update_snapping(
 #[reflect(Component)]
 pub struct Grabbable;
 
+/// Marks a [`Grabbable`] as heavy, players carrying it will be
+/// slowed down and unable to jump, dash or attack.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct Heavy {
+    /// Multiplier applied to the carrier's max walking speed.
+    pub walk_multiplier: f32,
+}
+
+impl Default for Heavy {
+    fn default() -> Self {
+        Self {
+            walk_multiplier: 0.5,
+        }
+    }
+}
+
+/// Inserted on players that are carrying a [`Heavy`] item.
+/// Stores the walk speed multiplier.
+#[derive(Component, Deref, Debug, Clone, Copy)]
+pub struct CarryingHeavy(pub f32);
+
+/// Where grabbed items are held, authored as a child of the
+/// character prefab.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct HoldAnchor {
+    /// How quickly held items catch up with the anchor.
+    pub stiffness: f32,
+    /// Maximum speed of held items while following the anchor.
+    pub max_speed: f32,
+}
+
+impl Default for HoldAnchor {
+    fn default() -> Self {
+        Self {
+            stiffness: 20.0,
+            max_speed: 15.0,
+        }
+    }
+}
+
+/// The [`HoldAnchor`] entity of a player.
+#[derive(Component, Deref, Debug)]
+pub struct PlayerHoldAnchor(Entity);
+
+/// Attached to items that are being held.
+#[derive(Component, Debug)]
+pub struct Held {
+    /// The [`HoldAnchor`] entity to follow.
+    pub anchor: Entity,
+    /// Collision layers before the item was grabbed.
+    layers: CollisionLayers,
+}
+
 /// Tracks the currently held entity if any.
 #[derive(Component, Default)]
 pub struct GrabState {
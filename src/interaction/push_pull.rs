@@ -0,0 +1,381 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::{
+    IsMoving, camera_relative_direction, check_grounded,
+};
+use crate::physics::GameLayer;
+use crate::player::PlayerType;
+use crate::tile::{PlacedOn, TileMap};
+
+use super::{
+    InteractionPlayer, MarkerOf, Occupied, detect_interactables,
+};
+
+/// Plugin that sets up pushing and pulling of crates
+/// tile by tile.
+pub(super) struct PushPullPlugin;
+
+impl Plugin for PushPullPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_push_pull_tile,
+                toggle_push_pull.after(detect_interactables),
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            (push_pull_movement, move_crates, follow_crates)
+                .chain()
+                .after(check_grounded),
+        );
+
+        app.register_type::<PushPull>();
+    }
+}
+
+/// Snap crates onto the tile beneath them so that they
+/// occupy it.
+fn setup_push_pull_tile(
+    mut commands: Commands,
+    q_crates: Query<
+        (&Position, Entity),
+        (With<PushPull>, Without<PlacedOn>),
+    >,
+    tile_map: Res<TileMap>,
+) {
+    for (position, entity) in q_crates.iter() {
+        // The tiles might not be set up yet.
+        let Some(coord) = tile_map
+            .translation_to_tile_coord(position)
+            .map(|c| c.as_ivec3())
+        else {
+            continue;
+        };
+        let Some(tile) = tile_map.get_tile(&coord) else {
+            continue;
+        };
+
+        let center = tile_map.tile_coord_to_world_space(&coord);
+        commands.entity(entity).insert((
+            // Crates are only moved by the players.
+            RigidBody::Kinematic,
+            Position(Vec3::new(center.x, position.y, center.z)),
+            PlacedOn(tile.target()),
+        ));
+    }
+}
+
+/// Start or stop gripping the marked crate.
+fn toggle_push_pull(
+    mut commands: Commands,
+    q_players: Query<
+        (
+            &TargetAction,
+            &GlobalTransform,
+            Option<&MarkerOf>,
+            Option<&PushingOf>,
+            Has<Occupied>,
+            Entity,
+        ),
+        With<InteractionPlayer>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_crates: Query<&GlobalTransform, With<PushPull>>,
+) {
+    for (
+        target_action,
+        player_tf,
+        marker_of,
+        pushing_of,
+        occupied,
+        entity,
+    ) in q_players.iter()
+    {
+        let Ok(action) = q_actions.get(target_action.get()) else {
+            continue;
+        };
+
+        if action.just_pressed(&PlayerAction::Interact) == false {
+            continue;
+        }
+
+        if pushing_of.is_some() {
+            commands.entity(entity).remove::<(Occupied, PushingOf)>();
+            continue;
+        }
+
+        if occupied {
+            continue;
+        }
+
+        let Some((target, crate_tf)) = marker_of
+            .map(|m| m.entity())
+            .and_then(|e| Some((e, q_crates.get(e).ok()?)))
+        else {
+            continue;
+        };
+
+        // Grip the side of the crate facing the player.
+        let offset =
+            (player_tf.translation() - crate_tf.translation()).xz();
+        let axis = match offset.x.abs() > offset.y.abs() {
+            true => IVec2::new(offset.x.signum() as i32, 0),
+            false => IVec2::new(0, offset.y.signum() as i32),
+        };
+
+        commands.entity(entity).insert((
+            Occupied,
+            PushingOf {
+                object: target,
+                axis,
+            },
+        ));
+    }
+}
+
+/// Push or pull the crate along the gripped axis based on the
+/// movement input, one tile at a time.
+fn push_pull_movement(
+    mut commands: Commands,
+    mut q_players: Query<(
+        &PushingOf,
+        &TargetAction,
+        &PlayerType,
+        &Position,
+        &mut LinearVelocity,
+        &mut Rotation,
+        &mut IsMoving,
+    )>,
+    q_crates: Query<(&PushPull, &Position, Has<CrateMove>)>,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    tile_map: Res<TileMap>,
+    spatial_query: SpatialQuery,
+) {
+    /// Minimum input along the axis to start moving the crate.
+    const INPUT_THRESHOLD: f32 = 0.5;
+
+    let mut mask = LayerMask::ALL;
+    mask.remove([
+        GameLayer::Player,
+        GameLayer::Enemy,
+        GameLayer::InventoryItem,
+        GameLayer::Projectile,
+    ]);
+    let filter = SpatialQueryFilter::default().with_mask(mask);
+
+    for (
+        pushing_of,
+        target_action,
+        player_type,
+        player_position,
+        mut linear_velocity,
+        mut rotation,
+        mut is_moving,
+    ) in q_players.iter_mut()
+    {
+        // Movement is fully driven by the crate.
+        linear_velocity.x = 0.0;
+        linear_velocity.z = 0.0;
+
+        let Ok((push_pull, crate_position, crate_moving)) =
+            q_crates.get(pushing_of.object)
+        else {
+            continue;
+        };
+
+        let axis = pushing_of.axis_direction();
+        // Face the crate.
+        rotation.0 =
+            Quat::from_rotation_y(f32::atan2(axis.x, axis.z));
+        is_moving.set_if_neq(IsMoving(crate_moving));
+
+        if crate_moving {
+            continue;
+        }
+
+        let (Ok(action), Ok(cam_global_transform)) = (
+            q_actions.get(target_action.get()),
+            q_cameras.get(player_type.camera_type()),
+        ) else {
+            continue;
+        };
+
+        let movement = action
+            .clamped_axis_pair(&PlayerAction::Move)
+            .clamp_length_max(1.0);
+        let input =
+            camera_relative_direction(cam_global_transform, movement)
+                .dot(*axis);
+
+        if input.abs() < INPUT_THRESHOLD {
+            continue;
+        }
+
+        // Pulling moves towards the player, pushing moves away.
+        let step = match input > 0.0 {
+            true => pushing_of.axis,
+            false => -pushing_of.axis,
+        };
+
+        let Some(coord) =
+            tile_map.translation_to_tile_coord(crate_position)
+        else {
+            continue;
+        };
+        // Crates stay on their layer.
+        let target_coord =
+            coord.as_ivec3() + IVec3::new(step.x, 0, step.y);
+
+        let Some(target_tile) = tile_map
+            .get_tile(&target_coord)
+            .filter(|t| t.occupied() == false)
+        else {
+            continue;
+        };
+
+        let target_center =
+            tile_map.tile_coord_to_world_space(&target_coord);
+
+        // Make sure the player has room to back off when pulling.
+        if step == pushing_of.axis
+            && spatial_query
+                .cast_ray(
+                    player_position.0 + Vec3::Y * 0.5,
+                    axis,
+                    target_center.xz().distance(crate_position.xz()),
+                    true,
+                    &filter,
+                )
+                .is_some()
+        {
+            continue;
+        }
+
+        commands.entity(pushing_of.object).insert((
+            CrateMove {
+                start: crate_position.0,
+                end: Vec3::new(
+                    target_center.x,
+                    crate_position.y,
+                    target_center.z,
+                ),
+                timer: Timer::from_seconds(
+                    push_pull.move_duration,
+                    TimerMode::Once,
+                ),
+            },
+            // Occupy the new tile right away so that enemies
+            // can reroute.
+            PlacedOn(target_tile.target()),
+        ));
+    }
+}
+
+/// Slide the crates towards their target tiles.
+fn move_crates(
+    mut commands: Commands,
+    mut q_crates: Query<(
+        &mut CrateMove,
+        &mut Position,
+        &mut LinearVelocity,
+        Entity,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut crate_move, mut position, mut linear_velocity, entity) in
+        q_crates.iter_mut()
+    {
+        linear_velocity.0 = Vec3::ZERO;
+
+        crate_move.timer.tick(time.delta());
+        position.0 = crate_move
+            .start
+            .lerp(crate_move.end, crate_move.timer.fraction());
+
+        if crate_move.timer.finished() {
+            commands.entity(entity).remove::<CrateMove>();
+        }
+    }
+}
+
+/// Keep the players gripping the side of their crates.
+fn follow_crates(
+    mut q_players: Query<(&PushingOf, &mut Position)>,
+    q_crates: Query<(&PushPull, &Position), Without<PushingOf>>,
+) {
+    for (pushing_of, mut position) in q_players.iter_mut() {
+        let Ok((push_pull, crate_position)) =
+            q_crates.get(pushing_of.object)
+        else {
+            continue;
+        };
+
+        let grip = crate_position.0
+            + pushing_of.axis_direction() * push_pull.grip_distance;
+        position.0 = grip.with_y(position.y);
+    }
+}
+
+/// A crate that can be pushed and pulled across tiles.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct PushPull {
+    /// Duration in seconds to move a single tile.
+    pub move_duration: f32,
+    /// Distance from the center of the crate to the player
+    /// while gripping it.
+    pub grip_distance: f32,
+}
+
+impl Default for PushPull {
+    fn default() -> Self {
+        Self {
+            move_duration: 0.6,
+            grip_distance: 1.6,
+        }
+    }
+}
+
+/// Inserted while a [`PushPull`] crate is moving between tiles.
+#[derive(Component, Debug)]
+pub struct CrateMove {
+    start: Vec3,
+    end: Vec3,
+    timer: Timer,
+}
+
+/// Attached to the players gripping a [`PushPull`] crate.
+/// Regular movement is disabled until it's removed.
+#[derive(Component, Debug)]
+#[component(immutable)]
+#[relationship(relationship_target = PushedBy)]
+pub struct PushingOf {
+    #[relationship]
+    pub object: Entity,
+    /// Tile direction from the crate to the player.
+    pub axis: IVec2,
+}
+
+impl PushingOf {
+    /// World space direction from the crate to the player.
+    pub fn axis_direction(&self) -> Dir3 {
+        Dir3::new(Vec3::new(
+            self.axis.x as f32,
+            0.0,
+            self.axis.y as f32,
+        ))
+        .unwrap_or(Dir3::Z)
+    }
+}
+
+/// Attached to the [`PushPull`] crate being gripped.
+#[derive(Component, Deref, Default, Debug)]
+#[relationship_target(relationship = PushingOf)]
+pub struct PushedBy(Vec<Entity>);
@@ -1,11 +1,14 @@
+use crate::action::PlayerAction;
+use crate::perks::PerkProfile;
 use crate::physics::GameLayer;
+use crate::player::character_stats::CharacterStats;
 use crate::{
     character_controller::CharacterController,
     machine::recipe::RecipeMeta,
 };
 use avian3d::prelude::*;
-use bevy::{platform::collections::HashMap, prelude::*};
-use item::{ItemRegistry, ItemType};
+use bevy::prelude::*;
+use item::{ItemMeta, ItemRegistry, ItemType, StatModifiers};
 
 mod inventory_input;
 pub mod item;
@@ -19,9 +22,16 @@ impl Plugin for InventoryPlugin {
             item::ItemPlugin,
         ))
         .add_observer(handle_item_collection)
-        .add_systems(Update, detect_item_collisions);
-
-        app.register_type::<Inventory>().register_type::<Item>();
+        .add_systems(
+            Update,
+            (detect_item_collisions, tick_collect_cooldowns),
+        )
+        .add_systems(FixedUpdate, attract_items);
+
+        app.register_type::<Inventory>()
+            .register_type::<Item>()
+            .register_type::<ItemMagnet>()
+            .register_type::<StatModifiers>();
     }
 }
 
@@ -29,7 +39,7 @@ impl Plugin for InventoryPlugin {
 fn detect_item_collisions(
     mut collision_events: EventReader<CollisionStarted>,
     q_players: Query<Entity, With<CharacterController>>,
-    q_items: Query<&Item>,
+    q_items: Query<&Item, Without<CollectCooldown>>,
     q_collider_of: Query<&ColliderOf>,
     item_registry: ItemRegistry,
     mut commands: Commands,
@@ -90,14 +100,75 @@ fn detect_item_collisions(
     }
 }
 
+/// Pull nearby ingredients towards players with an [`ItemMagnet`].
+fn attract_items(
+    q_players: Query<(&ItemMagnet, &GlobalTransform)>,
+    mut q_items: Query<(
+        &Item,
+        &GlobalTransform,
+        &mut LinearVelocity,
+    )>,
+    item_registry: ItemRegistry,
+) {
+    let Some(item_meta_asset) = item_registry.get() else {
+        return;
+    };
+
+    for (item, item_tf, mut linear_velocity) in q_items.iter_mut() {
+        // Only ingredients are auto-collected.
+        if item_meta_asset
+            .get(&item.id)
+            .is_none_or(|m| m.item_type != ItemType::Ingredient)
+        {
+            continue;
+        }
+
+        let item_translation = item_tf.translation();
+        let Some((magnet, offset)) = q_players
+            .iter()
+            .map(|(magnet, player_tf)| {
+                (magnet, player_tf.translation() - item_translation)
+            })
+            .filter(|(magnet, offset)| {
+                offset.length_squared()
+                    <= magnet.radius * magnet.radius
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.length_squared().total_cmp(&b.length_squared())
+            })
+        else {
+            continue;
+        };
+
+        linear_velocity.0 = offset.normalize_or_zero() * magnet.speed;
+    }
+}
+
+/// Remove [`CollectCooldown`] once it runs out.
+fn tick_collect_cooldowns(
+    mut commands: Commands,
+    mut q_cooldowns: Query<(&mut CollectCooldown, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut cooldown, entity) in q_cooldowns.iter_mut() {
+        if cooldown.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<CollectCooldown>();
+        }
+    }
+}
+
 /// Observer that handles item collection
 fn handle_item_collection(
     trigger: Trigger<ItemCollectionEvent>,
     mut commands: Commands,
     mut q_inventories: Query<&mut Inventory>,
     q_items: Query<&Item>,
-    q_players: Query<Entity, With<CharacterController>>,
+    q_players: Query<
+        Option<&CharacterStats>,
+        With<CharacterController>,
+    >,
     item_registry: ItemRegistry,
+    perks: Res<PerkProfile>,
 ) {
     let Some(item_meta_asset) = item_registry.get() else {
         return;
@@ -106,13 +177,13 @@ fn handle_item_collection(
     let player_entity = trigger.target();
     let item_entity = trigger.event().item;
 
-    if q_players.get(player_entity).is_err() {
+    let Ok(character_stats) = q_players.get(player_entity) else {
         warn!(
             "Attempted to collect item for non-player entity: {}.",
             player_entity
         );
         return;
-    }
+    };
 
     // Get the item being collected
     let Ok(world_item) = q_items.get(item_entity) else {
@@ -131,7 +202,11 @@ fn handle_item_collection(
     // Ensure player has an inventory
     let mut inventory_just_created = false;
     if q_inventories.get(player_entity).is_err() {
-        commands.entity(player_entity).insert(Inventory::default());
+        let capacity = character_stats
+            .map_or(DEFAULT_CAPACITY, |s| s.inventory_capacity());
+        let inventory =
+            Inventory::with_capacity(capacity + perks.extra_slots());
+        commands.entity(player_entity).insert(inventory);
         inventory_just_created = true;
         info!("Created new inventory for player {}", player_entity);
     }
@@ -153,40 +228,21 @@ fn handle_item_collection(
     let item_id = &world_item.id;
     let collected_quantity = world_item.quantity;
 
-    // Add to inventory based on item type
-    let success = match item_meta.item_type {
-        ItemType::Ingredient => inventory.add_ingredient(
-            item_id.clone(),
-            collected_quantity,
-            item_meta.max_stack_size,
-        ),
-        ItemType::Tower => inventory.add_tower(
-            item_id.clone(),
-            collected_quantity,
-            item_meta.max_stack_size,
-        ),
-    };
-
-    if success {
+    if inventory.add(item_id, item_meta, collected_quantity) {
         info!(
-            "Player {:?} collected {}x {} ({})",
+            "Player {:?} collected {}x {} ({:?})",
             player_entity,
             collected_quantity,
             item_id,
-            match item_meta.item_type {
-                ItemType::Ingredient => "ingredient",
-                ItemType::Tower => "tower",
-            }
+            item_meta.item_type,
         );
 
         // Remove the item from the world
         commands.entity(item_entity).despawn();
     } else {
-        // TODO: Handle stack overflow
-        // For now, just log a warning
         warn!(
-            "Could not collect {}x {}: would exceed max stack size ({})",
-            collected_quantity, item_id, item_meta.max_stack_size
+            "Could not collect {}x {}: inventory is full",
+            collected_quantity, item_id
         );
     }
 }
@@ -196,131 +252,479 @@ pub struct ItemCollectionEvent {
     pub item: Entity,
 }
 
-/// Marks an entity as having an inventory for both towers and ingredients
-#[derive(Component, Reflect, Default)]
-#[reflect(Component)]
+/// Default number of slots in an [`Inventory`].
+const DEFAULT_CAPACITY: usize = 12;
+/// Number of slots at the start of the [`Inventory`]
+/// that can be quickly selected.
+pub const HOTBAR_SIZE: usize = PlayerAction::HOTBAR.len();
+
+/// Slot based inventory for both towers and ingredients.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct Inventory {
-    /// Map of tower ID to quantity available (can be selected and placed)
-    towers: HashMap<String, u32>,
-    /// Map of ingredient ID to quantity collected (display only, cannot be selected)
-    ingredients: HashMap<String, u32>,
-    /// Currently selected tower for placement (if any)
-    pub selected_tower: Option<String>,
+    slots: Vec<Option<ItemStack>>,
+    /// Index of the selected tower slot (if any).
+    selected_index: Option<usize>,
 }
 
-impl Inventory {
-    /// Add towers to the inventory.
-    pub fn add_tower(
-        &mut self,
-        tower_id: String,
-        quantity: u32,
-        max_stack_size: u32,
-    ) -> bool {
-        let current_count =
-            self.towers.get(&tower_id).copied().unwrap_or(0);
-        let new_total = current_count + quantity;
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
 
-        if new_total <= max_stack_size {
-            self.towers.insert(tower_id, new_total);
-            true
-        } else {
-            false
+impl Inventory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: vec![None; capacity],
+            selected_index: None,
         }
     }
 
-    /// Remove towers from the inventory
-    pub fn remove_tower(
+    /// Add items into the inventory, merging into existing
+    /// stacks before filling up empty slots.
+    ///
+    /// Nothing will be added if there isn't enough room
+    /// for the whole quantity.
+    pub fn add(
         &mut self,
-        tower_id: &str,
+        item_id: &str,
+        item_meta: &ItemMeta,
         quantity: u32,
     ) -> bool {
-        let current_count =
-            self.towers.get(tower_id).copied().unwrap_or(0);
-        if current_count >= quantity {
-            let new_count = current_count - quantity;
-            if new_count == 0 {
-                self.towers.remove(tower_id);
-            } else {
-                self.towers.insert(tower_id.to_string(), new_count);
-            }
-            true
-        } else {
-            false
-        }
+        self.add_with_modifiers(
+            item_id,
+            item_meta,
+            quantity,
+            StatModifiers::default(),
+        )
     }
 
-    /// Add ingredients to the inventory with stack limit checking
-    pub fn add_ingredient(
+    /// Same as [`Self::add()`], items are only stacked with
+    /// items that have the same [`StatModifiers`].
+    pub fn add_with_modifiers(
         &mut self,
-        ingredient_id: String,
+        item_id: &str,
+        item_meta: &ItemMeta,
         quantity: u32,
-        max_stack_size: u32,
+        modifiers: StatModifiers,
     ) -> bool {
-        let current_count = self
-            .ingredients()
-            .get(&ingredient_id)
-            .copied()
-            .unwrap_or(0);
-        let new_total = current_count + quantity;
-
-        if new_total <= max_stack_size {
-            self.ingredients.insert(ingredient_id, new_total);
-            true
-        } else {
-            false
+        // Carryables live in the world instead.
+        if item_meta.item_type == ItemType::Carryable {
+            return false;
         }
+
+        let max_stack_size = item_meta.max_stack_size.max(1);
+
+        let stack_room = self
+            .stacks()
+            .filter(|(_, stack)| stack.is_same(item_id, &modifiers))
+            .map(|(_, stack)| {
+                max_stack_size.saturating_sub(stack.quantity)
+            })
+            .sum::<u32>();
+        let empty_room =
+            self.slots.iter().filter(|s| s.is_none()).count() as u32
+                * max_stack_size;
+
+        if stack_room + empty_room < quantity {
+            return false;
+        }
+
+        let mut remaining = quantity;
+
+        for stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+
+            if stack.is_same(item_id, &modifiers) {
+                let added = remaining.min(
+                    max_stack_size.saturating_sub(stack.quantity),
+                );
+                stack.quantity += added;
+                remaining -= added;
+            }
+        }
+
+        for slot in self.slots.iter_mut().filter(|s| s.is_none()) {
+            if remaining == 0 {
+                break;
+            }
+
+            let added = remaining.min(max_stack_size);
+            *slot = Some(ItemStack {
+                item_id: item_id.to_string(),
+                item_type: item_meta.item_type,
+                quantity: added,
+                modifiers,
+            });
+            remaining -= added;
+        }
+
+        true
     }
 
-    pub fn has_recipe(&self, recipe: &RecipeMeta) -> bool {
-        for ingredient in recipe.ingredients.iter() {
-            let available_quantity = self
-                .ingredients
-                .get(&ingredient.item_id)
-                .copied()
-                .unwrap_or(0);
-
-            if available_quantity < ingredient.quantity {
-                return false;
+    /// Remove items from the inventory, emptying slots whose
+    /// stacks run out.
+    ///
+    /// Nothing will be removed if there isn't enough of it.
+    pub fn remove(&mut self, item_id: &str, quantity: u32) -> bool {
+        if self.count(item_id) < quantity {
+            return false;
+        }
+
+        let mut remaining = quantity;
+
+        // Take from the last stacks first.
+        for slot in self.slots.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+
+            let Some(stack) =
+                slot.as_mut().filter(|s| s.item_id == item_id)
+            else {
+                continue;
+            };
+
+            let removed = remaining.min(stack.quantity);
+            stack.quantity -= removed;
+            remaining -= removed;
+
+            if stack.quantity == 0 {
+                *slot = None;
             }
         }
 
+        self.validate_selection();
         true
     }
 
-    /// Check if the inventory has the required ingredients and use it.
+    /// Total quantity of an item across all slots.
+    pub fn count(&self, item_id: &str) -> u32 {
+        self.stacks()
+            .filter(|(_, stack)| stack.item_id == item_id)
+            .map(|(_, stack)| stack.quantity)
+            .sum()
+    }
+
+    pub fn has_recipe(&self, recipe: &RecipeMeta) -> bool {
+        recipe.ingredients.iter().all(|ingredient| {
+            self.count(&ingredient.item_id) >= ingredient.quantity
+        })
+    }
+
+    /// Check if the inventory has the required ingredients and use it,
+    /// along with as many of the recipe extras as available.
     ///
     /// This will call [`Self::has_recipe()`] first.
     pub fn check_and_use_recipe(
         &mut self,
         recipe: &RecipeMeta,
-    ) -> bool {
+    ) -> Option<ConsumptionReport> {
         if self.has_recipe(recipe) == false {
-            return false;
+            return None;
         }
 
+        let mut report = ConsumptionReport::default();
+
         for ingredient in recipe.ingredients.iter() {
-            // SAFETY: We already made sure that the ingredients above.
-            // In caes the quantity is actually zero!
-            let mut default_0 = 0;
-            let available_quantity = self
-                .ingredients
-                .get_mut(&ingredient.item_id)
-                .unwrap_or(&mut default_0);
-
-            *available_quantity -= ingredient.quantity;
+            self.remove(&ingredient.item_id, ingredient.quantity);
+            report.consumed.push((
+                ingredient.item_id.clone(),
+                ingredient.quantity,
+            ));
+        }
+
+        for extra in recipe.extras.iter() {
+            let quantity =
+                self.count(&extra.item_id).min(extra.quantity);
+            if quantity > 0 {
+                self.remove(&extra.item_id, quantity);
+                report
+                    .consumed
+                    .push((extra.item_id.clone(), quantity));
+            }
         }
 
+        Some(report)
+    }
+
+    /// Make sure the selection points to a tower slot,
+    /// selecting the first tower if it doesn't.
+    pub fn validate_selection(&mut self) {
+        let selected_valid = self
+            .selected_index
+            .and_then(|i| self.slots.get(i)?.as_ref())
+            .is_some_and(|s| s.item_type == ItemType::Tower);
+
+        if selected_valid == false {
+            self.selected_index = self
+                .stacks_of_type(ItemType::Tower)
+                .next()
+                .map(|(i, _)| i);
+        }
+    }
+
+    /// Select a slot if it holds a tower.
+    pub fn select_slot(&mut self, index: usize) -> bool {
+        let is_tower = self
+            .slots
+            .get(index)
+            .and_then(|s| s.as_ref())
+            .is_some_and(|s| s.item_type == ItemType::Tower);
+
+        if is_tower {
+            self.selected_index = Some(index);
+        }
+
+        is_tower
+    }
+
+    /// Move a quantity of items from one slot to another.
+    ///
+    /// Items are merged into a stack of the same item (up to
+    /// `max_stack_size`) and swapped with a stack of a different
+    /// item. Partial quantities can only be moved into an empty
+    /// slot or merged.
+    pub fn move_items(
+        &mut self,
+        from: usize,
+        to: usize,
+        quantity: u32,
+        max_stack_size: u32,
+    ) -> bool {
+        if from == to || to >= self.slots.len() || quantity == 0 {
+            return false;
+        }
+
+        let Some(source) = self.slots.get(from).cloned().flatten()
+        else {
+            return false;
+        };
+        let quantity = quantity.min(source.quantity);
+        let whole_stack = quantity == source.quantity;
+
+        // The selection follows the whole stack, update it before
+        // the source slot empties and gets revalidated.
+        let follow_selection =
+            whole_stack && self.selected_index == Some(from);
+
+        match self.slots[to].as_mut() {
+            None => {
+                self.slots[to] =
+                    Some(ItemStack { quantity, ..source });
+                if follow_selection {
+                    self.selected_index = Some(to);
+                }
+            }
+            Some(target)
+                if target
+                    .is_same(&source.item_id, &source.modifiers) =>
+            {
+                let moved = quantity.min(
+                    max_stack_size
+                        .max(1)
+                        .saturating_sub(target.quantity),
+                );
+                if moved == 0 {
+                    return false;
+                }
+
+                target.quantity += moved;
+                if follow_selection && moved == quantity {
+                    self.selected_index = Some(to);
+                }
+                self.take(from, moved);
+                self.validate_selection();
+                return true;
+            }
+            Some(_) if whole_stack => {
+                self.slots.swap(from, to);
+                if self.selected_index == Some(to) {
+                    self.selected_index = Some(from);
+                } else if self.selected_index == Some(from) {
+                    self.selected_index = Some(to);
+                }
+                return true;
+            }
+            Some(_) => return false,
+        }
+
+        self.take(from, quantity);
+        self.validate_selection();
         true
     }
+
+    /// Take a quantity of items out of a slot, emptying the slot
+    /// if the stack runs out.
+    pub fn take(
+        &mut self,
+        index: usize,
+        quantity: u32,
+    ) -> Option<ItemStack> {
+        let slot = self.slots.get_mut(index)?;
+        let stack = slot.as_mut()?;
+
+        let quantity = quantity.min(stack.quantity);
+        stack.quantity -= quantity;
+
+        let taken = ItemStack {
+            quantity,
+            ..stack.clone()
+        };
+
+        if stack.quantity == 0 {
+            *slot = None;
+            self.validate_selection();
+        }
+
+        Some(taken)
+    }
+
+    /// Select the next (or previous) tower slot.
+    pub fn cycle_selection(&mut self, forward: bool) {
+        let towers = self
+            .stacks_of_type(ItemType::Tower)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let Some(current) = self
+            .selected_index
+            .and_then(|i| towers.iter().position(|t| *t == i))
+        else {
+            self.validate_selection();
+            return;
+        };
+
+        let next = match forward {
+            true => (current + 1) % towers.len(),
+            false => (current + towers.len() - 1) % towers.len(),
+        };
+        self.selected_index = Some(towers[next]);
+    }
 }
 
 impl Inventory {
-    pub fn ingredients(&self) -> &HashMap<String, u32> {
-        &self.ingredients
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected_index
+    }
+
+    /// The selected tower stack.
+    pub fn selected(&self) -> Option<&ItemStack> {
+        self.slots.get(self.selected_index?)?.as_ref()
+    }
+
+    /// Iterate over all non-empty slots with their index.
+    pub fn stacks(
+        &self,
+    ) -> impl Iterator<Item = (usize, &ItemStack)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| Some((i, s.as_ref()?)))
+    }
+
+    /// Mutably iterate over all non-empty slots.
+    pub fn stacks_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (usize, &mut ItemStack)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, s)| Some((i, s.as_mut()?)))
     }
 
-    pub fn towers(&self) -> &HashMap<String, u32> {
-        &self.towers
+    /// Iterate over all non-empty slots of an item type.
+    pub fn stacks_of_type(
+        &self,
+        item_type: ItemType,
+    ) -> impl Iterator<Item = (usize, &ItemStack)> {
+        self.stacks().filter(move |(_, s)| s.item_type == item_type)
+    }
+}
+
+/// Attracts nearby ingredients so that they get collected.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct ItemMagnet {
+    pub radius: f32,
+    /// Speed at which the ingredients are pulled in.
+    pub speed: f32,
+}
+
+impl Default for ItemMagnet {
+    fn default() -> Self {
+        Self {
+            radius: 3.0,
+            speed: 8.0,
+        }
+    }
+}
+
+/// Prevents a dropped [`Item`] from being collected right away.
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct CollectCooldown(Timer);
+
+impl Default for CollectCooldown {
+    fn default() -> Self {
+        Self(Timer::from_seconds(2.0, TimerMode::Once))
+    }
+}
+
+/// A stack of items occupying an [`Inventory`] slot.
+#[derive(Reflect, Debug, Clone)]
+pub struct ItemStack {
+    pub item_id: String,
+    pub item_type: ItemType,
+    pub quantity: u32,
+    /// Stat modifiers of crafted towers.
+    pub modifiers: StatModifiers,
+}
+
+impl ItemStack {
+    /// Returns `true` if the items can be stacked together.
+    pub fn is_same(
+        &self,
+        item_id: &str,
+        modifiers: &StatModifiers,
+    ) -> bool {
+        self.item_id == item_id && self.modifiers == *modifiers
+    }
+}
+
+/// Items consumed by [`Inventory::check_and_use_recipe()`].
+#[derive(Default, Debug)]
+pub struct ConsumptionReport {
+    pub consumed: Vec<(String, u32)>,
+}
+
+impl ConsumptionReport {
+    /// Sum of the [`StatModifiers`] of every consumed item.
+    pub fn modifiers(
+        &self,
+        item_registry: &ItemRegistry,
+    ) -> StatModifiers {
+        self.consumed
+            .iter()
+            .filter_map(|(item_id, quantity)| {
+                Some(
+                    item_registry
+                        .get_item(item_id)?
+                        .modifiers
+                        .scaled(*quantity as f32),
+                )
+            })
+            .fold(StatModifiers::default(), |a, b| a + b)
     }
 }
 
@@ -337,3 +741,100 @@ pub struct Item {
     /// How many are in this stack.
     pub quantity: u32,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stack(item_id: &str, quantity: u32) -> ItemStack {
+        ItemStack {
+            item_id: item_id.to_string(),
+            item_type: ItemType::Tower,
+            quantity,
+            modifiers: StatModifiers::default(),
+        }
+    }
+
+    fn inventory(slots: Vec<Option<ItemStack>>) -> Inventory {
+        let mut inventory = Inventory {
+            slots,
+            selected_index: None,
+        };
+        inventory.validate_selection();
+        inventory
+    }
+
+    fn quantity(inventory: &Inventory, index: usize) -> Option<u32> {
+        inventory.slots[index].as_ref().map(|s| s.quantity)
+    }
+
+    #[test]
+    fn test_move_whole_stack_keeps_selection() {
+        let mut inventory = inventory(vec![
+            Some(stack("gun_tower", 2)),
+            Some(stack("cannon_tower", 3)),
+            None,
+        ]);
+        assert!(inventory.select_slot(1));
+
+        assert!(inventory.move_items(1, 2, 3, 10));
+        assert_eq!(quantity(&inventory, 1), None);
+        assert_eq!(quantity(&inventory, 2), Some(3));
+        assert_eq!(inventory.selected_index(), Some(2));
+    }
+
+    #[test]
+    fn test_merge_into_same_stack() {
+        let mut inventory = inventory(vec![
+            Some(stack("gun_tower", 4)),
+            Some(stack("gun_tower", 3)),
+        ]);
+
+        // Only up to the max stack size gets merged.
+        assert!(inventory.move_items(0, 1, 4, 5));
+        assert_eq!(quantity(&inventory, 0), Some(2));
+        assert_eq!(quantity(&inventory, 1), Some(5));
+
+        // Nothing to merge into a full stack.
+        assert!(inventory.move_items(0, 1, 2, 5) == false);
+
+        assert!(inventory.move_items(1, 0, 3, 5));
+        assert_eq!(quantity(&inventory, 0), Some(5));
+        assert_eq!(quantity(&inventory, 1), Some(2));
+    }
+
+    #[test]
+    fn test_swap_different_stacks() {
+        let mut inventory = inventory(vec![
+            Some(stack("gun_tower", 2)),
+            Some(stack("cannon_tower", 3)),
+        ]);
+        assert_eq!(inventory.selected_index(), Some(0));
+
+        assert!(inventory.move_items(0, 1, 2, 10));
+        assert_eq!(
+            inventory.slots[0].as_ref().unwrap().item_id,
+            "cannon_tower"
+        );
+        assert_eq!(
+            inventory.slots[1].as_ref().unwrap().item_id,
+            "gun_tower"
+        );
+        assert_eq!(inventory.selected_index(), Some(1));
+
+        // Partial stacks can't be swapped.
+        assert!(inventory.move_items(0, 1, 1, 10) == false);
+    }
+
+    #[test]
+    fn test_split_into_empty_slot() {
+        let mut inventory =
+            inventory(vec![Some(stack("gun_tower", 5)), None]);
+
+        assert!(inventory.move_items(0, 1, 2, 10));
+        assert_eq!(quantity(&inventory, 0), Some(3));
+        assert_eq!(quantity(&inventory, 1), Some(2));
+        // The selection stays with the rest of the stack.
+        assert_eq!(inventory.selected_index(), Some(0));
+    }
+}
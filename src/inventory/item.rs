@@ -6,6 +6,7 @@ use bevy::prelude::*;
 use serde::Deserialize;
 
 use crate::asset_pipeline::PrefabName;
+use crate::asset_pipeline::loading::TrackedAssets;
 
 /// Plugin to handle item metadata loading and registry setup
 pub(super) struct ItemPlugin;
@@ -23,41 +24,163 @@ impl Plugin for ItemPlugin {
 fn load_item_registry(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
 ) {
-    commands.insert_resource(ItemMetaAssetHandle(
-        asset_server.load("items.item_meta.ron"),
-    ));
+    let handle = asset_server.load("items.item_meta.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(ItemMetaAssetHandle(handle));
 }
 
 #[derive(Asset, TypePath, Deref, Debug, Clone, Deserialize)]
 pub struct ItemMetaAsset(HashMap<String, ItemMeta>);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ItemType {
     Tower,
     Ingredient,
+    /// Spawned into the world to be carried around
+    /// instead of being stored in the inventory.
+    Carryable,
+    /// Can be switched to as the player's weapon while in the
+    /// inventory.
+    Weapon,
 }
 
 /// Metadata for each item type in the game - loaded from RON files.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ItemMeta {
     pub icon_path: String,
-    prefab_name: String,
+    /// [`None`] for items that are never spawned from a prefab,
+    /// e.g. carryables spawned by their own logic.
+    #[serde(default)]
+    prefab_name: Option<String>,
     pub max_stack_size: u32,
     pub item_type: ItemType,
+    /// Short description shown in tooltips.
+    #[serde(default)]
+    pub description: String,
+    /// Stats shown in tooltips of [`ItemType::Tower`] items.
+    #[serde(default)]
+    pub tower_stats: Option<TowerStats>,
+    /// Stats of [`ItemType::Weapon`] items.
+    #[serde(default)]
+    pub weapon_stats: Option<WeaponStats>,
+    /// Stat deltas granted to the crafted tower per unit used
+    /// as a recipe extra.
+    #[serde(default)]
+    pub modifiers: StatModifiers,
 
     #[serde(skip_serializing, skip_deserializing)]
     pub icon: Handle<Image>,
 }
 
+/// Stats of a tower item for display purposes.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TowerStats {
+    pub damage: f32,
+    pub range: f32,
+    pub attack_cooldown: f32,
+}
+
+/// Stats of a weapon item, applied to the player's weapon when
+/// switched to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeaponStats {
+    pub damage: f32,
+    pub attack_cooldown: f32,
+    pub projectile_speed: f32,
+    pub projectile_lifetime: f32,
+    #[serde(default)]
+    pub knockback: f32,
+    /// Infinite ammo if [`None`].
+    #[serde(default)]
+    pub ammo: Option<AmmoStats>,
+}
+
+/// Ammunition of a weapon, reloaded from the inventory.
+#[derive(Reflect, Debug, Clone, PartialEq, Deserialize)]
+pub struct AmmoStats {
+    /// The item consumed as ammunition.
+    pub item_id: String,
+    pub magazine_size: u32,
+    /// Duration of a reload in seconds.
+    pub reload_duration: f32,
+}
+
+/// Tower stat deltas that depend on the ingredients used.
+#[derive(
+    Component,
+    Reflect,
+    Deserialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+)]
+#[reflect(Component, Default)]
+#[serde(default)]
+pub struct StatModifiers {
+    pub damage: f32,
+    pub range: f32,
+    pub attack_cooldown: f32,
+}
+
+impl StatModifiers {
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            damage: self.damage * factor,
+            range: self.range * factor,
+            attack_cooldown: self.attack_cooldown * factor,
+        }
+    }
+}
+
+impl core::ops::Add for StatModifiers {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            damage: self.damage + rhs.damage,
+            range: self.range + rhs.range,
+            attack_cooldown: self.attack_cooldown
+                + rhs.attack_cooldown,
+        }
+    }
+}
+
+impl core::fmt::Display for StatModifiers {
+    /// Only non-zero deltas are written, e.g. "+1.0 damage, -0.2s cooldown".
+    fn fmt(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        let deltas = [
+            (self.damage, " damage"),
+            (self.range, " range"),
+            (self.attack_cooldown, "s cooldown"),
+        ]
+        .into_iter()
+        .filter(|(delta, _)| *delta != 0.0)
+        .map(|(delta, suffix)| format!("{delta:+.1}{suffix}"))
+        .collect::<Vec<_>>();
+
+        write!(f, "{}", deltas.join(", "))
+    }
+}
+
 impl ItemMeta {
-    pub fn prefab_name(&self) -> PrefabName {
-        PrefabName::FileName(&self.prefab_name)
+    pub fn prefab_name(&self) -> Option<PrefabName> {
+        self.prefab_name.as_deref().map(PrefabName::FileName)
     }
 
-    pub fn raw_prefab_name(&self) -> &str {
-        &self.prefab_name
+    pub fn raw_prefab_name(&self) -> Option<&str> {
+        self.prefab_name.as_deref()
     }
 }
 
@@ -99,8 +222,9 @@ impl AssetLoader for ItemMetaAssetLoader {
         let mut ron_str = String::new();
         reader.read_to_string(&mut ron_str).await?;
 
+        // Surface as a failed load instead of panicking.
         let mut asset = ron::from_str::<ItemMetaAsset>(&ron_str)
-            .expect("Failed to parse items.ron");
+            .map_err(std::io::Error::other)?;
 
         // Load icons for each item meta
         for item_meta in asset.0.values_mut() {
@@ -0,0 +1,199 @@
+//! Input latency diagnostics for tuning responsiveness (especially
+//! on the wasm build).
+//!
+//! Press [`PROBE_KEY`] to toggle the probe. While enabled, a marker
+//! in the corner of the screen flashes on the frame an input is
+//! pressed, film it alongside the input device with a high speed
+//! camera to measure input-to-photon latency. The in-engine part
+//! of the latency (start of frame to the end of the main world
+//! update) is shown in the "Input Latency" panel.
+
+use std::collections::VecDeque;
+
+use bevy::input::InputSystem;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContextPass, EguiContexts};
+use bevy_inspector_egui::egui;
+
+use crate::camera_controller::UI_RENDER_LAYER;
+
+/// Key to toggle the latency probe.
+const PROBE_KEY: KeyCode = KeyCode::F5;
+/// Number of latency samples to average over.
+const MAX_SAMPLES: usize = 60;
+const MARKER_SIZE: f32 = 48.0;
+
+pub(super) struct LatencyProbePlugin;
+
+impl Plugin for LatencyProbePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LatencyProbe>()
+            .add_systems(Startup, spawn_marker)
+            .add_systems(First, mark_frame_start)
+            .add_systems(
+                PreUpdate,
+                (toggle_probe, detect_input)
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(
+                Last,
+                record_latency
+                    .run_if(|probe: Res<LatencyProbe>| probe.enabled),
+            )
+            .add_systems(EguiContextPass, latency_panel);
+    }
+}
+
+fn spawn_marker(mut commands: Commands) {
+    commands.spawn((
+        UI_RENDER_LAYER,
+        LatencyMarker,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            width: Val::Px(MARKER_SIZE),
+            height: Val::Px(MARKER_SIZE),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK),
+        // Always on top of the game ui.
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+    ));
+}
+
+fn mark_frame_start(mut probe: ResMut<LatencyProbe>) {
+    probe.frame_start = Some(Instant::now());
+}
+
+fn toggle_probe(
+    mut probe: ResMut<LatencyProbe>,
+    mut q_markers: Query<&mut Visibility, With<LatencyMarker>>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+) {
+    if kbd_inputs.just_pressed(PROBE_KEY) == false {
+        return;
+    }
+
+    probe.enabled = !probe.enabled;
+    probe.samples.clear();
+
+    for mut viz in q_markers.iter_mut() {
+        *viz = match probe.enabled {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+    }
+}
+
+/// Flash the marker on the frame any input is pressed.
+fn detect_input(
+    mut probe: ResMut<LatencyProbe>,
+    mut q_markers: Query<&mut BackgroundColor, With<LatencyMarker>>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+    mouse_inputs: Res<ButtonInput<MouseButton>>,
+    q_gamepads: Query<&Gamepad>,
+) {
+    if probe.enabled == false {
+        return;
+    }
+
+    let pressed =
+        kbd_inputs.get_just_pressed().any(|key| *key != PROBE_KEY)
+            || mouse_inputs.get_just_pressed().next().is_some()
+            || q_gamepads
+                .iter()
+                .any(|g| g.get_just_pressed().next().is_some());
+
+    // Input events are read at the start of the frame.
+    probe.pressed_at = probe.frame_start.filter(|_| pressed);
+
+    for mut background in q_markers.iter_mut() {
+        background.0 = match pressed {
+            true => Color::WHITE,
+            false => Color::BLACK,
+        };
+    }
+}
+
+fn record_latency(mut probe: ResMut<LatencyProbe>) {
+    let Some(pressed_at) = probe.pressed_at.take() else {
+        return;
+    };
+
+    if probe.samples.len() >= MAX_SAMPLES {
+        probe.samples.pop_front();
+    }
+
+    let latency = pressed_at.elapsed().as_secs_f32() * 1000.0;
+    probe.samples.push_back(latency);
+}
+
+fn latency_panel(
+    mut contexts: EguiContexts,
+    mut probe: ResMut<LatencyProbe>,
+    time: Res<Time>,
+) {
+    if probe.enabled == false {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let probe = probe.bypass_change_detection();
+
+    egui::Window::new("Input Latency").show(ctx, |ui| {
+        ui.label(format!(
+            "Frame time: {:.1} ms",
+            time.delta_secs() * 1000.0
+        ));
+
+        let Some(last) = probe.samples.back() else {
+            ui.label("Press any input to sample.");
+            return;
+        };
+
+        let average = probe.samples.iter().sum::<f32>()
+            / probe.samples.len() as f32;
+        let max = probe.samples.iter().copied().fold(0.0, f32::max);
+
+        egui::Grid::new("input_latency").striped(true).show(
+            ui,
+            |ui| {
+                ui.label("Last");
+                ui.label(format!("{last:.2} ms"));
+                ui.end_row();
+                ui.label("Average");
+                ui.label(format!("{average:.2} ms"));
+                ui.end_row();
+                ui.label("Max");
+                ui.label(format!("{max:.2} ms"));
+                ui.end_row();
+            },
+        );
+
+        if ui.button("Clear").clicked() {
+            probe.samples.clear();
+        }
+    });
+}
+
+/// In-engine latency from the start of the frame that read an
+/// input to the end of the main world update, in milliseconds.
+#[derive(Resource, Default, Debug)]
+pub struct LatencyProbe {
+    enabled: bool,
+    frame_start: Option<Instant>,
+    /// Start of the frame that received an input.
+    pressed_at: Option<Instant>,
+    samples: VecDeque<f32>,
+}
+
+/// Flashes white on the frame an input is pressed.
+#[derive(Component)]
+struct LatencyMarker;
@@ -0,0 +1,353 @@
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::PlayerAction;
+use crate::asset_pipeline::CurrentScene;
+use crate::enemy::spawner::WaveCountdown;
+use crate::player::PlayerState;
+use crate::player::player_mark::FellOnWave;
+use crate::storage::Profile;
+use crate::tile::TileMap;
+use crate::tower::{InPlacementMode, Preview, Projectile};
+use crate::ui::Screen;
+
+/// Every level in playing order, beating a level unlocks the
+/// next one.
+///
+/// The scenes are preloaded from "scenes.levels" in the manifest
+/// ("dynamic_asset.assets.ron").
+pub const LEVELS: [LevelInfo; 1] = [LevelInfo {
+    name: "Bread Bakery",
+    scene: "scenes/bread_level.gltf",
+}];
+
+pub(super) struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LevelProgress::load())
+            .init_resource::<CurrentLevel>()
+            .init_resource::<GameMode>()
+            .add_observer(on_restart_level)
+            .add_observer(on_next_level)
+            .add_systems(
+                OnEnter(Screen::Restarting),
+                (unload_level, reenter_level).chain(),
+            )
+            .add_systems(OnEnter(Screen::Victory), unlock_next_level)
+            .add_systems(
+                OnEnter(Screen::Defeat),
+                record_best_endless_wave
+                    .run_if(resource_equals(GameMode::Endless)),
+            );
+    }
+}
+
+/// Leave the current screen so that the level can be torn down.
+fn on_restart_level(
+    _: Trigger<RestartLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    info!("Restarting level...");
+    next_screen.set(Screen::Restarting);
+}
+
+/// Despawn everything that belongs to the current level and
+/// reset all level related resources and states back to their
+/// defaults.
+///
+/// [`StateScoped`] ui roots are already despawned
+/// upon exiting [`Screen::EnterLevel`].
+fn unload_level(
+    mut commands: Commands,
+    mut current_scene: ResMut<CurrentScene>,
+    q_projectiles: Query<Entity, With<Projectile>>,
+    mut q_previews: Query<&mut Visibility, With<Preview>>,
+    q_placement_modes: Query<Entity, With<InPlacementMode>>,
+    q_actions: Query<Entity, With<InputMap<PlayerAction>>>,
+    mut player_state: ResMut<NextState<PlayerState>>,
+) {
+    // Despawning the scene root will despawn every entity
+    // in the level hierarchy (players, towers, enemies, etc.).
+    if let Some(scene) = current_scene.take() {
+        commands.entity(scene).despawn();
+    }
+
+    // Projectiles are not part of the scene hierarchy.
+    for entity in q_projectiles.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for mut viz in q_previews.iter_mut() {
+        *viz = Visibility::Hidden;
+    }
+
+    for entity in q_placement_modes.iter() {
+        commands.entity(entity).remove::<InPlacementMode>();
+    }
+
+    // The players possess their characters again, along with
+    // new input entities.
+    for entity in q_actions.iter() {
+        commands.entity(entity).despawn();
+    }
+    player_state.set(PlayerState::Possessing);
+
+    commands.insert_resource(TileMap::default());
+    commands.insert_resource(WaveCountdown::default());
+}
+
+fn reenter_level(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::EnterLevel);
+}
+
+/// Move on to the next level, going through the same teardown as
+/// restarting.
+fn on_next_level(
+    _: Trigger<NextLevel>,
+    mut commands: Commands,
+    mut current_level: ResMut<CurrentLevel>,
+) {
+    if current_level.next().is_none() {
+        warn!("Already at the last level!");
+        return;
+    }
+
+    current_level.0 += 1;
+    commands.trigger(RestartLevel);
+}
+
+/// Mark the current level as cleared and unlock the one after.
+pub(crate) fn unlock_next_level(
+    current_level: Res<CurrentLevel>,
+    mut progress: ResMut<LevelProgress>,
+) {
+    let mut changed = progress
+        .cleared
+        .insert(current_level.info().name.to_string());
+
+    if let Some(next) = current_level.next() {
+        if next > progress.unlocked {
+            progress.unlocked = next;
+            info!("Unlocked {}!", LEVELS[next].name);
+            changed = true;
+        }
+    }
+
+    if changed {
+        progress.save();
+    }
+}
+
+pub fn record_best_endless_wave(
+    current_level: Res<CurrentLevel>,
+    fell_on_wave: Option<Res<FellOnWave>>,
+    mut progress: ResMut<LevelProgress>,
+) {
+    let Some(number) = fell_on_wave.and_then(|w| w.number()) else {
+        return;
+    };
+
+    let name = current_level.info().name;
+    if number > progress.best_endless_wave(name) {
+        progress.best_endless_waves.insert(name.to_string(), number);
+        info!("New best endless wave {number} on {name}!");
+        progress.save();
+    }
+}
+
+/// Trigger this event to tear down the current level
+/// and load it again from scratch.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RestartLevel;
+
+/// Trigger this event to tear down the current level and load the
+/// next one in [`LEVELS`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NextLevel;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LevelInfo {
+    pub name: &'static str,
+    /// Path of the level's scene.
+    pub scene: &'static str,
+}
+
+/// Index of the level in [`LEVELS`] being played.
+#[derive(Resource, Deref, Default, Debug, Clone, Copy)]
+pub struct CurrentLevel(pub usize);
+
+impl CurrentLevel {
+    pub fn info(&self) -> &'static LevelInfo {
+        &LEVELS[self.0.min(LEVELS.len() - 1)]
+    }
+
+    /// Index of the level after this one, [`None`] for the last
+    /// level.
+    pub fn next(&self) -> Option<usize> {
+        let next = self.0 + 1;
+        (next < LEVELS.len()).then_some(next)
+    }
+}
+
+/// How the waves go on after the authored ones.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Clear the authored waves to win.
+    #[default]
+    Campaign,
+    /// Keep going with generated waves until the base falls.
+    Endless,
+}
+
+impl GameMode {
+    pub fn toggled(&self) -> Self {
+        match self {
+            GameMode::Campaign => GameMode::Endless,
+            GameMode::Endless => GameMode::Campaign,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Campaign => "Mode: Campaign",
+            GameMode::Endless => "Mode: Endless",
+        }
+    }
+}
+
+/// Levels unlocked by the players so far.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct LevelProgress {
+    /// Index of the furthest unlocked level in [`LEVELS`].
+    unlocked: usize,
+    /// Names of the levels that were cleared at least once.
+    cleared: HashSet<String>,
+    /// Furthest wave reached in [`GameMode::Endless`], keyed by
+    /// the level's name.
+    best_endless_waves: HashMap<String, u32>,
+}
+
+impl Profile for LevelProgress {
+    const KEY: &'static str = "levels.profile.ron";
+}
+
+impl LevelProgress {
+    /// The furthest unlocked level, in case [`LEVELS`] shrunk
+    /// since it was saved.
+    pub fn latest(&self) -> usize {
+        self.unlocked.min(LEVELS.len() - 1)
+    }
+
+    /// Whether the level at `index` in [`LEVELS`] was cleared
+    /// before.
+    pub fn is_cleared(&self, index: usize) -> bool {
+        // Profiles saved before clears were recorded only know
+        // about the unlocked levels.
+        index < self.unlocked
+            || LEVELS
+                .get(index)
+                .is_some_and(|l| self.cleared.contains(l.name))
+    }
+
+    /// The furthest wave reached in [`GameMode::Endless`], 0 if
+    /// never played.
+    pub fn best_endless_wave(&self, level_name: &str) -> u32 {
+        self.best_endless_waves
+            .get(level_name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::state::app::StatesPlugin;
+
+    use crate::asset_pipeline::AssetState;
+    use crate::player::PlayerType;
+    use crate::tile::Tile;
+
+    use super::*;
+
+    /// Mimics the level scene being loaded.
+    fn spawn_level(
+        mut commands: Commands,
+        mut current_scene: ResMut<CurrentScene>,
+    ) {
+        let scene = commands
+            .spawn(Transform::default())
+            .with_children(|parent| {
+                for x in 0..4 {
+                    parent.spawn((
+                        Tile,
+                        Transform::from_xyz(x as f32 * 2.0, 0.0, 0.0),
+                    ));
+                }
+                parent.spawn(Transform::default()).with_child((
+                    Transform::default(),
+                    Visibility::Inherited,
+                ));
+            })
+            .id();
+
+        if let Some(last_scene) = current_scene.replace(scene) {
+            commands.entity(last_scene).despawn();
+        }
+    }
+
+    #[test]
+    fn test_restart_does_not_leak() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, LevelPlugin))
+            .init_state::<AssetState>()
+            .init_state::<PlayerState>()
+            .add_sub_state::<Screen>()
+            .init_resource::<CurrentScene>()
+            .init_resource::<TileMap>()
+            .init_resource::<WaveCountdown>()
+            .add_systems(OnEnter(Screen::EnterLevel), spawn_level);
+
+        app.world_mut()
+            .resource_mut::<NextState<AssetState>>()
+            .set(AssetState::Loaded);
+        app.update();
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::EnterLevel);
+        app.update();
+
+        let entity_count = app.world().entities().len();
+
+        for _ in 0..5 {
+            // Mimics the players possessing their characters.
+            app.world_mut().spawn((
+                InputMap::<PlayerAction>::default(),
+                PlayerType::A,
+            ));
+            app.world_mut()
+                .resource_mut::<NextState<PlayerState>>()
+                .set(PlayerState::Possessed);
+            app.update();
+
+            app.world_mut().trigger(RestartLevel);
+            // Enter `Screen::Restarting`.
+            app.update();
+            // Back to `Screen::EnterLevel`.
+            app.update();
+
+            assert_eq!(
+                app.world().resource::<State<Screen>>().get(),
+                &Screen::EnterLevel
+            );
+            assert_eq!(
+                app.world().resource::<State<PlayerState>>().get(),
+                &PlayerState::Possessing
+            );
+            assert_eq!(app.world().entities().len(), entity_count);
+        }
+    }
+}
@@ -1,52 +1,134 @@
 use bevy::prelude::*;
 
+mod achievements;
 mod action;
+mod ai;
 mod asset_pipeline;
 mod audio;
+mod bomb;
 mod camera_controller;
+mod celebration;
 mod character_controller;
+#[cfg(feature = "dev")]
+mod console;
+mod damage;
+mod death;
+mod debug_flags;
+mod decoration;
+mod decoy;
 mod enemy;
+mod environment;
+mod foot_ik;
+mod footstep;
+mod gameplay_event;
+#[cfg(feature = "headless")]
+pub mod headless;
+mod highlights;
+#[cfg(feature = "dev")]
+mod inspector;
 mod interaction;
 mod inventory;
+#[cfg(feature = "dev")]
+mod latency_probe;
+mod level;
 mod machine;
+mod migration;
+mod performance;
+mod perks;
 mod physics;
 mod player;
+#[cfg(feature = "dev")]
+mod practice;
+mod rng;
+#[cfg(feature = "dev")]
+mod scene_diagnostics;
+mod session_stats;
+pub mod smoke_test;
+mod squash_stretch;
+mod storage;
+mod streaming;
 mod tile;
-mod tower;
+#[cfg(feature = "dev")]
+mod timeline;
+pub mod tower;
+mod tuning;
 pub mod ui;
 mod util;
+mod vfx;
+mod water;
 
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
+        // Headless simulations run as fast as possible
+        // without an audio device.
+        #[cfg(not(feature = "headless"))]
         app.add_plugins((
             bevy_framepace::FramepacePlugin,
-            bevy_skein::SkeinPlugin::default(),
-        ))
-        .add_plugins((
-            action::ActionPlugin,
             audio::AudioPlugin,
-            ui::UiPlugin,
-            physics::PhysicsPlugin,
-            asset_pipeline::AssetPipelinePlugin,
-            camera_controller::CameraControllerPlugin,
-            character_controller::CharacterControllerPlugin,
-            interaction::InteractionPlugin,
-            inventory::InventoryPlugin,
-            player::PlayerPlugin,
-            machine::MachinePlugin,
-            tower::TowerPlugin,
-            tile::TilePlugin,
-            enemy::EnemyPlugin,
         ));
 
+        app.add_plugins(bevy_skein::SkeinPlugin::default())
+            .add_plugins((
+                action::ActionPlugin,
+                ui::UiPlugin,
+                physics::PhysicsPlugin,
+                asset_pipeline::AssetPipelinePlugin,
+                camera_controller::CameraControllerPlugin,
+                character_controller::CharacterControllerPlugin,
+                interaction::InteractionPlugin,
+                inventory::InventoryPlugin,
+                player::PlayerPlugin,
+                machine::MachinePlugin,
+                tower::TowerPlugin,
+                tile::TilePlugin,
+                enemy::EnemyPlugin,
+                level::LevelPlugin,
+                environment::EnvironmentPlugin,
+            ))
+            .add_plugins((
+                squash_stretch::SquashStretchPlugin,
+                foot_ik::FootIkPlugin,
+                bomb::BombPlugin,
+                decoy::DecoyPlugin,
+                tuning::TuningPlugin,
+                streaming::StreamingPlugin,
+                performance::PerformancePlugin,
+                celebration::CelebrationPlugin,
+                decoration::DecorationPlugin,
+                highlights::HighlightsPlugin,
+                rng::RngPlugin,
+                ai::AiPlugin,
+                damage::DamagePlugin,
+                death::DeathPlugin,
+                gameplay_event::GameplayEventPlugin,
+            ))
+            .add_plugins((
+                perks::PerksPlugin,
+                footstep::FootstepPlugin,
+                water::WaterPlugin,
+                migration::MigrationPlugin,
+            ))
+            // Optional, only subscribe to the gameplay events.
+            .add_plugins((
+                session_stats::SessionStatsPlugin,
+                achievements::AchievementsPlugin,
+                vfx::VfxPlugin,
+            ));
+
         #[cfg(feature = "dev")]
         app.add_plugins((
             bevy_inspector_egui::bevy_egui::EguiPlugin {
                 enable_multipass_for_primary_context: true,
             },
-            bevy_inspector_egui::quick::WorldInspectorPlugin::new(),
+            console::ConsolePlugin,
+            debug_flags::DebugFlagsPlugin,
+            inspector::InspectorPlugin,
+            latency_probe::LatencyProbePlugin,
+            practice::PracticePlugin,
+            scene_diagnostics::SceneDiagnosticsPlugin,
+            timeline::TimelinePlugin,
         ));
     }
 }
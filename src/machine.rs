@@ -1,15 +1,25 @@
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 use recipe::RecipeMeta;
+use serde::Deserialize;
 
 use crate::action::{PlayerAction, TargetAction};
-use crate::interaction::MarkerOf;
-use crate::inventory::Inventory;
-use crate::inventory::item::ItemRegistry;
-use crate::machine::recipe::RecipeRegistry;
+use crate::debug_flags::{DebugFlags, debug_flag};
+use crate::interaction::{InteractionFilterAppExt, MarkerOf};
+use crate::inventory::item::{ItemRegistry, ItemType, StatModifiers};
+use crate::inventory::{ConsumptionReport, Inventory};
+use crate::machine::fuel::Fuel;
+use crate::machine::overcook::CookedOutput;
+use crate::machine::recipe::{DiscoveredRecipes, RecipeRegistry};
+use crate::perks::PerkProfile;
+use crate::player::PlayerType;
+use crate::player::character_stats::CharacterStats;
+use crate::ui::toast_ui::Toast;
 
 mod animation;
+pub mod fuel;
 mod machine_ui;
+pub mod overcook;
 pub mod recipe;
 
 pub(super) struct MachinePlugin;
@@ -20,9 +30,120 @@ impl Plugin for MachinePlugin {
             machine_ui::MachineUiPlugin,
             recipe::RecipePlugin,
             animation::MachineAnimationPlugin,
+            overcook::OvercookPlugin,
+            fuel::FuelPlugin,
         ))
+        .add_systems(Update, infer_machine_kinds)
+        .add_systems(Update, cycle_machine_recipe)
         .add_systems(Update, handle_player_machine_interaction)
-        .add_systems(Update, update_cooking_machines);
+        .add_systems(
+            Update,
+            (
+                finish_cooking_instantly
+                    .run_if(debug_flag(|f| f.instant_cook)),
+                update_cooking_machines,
+            )
+                .chain(),
+        )
+        // Machines can't be interacted while operating.
+        .disable_interaction_if::<(), With<OperatedBy>>(|_| true)
+        .add_observer(setup_selected_recipe);
+
+        app.register_type::<MachineKind>();
+    }
+}
+
+/// Give machines without a [`MachineKind`] the first machine kind
+/// that is compatible with their recipe.
+fn infer_machine_kinds(
+    mut commands: Commands,
+    q_machines: Query<(&Machine, Entity), Without<MachineKind>>,
+    recipe_registry: RecipeRegistry,
+) {
+    for (machine, entity) in q_machines.iter() {
+        let Some(machine_kind) = machine
+            .get_recipe(&recipe_registry)
+            .and_then(|r| r.compatible_machines.first())
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert(*machine_kind);
+    }
+}
+
+/// Default the selected recipe to the recipe of the machine.
+fn setup_selected_recipe(
+    trigger: Trigger<OnInsert, Machine>,
+    mut commands: Commands,
+    q_machines: Query<&Machine>,
+) -> Result {
+    let entity = trigger.target();
+    let machine = q_machines.get(entity)?;
+
+    commands
+        .entity(entity)
+        .insert(SelectedRecipe(machine.recipe_id.clone()));
+
+    Ok(())
+}
+
+/// Scroll through the compatible recipes of the marked machine.
+fn cycle_machine_recipe(
+    q_players: Query<(&MarkerOf, &TargetAction)>,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_machines: Query<
+        (&MachineKind, &mut SelectedRecipe),
+        (Without<OperatedBy>, Without<CookedOutput>),
+    >,
+    recipe_registry: RecipeRegistry,
+) {
+    for (marker_of, target_action) in q_players.iter() {
+        let Ok((machine_kind, mut selected_recipe)) =
+            q_machines.get_mut(marker_of.entity())
+        else {
+            continue;
+        };
+
+        let Ok(action_state) = q_actions.get(target_action.get())
+        else {
+            continue;
+        };
+
+        // Scrolling zooms the camera instead.
+        if action_state.value(&PlayerAction::Zoom) != 0.0 {
+            continue;
+        }
+
+        let offset = if action_state
+            .just_pressed(&PlayerAction::CycleNext)
+        {
+            1
+        } else if action_state.just_pressed(&PlayerAction::CyclePrev)
+        {
+            -1
+        } else {
+            continue;
+        };
+
+        let recipe_ids = recipe_registry
+            .compatible_recipes(*machine_kind)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        if recipe_ids.is_empty() {
+            continue;
+        }
+
+        let index = recipe_ids
+            .iter()
+            .position(|id| **id == selected_recipe.0)
+            .unwrap_or_default() as isize;
+        let index =
+            (index + offset).rem_euclid(recipe_ids.len() as isize);
+
+        selected_recipe.0 = recipe_ids[index as usize].clone();
     }
 }
 
@@ -33,18 +154,46 @@ fn handle_player_machine_interaction(
         &MarkerOf,
         &TargetAction,
         &mut Inventory,
+        &PlayerType,
+        Option<&CharacterStats>,
         Entity,
     )>,
     q_actions: Query<&ActionState<PlayerAction>>,
     // Get only non-operating machines.
-    q_machines: Query<&Machine, Without<OperatedBy>>,
+    mut q_machines: Query<
+        (
+            &MachineKind,
+            &SelectedRecipe,
+            Option<&CookedOutput>,
+            Option<&mut Fuel>,
+        ),
+        Without<OperatedBy>,
+    >,
     recipe_registry: RecipeRegistry,
+    item_registry: ItemRegistry,
+    perks: Res<PerkProfile>,
+    debug_flags: Option<Res<DebugFlags>>,
 ) {
-    for (marked_item, target_action, mut inventory, player_entity) in
-        q_players.iter_mut()
+    let infinite_ingredients =
+        debug_flags.is_some_and(|f| f.infinite_ingredients);
+
+    for (
+        marked_item,
+        target_action,
+        mut inventory,
+        player_type,
+        character_stats,
+        player_entity,
+    ) in q_players.iter_mut()
     {
         let machine_entity = marked_item.entity();
-        let Ok(machine) = q_machines.get(machine_entity) else {
+        let Ok((
+            machine_kind,
+            selected_recipe,
+            cooked_output,
+            mut fuel,
+        )) = q_machines.get_mut(machine_entity)
+        else {
             continue;
         };
 
@@ -57,48 +206,128 @@ fn handle_player_machine_interaction(
             continue;
         };
 
+        // Collect the output before cooking anything else.
+        if let Some(cooked_output) = cooked_output {
+            if collect_cooked_output(
+                &mut commands,
+                cooked_output,
+                &mut inventory,
+                &item_registry,
+                player_entity,
+                machine_entity,
+            ) {
+                commands.trigger(Toast {
+                    player_type: *player_type,
+                    message: cooked_output.summary(),
+                });
+            }
+            continue;
+        }
+
         let Some(recipe) =
-            recipe_registry.get_recipe(&machine.recipe_id)
+            recipe_registry.get_recipe(selected_recipe)
         else {
             warn!(
                 "Recipe '{}' not found in registry!",
-                machine.recipe_id
+                **selected_recipe
+            );
+            continue;
+        };
+
+        if recipe.can_cook_in(*machine_kind) == false {
+            warn!(
+                "Recipe '{}' can't be cooked in {machine_kind:?}!",
+                **selected_recipe
             );
             continue;
+        }
+
+        // Top up the fuel before cooking.
+        if let Some(fuel) =
+            fuel.as_mut().filter(|_| infinite_ingredients == false)
+        {
+            fuel.load_from(&mut inventory);
+
+            if fuel.can_cook() == false {
+                commands.trigger(Toast {
+                    player_type: *player_type,
+                    message: format!(
+                        "Needs {} to operate!",
+                        fuel.item_id.replace('_', " ")
+                    ),
+                });
+                continue;
+            }
+        }
+
+        let is_tower = item_registry
+            .get_item(&recipe.output_id)
+            .is_some_and(|m| m.item_type == ItemType::Tower);
+        let recipe = recipe.discounted(match is_tower {
+            true => perks.tower_discount(),
+            false => 0,
+        });
+
+        let report = match infinite_ingredients {
+            true => Some(ConsumptionReport::default()),
+            false => inventory.check_and_use_recipe(&recipe),
         };
 
-        if inventory.check_and_use_recipe(recipe) {
+        if let Some(report) = report {
+            if let Some(fuel) = fuel
+                .as_mut()
+                .filter(|_| infinite_ingredients == false)
+            {
+                fuel.consume();
+            }
+
+            let cook_duration = character_stats
+                .map_or(1.0, |s| s.cook_duration())
+                * perks.cook_duration();
+
             commands.entity(machine_entity).insert((
                 OperationTimer(Timer::from_seconds(
-                    recipe.cooking_duration,
+                    recipe.cooking_duration * cook_duration,
                     TimerMode::Once,
                 )),
                 OperatedBy(player_entity),
+                CookingModifiers(report.modifiers(&item_registry)),
             ));
         } else {
             info!(
                 "Player {} doesn't have required ingredients for recipe '{}'",
-                player_entity, machine.recipe_id
+                player_entity, **selected_recipe
             );
         }
     }
 }
 
+fn finish_cooking_instantly(
+    mut q_timers: Query<&mut OperationTimer>,
+) {
+    for mut timer in q_timers.iter_mut() {
+        let duration = timer.duration();
+        timer.set_elapsed(duration);
+    }
+}
+
 /// Update cooking machines and complete cooking when timer finishes.
+///
+/// The output waits in the machine as a [`CookedOutput`] until
+/// it gets collected.
 fn update_cooking_machines(
     mut commands: Commands,
     mut q_machines: Query<(
-        &Machine,
+        &SelectedRecipe,
         &mut OperationTimer,
-        &OperatedBy,
+        Option<&CookingModifiers>,
         Entity,
     )>,
-    mut q_inventories: Query<&mut Inventory>,
     recipe_registry: RecipeRegistry,
-    item_registry: ItemRegistry,
+    mut discovered_recipes: ResMut<DiscoveredRecipes>,
     time: Res<Time>,
 ) {
-    for (machine, mut timer, operated_by, entity) in
+    for (selected_recipe, mut timer, modifiers, entity) in
         q_machines.iter_mut()
     {
         if timer.tick(time.delta()).finished() == false {
@@ -106,59 +335,121 @@ fn update_cooking_machines(
         }
 
         let Some(recipe) =
-            recipe_registry.get_recipe(&machine.recipe_id)
+            recipe_registry.get_recipe(selected_recipe)
         else {
             warn!(
                 "Recipe '{}' not found in registry",
-                machine.recipe_id
-            );
-            continue;
-        };
-
-        let Some(item) = item_registry.get_item(&recipe.output_id)
-        else {
-            warn!(
-                "Output item '{}' not found in item registry",
-                recipe.output_id
+                **selected_recipe
             );
             continue;
         };
 
         commands
             .entity(entity)
-            .remove::<(OperationTimer, OperatedBy)>();
-
-        let player_entity = operated_by.entity();
-        if let Ok(mut inventory) =
-            q_inventories.get_mut(player_entity)
-        {
-            // Add tower to player's inventory.
-            inventory.add_tower(
-                recipe.output_id.clone(),
-                recipe.output_quantity,
-                // TODO: Handle when stack size exceeds!
-                // Should not happen in the first place anyways...
-                // Could happen if there are more than 1 similar machines...
-                item.max_stack_size,
-            );
-        } else {
-            error!(
-                "Could not get inventory for player {}",
-                player_entity
+            .remove::<(OperationTimer, OperatedBy, CookingModifiers)>(
+            )
+            .insert(
+                CookedOutput::new(
+                    recipe.output_id.clone(),
+                    recipe.output_quantity,
+                )
+                .with_modifiers(
+                    modifiers.map(|m| m.0).unwrap_or_default(),
+                ),
             );
+
+        if discovered_recipes.contains(&**selected_recipe) == false {
+            discovered_recipes.insert(selected_recipe.to_string());
         }
     }
 }
 
-/// Component representing a machine that can convert ingredients to towers
+/// Give the [`CookedOutput`] of a machine to the collecting player.
+///
+/// Returns `false` if the output can't be collected yet.
+fn collect_cooked_output(
+    commands: &mut Commands,
+    cooked_output: &CookedOutput,
+    inventory: &mut Inventory,
+    item_registry: &ItemRegistry,
+    player_entity: Entity,
+    machine_entity: Entity,
+) -> bool {
+    let Some(item) = item_registry.get_item(&cooked_output.item_id)
+    else {
+        warn!(
+            "Output item '{}' not found in item registry",
+            cooked_output.item_id
+        );
+        return false;
+    };
+
+    // Carryables are spawned into the world by their own logic.
+    if item.item_type == ItemType::Carryable {
+        commands.trigger_targets(
+            CarryableCrafted {
+                item_id: cooked_output.item_id.clone(),
+                player: player_entity,
+            },
+            machine_entity,
+        );
+    } else if inventory.add_with_modifiers(
+        &cooked_output.item_id,
+        item,
+        cooked_output.quantity,
+        cooked_output.modifiers,
+    ) == false
+    {
+        info!(
+            "Inventory of player {player_entity} is full, can't collect {}!",
+            cooked_output.item_id
+        );
+        return false;
+    }
+
+    commands.entity(machine_entity).remove::<CookedOutput>();
+    true
+}
+
+/// Component representing a machine that can convert ingredients
+/// to towers, carryables or other ingredients
 #[derive(Component, Reflect, Debug, Clone)]
 #[component(immutable)]
 #[reflect(Component)]
 pub struct Machine {
-    /// The ID of the recipe to use from the registry
+    /// The ID of the default recipe to use from the registry
     pub recipe_id: String,
 }
 
+/// The recipe that the [`Machine`] will cook, the marking player
+/// can scroll through every recipe compatible with its [`MachineKind`].
+#[derive(Component, Deref, Debug, Clone)]
+pub struct SelectedRecipe(String);
+
+/// The kind of a [`Machine`], determines which recipes it can cook.
+///
+/// Machines without a kind will be inferred from their recipe.
+#[derive(
+    Component,
+    Reflect,
+    Deserialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[reflect(Component, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MachineKind {
+    #[default]
+    Oven,
+    Mixer,
+    Fryer,
+}
+
 impl Machine {
     /// Get the recipe data from the registry
     pub fn get_recipe<'a>(
@@ -167,17 +458,15 @@ impl Machine {
     ) -> Option<&'a RecipeMeta> {
         registry.get_recipe(&self.recipe_id)
     }
+}
 
-    pub fn get_icon(
-        &self,
-        recipe_registry: &RecipeRegistry,
-        item_registry: &ItemRegistry,
-    ) -> Option<Handle<Image>> {
-        let recipe = self.get_recipe(recipe_registry)?;
-        let item = item_registry.get_item(&recipe.output_id)?;
-
-        Some(item.icon.clone())
-    }
+/// Triggered on the machine when it finishes crafting an
+/// [`ItemType::Carryable`] item.
+#[derive(Event, Debug)]
+pub struct CarryableCrafted {
+    pub item_id: String,
+    /// The player that operated the machine.
+    pub player: Entity,
 }
 
 #[derive(Component, Deref, Default, Debug)]
@@ -190,3 +479,7 @@ pub struct OperatedBy(Entity);
 
 #[derive(Component, Deref, DerefMut)]
 pub struct OperationTimer(Timer);
+
+/// Stat modifiers from the ingredients used in the current cook.
+#[derive(Component, Deref, Debug)]
+pub struct CookingModifiers(StatModifiers);
@@ -1,11 +1,13 @@
-use std::time::Duration;
-
 use bevy::animation::AnimationTarget;
 use bevy::ecs::query::QueryFilter;
 use bevy::prelude::*;
 
+use crate::asset_pipeline::animation_controller::{
+    AnimationControllerAppExt, AnimationControllerSystems,
+    AnimationGraphController, AnimationState, StateClip,
+};
 use crate::asset_pipeline::animation_pipeline::{
-    AnimationGraphMap, NodeMap,
+    AnimationGraphMap, AnimationOwner, NodeMap,
 };
 use crate::asset_pipeline::{AssetState, PrefabAssets};
 use crate::interaction::MarkerPlayers;
@@ -17,86 +19,32 @@ pub(super) struct MachineAnimationPlugin;
 
 impl Plugin for MachineAnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (setup_animation_graph, on_play)
-                .run_if(in_state(AssetState::Loaded)),
-        );
+        app.add_animation_controller::<MachineAnimation>()
+            .add_systems(
+                Update,
+                setup_animation_graph
+                    .before(AnimationControllerSystems)
+                    .run_if(in_state(AssetState::Loaded)),
+            );
     }
 }
 
-fn on_play(
-    q_machines: Query<
-        (&NodeMap, &AnimationTarget, &Machine),
-        With<OperationTimer>,
-    >,
-    mut q_animation_players: Query<(
-        &mut AnimationPlayer,
-        &mut AnimationTransitions,
-    )>,
-) -> Result {
-    for (node_map, animation_target, machine) in q_machines.iter() {
-        let (mut anim_player, mut anim_transitions) =
-            q_animation_players.get_mut(animation_target.player)?;
-
-        if anim_player.all_finished() {
-            let play_node =
-                *node_map.get("OnPlay").ok_or(format!(
-                    "No 'OnPlay' animation for {}!",
-                    machine.recipe_id
-                ))?;
-
-            anim_transitions
-                .play(
-                    &mut anim_player,
-                    play_node,
-                    Duration::from_millis(0),
-                )
-                .repeat();
-        }
-    }
-
-    Ok(())
-}
-
 fn on_trigger_animation<E: Event, B: Bundle, F: QueryFilter>(
-    animation_name: &str,
+    state: MachineAnimation,
 ) -> impl Fn(
     Trigger<'_, E, B>,
-    Query<'_, '_, (&NodeMap, &AnimationTarget, &Machine), F>,
-    Query<'_, '_, (&mut AnimationPlayer, &mut AnimationTransitions)>,
-) -> Result {
+    Query<'_, '_, &mut AnimationGraphController<MachineAnimation>, F>,
+) {
     move |trigger: Trigger<E, B>,
-          q_machines: Query<
-        (&NodeMap, &AnimationTarget, &Machine),
+          mut q_machines: Query<
+        &mut AnimationGraphController<MachineAnimation>,
         F,
-    >,
-          mut q_animation_players: Query<(
-        &mut AnimationPlayer,
-        &mut AnimationTransitions,
-    )>| {
-        let entity = trigger.target();
-        let Ok((node_map, animation_target, machine)) =
-            q_machines.get(entity)
-        else {
-            return Ok(());
-        };
-
-        let (mut anim_player, mut anim_transitions) =
-            q_animation_players.get_mut(animation_target.player)?;
-
-        let node = *node_map.get(animation_name).ok_or(format!(
-            "No '{animation_name}' animation for {}!",
-            machine.recipe_id
-        ))?;
-
-        anim_transitions.play(
-            &mut anim_player,
-            node,
-            Duration::from_millis(500),
-        );
-
-        Ok(())
+    >| {
+        if let Ok(mut controller) =
+            q_machines.get_mut(trigger.target())
+        {
+            controller.set(state);
+        }
     }
 }
 
@@ -123,31 +71,36 @@ fn setup_animation_graph(
 
         commands
             .entity(entity)
-            .insert(node_map.clone())
+            .insert((
+                node_map.clone(),
+                AnimationGraphController::<MachineAnimation>::default(
+                ),
+            ))
             .observe(on_trigger_animation::<
                 OnAdd,
                 MarkerPlayers,
                 Without<OperationTimer>,
-            >("OnEnter"))
+            >(MachineAnimation::Enter))
             .observe(on_trigger_animation::<
                 OnRemove,
                 MarkerPlayers,
                 Without<OperationTimer>,
-            >("OnExit"))
+            >(MachineAnimation::Exit))
             .observe(
                 on_trigger_animation::<OnAdd, OperationTimer, ()>(
-                    "OnStart",
+                    MachineAnimation::Start,
                 ),
             )
             .observe(on_trigger_animation::<
                 OnRemove,
                 OperationTimer,
                 (),
-            >("OnStop"));
+            >(MachineAnimation::Stop));
 
         commands.entity(animation_target.player).insert((
             AnimationGraphHandle(graph.clone()),
             AnimationTransitions::new(),
+            AnimationOwner(entity),
         ));
 
         info!("Setup animation graph for {}.", machine.recipe_id);
@@ -155,3 +108,45 @@ fn setup_animation_graph(
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineAnimation {
+    /// A player walked up to the machine.
+    Enter,
+    /// The players walked away from the machine.
+    Exit,
+    /// Started cooking, continues with [`Self::Play`].
+    Start,
+    /// Cooking.
+    Play,
+    Stop,
+}
+
+impl AnimationState for MachineAnimation {
+    fn clip(&self) -> StateClip {
+        match self {
+            MachineAnimation::Enter => {
+                StateClip::new("OnEnter").with_transition(500)
+            }
+            MachineAnimation::Exit => {
+                StateClip::new("OnExit").with_transition(500)
+            }
+            MachineAnimation::Start => {
+                StateClip::new("OnStart").with_transition(500)
+            }
+            MachineAnimation::Play => {
+                StateClip::new("OnPlay").repeat()
+            }
+            MachineAnimation::Stop => {
+                StateClip::new("OnStop").with_transition(500)
+            }
+        }
+    }
+
+    fn next(&self) -> Option<Self> {
+        match self {
+            MachineAnimation::Start => Some(MachineAnimation::Play),
+            _ => None,
+        }
+    }
+}
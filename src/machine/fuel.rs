@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::inventory::Inventory;
+use crate::ui::Screen;
+
+/// Item used as fuel by default.
+pub const DEFAULT_FUEL_ID: &str = "butter";
+
+pub(super) struct FuelPlugin;
+
+impl Plugin for FuelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            run_fuel_generators.run_if(in_state(Screen::EnterLevel)),
+        )
+        .add_observer(setup_generator_timer);
+
+        app.register_type::<Fuel>().register_type::<FuelGenerator>();
+    }
+}
+
+fn setup_generator_timer(
+    trigger: Trigger<OnAdd, FuelGenerator>,
+    mut commands: Commands,
+    q_generators: Query<&FuelGenerator>,
+) -> Result {
+    let entity = trigger.target();
+    let generator = q_generators.get(entity)?;
+
+    commands.entity(entity).insert(GeneratorTimer(
+        Timer::from_seconds(generator.interval, TimerMode::Repeating),
+    ));
+
+    Ok(())
+}
+
+/// Refuel machines that are within range of a generator.
+fn run_fuel_generators(
+    mut q_generators: Query<(
+        &FuelGenerator,
+        &mut GeneratorTimer,
+        &GlobalTransform,
+    )>,
+    mut q_machines: Query<(&mut Fuel, &GlobalTransform)>,
+    time: Res<Time>,
+) {
+    for (generator, mut timer, generator_transform) in
+        q_generators.iter_mut()
+    {
+        if timer.tick(time.delta()).just_finished() == false {
+            continue;
+        }
+
+        for (mut fuel, machine_transform) in q_machines.iter_mut() {
+            let distance = generator_transform
+                .translation()
+                .distance(machine_transform.translation());
+
+            if distance <= generator.range && fuel.is_full() == false
+            {
+                fuel.amount += 1;
+            }
+        }
+    }
+}
+
+/// Fuel that a [`Machine`][super::Machine] needs to operate.
+///
+/// Fuel items are loaded from the player's inventory when
+/// interacting with the machine and consumed on every cook.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct Fuel {
+    /// Id of the item used as fuel.
+    pub item_id: String,
+    /// Maximum amount of fuel that can be loaded.
+    pub capacity: u32,
+    /// Amount of fuel consumed per cook.
+    pub per_cook: u32,
+    /// Amount of fuel currently loaded.
+    pub amount: u32,
+}
+
+impl Fuel {
+    pub fn is_full(&self) -> bool {
+        self.amount >= self.capacity
+    }
+
+    /// Returns `true` if there is enough fuel for a cook.
+    pub fn can_cook(&self) -> bool {
+        self.amount >= self.per_cook
+    }
+
+    /// Fraction of the capacity that is loaded.
+    pub fn fraction(&self) -> f32 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+
+        self.amount as f32 / self.capacity as f32
+    }
+
+    /// Load as much fuel as possible from the inventory, returning
+    /// the amount loaded.
+    pub fn load_from(&mut self, inventory: &mut Inventory) -> u32 {
+        let quantity = self
+            .capacity
+            .saturating_sub(self.amount)
+            .min(inventory.count(&self.item_id));
+
+        if quantity > 0 && inventory.remove(&self.item_id, quantity) {
+            self.amount += quantity;
+            return quantity;
+        }
+
+        0
+    }
+
+    /// Consume fuel for a cook, returns `false` if there isn't
+    /// enough of it.
+    pub fn consume(&mut self) -> bool {
+        if self.can_cook() == false {
+            return false;
+        }
+
+        self.amount -= self.per_cook;
+        true
+    }
+}
+
+impl Default for Fuel {
+    fn default() -> Self {
+        Self {
+            item_id: DEFAULT_FUEL_ID.to_string(),
+            capacity: 5,
+            per_cook: 1,
+            amount: 0,
+        }
+    }
+}
+
+/// Periodically adds a unit of fuel to every [`Fuel`] machine
+/// within range.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct FuelGenerator {
+    pub range: f32,
+    /// Seconds between each unit of fuel.
+    pub interval: f32,
+}
+
+impl Default for FuelGenerator {
+    fn default() -> Self {
+        Self {
+            range: 4.0,
+            interval: 5.0,
+        }
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct GeneratorTimer(Timer);
@@ -1,4 +1,5 @@
 use bevy::color::palettes::tailwind::*;
+use bevy::ecs::spawn::SpawnIter;
 use bevy::prelude::*;
 
 use crate::camera_controller::split_screen::{
@@ -7,11 +8,14 @@ use crate::camera_controller::split_screen::{
 use crate::interaction::MarkerPlayers;
 use crate::inventory::item::ItemRegistry;
 use crate::player::PlayerType;
+use crate::ui::item_tooltip::item_tooltip_bundle;
 use crate::ui::widgets::progress_bar::ProgressBar;
 use crate::ui::world_space::WorldUi;
 
+use super::fuel::Fuel;
+use super::overcook::CookedOutput;
 use super::recipe::{RecipeMeta, RecipeRegistry};
-use super::{Machine, OperationTimer};
+use super::{Machine, MachineKind, OperationTimer, SelectedRecipe};
 
 pub(super) struct MachineUiPlugin;
 
@@ -76,7 +80,7 @@ fn setup_machine_ui(
 /// by the player.
 fn machine_ui_visibility(
     q_machines: Query<
-        (Option<&MarkerPlayers>, &MachineUis),
+        (Option<&MarkerPlayers>, &MachineUis, Has<CookedOutput>),
         With<Machine>,
     >,
     q_target_cameras: Query<&UiTargetCamera>,
@@ -84,7 +88,7 @@ fn machine_ui_visibility(
     q_player_types: Query<&PlayerType>,
     mut q_viz: Query<&mut Visibility>,
 ) -> Result {
-    for (players, uis) in q_machines.iter() {
+    for (players, uis, has_cooked_output) in q_machines.iter() {
         let mut marked_by_players = vec![];
 
         if let Some(players) = players {
@@ -106,7 +110,10 @@ fn machine_ui_visibility(
 
             // Set node visibility based on who marked the machine.
             let mut viz = q_viz.get_mut(ui)?;
-            if marked_by_players.contains(&player_type) {
+            // Outputs waiting to be collected are shown to everyone.
+            if marked_by_players.contains(&player_type)
+                || has_cooked_output
+            {
                 *viz = Visibility::Inherited;
             } else {
                 *viz = Visibility::Hidden;
@@ -120,7 +127,14 @@ fn machine_ui_visibility(
 /// System to update machine popup UI content based on machine state
 fn machine_ui_content(
     mut commands: Commands,
-    q_machines: Query<(&Machine, Option<&OperationTimer>, Entity)>,
+    q_machines: Query<(
+        &SelectedRecipe,
+        &MachineKind,
+        Option<&OperationTimer>,
+        Option<&CookedOutput>,
+        Option<&Fuel>,
+        Entity,
+    )>,
     q_machine_uis: Query<(Entity, &MachineUiOf)>,
     recipe_registry: RecipeRegistry,
     item_registry: ItemRegistry,
@@ -128,8 +142,14 @@ fn machine_ui_content(
     // Update each content marker with its specific machine's data
     for (root_id, ui_of) in q_machine_uis.iter() {
         // Find the machine that owns this content marker
-        let Ok((machine, operation_timer, machine_entity)) =
-            q_machines.get(ui_of.entity())
+        let Ok((
+            selected_recipe,
+            machine_kind,
+            operation_timer,
+            cooked_output,
+            fuel,
+            machine_entity,
+        )) = q_machines.get(ui_of.entity())
         else {
             continue;
         };
@@ -138,17 +158,33 @@ fn machine_ui_content(
         commands.entity(root_id).despawn_related::<Children>();
 
         // Handle empty recipe ID
-        if machine.recipe_id.is_empty() {
+        if selected_recipe.is_empty() {
             error!("No recipe set for machine {machine_entity}!");
             continue;
         }
 
-        let recipe =
-            machine.get_recipe(&recipe_registry).ok_or(format!(
+        let recipe = recipe_registry
+            .get_recipe(selected_recipe)
+            .ok_or(format!(
                 "Recipe: {} does not exists for {machine_entity}!",
-                machine.recipe_id
+                **selected_recipe
             ))?;
 
+        let kind_id = commands
+            .spawn((
+                Text::new(format!("{machine_kind:?}")),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(AMBER_200.into()),
+                Node {
+                    margin: UiRect::bottom(Val::Px(4.0)),
+                    ..default()
+                },
+            ))
+            .id();
+
         let icon_id = commands
             .spawn((
                 Node {
@@ -157,29 +193,45 @@ fn machine_ui_content(
                     ..default()
                 },
                 ImageNode::new(
-                    machine
-                        .get_icon(&recipe_registry, &item_registry)
+                    item_registry
+                        .get_item(
+                            cooked_output
+                                .map(|o| &o.item_id)
+                                .unwrap_or(&recipe.output_id),
+                        )
+                        .map(|i| i.icon.clone())
                         .ok_or("Should have output icon.")?,
                 ),
             ))
             .id();
 
-        let content_ids = match operation_timer {
-            Some(operation_timer) => operating_machine_ui(
+        let content_ids = match (operation_timer, cooked_output) {
+            (Some(operation_timer), _) => operating_machine_ui(
                 commands.reborrow(),
                 &operation_timer.0,
             ),
-            None => freed_machine_ui(
+            (None, Some(cooked_output)) => {
+                cooked_machine_ui(commands.reborrow(), cooked_output)
+            }
+            (None, None) => freed_machine_ui(
                 commands.reborrow(),
                 recipe,
+                selected_recipe,
+                *machine_kind,
                 &item_registry,
+                &recipe_registry,
             ),
         };
 
         commands
             .entity(root_id)
-            .add_child(icon_id)
+            .add_children(&[kind_id, icon_id])
             .add_children(&content_ids);
+
+        if let Some(fuel) = fuel {
+            let gauge_id = fuel_gauge_ui(commands.reborrow(), fuel);
+            commands.entity(root_id).add_child(gauge_id);
+        }
     }
 
     Ok(())
@@ -188,7 +240,10 @@ fn machine_ui_content(
 fn freed_machine_ui(
     mut commands: Commands,
     recipe: &RecipeMeta,
+    selected_recipe: &str,
+    machine_kind: MachineKind,
     item_registry: &ItemRegistry,
+    recipe_registry: &RecipeRegistry,
 ) -> Vec<Entity> {
     let mut children = vec![];
 
@@ -274,6 +329,136 @@ fn freed_machine_ui(
             .id(),
     ]);
 
+    // Details of the output item, shown while the machine is marked.
+    if let Some(tooltip) = item_tooltip_bundle(
+        Node {
+            margin: UiRect::top(Val::Px(8.0)),
+            max_width: Val::Px(200.0),
+            ..default()
+        },
+        &recipe.output_id,
+        item_registry,
+        recipe_registry,
+    ) {
+        children.push(commands.spawn(tooltip).id());
+    }
+
+    // Recipes available in this kind of machine.
+    let available_icons = recipe_registry
+        .compatible_recipes(machine_kind)
+        .into_iter()
+        .filter_map(|(id, r)| {
+            item_registry.get_item(&r.output_id).map(|item| {
+                (item.icon.clone(), id == selected_recipe)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if available_icons.len() > 1 {
+        children.push(
+            commands
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(4.0),
+                        margin: UiRect::top(Val::Px(8.0)),
+                        ..default()
+                    },
+                    Children::spawn((
+                        Spawn((
+                            Text::new("< Scroll >"),
+                            TextFont {
+                                font_size: 11.0,
+                                ..default()
+                            },
+                            TextColor(GRAY_400.into()),
+                        )),
+                        SpawnIter(available_icons.into_iter().map(
+                            |(icon, selected)| {
+                                (
+                                    Node {
+                                        width: Val::Px(24.0),
+                                        height: Val::Px(24.0),
+                                        border: UiRect::all(Val::Px(
+                                            2.0,
+                                        )),
+                                        ..default()
+                                    },
+                                    BorderColor(match selected {
+                                        true => AMBER_300.into(),
+                                        false => Color::NONE,
+                                    }),
+                                    BorderRadius::all(Val::Px(4.0)),
+                                    ImageNode::new(icon),
+                                )
+                            },
+                        )),
+                    )),
+                ))
+                .id(),
+        );
+    }
+
+    children
+}
+
+fn cooked_machine_ui(
+    mut commands: Commands,
+    cooked_output: &CookedOutput,
+) -> Vec<Entity> {
+    let (status, color) = match cooked_output.burnt {
+        true => ("Burnt!".to_string(), RED_400),
+        false => (
+            format!(
+                "Ready! Burns in {:.1}s",
+                cooked_output.timer.remaining_secs()
+            ),
+            GREEN_300,
+        ),
+    };
+
+    let mut children = vec![
+        commands
+            .spawn((
+                Text::new(status),
+                TextLayout::new_with_justify(JustifyText::Center),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(color.into()),
+                Node {
+                    margin: UiRect::vertical(Val::Px(8.0)),
+                    ..default()
+                },
+            ))
+            .id(),
+    ];
+
+    if cooked_output.burnt == false {
+        const RADIUS: BorderRadius = BorderRadius::all(Val::Px(4.0));
+        children.push(
+            commands
+                .spawn((
+                    Node {
+                        width: Val::Px(140.0),
+                        height: Val::Px(8.0),
+                        margin: UiRect::bottom(Val::Px(12.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BackgroundColor(GRAY_700.into()),
+                    RADIUS,
+                    ProgressBar::new(RED_500, RADIUS)
+                        .with_init_progress(
+                            1.0 - cooked_output.timer.fraction(),
+                        ),
+                ))
+                .id(),
+        );
+    }
+
     children
 }
 
@@ -344,8 +529,55 @@ fn operating_machine_ui(
     ]
 }
 
+fn fuel_gauge_ui(mut commands: Commands, fuel: &Fuel) -> Entity {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(4.0));
+
+    let color = match fuel.can_cook() {
+        true => AMBER_400,
+        false => RED_500,
+    };
+
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(6.0),
+                ..default()
+            },
+            Children::spawn((
+                Spawn((
+                    Text::new(format!(
+                        "{} {}/{}",
+                        fuel.item_id.replace('_', " "),
+                        fuel.amount,
+                        fuel.capacity
+                    )),
+                    TextFont {
+                        font_size: 11.0,
+                        ..default()
+                    },
+                    TextColor(color.into()),
+                )),
+                Spawn((
+                    Node {
+                        width: Val::Px(80.0),
+                        height: Val::Px(6.0),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BackgroundColor(GRAY_700.into()),
+                    RADIUS,
+                    ProgressBar::new(color, RADIUS)
+                        .with_init_progress(fuel.fraction()),
+                )),
+            )),
+        ))
+        .id()
+}
+
 #[derive(Component, Deref, Debug)]
-#[relationship_target(relationship = MachineUiOf)]
+#[relationship_target(relationship = MachineUiOf, linked_spawn)]
 pub struct MachineUis(Vec<Entity>);
 
 /// Relation target for [`MachineUis`], relating the Ui for the [`Machine`].
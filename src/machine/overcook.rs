@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+
+use crate::interaction::InteractionPrompt;
+use crate::inventory::item::StatModifiers;
+use crate::ui::Screen;
+use crate::vfx::{VfxEmitter, VfxPreset};
+
+/// Seconds before an uncollected output burns.
+const OVERCOOK_DURATION: f32 = 10.0;
+/// Item that uncollected outputs degrade into.
+const BURNT_ITEM_ID: &str = "burnt_food";
+
+pub(super) struct OvercookPlugin;
+
+impl Plugin for OvercookPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (overcook_outputs, start_smoking)
+                .run_if(in_state(Screen::EnterLevel)),
+        )
+        .add_observer(prompt_collect)
+        .add_observer(reset_prompt)
+        .add_observer(stop_smoking);
+    }
+}
+
+fn prompt_collect(
+    trigger: Trigger<OnAdd, CookedOutput>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(trigger.target())
+        .insert(InteractionPrompt("Collect".to_string()));
+}
+
+fn reset_prompt(
+    trigger: Trigger<OnRemove, CookedOutput>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(trigger.target())
+        .try_remove::<InteractionPrompt>();
+}
+
+fn stop_smoking(
+    trigger: Trigger<OnRemove, CookedOutput>,
+    mut commands: Commands,
+) {
+    commands.entity(trigger.target()).try_remove::<VfxEmitter>();
+}
+
+/// Burn outputs that are not collected in time.
+fn overcook_outputs(
+    mut q_outputs: Query<(&mut CookedOutput, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut cooked_output, entity) in q_outputs.iter_mut() {
+        if cooked_output.burnt
+            || cooked_output.timer.tick(time.delta()).finished()
+                == false
+        {
+            continue;
+        }
+
+        info!(
+            "{} in machine {entity} got burnt!",
+            cooked_output.item_id
+        );
+        cooked_output.item_id = BURNT_ITEM_ID.to_string();
+        cooked_output.modifiers = StatModifiers::default();
+        cooked_output.burnt = true;
+    }
+}
+
+/// Smoke starts rising halfway through the overcook window.
+fn start_smoking(
+    mut commands: Commands,
+    q_outputs: Query<(&CookedOutput, Option<&VfxEmitter>, Entity)>,
+) {
+    for (cooked_output, emitter, entity) in q_outputs.iter() {
+        if cooked_output.burnt == false
+            && cooked_output.timer.fraction() < 0.5
+        {
+            continue;
+        }
+
+        if emitter.is_some_and(|e| e.preset == VfxPreset::Smoke) {
+            continue;
+        }
+
+        commands.entity(entity).insert(
+            VfxEmitter::new(VfxPreset::Smoke)
+                .with_offset(Vec3::Y * 2.0),
+        );
+    }
+}
+
+/// Output of a machine that is waiting to be collected.
+#[derive(Component, Debug)]
+pub struct CookedOutput {
+    pub item_id: String,
+    pub quantity: u32,
+    /// Stat modifiers from the ingredients used.
+    pub modifiers: StatModifiers,
+    /// Remaining time before the output burns.
+    pub timer: Timer,
+    /// Whether the output has been burnt.
+    pub burnt: bool,
+}
+
+impl CookedOutput {
+    pub fn new(item_id: String, quantity: u32) -> Self {
+        Self {
+            item_id,
+            quantity,
+            modifiers: StatModifiers::default(),
+            timer: Timer::from_seconds(
+                OVERCOOK_DURATION,
+                TimerMode::Once,
+            ),
+            burnt: false,
+        }
+    }
+
+    pub fn with_modifiers(
+        mut self,
+        modifiers: StatModifiers,
+    ) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Short summary of the output, e.g. "Crafted gun tower x1
+    /// (+0.5 range)".
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Crafted {} x{}",
+            self.item_id.replace('_', " "),
+            self.quantity
+        );
+
+        if self.modifiers.is_zero() == false {
+            summary += &format!(" ({})", self.modifiers);
+        }
+
+        summary
+    }
+}
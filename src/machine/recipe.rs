@@ -1,9 +1,15 @@
+use std::borrow::Cow;
+
 use crate::asset_pipeline::PrefabName;
+use crate::asset_pipeline::loading::TrackedAssets;
 use crate::inventory::item::{ItemRegistry, ItemType};
+use crate::machine::MachineKind;
+use crate::machine::fuel::DEFAULT_FUEL_ID;
+use crate::migration::Migrations;
 use bevy::asset::{AssetLoader, io::Reader};
 use bevy::asset::{AsyncReadExt, LoadContext};
 use bevy::ecs::system::SystemParam;
-use bevy::platform::collections::HashMap;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use serde::Deserialize;
 
@@ -13,7 +19,8 @@ pub(super) struct RecipePlugin;
 impl Plugin for RecipePlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<RecipeMetaAsset>()
-            .init_asset_loader::<RecipeMetaAssetLoader>();
+            .init_asset_loader::<RecipeMetaAssetLoader>()
+            .init_resource::<DiscoveredRecipes>();
 
         app.add_systems(PreStartup, load_recipe_registry)
             .add_systems(Update, validate_recipes_against_items);
@@ -24,13 +31,15 @@ impl Plugin for RecipePlugin {
 fn load_recipe_registry(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
 ) {
-    commands.insert_resource(RecipeMetaAssetHandle(
-        asset_server.load("machines.recipe_meta.ron"),
-    ));
+    let handle = asset_server.load("machines.recipe_meta.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(RecipeMetaAssetHandle(handle));
 }
 
 /// System to validate that all recipe ingredients and outputs exist in the item registry
+/// and that recipe chains are well formed
 fn validate_recipes_against_items(
     recipe_registry: RecipeRegistry,
     item_registry: ItemRegistry,
@@ -55,28 +64,46 @@ fn validate_recipes_against_items(
     info!("Validating recipes against item registry...");
 
     for (recipe_id, recipe) in recipes.iter() {
-        // Validate output item exists and is correct type
-        if let Some(output_item) = items.get(&recipe.output_id) {
-            match output_item.item_type {
-                ItemType::Tower => {
-                    // Recipes should produce towers, no warning needed
-                }
-                ItemType::Ingredient => {
-                    warn!(
-                        "Recipe '{}' produces ingredient '{}' - consider if this is intended",
-                        recipe_id, recipe.output_id
-                    );
-                }
-            }
-        } else {
+        // Validate output item exists
+        let Some(output_item) = items.get(&recipe.output_id) else {
             error!(
                 "Recipe '{}' output '{}' not found in item registry!",
                 recipe_id, recipe.output_id
             );
+            continue;
+        };
+
+        // Intermediate products should be consumed by another recipe,
+        // fuel is consumed by machines instead.
+        if output_item.item_type == ItemType::Ingredient
+            && recipe.output_id != DEFAULT_FUEL_ID
+            && recipes.values().any(|r| r.uses(&recipe.output_id))
+                == false
+        {
+            warn!(
+                "Recipe '{}' produces ingredient '{}' that no recipe uses!",
+                recipe_id, recipe.output_id
+            );
+        }
+
+        if recipe.uses(&recipe.output_id) {
+            error!(
+                "Recipe '{}' consumes its own output '{}'!",
+                recipe_id, recipe.output_id
+            );
+        }
+
+        if recipe.compatible_machines.is_empty() {
+            error!(
+                "Recipe '{}' can't be cooked in any machine!",
+                recipe_id
+            );
         }
 
         // Validate all ingredient items exist and are ingredients
-        for ingredient in &recipe.ingredients {
+        for ingredient in
+            recipe.ingredients.iter().chain(&recipe.extras)
+        {
             if let Some(item_meta) = items.get(&ingredient.item_id) {
                 if item_meta.item_type != ItemType::Ingredient {
                     warn!(
@@ -105,16 +132,55 @@ pub struct RecipeMetaAsset(HashMap<String, RecipeMeta>);
 #[derive(Debug, Clone, Deserialize)]
 pub struct RecipeMeta {
     pub ingredients: Vec<RecipeIngredient>,
+    /// Optional ingredients that are used (up to the quantity)
+    /// when available, modifying the stats of the output.
+    #[serde(default)]
+    pub extras: Vec<RecipeIngredient>,
     pub output_id: String,
     pub output_quantity: u32,
     pub cooking_duration: f32,
     prefab_name: String,
+    /// Kinds of machines that can cook this recipe.
+    pub compatible_machines: Vec<MachineKind>,
 }
 
 impl RecipeMeta {
     pub fn prefab_name(&self) -> PrefabName {
         PrefabName::FileName(&self.prefab_name)
     }
+
+    pub fn raw_prefab_name(&self) -> &str {
+        &self.prefab_name
+    }
+
+    /// Returns `true` if the machine kind can cook this recipe.
+    pub fn can_cook_in(&self, machine_kind: MachineKind) -> bool {
+        self.compatible_machines.contains(&machine_kind)
+    }
+
+    /// The recipe with `discount` less of each ingredient, never
+    /// below 1.
+    pub fn discounted(&self, discount: u32) -> Cow<'_, Self> {
+        if discount == 0 {
+            return Cow::Borrowed(self);
+        }
+
+        let mut recipe = self.clone();
+        for ingredient in recipe.ingredients.iter_mut() {
+            ingredient.quantity =
+                ingredient.quantity.saturating_sub(discount).max(1);
+        }
+
+        Cow::Owned(recipe)
+    }
+
+    /// Returns `true` if the item is one of the ingredients or extras.
+    pub fn uses(&self, item_id: &str) -> bool {
+        self.ingredients
+            .iter()
+            .chain(&self.extras)
+            .any(|i| i.item_id == item_id)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -123,6 +189,10 @@ pub struct RecipeIngredient {
     pub quantity: u32,
 }
 
+/// Ids of recipes that have been cooked at least once.
+#[derive(Resource, Deref, DerefMut, Default, Debug)]
+pub struct DiscoveredRecipes(HashSet<String>);
+
 #[derive(Resource)]
 pub struct RecipeMetaAssetHandle(Handle<RecipeMetaAsset>);
 
@@ -140,6 +210,25 @@ impl RecipeRegistry<'_> {
     pub fn get_recipe(&self, recipe_id: &str) -> Option<&RecipeMeta> {
         self.get()?.get(recipe_id)
     }
+
+    /// Get all recipes that can be cooked in the machine kind,
+    /// sorted by their ids.
+    pub fn compatible_recipes(
+        &self,
+        machine_kind: MachineKind,
+    ) -> Vec<(&String, &RecipeMeta)> {
+        let Some(recipes) = self.get() else {
+            return Vec::new();
+        };
+
+        let mut compatible_recipes = recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.can_cook_in(machine_kind))
+            .collect::<Vec<_>>();
+        compatible_recipes.sort_by_key(|(id, _)| *id);
+
+        compatible_recipes
+    }
 }
 
 #[derive(Default)]
@@ -159,10 +248,24 @@ impl AssetLoader for RecipeMetaAssetLoader {
         let mut ron_str = String::new();
         reader.read_to_string(&mut ron_str).await?;
 
-        let asset = ron::from_str::<RecipeMetaAsset>(&ron_str)
-            .expect("Failed to parse recipes.recipe_meta.ron");
+        // Surface as a failed load instead of panicking.
+        let mut recipes = ron::from_str::<RecipeMetaAsset>(&ron_str)
+            .map_err(std::io::Error::other)?;
+
+        // The data (e.g. from a mod) may predate an item rename.
+        let migrations = Migrations::embedded();
+        for recipe in recipes.0.values_mut() {
+            for ingredient in recipe
+                .ingredients
+                .iter_mut()
+                .chain(&mut recipe.extras)
+            {
+                migrations.rename_item(&mut ingredient.item_id);
+            }
+            migrations.rename_item(&mut recipe.output_id);
+        }
 
-        Ok(asset)
+        Ok(recipes)
     }
 
     fn extensions(&self) -> &[&str] {
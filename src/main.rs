@@ -2,41 +2,57 @@ use bevy::asset::AssetMetaCheck;
 use bevy::audio::{AudioPlugin, Volume};
 use bevy::ecs::error::{GLOBAL_ERROR_HANDLER, error};
 use bevy::prelude::*;
+use recipe_game::smoke_test::{
+    self, SMOKE_TEST_FLAG, SmokeTestPlugin,
+};
+
+fn main() -> AppExit {
+    let is_smoke_test =
+        std::env::args().any(|arg| arg == SMOKE_TEST_FLAG);
 
-fn main() {
     // Log error by desault instead of panicking.
     GLOBAL_ERROR_HANDLER
-        .set(error)
+        .set(match is_smoke_test {
+            true => smoke_test::error_handler,
+            false => error,
+        })
         .expect("The error handler can only be set once, globally.");
 
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(AssetPlugin {
-                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
-                    // This causes errors and even panics on web build on itch.
-                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
-                    meta_check: AssetMetaCheck::Never,
-                    ..default()
-                })
-                .set(WindowPlugin {
-                    primary_window: Window {
-                        title: "Recipe".to_string(),
-                        canvas: Some("#bevy".to_string()),
-                        fit_canvas_to_parent: true,
-                        prevent_default_event_handling: false,
-                        ..default()
-                    }
-                    .into(),
-                    ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::Linear(0.3),
-                    },
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics on web build on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: Window {
+                    title: "Recipe".to_string(),
+                    canvas: Some("#bevy".to_string()),
+                    fit_canvas_to_parent: true,
+                    prevent_default_event_handling: false,
+                    // Keep rendering to a minimum during smoke tests.
+                    visible: is_smoke_test == false,
                     ..default()
-                }),
-        )
-        .add_plugins(recipe_game::AppPlugin)
-        .run();
+                }
+                .into(),
+                ..default()
+            })
+            .set(AudioPlugin {
+                global_volume: GlobalVolume {
+                    volume: Volume::Linear(0.3),
+                },
+                ..default()
+            }),
+    )
+    .add_plugins(recipe_game::AppPlugin);
+
+    if is_smoke_test {
+        app.add_plugins(SmokeTestPlugin);
+    }
+
+    app.run()
 }
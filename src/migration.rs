@@ -0,0 +1,343 @@
+//! Migrates item and recipe ids that no longer exist in the
+//! registries using the rename map in `assets/migrations.ron`.
+//!
+//! Ids are migrated at load time, in the recipe data and on the
+//! items, machines and inventories spawned from level scenes.
+//!
+//! In dev builds, press [`MIGRATE_KEY`] to re-read the map and scan
+//! the loaded level (including discovered recipes). References
+//! that can't be resolved are logged instead of failing.
+//!
+//! *Note: Enemies don't have ids yet and are not scanned.*
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::inventory::item::ItemRegistry;
+use crate::inventory::{Inventory, Item};
+use crate::machine::Machine;
+#[cfg(feature = "dev")]
+use crate::machine::recipe::DiscoveredRecipes;
+use crate::machine::recipe::RecipeRegistry;
+
+/// Key to run the migration.
+#[cfg(feature = "dev")]
+const MIGRATE_KEY: KeyCode = KeyCode::F4;
+/// Path to the rename map, re-read by the dev tool.
+#[cfg(feature = "dev")]
+const MIGRATIONS_PATH: &str = "assets/migrations.ron";
+
+pub(super) struct MigrationPlugin;
+
+impl Plugin for MigrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Migrations::embedded())
+            .add_systems(Update, migrate_spawned_ids);
+
+        #[cfg(feature = "dev")]
+        app.add_systems(
+            Update,
+            migrate_ids.run_if(
+                |kbd_inputs: Res<ButtonInput<KeyCode>>| {
+                    kbd_inputs.just_pressed(MIGRATE_KEY)
+                },
+            ),
+        );
+    }
+}
+
+/// Migrate the ids of newly spawned entities, e.g. from a level
+/// scene authored before the rename.
+fn migrate_spawned_ids(
+    mut commands: Commands,
+    mut q_items: Query<(&mut Item, Entity), Added<Item>>,
+    q_machines: Query<(&Machine, Entity), Added<Machine>>,
+    mut q_inventories: Query<
+        (&mut Inventory, Entity),
+        Added<Inventory>,
+    >,
+    migrations: Res<Migrations>,
+    item_registry: ItemRegistry,
+    recipe_registry: RecipeRegistry,
+) {
+    let (Some(items), Some(recipes)) =
+        (item_registry.get(), recipe_registry.get())
+    else {
+        return;
+    };
+
+    let mut report = MigrationReport::default();
+    let item_exists = |id: &str| items.contains_key(id);
+    let recipe_exists = |id: &str| recipes.contains_key(id);
+
+    report.migrate_items(
+        q_items.iter_mut(),
+        &migrations,
+        item_exists,
+    );
+    report.migrate_machines(
+        &mut commands,
+        q_machines.iter(),
+        &migrations,
+        recipe_exists,
+    );
+    report.migrate_inventories(
+        q_inventories.iter_mut(),
+        &migrations,
+        item_exists,
+    );
+
+    if report.migrated > 0 || report.unresolved.is_empty() == false {
+        report.log();
+    }
+}
+
+#[cfg(feature = "dev")]
+fn migrate_ids(
+    mut commands: Commands,
+    mut q_items: Query<(&mut Item, Entity)>,
+    q_machines: Query<(&Machine, Entity)>,
+    mut q_inventories: Query<(&mut Inventory, Entity)>,
+    mut discovered_recipes: ResMut<DiscoveredRecipes>,
+    mut migrations: ResMut<Migrations>,
+    item_registry: ItemRegistry,
+    recipe_registry: RecipeRegistry,
+) {
+    // Pick up the renames added since the game started.
+    match Migrations::read() {
+        Ok(read) => *migrations = read,
+        Err(err) => {
+            error!("Unable to read {MIGRATIONS_PATH}: {err}");
+            return;
+        }
+    }
+
+    let Some(items) = item_registry.get() else {
+        warn!("Item registry is not loaded yet.");
+        return;
+    };
+
+    let Some(recipes) = recipe_registry.get() else {
+        warn!("Recipe registry is not loaded yet.");
+        return;
+    };
+
+    let mut report = MigrationReport::default();
+
+    let item_exists = |id: &str| items.contains_key(id);
+    let recipe_exists = |id: &str| recipes.contains_key(id);
+
+    report.migrate_items(
+        q_items.iter_mut(),
+        &migrations,
+        item_exists,
+    );
+    report.migrate_machines(
+        &mut commands,
+        q_machines.iter(),
+        &migrations,
+        recipe_exists,
+    );
+    report.migrate_inventories(
+        q_inventories.iter_mut(),
+        &migrations,
+        item_exists,
+    );
+
+    let recipe_ids =
+        discovered_recipes.iter().cloned().collect::<Vec<_>>();
+    for recipe_id in recipe_ids {
+        if let Some(new_id) = report.resolve(
+            &recipe_id,
+            &migrations.recipes,
+            recipe_exists,
+            "discovered recipes",
+        ) {
+            discovered_recipes.remove(&recipe_id);
+            discovered_recipes.insert(new_id);
+        }
+    }
+
+    // Registries are migrated as they load, report what's left.
+    for (recipe_id, recipe) in recipes.iter() {
+        for item_id in recipe
+            .ingredients
+            .iter()
+            .chain(&recipe.extras)
+            .map(|i| &i.item_id)
+            .chain([&recipe.output_id])
+        {
+            if item_exists(item_id) == false {
+                report.unresolved.push(format!(
+                    "'{item_id}' in recipe '{recipe_id}'{}",
+                    migrations
+                        .items
+                        .get(item_id)
+                        .map(|new_id| format!(
+                            " (rename it to '{new_id}' in the asset)"
+                        ))
+                        .unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    report.log();
+}
+
+/// Rename maps from old ids to new ids.
+#[derive(Resource, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Migrations {
+    items: HashMap<String, String>,
+    recipes: HashMap<String, String>,
+}
+
+impl Migrations {
+    /// The rename map shipped with the game.
+    pub fn embedded() -> Self {
+        ron::from_str(include_str!("../assets/migrations.ron"))
+            .unwrap_or_else(|e| {
+                error!("Unable to parse the migrations: {e}");
+                Self::default()
+            })
+    }
+
+    #[cfg(feature = "dev")]
+    fn read() -> Result<Self> {
+        let ron_str = std::fs::read_to_string(MIGRATIONS_PATH)?;
+        Ok(ron::from_str(&ron_str)?)
+    }
+
+    /// Rename the item id in place if it was renamed.
+    pub fn rename_item(&self, item_id: &mut String) {
+        if let Some(new_id) = self.items.get(item_id) {
+            info!("Migrated item '{item_id}' -> '{new_id}'.");
+            *item_id = new_id.clone();
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct MigrationReport {
+    migrated: usize,
+    unresolved: Vec<String>,
+}
+
+impl MigrationReport {
+    fn migrate_items<'a>(
+        &mut self,
+        items: impl Iterator<Item = (Mut<'a, Item>, Entity)>,
+        migrations: &Migrations,
+        item_exists: impl Fn(&str) -> bool + Copy,
+    ) {
+        for (mut item, entity) in items {
+            if let Some(new_id) = self.resolve(
+                &item.id,
+                &migrations.items,
+                item_exists,
+                &format!("item {entity}"),
+            ) {
+                item.id = new_id;
+            }
+        }
+    }
+
+    fn migrate_machines<'a>(
+        &mut self,
+        commands: &mut Commands,
+        machines: impl Iterator<Item = (&'a Machine, Entity)>,
+        migrations: &Migrations,
+        recipe_exists: impl Fn(&str) -> bool + Copy,
+    ) {
+        for (machine, entity) in machines {
+            if let Some(new_id) = self.resolve(
+                &machine.recipe_id,
+                &migrations.recipes,
+                recipe_exists,
+                &format!("machine {entity}"),
+            ) {
+                // Re-inserting also resets the selected recipe.
+                commands
+                    .entity(entity)
+                    .insert(Machine { recipe_id: new_id });
+            }
+        }
+    }
+
+    fn migrate_inventories<'a>(
+        &mut self,
+        inventories: impl Iterator<Item = (Mut<'a, Inventory>, Entity)>,
+        migrations: &Migrations,
+        item_exists: impl Fn(&str) -> bool + Copy,
+    ) {
+        for (mut inventory, entity) in inventories {
+            let mut changed = false;
+
+            // Only trigger change detection when something is
+            // migrated.
+            for (index, stack) in
+                inventory.bypass_change_detection().stacks_mut()
+            {
+                if let Some(new_id) = self.resolve(
+                    &stack.item_id,
+                    &migrations.items,
+                    item_exists,
+                    &format!("slot {index} of inventory {entity}"),
+                ) {
+                    stack.item_id = new_id;
+                    changed = true;
+                }
+            }
+
+            if changed {
+                inventory.set_changed();
+            }
+        }
+    }
+
+    fn log(&self) {
+        for unresolved in self.unresolved.iter() {
+            warn!("Unresolved reference: {unresolved}");
+        }
+
+        info!(
+            "Migrated {} reference(s), {} unresolved.",
+            self.migrated,
+            self.unresolved.len()
+        );
+    }
+
+    /// Returns the new id if the id is missing and a valid
+    /// rename exists for it.
+    fn resolve(
+        &mut self,
+        id: &str,
+        renames: &HashMap<String, String>,
+        exists: impl Fn(&str) -> bool,
+        location: &str,
+    ) -> Option<String> {
+        if exists(id) {
+            return None;
+        }
+
+        match renames.get(id) {
+            Some(new_id) if exists(new_id) => {
+                info!("Migrated '{id}' -> '{new_id}' in {location}.");
+                self.migrated += 1;
+                Some(new_id.clone())
+            }
+            Some(new_id) => {
+                self.unresolved.push(format!(
+                    "'{id}' in {location} (rename target '{new_id}' doesn't exist)"
+                ));
+                None
+            }
+            None => {
+                self.unresolved.push(format!("'{id}' in {location}"));
+                None
+            }
+        }
+    }
+}
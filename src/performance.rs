@@ -0,0 +1,158 @@
+//! Graphics presets, the web build defaults to
+//! [`GraphicsPreset::Constrained`] as the desktop effects are too
+//! heavy for most browsers.
+
+use bevy::core_pipeline::bloom::Bloom;
+use bevy::core_pipeline::smaa::Smaa;
+use bevy::pbr::DirectionalLightShadowMap;
+use bevy::prelude::*;
+use bevy_mod_outline::OutlineMode;
+
+use crate::camera_controller::split_screen::CameraType;
+
+pub(super) struct PerformancePlugin;
+
+impl Plugin for PerformancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GraphicsPreset>().add_systems(
+            PostUpdate,
+            (
+                apply_shadow_map_size,
+                apply_camera_effects,
+                apply_outline_mode,
+            ),
+        );
+
+        app.register_type::<GraphicsPreset>();
+    }
+}
+
+fn apply_shadow_map_size(
+    mut commands: Commands,
+    preset: Res<GraphicsPreset>,
+) {
+    if preset.is_changed() {
+        commands.insert_resource(DirectionalLightShadowMap {
+            size: preset.shadow_map_size(),
+        });
+    }
+}
+
+fn apply_camera_effects(
+    mut commands: Commands,
+    q_cameras: Query<(&CameraType, Ref<Camera>, Entity)>,
+    preset: Res<GraphicsPreset>,
+) {
+    for (camera_type, camera, entity) in q_cameras.iter() {
+        // Post processing only happens on the full camera.
+        if *camera_type != CameraType::Full
+            || (preset.is_changed() == false
+                && camera.is_added() == false)
+        {
+            continue;
+        }
+
+        match *preset {
+            GraphicsPreset::Full => {
+                commands
+                    .entity(entity)
+                    .insert((Smaa::default(), Bloom::NATURAL));
+            }
+            GraphicsPreset::Constrained => {
+                commands.entity(entity).remove::<(Smaa, Bloom)>();
+            }
+        }
+    }
+}
+
+fn apply_outline_mode(
+    mut q_outlines: Query<&mut OutlineMode>,
+    preset: Res<GraphicsPreset>,
+) {
+    for mut mode in q_outlines.iter_mut() {
+        if preset.is_changed() || mode.is_added() {
+            *mode = preset.outline_mode();
+        }
+    }
+}
+
+/// Graphics quality of the game, can be overridden from the menu.
+///
+/// *Note: There is no SSAO in the game so far, keep it out of the
+/// [`GraphicsPreset::Constrained`] preset if it gets added.*
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum GraphicsPreset {
+    /// Every effect enabled, the default for native builds.
+    Full,
+    /// Smaller shadow maps, no bloom or anti-aliasing, cheaper
+    /// outlines, fewer enemies alive at once and no ragdolls.
+    Constrained,
+}
+
+impl GraphicsPreset {
+    pub fn toggled(self) -> Self {
+        match self {
+            GraphicsPreset::Full => GraphicsPreset::Constrained,
+            GraphicsPreset::Constrained => GraphicsPreset::Full,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GraphicsPreset::Full => "Graphics: High",
+            GraphicsPreset::Constrained => "Graphics: Low",
+        }
+    }
+
+    pub fn shadow_map_size(&self) -> usize {
+        match self {
+            GraphicsPreset::Full => 2048,
+            GraphicsPreset::Constrained => 512,
+        }
+    }
+
+    /// Flood outlines need extra render passes, extruded outlines
+    /// are drawn with the mesh.
+    pub fn outline_mode(&self) -> OutlineMode {
+        match self {
+            GraphicsPreset::Full => OutlineMode::FloodFlat,
+            GraphicsPreset::Constrained => OutlineMode::ExtrudeFlat,
+        }
+    }
+
+    /// Maximum number of enemies alive at once, [`None`] if
+    /// uncapped.
+    pub fn enemy_cap(&self) -> Option<usize> {
+        match self {
+            GraphicsPreset::Full => None,
+            GraphicsPreset::Constrained => Some(16),
+        }
+    }
+
+    /// Maximum number of enemy footsteps played per frame.
+    pub fn enemy_footstep_cap(&self) -> usize {
+        match self {
+            GraphicsPreset::Full => 4,
+            GraphicsPreset::Constrained => 0,
+        }
+    }
+
+    /// Whether killed enemies and towers tumble over with physics
+    /// instead of freezing in place.
+    pub fn ragdolls(&self) -> bool {
+        match self {
+            GraphicsPreset::Full => true,
+            GraphicsPreset::Constrained => false,
+        }
+    }
+}
+
+impl Default for GraphicsPreset {
+    fn default() -> Self {
+        match cfg!(target_arch = "wasm32") {
+            true => GraphicsPreset::Constrained,
+            false => GraphicsPreset::Full,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Meta-progression across levels: clearing a level for the first
+//! time grants perk points that are spent on persistent [`Perk`]s
+//! from the [`Screen::Perks`] screen, saved to the profile.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::level::{CurrentLevel, LevelProgress, unlock_next_level};
+use crate::storage::Profile;
+use crate::ui::Screen;
+
+/// Points granted for the first clear of every level.
+const POINTS_PER_LEVEL: u32 = 1;
+/// Fraction of the cooking time saved per rank of
+/// [`Perk::FasterCooking`].
+const COOK_SPEED_PER_RANK: f32 = 0.1;
+
+pub(super) struct PerksPlugin;
+
+impl Plugin for PerksPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PerkProfile::load()).add_systems(
+            OnEnter(Screen::Victory),
+            // Check the progress before this clear is recorded.
+            grant_perk_points.before(unlock_next_level),
+        );
+    }
+}
+
+/// Only the first clear of each level grants points.
+fn grant_perk_points(
+    current_level: Res<CurrentLevel>,
+    progress: Res<LevelProgress>,
+    mut profile: ResMut<PerkProfile>,
+) {
+    if progress.is_cleared(current_level.0) {
+        return;
+    }
+
+    profile.points += POINTS_PER_LEVEL;
+    info!("Earned {POINTS_PER_LEVEL} perk point(s).");
+    profile.save();
+}
+
+/// Persistent upgrades bought with perk points.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+pub enum Perk {
+    FasterCooking,
+    ExtraSlot,
+    CheaperTowers,
+}
+
+impl Perk {
+    pub const ALL: [Self; 3] =
+        [Self::FasterCooking, Self::ExtraSlot, Self::CheaperTowers];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perk::FasterCooking => "Faster Cooking",
+            Perk::ExtraSlot => "Extra Slot",
+            Perk::CheaperTowers => "Cheaper Towers",
+        }
+    }
+
+    /// Effect of a single rank.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::FasterCooking => "Cook 10% faster",
+            Perk::ExtraSlot => "+1 inventory slot",
+            Perk::CheaperTowers => {
+                "Towers need 1 less of each ingredient"
+            }
+        }
+    }
+
+    /// Points needed for each rank.
+    pub fn cost(&self) -> u32 {
+        match self {
+            Perk::FasterCooking => 1,
+            Perk::ExtraSlot => 2,
+            Perk::CheaperTowers => 3,
+        }
+    }
+
+    pub fn max_rank(&self) -> u32 {
+        match self {
+            Perk::FasterCooking => 3,
+            Perk::ExtraSlot => 2,
+            Perk::CheaperTowers => 1,
+        }
+    }
+}
+
+/// Unspent perk points and the ranks of the bought perks.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct PerkProfile {
+    pub points: u32,
+    ranks: HashMap<Perk, u32>,
+}
+
+impl Profile for PerkProfile {
+    const KEY: &'static str = "perks.profile.ron";
+}
+
+impl PerkProfile {
+    pub fn rank(&self, perk: Perk) -> u32 {
+        self.ranks.get(&perk).copied().unwrap_or_default()
+    }
+
+    pub fn can_buy(&self, perk: Perk) -> bool {
+        self.rank(perk) < perk.max_rank()
+            && self.points >= perk.cost()
+    }
+
+    /// Spend the points on the next rank of the perk and save the
+    /// profile, returns `false` if it can't be bought.
+    pub fn buy(&mut self, perk: Perk) -> bool {
+        if self.can_buy(perk) == false {
+            return false;
+        }
+
+        self.points -= perk.cost();
+        *self.ranks.entry(perk).or_default() += 1;
+        self.save();
+        true
+    }
+
+    /// Multiplier of the cooking duration.
+    pub fn cook_duration(&self) -> f32 {
+        1.0 / (1.0
+            + self.rank(Perk::FasterCooking) as f32
+                * COOK_SPEED_PER_RANK)
+    }
+
+    /// Inventory slots on top of the character's capacity.
+    pub fn extra_slots(&self) -> usize {
+        self.rank(Perk::ExtraSlot) as usize
+    }
+
+    /// Fewer of each ingredient needed by tower recipes.
+    pub fn tower_discount(&self) -> u32 {
+        self.rank(Perk::CheaperTowers)
+    }
+}
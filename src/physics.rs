@@ -58,7 +58,16 @@ pub struct CollisionLayerConstructor {
 }
 
 #[derive(
-    PhysicsLayer, Component, Reflect, Default, Debug, Clone, Copy,
+    PhysicsLayer,
+    Component,
+    Reflect,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
 )]
 #[reflect(Component, Default)]
 pub enum GameLayer {
@@ -70,4 +79,7 @@ pub enum GameLayer {
     InventoryItem,
     Projectile,
     Tower,
+    /// Ragdolling [`Dying`](crate::death::Dying) bodies, they only
+    /// collide with the level.
+    Corpse,
 }
@@ -7,29 +7,52 @@ use bevy::ecs::query::{
 use bevy::ecs::spawn::SpawnWith;
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use leafwing_input_manager::prelude::InputMap;
+use serde::Deserialize;
 
+use crate::action::touch_controls::TouchControls;
 use crate::action::{GamepadIndex, PlayerAction};
-use crate::asset_pipeline::PrefabName;
+use crate::asset_pipeline::{PrefabId, PrefabName};
 use crate::camera_controller::split_screen::{
     CameraType, QueryCameras,
 };
+use crate::camera_controller::{A_RENDER_LAYER, B_RENDER_LAYER};
 use crate::character_controller::CharacterController;
 use crate::ui::world_space::WorldUi;
 use crate::util::PropagateComponentAppExt;
+use companion::Companion;
 
+pub mod character_stats;
+pub mod companion;
+pub mod downed;
+pub mod lock_on;
 pub mod player_attack;
 pub mod player_mark;
+pub mod player_melee;
+pub mod player_paint;
+pub mod player_ping;
 
 pub(super) struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            character_stats::CharacterStatsPlugin,
+            companion::CompanionPlugin,
+            downed::DownedPlugin,
+            lock_on::LockOnPlugin,
             player_attack::PlayerAttackPlugin,
             player_mark::PlayerMarkPlugin,
+            player_melee::PlayerMeleePlugin,
+            player_paint::PlayerPaintPlugin,
+            player_ping::PlayerPingPlugin,
         ));
 
         app.init_state::<PlayerState>()
+            .init_resource::<SelectedCharacter>()
+            .add_observer(assign_selected_character)
+            .add_observer(insert_default_character)
             .add_observer(setup_name_ui_for_player)
             .add_systems(
                 OnEnter(PlayerState::Possessing),
@@ -45,9 +68,11 @@ impl Plugin for PlayerPlugin {
                     .run_if(in_state(PlayerState::Possessing)),
             )
             .add_observer(handle_possession_triggers)
+            .add_observer(cycle_character)
             .propagate_component::<PlayerType, Children>();
 
-        app.register_type::<PlayerType>();
+        app.register_type::<PlayerType>()
+            .register_type::<CharacterId>();
     }
 }
 
@@ -55,6 +80,7 @@ fn ready_inputs(
     mut commands: Commands,
     player_possessor: Res<PlayerPossessor>,
     q_gamepads: Query<&Gamepad>,
+    q_interactions: Query<&Interaction>,
     kbd_inputs: Res<ButtonInput<KeyCode>>,
     mut player_state: ResMut<NextState<PlayerState>>,
 ) {
@@ -64,7 +90,11 @@ fn ready_inputs(
         return;
     };
 
-    let mut ready = kbd_inputs.just_pressed(KeyCode::Enter);
+    let mut ready = kbd_inputs.just_pressed(KeyCode::Enter)
+        // Tapped on the ready text.
+        || q_interactions
+            .get(player_possessor.ui_ready)
+            .is_ok_and(|i| *i == Interaction::Pressed);
     for gamepad in q_gamepads.iter() {
         ready = ready || gamepad.just_pressed(GamepadButton::South);
     }
@@ -79,15 +109,28 @@ fn ready_inputs(
         }
         PossessorType::Gamepad(entity) => commands
             .spawn(PlayerAction::new_gamepad().with_gamepad(*entity)),
+        PossessorType::Touch => commands.spawn((
+            InputMap::<PlayerAction>::default(),
+            TouchControls::default(),
+        )),
     }
     .insert(PlayerType::A);
 
     match player_b {
-        PossessorType::Keyboard => {
+        Some(PossessorType::Keyboard) => {
             commands.spawn(PlayerAction::new_kbm())
         }
-        PossessorType::Gamepad(entity) => commands
+        Some(PossessorType::Gamepad(entity)) => commands
             .spawn(PlayerAction::new_gamepad().with_gamepad(*entity)),
+        Some(PossessorType::Touch) => commands.spawn((
+            InputMap::<PlayerAction>::default(),
+            TouchControls::default(),
+        )),
+        // Solo play.
+        None => commands.spawn((
+            InputMap::<PlayerAction>::default(),
+            Companion::default(),
+        )),
     }
     .insert(PlayerType::B);
 
@@ -121,6 +164,15 @@ fn process_posessing_inputs(
         });
     }
 
+    for (key, step) in [(KeyCode::KeyW, -1), (KeyCode::KeyS, 1)] {
+        if kbd_inputs.just_pressed(key) {
+            commands.trigger(CycleCharacter {
+                possessor: PossessorType::Keyboard,
+                step,
+            });
+        }
+    }
+
     for (gamepad, entity) in q_gamepads.iter() {
         if gamepad.just_pressed(GamepadButton::DPadLeft) {
             commands.trigger(Possession {
@@ -143,6 +195,18 @@ fn process_posessing_inputs(
                 possessor: PossessorType::Gamepad(entity),
             });
         }
+
+        for (button, step) in [
+            (GamepadButton::DPadUp, -1),
+            (GamepadButton::DPadDown, 1),
+        ] {
+            if gamepad.just_pressed(button) {
+                commands.trigger(CycleCharacter {
+                    possessor: PossessorType::Gamepad(entity),
+                    step,
+                });
+            }
+        }
     }
 }
 
@@ -193,6 +257,7 @@ fn handle_possession_triggers(
     let get_text = |possessor: &PossessorType| {
         let text = match possessor {
             PossessorType::Keyboard => "Keyboard".to_string(),
+            PossessorType::Touch => "Touch".to_string(),
             PossessorType::Gamepad(entity) => {
                 let s = "Gamepad #".to_string();
                 s + &format!(
@@ -230,7 +295,7 @@ fn handle_possession_triggers(
             .entity(player_possessor.ui_slot_b)
             .insert(BackgroundColor(RED_900.into()))
             .despawn_related::<Children>()
-            .with_child(centered_text("N/A"));
+            .with_child(centered_text(COMPANION_TEXT));
     }
 
     if player_possessor.is_ready() {
@@ -247,15 +312,58 @@ fn handle_possession_triggers(
     Ok(())
 }
 
-fn setup_possession_ui(mut commands: Commands) {
-    const INSTRUCTION_CANCEL: &str =
-        "Press Esc (keyboard) | B (controller) to cancel.";
+/// Pick another character for the player possessed by the
+/// [`CycleCharacter::possessor`].
+fn cycle_character(
+    trigger: Trigger<CycleCharacter>,
+    player_possessor: Option<Res<PlayerPossessor>>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut q_texts: Query<&mut Text>,
+) {
+    let CycleCharacter { possessor, step } = *trigger.event();
+
+    let Some(player_possessor) = player_possessor else {
+        return;
+    };
+    let Some(player_type) = player_possessor.player_of(possessor)
+    else {
+        return;
+    };
+
+    selected.cycle(player_type, step);
+
+    for (player_type, ui_character) in [
+        (PlayerType::A, player_possessor.ui_character_a),
+        (PlayerType::B, player_possessor.ui_character_b),
+    ] {
+        if let Ok(mut text) = q_texts.get_mut(ui_character) {
+            text.0 = character_label(selected.get(player_type));
+        }
+    }
+}
+
+fn character_label(character_id: CharacterId) -> String {
+    format!("< {} >", character_id.name())
+}
+
+/// Shown in the unclaimed slot of Player B.
+const COMPANION_TEXT: &str = "N/A (AI)";
+
+fn setup_possession_ui(
+    mut commands: Commands,
+    selected: Res<SelectedCharacter>,
+) {
+    const INSTRUCTION_CANCEL: &str = "Press Esc (keyboard) | \
+    B (controller) to cancel.\n\
+    Press W / S (keyboard) | DPadUp / DPadDown (controller) | \
+    tap the character (touch) to pick a character.";
     const INSTRUCTION_A: &str = "Press:\n\
-    A (keyboard) / DPadLeft (controller)";
+    A (keyboard) / DPadLeft (controller) / tap below (touch)";
     const INSTRUCTION_B: &str = "Press:\n\
-    D (keyboard) / DPadRight (controller)";
-    const INSTRUCTION_READY: &str =
-        "Press Enter (keyboard) / A (controller) to confirm!";
+    D (keyboard) / DPadRight (controller) / tap below (touch)";
+    const INSTRUCTION_READY: &str = "Press Enter (keyboard) / \
+    A (controller) / tap here (touch) to confirm!\n\
+    An unclaimed Player B is played by the AI.";
 
     let instruction_ui_node = Node {
         justify_content: JustifyContent::Center,
@@ -283,10 +391,22 @@ fn setup_possession_ui(mut commands: Commands) {
     let ui_slot_a = commands
         .spawn(possession_slot.clone())
         .with_child(centered_text("N/A"))
+        .observe(possess_on_tap(PlayerType::A))
         .id();
     let ui_slot_b = commands
         .spawn(possession_slot)
-        .with_child(centered_text("N/A"))
+        .with_child(centered_text(COMPANION_TEXT))
+        .observe(possess_on_tap(PlayerType::B))
+        .id();
+
+    // The character picked by each player.
+    let ui_character_a = commands
+        .spawn(centered_text(character_label(selected.a)))
+        .observe(cycle_character_on_tap)
+        .id();
+    let ui_character_b = commands
+        .spawn(centered_text(character_label(selected.b)))
+        .observe(cycle_character_on_tap)
         .id();
 
     let ui_ready = commands
@@ -294,6 +414,7 @@ fn setup_possession_ui(mut commands: Commands) {
             Text::new(INSTRUCTION_READY),
             TextLayout::new_with_justify(JustifyText::Center),
             Visibility::Hidden,
+            Interaction::default(),
         ))
         .id();
 
@@ -302,6 +423,8 @@ fn setup_possession_ui(mut commands: Commands) {
         player_b: None,
         ui_slot_a,
         ui_slot_b,
+        ui_character_a,
+        ui_character_b,
         ui_ready,
     });
 
@@ -319,7 +442,7 @@ fn setup_possession_ui(mut commands: Commands) {
                         },
                     ))
                     .with_child(Text::new(INSTRUCTION_A))
-                    .add_child(ui_slot_a);
+                    .add_children(&[ui_slot_a, ui_character_a]);
             }
         }),
         // Separation line.
@@ -342,7 +465,7 @@ fn setup_possession_ui(mut commands: Commands) {
                     },
                 ))
                 .with_child(Text::new(INSTRUCTION_B))
-                .add_child(ui_slot_b);
+                .add_children(&[ui_slot_b, ui_character_b]);
         }),
     ));
 
@@ -410,6 +533,21 @@ fn setup_possession_ui(mut commands: Commands) {
     ));
 }
 
+/// Possess the player with [`PossessorType::Touch`] by tapping
+/// on its slot.
+fn possess_on_tap(
+    player_type: PlayerType,
+) -> impl Fn(Trigger<'_, Pointer<Click>>, Commands<'_, '_>) {
+    move |trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+        if trigger.pointer_id.is_touch() {
+            commands.trigger(Possession {
+                player_type: Some(player_type),
+                possessor: PossessorType::Touch,
+            });
+        }
+    }
+}
+
 fn centered_text(text: impl Into<String>) -> impl Bundle {
     (
         Text::new(text),
@@ -417,23 +555,93 @@ fn centered_text(text: impl Into<String>) -> impl Bundle {
     )
 }
 
+/// Cycle the character of the player possessed by
+/// [`PossessorType::Touch`] by tapping on its character.
+fn cycle_character_on_tap(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+) {
+    if trigger.pointer_id.is_touch() {
+        commands.trigger(CycleCharacter {
+            possessor: PossessorType::Touch,
+            step: 1,
+        });
+    }
+}
+
+/// Hand the characters authored with a [`CharacterId`] to the
+/// player that selected them, characters that nobody selected
+/// are removed from the level.
+fn assign_selected_character(
+    trigger: Trigger<OnAdd, CharacterId>,
+    mut commands: Commands,
+    q_characters: Query<(&CharacterId, Has<PlayerType>)>,
+    selected: Res<SelectedCharacter>,
+) -> Result {
+    let entity = trigger.target();
+    let (character_id, has_player_type) = q_characters.get(entity)?;
+
+    // Already played by a specific player.
+    if has_player_type {
+        return Ok(());
+    }
+
+    match selected.player_of(*character_id) {
+        Some(player_type) => {
+            commands.entity(entity).insert(player_type);
+        }
+        None => commands.entity(entity).despawn(),
+    }
+
+    Ok(())
+}
+
+/// Characters authored with only a [`PlayerType`] play as the
+/// player's default character.
+fn insert_default_character(
+    trigger: Trigger<OnAdd, PlayerType>,
+    mut commands: Commands,
+    q_characters: Query<
+        &PlayerType,
+        (With<CharacterController>, Without<CharacterId>),
+    >,
+) {
+    let entity = trigger.target();
+
+    if let Ok(player_type) = q_characters.get(entity) {
+        commands
+            .entity(entity)
+            .insert(CharacterId::default_for(*player_type));
+    }
+}
+
 /// Setup world space name ui for players.
 fn setup_name_ui_for_player(
     trigger: Trigger<OnAdd, PlayerType>,
     mut commands: Commands,
-    q_players: Query<&PlayerType, With<CharacterController>>,
+    q_players: Query<
+        (&PlayerType, Option<&CharacterId>),
+        With<CharacterController>,
+    >,
     q_cameras: QueryCameras<Entity>,
 ) -> Result {
     let entity = trigger.target();
 
-    let Ok(player_type) = q_players.get(entity) else {
+    let Ok((player_type, character_id)) = q_players.get(entity)
+    else {
         // Spawned entity might not be a character.
         return Ok(());
     };
+    // Not inserted yet for characters authored with a PlayerType.
+    let character_id = character_id
+        .copied()
+        .unwrap_or(CharacterId::default_for(*player_type));
 
-    let ui_bundle = move |name: &str, height: f32| {
+    let ui_bundle = move |name: &str| {
         (
-            WorldUi::new(entity).with_world_offset(Vec3::Y * height),
+            WorldUi::new(entity).with_world_offset(
+                Vec3::Y * character_id.name_ui_height(),
+            ),
             Node {
                 padding: UiRect::all(Val::Px(8.0)),
                 justify_content: JustifyContent::Center,
@@ -458,20 +666,12 @@ fn setup_name_ui_for_player(
     };
 
     // Spawn ui only for the other player to view their floating tag.
-    match player_type {
-        PlayerType::A => {
-            commands.spawn((
-                ui_bundle("Polo Bun", 1.0),
-                UiTargetCamera(q_cameras.get(CameraType::B)?),
-            ));
-        }
-        PlayerType::B => {
-            commands.spawn((
-                ui_bundle("Baguette", 1.5),
-                UiTargetCamera(q_cameras.get(CameraType::A)?),
-            ));
-        }
-    }
+    commands.spawn((
+        ui_bundle(character_id.name()),
+        UiTargetCamera(
+            q_cameras.get(player_type.partner().camera_type())?,
+        ),
+    ));
 
     Ok(())
 }
@@ -479,17 +679,34 @@ fn setup_name_ui_for_player(
 #[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
 #[reflect(Component)]
 pub enum PlayerType {
-    /// Polo Bun.
+    /// Plays through [`CameraType::A`].
     A,
-    /// Baguette.
+    /// Plays through [`CameraType::B`].
     B,
 }
 
 impl PlayerType {
-    pub fn prefab_name(&self) -> PrefabName {
+    /// The other player.
+    pub fn partner(&self) -> Self {
+        match self {
+            PlayerType::A => PlayerType::B,
+            PlayerType::B => PlayerType::A,
+        }
+    }
+
+    /// The camera that renders the POV of this player.
+    pub fn camera_type(&self) -> CameraType {
         match self {
-            PlayerType::A => PrefabName::FileName("polo_bun"),
-            PlayerType::B => PrefabName::FileName("baguette"),
+            PlayerType::A => CameraType::A,
+            PlayerType::B => CameraType::B,
+        }
+    }
+
+    /// The layer only rendered by this player's camera.
+    pub fn render_layer(&self) -> RenderLayers {
+        match self {
+            PlayerType::A => A_RENDER_LAYER,
+            PlayerType::B => B_RENDER_LAYER,
         }
     }
 }
@@ -518,6 +735,135 @@ impl Component for PlayerType {
     }
 }
 
+/// Playable breads, authored on the characters of the level
+/// scene and handed to the player in [`SelectedCharacter`].
+#[derive(
+    Component,
+    Reflect,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[reflect(Component)]
+pub enum CharacterId {
+    PoloBun,
+    Baguette,
+}
+
+impl CharacterId {
+    /// In the order of the character selection.
+    pub const ALL: [Self; 2] = [Self::PoloBun, Self::Baguette];
+
+    /// Character of the player when nothing else is selected.
+    pub fn default_for(player_type: PlayerType) -> Self {
+        match player_type {
+            PlayerType::A => Self::PoloBun,
+            PlayerType::B => Self::Baguette,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CharacterId::PoloBun => "Polo Bun",
+            CharacterId::Baguette => "Baguette",
+        }
+    }
+
+    /// Prefab holding the animations of the character.
+    pub fn prefab_name(&self) -> PrefabName {
+        match self {
+            CharacterId::PoloBun => PrefabId::PoloBun.into(),
+            CharacterId::Baguette => PrefabId::Baguette.into(),
+        }
+    }
+
+    /// Prefab of the small bread shot by the character.
+    pub fn projectile_id(&self) -> PrefabId {
+        match self {
+            CharacterId::PoloBun => PrefabId::PoloBunSmall,
+            CharacterId::Baguette => PrefabId::BaguetteSmall,
+        }
+    }
+
+    /// Height of the floating name tag above the character.
+    pub fn name_ui_height(&self) -> f32 {
+        match self {
+            CharacterId::PoloBun => 1.0,
+            CharacterId::Baguette => 1.5,
+        }
+    }
+}
+
+/// Characters picked on the possession screen, consumed when the
+/// characters of a level are spawned.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SelectedCharacter {
+    pub a: CharacterId,
+    pub b: CharacterId,
+}
+
+impl SelectedCharacter {
+    pub fn get(&self, player_type: PlayerType) -> CharacterId {
+        match player_type {
+            PlayerType::A => self.a,
+            PlayerType::B => self.b,
+        }
+    }
+
+    /// The player that selected the character.
+    pub fn player_of(
+        &self,
+        character_id: CharacterId,
+    ) -> Option<PlayerType> {
+        [PlayerType::A, PlayerType::B]
+            .into_iter()
+            .find(|p| self.get(*p) == character_id)
+    }
+
+    /// Move the player's pick by the step, trading characters with
+    /// the partner when they picked it already.
+    pub fn cycle(&mut self, player_type: PlayerType, step: isize) {
+        let current = self.get(player_type);
+        let index = CharacterId::ALL
+            .iter()
+            .position(|c| *c == current)
+            .unwrap_or_default() as isize;
+        let next = CharacterId::ALL[(index + step)
+            .rem_euclid(CharacterId::ALL.len() as isize)
+            as usize];
+
+        let partner = player_type.partner();
+        if self.get(partner) == next {
+            self.set(partner, current);
+        }
+        self.set(player_type, next);
+    }
+
+    fn set(
+        &mut self,
+        player_type: PlayerType,
+        character_id: CharacterId,
+    ) {
+        match player_type {
+            PlayerType::A => self.a = character_id,
+            PlayerType::B => self.b = character_id,
+        }
+    }
+}
+
+impl Default for SelectedCharacter {
+    fn default() -> Self {
+        Self {
+            a: CharacterId::default_for(PlayerType::A),
+            b: CharacterId::default_for(PlayerType::B),
+        }
+    }
+}
+
 /// A shorthand [`SystemParam`] for getting all types of players
 /// using exclusive queries.
 #[derive(SystemParam)]
@@ -589,26 +935,55 @@ pub struct PlayerPossessor {
     pub player_b: Option<PossessorType>,
     pub ui_slot_a: Entity,
     pub ui_slot_b: Entity,
+    pub ui_character_a: Entity,
+    pub ui_character_b: Entity,
     pub ui_ready: Entity,
 }
 
 impl PlayerPossessor {
+    /// Player B is optional, a [`Companion`] plays it when
+    /// unclaimed.
     pub fn is_ready(&self) -> bool {
-        self.player_a.is_some() && self.player_b.is_some()
+        self.player_a.is_some()
+    }
+
+    /// The player possessed by the possessor.
+    pub fn player_of(
+        &self,
+        possessor: PossessorType,
+    ) -> Option<PlayerType> {
+        if self.player_a == Some(possessor) {
+            return Some(PlayerType::A);
+        }
+        if self.player_b == Some(possessor) {
+            return Some(PlayerType::B);
+        }
+        None
     }
 
     pub fn get_possessors(
         &self,
-    ) -> Option<(&PossessorType, &PossessorType)> {
-        Some((self.player_a.as_ref()?, self.player_b.as_ref()?))
+    ) -> Option<(&PossessorType, Option<&PossessorType>)> {
+        Some((self.player_a.as_ref()?, self.player_b.as_ref()))
     }
 }
 
-/// Possesion type, can be keyboard or a specific gamepad.
+/// Possesion type, can be keyboard, a specific gamepad or the
+/// touchscreen.
 #[derive(Component, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PossessorType {
     Keyboard,
     Gamepad(Entity),
+    Touch,
+}
+
+/// Pick another character for the player possessed by the
+/// possessor.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CycleCharacter {
+    pub possessor: PossessorType,
+    /// Positive for the next character, negative for the previous.
+    pub step: isize,
 }
 
 #[derive(Event, Debug, Clone, Copy)]
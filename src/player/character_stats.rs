@@ -0,0 +1,153 @@
+//! Per character stats defined in
+//! "characters.character_stats.ron", inserted onto the characters
+//! as they spawn so that each [`CharacterId`] plays differently.
+
+use bevy::asset::{AssetLoader, io::Reader};
+use bevy::asset::{AsyncReadExt, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::asset_pipeline::loading::TrackedAssets;
+use crate::character_controller::CharacterController;
+use crate::inventory::HOTBAR_SIZE;
+
+use super::CharacterId;
+
+pub(super) struct CharacterStatsPlugin;
+
+impl Plugin for CharacterStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<CharacterStatsAsset>()
+            .init_asset_loader::<CharacterStatsAssetLoader>()
+            .add_systems(PreStartup, load_character_stats)
+            .add_observer(apply_character_stats);
+    }
+}
+
+/// Startup system: load "characters.character_stats.ron" and
+/// insert the handle as a resource.
+fn load_character_stats(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
+) {
+    let handle = asset_server.load("characters.character_stats.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(CharacterStatsAssetHandle(handle));
+}
+
+/// Scale the movement of the character and keep its
+/// [`CharacterStats`] around for the other interactions.
+fn apply_character_stats(
+    trigger: Trigger<OnAdd, CharacterId>,
+    mut commands: Commands,
+    mut q_characters: Query<(&CharacterId, &mut CharacterController)>,
+    registry: CharacterStatsRegistry,
+) -> Result {
+    let entity = trigger.target();
+    let (character_id, mut controller) =
+        q_characters.get_mut(entity)?;
+
+    let stats = registry.get_stats(character_id).ok_or(format!(
+        "No character stats found for {character_id:?}!"
+    ))?;
+
+    controller.max_walk *= stats.move_speed;
+    controller.max_sprint *= stats.move_speed;
+
+    commands.entity(entity).insert(stats.clone());
+
+    Ok(())
+}
+
+#[derive(Asset, TypePath, Deref, Debug, Clone, Deserialize)]
+pub struct CharacterStatsAsset(HashMap<CharacterId, CharacterStats>);
+
+/// Mechanical differences between the characters, loaded from RON
+/// files.
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct CharacterStats {
+    /// Multiplier of the walking and sprinting speed.
+    pub move_speed: f32,
+    /// Number of inventory slots, at least the hotbar.
+    pub carry_capacity: usize,
+    /// Fraction of the cooking time saved when this character
+    /// starts a machine, e.g. 0.25 cooks 25% faster.
+    #[serde(default)]
+    pub cook_speed_bonus: f32,
+    /// Damage multipliers of the weapons (item ids) the character
+    /// is good or bad with.
+    #[serde(default)]
+    pub weapon_affinity: HashMap<String, f32>,
+}
+
+impl CharacterStats {
+    pub fn inventory_capacity(&self) -> usize {
+        self.carry_capacity.max(HOTBAR_SIZE)
+    }
+
+    /// Multiplier of the cooking duration.
+    pub fn cook_duration(&self) -> f32 {
+        1.0 / (1.0 + self.cook_speed_bonus.max(0.0))
+    }
+
+    /// Damage multiplier of the weapon, [`None`] for the base
+    /// weapon.
+    pub fn weapon_damage(&self, weapon_id: Option<&String>) -> f32 {
+        weapon_id
+            .and_then(|id| self.weapon_affinity.get(id))
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+#[derive(Resource)]
+pub struct CharacterStatsAssetHandle(Handle<CharacterStatsAsset>);
+
+#[derive(SystemParam)]
+pub struct CharacterStatsRegistry<'w> {
+    pub handle: Res<'w, CharacterStatsAssetHandle>,
+    pub assets: Res<'w, Assets<CharacterStatsAsset>>,
+}
+
+impl CharacterStatsRegistry<'_> {
+    pub fn get(&self) -> Option<&CharacterStatsAsset> {
+        self.assets.get(&self.handle.0)
+    }
+
+    pub fn get_stats(
+        &self,
+        character_id: &CharacterId,
+    ) -> Option<&CharacterStats> {
+        self.get()?.get(character_id)
+    }
+}
+
+#[derive(Default)]
+pub struct CharacterStatsAssetLoader;
+
+impl AssetLoader for CharacterStatsAssetLoader {
+    type Asset = CharacterStatsAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut ron_str = String::new();
+        reader.read_to_string(&mut ron_str).await?;
+
+        // Surface as a failed load instead of panicking.
+        ron::from_str::<CharacterStatsAsset>(&ron_str)
+            .map_err(std::io::Error::other)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["character_stats.ron"]
+    }
+}
@@ -0,0 +1,274 @@
+//! AI companion that plays as Player B when nobody claims it, so
+//! that the game is playable solo.
+//!
+//! Like any other possessor, the companion only drives the
+//! [`ActionState`] of Player B. Its [`Brain`] ("companion" in
+//! "brains.ai.ron") decides between:
+//!
+//! - `retreat` from nearby enemies.
+//! - `cook` at the machine Player A marked last, once it carries
+//!   the ingredients of the selected recipe.
+//! - `collect` nearby ingredients.
+//! - `follow` Player A.
+
+use bevy::prelude::*;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::PlayerAction;
+use crate::ai::{AiInputs, AiSet, Brain, Decision};
+use crate::camera_controller::split_screen::{
+    CameraType, QueryCameras,
+};
+use crate::character_controller::{
+    CharacterController, camera_relative_input,
+};
+use crate::enemy::spatial_grid::EnemyGrid;
+use crate::interaction::MarkerOf;
+use crate::inventory::item::{ItemRegistry, ItemType};
+use crate::inventory::{CollectCooldown, Inventory, Item};
+use crate::machine::overcook::CookedOutput;
+use crate::machine::recipe::RecipeRegistry;
+use crate::machine::{Machine, OperatedBy, SelectedRecipe};
+
+use super::{QueryPlayerA, QueryPlayerB};
+
+/// Enemies closer than this are avoided.
+const RETREAT_RADIUS: f32 = 6.0;
+/// Ingredients further than this are left alone.
+const COLLECT_RADIUS: f32 = 12.0;
+/// Distance the companion keeps from Player A when idle.
+const FOLLOW_DISTANCE: f32 = 4.0;
+/// Distance at which a destination counts as reached.
+const ARRIVE_DISTANCE: f32 = 0.5;
+/// Seconds between two interactions, in case one fails.
+const INTERACT_COOLDOWN: f32 = 1.0;
+
+pub(super) struct CompanionPlugin;
+
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, remember_marked_machine)
+            .add_systems(
+                PreUpdate,
+                (
+                    sense_companion.in_set(AiSet::Sense),
+                    drive_companion
+                        .in_set(InputManagerSystem::ManualControl),
+                ),
+            );
+
+        app.register_type::<Companion>();
+    }
+}
+
+/// Remember the last machine marked by Player A.
+fn remember_marked_machine(
+    q_player_a: QueryPlayerA<
+        &MarkerOf,
+        (With<CharacterController>, Changed<MarkerOf>),
+    >,
+    q_machines: Query<(), With<Machine>>,
+    mut q_companions: Query<&mut Companion>,
+) {
+    let Ok(marker_of) = q_player_a.single() else {
+        return;
+    };
+
+    if q_machines.contains(marker_of.entity()) {
+        for mut companion in q_companions.iter_mut() {
+            companion.machine = Some(marker_of.entity());
+        }
+    }
+}
+
+/// Find the targets of every behavior and sense how desirable
+/// they are.
+fn sense_companion(
+    mut q_companions: Query<(&mut Companion, &mut AiInputs)>,
+    q_player_a: QueryPlayerA<
+        &GlobalTransform,
+        With<CharacterController>,
+    >,
+    q_player_b: QueryPlayerB<
+        (&GlobalTransform, &Inventory),
+        With<CharacterController>,
+    >,
+    q_items: Query<
+        (&Item, &GlobalTransform),
+        Without<CollectCooldown>,
+    >,
+    // Only machines that are free to cook.
+    q_machines: Query<
+        (&GlobalTransform, &SelectedRecipe),
+        (With<Machine>, Without<OperatedBy>, Without<CookedOutput>),
+    >,
+    enemy_grid: Res<EnemyGrid>,
+    item_registry: ItemRegistry,
+    recipe_registry: RecipeRegistry,
+) {
+    let Ok((mut companion, mut inputs)) = q_companions.single_mut()
+    else {
+        return;
+    };
+
+    let Ok((companion_transform, inventory)) = q_player_b.single()
+    else {
+        companion.targets = CompanionTargets::default();
+        return;
+    };
+
+    let position = companion_transform.translation();
+    let closest = |a: &Vec3, b: &Vec3| {
+        a.distance_squared(position)
+            .total_cmp(&b.distance_squared(position))
+    };
+
+    let enemy = enemy_grid
+        .within_radius(position, RETREAT_RADIUS)
+        .map(|(_, enemy_position)| enemy_position)
+        .min_by(closest);
+
+    let machine = companion.machine.and_then(|entity| {
+        let (transform, selected_recipe) =
+            q_machines.get(entity).ok()?;
+        let recipe = recipe_registry.get_recipe(selected_recipe)?;
+
+        inventory
+            .has_recipe(recipe)
+            .then_some((entity, transform.translation()))
+    });
+
+    let ingredient =
+        item_registry.get().and_then(|item_meta_asset| {
+            q_items
+                .iter()
+                .filter(|(item, _)| {
+                    item_meta_asset.get(&item.id).is_some_and(|m| {
+                        m.item_type == ItemType::Ingredient
+                    })
+                })
+                .map(|(_, transform)| transform.translation())
+                .filter(|item_position| {
+                    item_position.distance(position) < COLLECT_RADIUS
+                })
+                .min_by(closest)
+        });
+
+    let player = q_player_a.single().ok().map(|t| t.translation());
+
+    let proximity = |target: Option<Vec3>, radius: f32| {
+        target.map_or(0.0, |t| 1.0 - t.distance(position) / radius)
+    };
+    inputs.set("enemy_proximity", proximity(enemy, RETREAT_RADIUS));
+    inputs.set("can_cook", machine.map_or(0.0, |_| 1.0));
+    inputs.set(
+        "ingredient_proximity",
+        proximity(ingredient, COLLECT_RADIUS),
+    );
+    // Above 0.5 when further than the follow distance.
+    inputs.set(
+        "player_distance",
+        player.map_or(0.0, |p| {
+            p.distance(position) / (FOLLOW_DISTANCE * 2.0)
+        }),
+    );
+
+    companion.targets = CompanionTargets {
+        enemy,
+        machine,
+        ingredient,
+        player,
+    };
+}
+
+/// Carry out the [`Decision`] through the [`ActionState`].
+fn drive_companion(
+    mut q_companions: Query<(
+        &mut Companion,
+        &Decision,
+        &mut ActionState<PlayerAction>,
+    )>,
+    q_player_b: QueryPlayerB<
+        (&GlobalTransform, Option<&MarkerOf>),
+        With<CharacterController>,
+    >,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    time: Res<Time>,
+) {
+    let Ok((mut companion, decision, mut action)) =
+        q_companions.single_mut()
+    else {
+        return;
+    };
+
+    let (Ok((companion_transform, marker_of)), Ok(camera)) =
+        (q_player_b.single(), q_cameras.get(CameraType::B))
+    else {
+        action.set_axis_pair(&PlayerAction::Move, Vec2::ZERO);
+        return;
+    };
+
+    let position = companion_transform.translation();
+    companion.interact_cooldown -= time.delta_secs();
+
+    let targets = companion.targets;
+    let mut interact = false;
+    let destination = match decision.as_deref() {
+        // Run directly away from the enemy.
+        Some("retreat") => targets.enemy.map(|e| position * 2.0 - e),
+        Some("cook") => targets.machine.map(
+            |(machine_entity, machine_position)| {
+                if marker_of
+                    .is_some_and(|m| m.entity() == machine_entity)
+                    && companion.interact_cooldown <= 0.0
+                {
+                    interact = true;
+                    companion.interact_cooldown = INTERACT_COOLDOWN;
+                }
+                machine_position
+            },
+        ),
+        Some("collect") => targets.ingredient,
+        Some("follow") => targets.player,
+        _ => None,
+    };
+
+    let movement = destination
+        .map(|destination| destination - position)
+        .filter(|offset| offset.xz().length() > ARRIVE_DISTANCE)
+        .map(|offset| {
+            camera_relative_input(camera, offset).normalize_or_zero()
+        })
+        .unwrap_or_default();
+    action.set_axis_pair(&PlayerAction::Move, movement);
+
+    match interact {
+        true => action.press(&PlayerAction::Interact),
+        false => action.release(&PlayerAction::Interact),
+    }
+}
+
+/// Drives the [`ActionState`] of the entity as an AI companion.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component, Default)]
+#[require(Brain::new("companion"))]
+pub struct Companion {
+    /// The machine Player A marked last, where the companion
+    /// cooks.
+    pub machine: Option<Entity>,
+    /// Seconds until the companion can interact again.
+    interact_cooldown: f32,
+    #[reflect(ignore)]
+    targets: CompanionTargets,
+}
+
+/// World space targets of the behaviors from the last sense.
+#[derive(Default, Debug, Clone, Copy)]
+struct CompanionTargets {
+    enemy: Option<Vec3>,
+    /// Machine that the companion can cook at right now.
+    machine: Option<(Entity, Vec3)>,
+    ingredient: Option<Vec3>,
+    player: Option<Vec3>,
+}
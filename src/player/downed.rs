@@ -0,0 +1,190 @@
+//! Downed players spectate their partner until they are revived.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::CameraTarget;
+use crate::camera_controller::split_screen::{
+    QueryCameras, Spectate,
+};
+use crate::ui::Screen;
+use crate::ui::widgets::progress_bar::ProgressBar;
+use crate::ui::world_space::WorldUi;
+
+use super::{PlayerType, QueryPlayers};
+
+/// Seconds until a downed player is revived.
+const REVIVE_DURATION: f32 = 8.0;
+
+pub(super) struct DownedPlugin;
+
+impl Plugin for DownedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            tick_revive.run_if(in_state(Screen::EnterLevel)),
+        )
+        .add_observer(on_downed)
+        .add_observer(on_revived);
+
+        app.register_type::<Downed>();
+    }
+}
+
+/// Spectate the partner and show the revive progress on the
+/// downed player's viewport, marking the downed player on the
+/// partner's viewport.
+fn on_downed(
+    trigger: Trigger<OnAdd, Downed>,
+    mut commands: Commands,
+    q_players: Query<&PlayerType>,
+    q_camera_targets: QueryPlayers<Entity, With<CameraTarget>>,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(4.0));
+
+    let entity = trigger.target();
+    let player_type = *q_players.get(entity)?;
+
+    commands
+        .entity(q_camera_targets.get(player_type)?)
+        .insert(Spectate(player_type.partner()));
+
+    commands.spawn((
+        StateScoped(Screen::EnterLevel),
+        UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(15.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        ReviveOverlay(entity),
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+        Children::spawn((
+            Spawn((
+                Text::new("Downed! Spectating your partner..."),
+                TextFont::from_font_size(20.0),
+                TextShadow::default(),
+            )),
+            Spawn((
+                Node {
+                    width: Val::Px(240.0),
+                    height: Val::Px(12.0),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BackgroundColor(ZINC_900.with_alpha(0.6).into()),
+                RADIUS,
+                ProgressBar::new(SKY_400, RADIUS),
+                ReviveBar(entity),
+            )),
+        )),
+    ));
+
+    commands.spawn((
+        StateScoped(Screen::EnterLevel),
+        UiTargetCamera(
+            q_cameras.get(player_type.partner().camera_type())?,
+        ),
+        WorldUi::new(entity)
+            .with_world_offset(Vec3::Y * 2.5)
+            .with_edge_clamp(),
+        Node {
+            position_type: PositionType::Absolute,
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+            ..default()
+        },
+        ReviveOverlay(entity),
+        BorderRadius::all(Val::Px(6.0)),
+        BackgroundColor(ZINC_900.with_alpha(0.6).into()),
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+        Children::spawn(Spawn((
+            Text::new("Downed!"),
+            TextColor(SKY_400.into()),
+            TextFont::from_font_size(16.0),
+            TextShadow::default(),
+        ))),
+    ));
+
+    Ok(())
+}
+
+fn tick_revive(
+    mut commands: Commands,
+    mut q_downed: Query<(&mut Downed, Entity)>,
+    mut q_bars: Query<(&mut ProgressBar, &ReviveBar)>,
+    time: Res<Time>,
+) {
+    for (mut downed, entity) in q_downed.iter_mut() {
+        let progress = downed.revive.tick(time.delta()).fraction();
+
+        for (mut bar, revive_bar) in q_bars.iter_mut() {
+            if revive_bar.0 == entity {
+                bar.progress = progress;
+            }
+        }
+
+        if downed.revive.finished() {
+            commands.entity(entity).remove::<Downed>();
+        }
+    }
+}
+
+/// Give the camera back and remove the overlay, also when the
+/// player is despawned while downed.
+fn on_revived(
+    trigger: Trigger<OnRemove, Downed>,
+    mut commands: Commands,
+    q_players: Query<&PlayerType>,
+    q_camera_targets: QueryPlayers<Entity, With<CameraTarget>>,
+    q_overlays: Query<(&ReviveOverlay, Entity)>,
+) {
+    let entity = trigger.target();
+
+    if let Some(camera_target) = q_players
+        .get(entity)
+        .ok()
+        .and_then(|p| q_camera_targets.get(*p).ok())
+    {
+        commands.entity(camera_target).try_remove::<Spectate>();
+    }
+
+    for (overlay, overlay_entity) in q_overlays.iter() {
+        if overlay.0 == entity {
+            commands.entity(overlay_entity).try_despawn();
+        }
+    }
+}
+
+/// A player character that is down and waiting to be revived.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct Downed {
+    pub revive: Timer,
+}
+
+impl Default for Downed {
+    fn default() -> Self {
+        Self {
+            revive: Timer::from_seconds(
+                REVIVE_DURATION,
+                TimerMode::Once,
+            ),
+        }
+    }
+}
+
+/// The revive overlay and marker of a downed player.
+#[derive(Component)]
+struct ReviveOverlay(Entity);
+
+/// The revive progress bar of a downed player.
+#[derive(Component)]
+struct ReviveBar(Entity);
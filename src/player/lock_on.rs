@@ -0,0 +1,221 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::enemy::Enemy;
+
+use super::PlayerType;
+
+/// Maximum distance from the camera an enemy can be locked on.
+const LOCK_DISTANCE: f32 = 30.0;
+/// The lock breaks once the enemy gets further than this.
+const BREAK_DISTANCE: f32 = 40.0;
+/// Cosine of the half angle of the cone in front of the camera
+/// that enemies can be locked on within.
+const LOCK_CONE: f32 = 0.8;
+/// How far the right stick needs to be pushed sideways to cycle
+/// targets.
+const FLICK_THRESHOLD: f32 = 0.7;
+
+/// Lets players lock onto an enemy, which their attacks then aim
+/// at directly.
+pub(super) struct LockOnPlugin;
+
+impl Plugin for LockOnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (toggle_lock_on, flick_lock_on, break_lock_on).chain(),
+        );
+    }
+}
+
+/// Lock onto the best enemy in view, or release the lock.
+fn toggle_lock_on(
+    mut commands: Commands,
+    q_players: Query<
+        (&PlayerType, &TargetAction, Has<LockOn>, Entity),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_enemies: Query<(&GlobalTransform, Entity), With<Enemy>>,
+) {
+    for (player_type, target_action, locked, entity) in
+        q_players.iter()
+    {
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::LockOn))
+            == false
+        {
+            continue;
+        }
+
+        if locked {
+            commands.entity(entity).remove::<LockOn>();
+            continue;
+        }
+
+        let Ok(camera_transform) =
+            q_cameras.get(player_type.camera_type())
+        else {
+            continue;
+        };
+
+        let best = q_enemies
+            .iter()
+            .filter_map(|(transform, enemy)| {
+                let score = lock_score(
+                    camera_transform,
+                    transform.translation(),
+                )?;
+                Some((score, enemy))
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        if let Some((_, enemy)) = best {
+            commands.entity(entity).insert(LockOn(enemy));
+        }
+    }
+}
+
+/// Flick the right stick sideways to cycle to the closest enemy
+/// in that direction.
+fn flick_lock_on(
+    mut commands: Commands,
+    q_players: Query<
+        (&PlayerType, &TargetAction, &LockOn, Entity),
+        With<CharacterController>,
+    >,
+    q_actions: Query<(
+        &ActionState<PlayerAction>,
+        &InputMap<PlayerAction>,
+    )>,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_enemies: Query<(&GlobalTransform, Entity), With<Enemy>>,
+    // Players that haven't released the stick since their last flick.
+    mut flicked: Local<HashSet<Entity>>,
+) {
+    for (player_type, target_action, lock_on, entity) in
+        q_players.iter()
+    {
+        let Ok((action, input_map)) =
+            q_actions.get(target_action.get())
+        else {
+            continue;
+        };
+
+        // Mouse motion is not a stick.
+        if input_map.gamepad().is_none() {
+            continue;
+        }
+
+        let aim_x = action.axis_pair(&PlayerAction::Aim).x;
+        if aim_x.abs() < FLICK_THRESHOLD {
+            flicked.remove(&entity);
+            continue;
+        }
+
+        if flicked.insert(entity) == false {
+            continue;
+        }
+
+        let (Ok(camera_transform), Ok((target_transform, _))) = (
+            q_cameras.get(player_type.camera_type()),
+            q_enemies.get(lock_on.entity()),
+        ) else {
+            continue;
+        };
+
+        let right = camera_transform.right();
+        let target_x = right.dot(target_transform.translation());
+
+        let next = q_enemies
+            .iter()
+            .filter(|(_, enemy)| *enemy != lock_on.entity())
+            .filter(|(transform, _)| {
+                lock_score(camera_transform, transform.translation())
+                    .is_some()
+            })
+            .map(|(transform, enemy)| {
+                let offset =
+                    right.dot(transform.translation()) - target_x;
+                (offset * aim_x.signum(), enemy)
+            })
+            .filter(|(offset, _)| *offset > 0.0)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        if let Some((_, enemy)) = next {
+            commands.entity(entity).insert(LockOn(enemy));
+        }
+    }
+}
+
+/// Release the lock once the enemy gets too far away.
+fn break_lock_on(
+    mut commands: Commands,
+    q_players: Query<
+        (&PlayerType, &LockOn, Entity),
+        With<CharacterController>,
+    >,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_enemies: Query<&GlobalTransform, With<Enemy>>,
+) {
+    for (player_type, lock_on, entity) in q_players.iter() {
+        let Ok(camera_transform) =
+            q_cameras.get(player_type.camera_type())
+        else {
+            continue;
+        };
+
+        let in_range =
+            q_enemies.get(lock_on.entity()).is_ok_and(|transform| {
+                transform
+                    .translation()
+                    .distance(camera_transform.translation())
+                    < BREAK_DISTANCE
+            });
+
+        if in_range == false {
+            commands.entity(entity).remove::<LockOn>();
+        }
+    }
+}
+
+/// Score of locking onto an enemy at `position`, favoring the
+/// center of the view over the distance. [`None`] if the enemy is
+/// out of the lock cone.
+fn lock_score(
+    camera_transform: &GlobalTransform,
+    position: Vec3,
+) -> Option<f32> {
+    let offset = position - camera_transform.translation();
+    let distance = offset.length();
+    if distance > LOCK_DISTANCE {
+        return None;
+    }
+
+    let alignment =
+        camera_transform.forward().dot(offset.normalize_or_zero());
+    if alignment < LOCK_CONE {
+        return None;
+    }
+
+    let centered = (alignment - LOCK_CONE) / (1.0 - LOCK_CONE);
+    Some(centered * 2.0 + (1.0 - distance / LOCK_DISTANCE))
+}
+
+/// Stores a list of player entities locked onto this enemy.
+#[derive(Component, Deref, Default, Debug)]
+#[relationship_target(relationship = LockOn)]
+pub struct LockedOnBy(Vec<Entity>);
+
+/// Stores the enemy the player is locked onto.
+#[derive(Component, Deref, Debug)]
+#[component(immutable)]
+#[relationship(relationship_target = LockedOnBy)]
+pub struct LockOn(Entity);
@@ -1,12 +1,21 @@
 use crate::action::{PlayerAction, TargetAction};
-use crate::asset_pipeline::{AssetState, PrefabAssets, PrefabName};
-use crate::camera_controller::split_screen::{
-    CameraType, QueryCameras,
-};
+use crate::asset_pipeline::{AssetState, PrefabAssets};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::damage::DamageRules;
 use crate::enemy::IsEnemy;
+use crate::interaction::grab::CarryingHeavy;
+use crate::inventory::Inventory;
+use crate::inventory::item::{
+    AmmoStats, ItemRegistry, ItemType, WeaponStats,
+};
 use crate::physics::GameLayer;
-use crate::player::PlayerType;
+use crate::player::character_stats::CharacterStats;
+use crate::player::lock_on::LockOn;
+use crate::player::{CharacterId, PlayerType, QueryPlayers};
 use crate::tower::Projectile;
+use crate::tower::ownership::owner_color;
+use crate::vfx::trail::Trail;
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
@@ -15,11 +24,20 @@ pub(super) struct PlayerAttackPlugin;
 
 impl Plugin for PlayerAttackPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_cooldowns).add_systems(
+        app.add_systems(
+            Update,
+            (
+                update_cooldowns,
+                (switch_weapon, reload_weapon).chain(),
+            ),
+        )
+        .add_systems(
             FixedUpdate,
             player_shooting.run_if(in_state(AssetState::Loaded)),
-        );
-        app.register_type::<PlayerWeapon>();
+        )
+        .add_observer(remember_base_weapon);
+        app.register_type::<PlayerWeapon>()
+            .register_type::<EquippedWeapon>();
     }
 }
 
@@ -32,6 +50,183 @@ fn update_cooldowns(
     }
 }
 
+/// Keep the weapon from the scene around to switch back to.
+fn remember_base_weapon(
+    trigger: Trigger<OnAdd, PlayerWeapon>,
+    mut commands: Commands,
+    q_weapons: Query<&PlayerWeapon>,
+) -> Result {
+    let entity = trigger.target();
+    let weapon = q_weapons.get(entity)?;
+    commands.entity(entity).insert(BaseWeapon(weapon.stats()));
+
+    Ok(())
+}
+
+/// Cycle through the base weapon and the weapons in the
+/// inventory.
+fn switch_weapon(
+    mut commands: Commands,
+    mut q_weapons: Query<(
+        &mut PlayerWeapon,
+        &mut EquippedWeapon,
+        &mut Magazine,
+        &BaseWeapon,
+        &PlayerType,
+        &TargetAction,
+        Entity,
+    )>,
+    mut q_inventories: QueryPlayers<
+        &mut Inventory,
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    item_registry: ItemRegistry,
+) {
+    for (
+        mut weapon,
+        mut equipped,
+        mut magazine,
+        base,
+        player_type,
+        target_action,
+        entity,
+    ) in q_weapons.iter_mut()
+    {
+        let Ok(mut inventory) = q_inventories.get_mut(*player_type)
+        else {
+            continue;
+        };
+
+        // `None` is the base weapon.
+        let mut weapon_ids = vec![None];
+        for (_, stack) in inventory.stacks_of_type(ItemType::Weapon) {
+            let id = Some(stack.item_id.clone());
+            if weapon_ids.contains(&id) == false {
+                weapon_ids.push(id);
+            }
+        }
+
+        let current =
+            weapon_ids.iter().position(|id| *id == equipped.0);
+        let switch =
+            q_actions.get(target_action.get()).is_ok_and(|a| {
+                a.just_pressed(&PlayerAction::SwitchWeapon)
+            });
+
+        let next = match (current, switch) {
+            (Some(current), true) => (current + 1) % weapon_ids.len(),
+            (Some(_), false) => continue,
+            // The equipped weapon is no longer in the inventory.
+            (None, _) => 0,
+        };
+
+        let next_id = weapon_ids.swap_remove(next);
+        let stats = match &next_id {
+            Some(id) => item_registry
+                .get_item(id)
+                .and_then(|item_meta| item_meta.weapon_stats.clone()),
+            None => Some(base.0.clone()),
+        };
+        let Some(stats) = stats else {
+            continue;
+        };
+
+        // Unload the magazine back into the inventory, rounds that
+        // don't fit are lost.
+        if let Some(ammo) = &weapon.ammo {
+            if let Some(item_meta) =
+                item_registry.get_item(&ammo.item_id)
+            {
+                if magazine.rounds > 0 {
+                    inventory.add(
+                        &ammo.item_id,
+                        item_meta,
+                        magazine.rounds,
+                    );
+                }
+            }
+        }
+        magazine.rounds = 0;
+        commands.entity(entity).remove::<Reloading>();
+
+        weapon.apply(stats);
+        equipped.set_if_neq(EquippedWeapon(next_id));
+    }
+}
+
+/// Start reloading on demand or when attacking with an empty
+/// magazine, the magazine is filled from the inventory once done.
+fn reload_weapon(
+    mut commands: Commands,
+    mut q_weapons: Query<(
+        &PlayerWeapon,
+        &mut Magazine,
+        Option<&mut Reloading>,
+        &PlayerType,
+        &TargetAction,
+        Entity,
+    )>,
+    mut q_inventories: QueryPlayers<
+        &mut Inventory,
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    time: Res<Time>,
+) {
+    for (
+        weapon,
+        mut magazine,
+        reloading,
+        player_type,
+        target_action,
+        entity,
+    ) in q_weapons.iter_mut()
+    {
+        let Some(ammo) = &weapon.ammo else {
+            continue;
+        };
+        let Ok(mut inventory) = q_inventories.get_mut(*player_type)
+        else {
+            continue;
+        };
+
+        if let Some(mut reloading) = reloading {
+            if reloading.tick(time.delta()).finished() {
+                let rounds = ammo
+                    .magazine_size
+                    .saturating_sub(magazine.rounds)
+                    .min(inventory.count(&ammo.item_id));
+
+                inventory.remove(&ammo.item_id, rounds);
+                magazine.rounds += rounds;
+                commands.entity(entity).remove::<Reloading>();
+            }
+            continue;
+        }
+
+        let Ok(action) = q_actions.get(target_action.get()) else {
+            continue;
+        };
+
+        let wants_reload = action.just_pressed(&PlayerAction::Reload)
+            || (magazine.rounds == 0
+                && action.pressed(&PlayerAction::Attack));
+
+        if wants_reload
+            && magazine.rounds < ammo.magazine_size
+            && inventory.count(&ammo.item_id) > 0
+        {
+            commands.entity(entity).insert(Reloading(
+                Timer::from_seconds(
+                    ammo.reload_duration,
+                    TimerMode::Once,
+                ),
+            ));
+        }
+    }
+}
+
 fn player_shooting(
     mut commands: Commands,
     mut q_player_weapons: Query<(
@@ -40,11 +235,21 @@ fn player_shooting(
         &PlayerWeapon,
         &TargetAction,
         &mut AttackCooldown,
+        &mut Magazine,
+        &EquippedWeapon,
+        Has<Reloading>,
     )>,
     q_cameras: QueryCameras<&GlobalTransform>,
     q_actions: Query<&ActionState<PlayerAction>>,
     q_enemies: Query<&GlobalTransform, With<IsEnemy>>,
+    q_carrying_heavy: QueryPlayers<(), With<CarryingHeavy>>,
+    q_lock_ons: QueryPlayers<&LockOn>,
+    q_characters: QueryPlayers<
+        (&CharacterId, Option<&CharacterStats>, Entity),
+        With<CharacterController>,
+    >,
     spatial_query: SpatialQuery,
+    damage_rules: Res<DamageRules>,
     prefabs: Res<PrefabAssets>,
     gltfs: Res<Assets<Gltf>>,
 ) -> Result {
@@ -54,6 +259,9 @@ fn player_shooting(
         weapon,
         target_action,
         mut cooldown,
+        mut magazine,
+        equipped,
+        reloading,
     ) in q_player_weapons.iter_mut()
     {
         // Check cooldown
@@ -61,6 +269,18 @@ fn player_shooting(
             continue;
         }
 
+        // Out of rounds or still reloading.
+        if weapon.ammo.is_some()
+            && (reloading || magazine.rounds == 0)
+        {
+            continue;
+        }
+
+        // Both hands are busy carrying something heavy.
+        if q_carrying_heavy.get(*player_type).is_ok() {
+            continue;
+        }
+
         let Ok(action) = q_actions.get(target_action.get()) else {
             continue;
         };
@@ -68,13 +288,20 @@ fn player_shooting(
             continue;
         }
 
-        let (camera_type, weapon_name) = match player_type {
-            PlayerType::A => (CameraType::A, "polo_bun_small"),
-            PlayerType::B => (CameraType::B, "baguette_small"),
+        let Ok(camera_transform) =
+            q_cameras.get(player_type.camera_type())
+        else {
+            continue;
         };
-        let Ok(camera_transform) = q_cameras.get(camera_type) else {
+
+        let Ok((character_id, character_stats, character)) =
+            q_characters.get(*player_type)
+        else {
             continue;
         };
+        let affinity = character_stats
+            .map_or(1.0, |s| s.weapon_damage(equipped.0.as_ref()));
+        let weapon_id = character_id.projectile_id();
 
         let camera_position = camera_transform.translation();
         let camera_forward = camera_transform.forward();
@@ -102,8 +329,19 @@ fn player_shooting(
                 .with_mask(GameLayer::Enemy),
         );
 
-        // Check if enemy was hit
-        let target_direction = if let Some(hit) = shape_hit {
+        let locked_enemy = q_lock_ons
+            .get(*player_type)
+            .ok()
+            .and_then(|lock_on| q_enemies.get(lock_on.entity()).ok());
+
+        let target_direction = if let Some(enemy_transform) =
+            locked_enemy
+        {
+            // Aim directly at the locked enemy.
+            (enemy_transform.translation() - projectile_start)
+                .normalize()
+        } else if let Some(hit) = shape_hit {
+            // Check if enemy was hit
             if let Ok(enemy_transform) = q_enemies.get(hit.entity) {
                 // Aim from projectile spawn point to the detected enemy
                 (enemy_transform.translation() - projectile_start)
@@ -118,12 +356,12 @@ fn player_shooting(
         };
 
         let handle = prefabs
-            .get_gltf(PrefabName::FileName(weapon_name), &gltfs)
-            .ok_or(format!("Can't find {weapon_name} prefab!"))?
+            .get_gltf(weapon_id.into(), &gltfs)
+            .ok_or(format!("Can't find {weapon_id:?} prefab!"))?
             .default_scene
             .clone()
             .ok_or(format!(
-                "{weapon_name} prefab should have a default scene."
+                "{weapon_id:?} prefab should have a default scene."
             ))?;
 
         // Spawn projectile using weapon stats
@@ -133,9 +371,17 @@ fn player_shooting(
             ),
             Projectile {
                 velocity: target_direction * weapon.projectile_speed,
-                damage: weapon.damage,
+                damage: weapon.damage * affinity,
                 lifetime: weapon.projectile_lifetime,
+                knockback: weapon.knockback,
+                shooter: character,
             },
+            Trail::new(owner_color(*player_type).with_alpha(0.7))
+                .with_width(0.1),
+            CollisionLayers::new(
+                GameLayer::Projectile,
+                damage_rules.projectile_filters(GameLayer::Player),
+            ),
             Visibility::Inherited,
             Children::spawn(Spawn((
                 SceneRoot(handle),
@@ -146,6 +392,9 @@ fn player_shooting(
 
         // Reset cooldown
         cooldown.0 = weapon.attack_cooldown;
+        if weapon.ammo.is_some() {
+            magazine.rounds -= 1;
+        }
     }
 
     Ok(())
@@ -154,14 +403,65 @@ fn player_shooting(
 /// Player weapon component with configurable stats.
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-#[require(AttackCooldown)]
+#[require(AttackCooldown, EquippedWeapon, Magazine)]
 pub struct PlayerWeapon {
     pub damage: f32,
     pub attack_cooldown: f32,
     pub projectile_speed: f32,
     pub projectile_lifetime: f32,
+    #[reflect(default = "default_weapon_knockback")]
+    pub knockback: f32,
+    /// Infinite ammo if [`None`].
+    #[reflect(default)]
+    pub ammo: Option<AmmoStats>,
+}
+
+fn default_weapon_knockback() -> f32 {
+    1.0
 }
 
+impl PlayerWeapon {
+    pub fn stats(&self) -> WeaponStats {
+        WeaponStats {
+            damage: self.damage,
+            attack_cooldown: self.attack_cooldown,
+            projectile_speed: self.projectile_speed,
+            projectile_lifetime: self.projectile_lifetime,
+            knockback: self.knockback,
+            ammo: self.ammo.clone(),
+        }
+    }
+
+    pub fn apply(&mut self, stats: WeaponStats) {
+        self.damage = stats.damage;
+        self.attack_cooldown = stats.attack_cooldown;
+        self.projectile_speed = stats.projectile_speed;
+        self.projectile_lifetime = stats.projectile_lifetime;
+        self.knockback = stats.knockback;
+        self.ammo = stats.ammo;
+    }
+}
+
+/// Item id of the equipped weapon, [`None`] for the
+/// [`BaseWeapon`].
+#[derive(Component, Reflect, Deref, Default, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct EquippedWeapon(pub Option<String>);
+
+/// Rounds loaded into a [`PlayerWeapon`] with ammo.
+#[derive(Component, Default, Debug)]
+pub struct Magazine {
+    pub rounds: u32,
+}
+
+/// Attached to the weapon while it's reloading.
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct Reloading(pub Timer);
+
+/// Stats of the weapon the player started with.
+#[derive(Component, Debug)]
+pub struct BaseWeapon(pub WeaponStats);
+
 /// Player attack cooldown.
 #[derive(Component, Deref, DerefMut, Debug, Default)]
 pub struct AttackCooldown(pub f32);
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+use crate::enemy::spawner::SpawnWave;
+use crate::enemy::{BASE_HEALTH, FinalTarget};
+use crate::tower::tower_attack::Health;
 use crate::ui::Screen;
 
 pub(super) struct PlayerMarkPlugin;
@@ -12,27 +15,54 @@ impl Plugin for PlayerMarkPlugin {
         )
         .add_systems(
             Update,
-            game_over_condition.run_if(
-                in_state(Screen::EnterLevel)
-                    .and(resource_changed::<PlayerMark>),
-            ),
+            (
+                sync_player_mark,
+                game_over_condition
+                    .run_if(resource_changed::<PlayerMark>),
+            )
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
         );
     }
 }
 
 /// Reset [`PlayerMark`] resource.
 pub fn init_player_mark(mut commands: Commands) {
-    commands.insert_resource(PlayerMark(10));
+    commands.insert_resource(PlayerMark(BASE_HEALTH.ceil() as u32));
+}
+
+/// Mirror the health of the [`FinalTarget`] into the
+/// [`PlayerMark`].
+fn sync_player_mark(
+    q_final_targets: Query<
+        &Health,
+        (With<FinalTarget>, Changed<Health>),
+    >,
+    mut player_mark: ResMut<PlayerMark>,
+) {
+    for health in q_final_targets.iter() {
+        player_mark
+            .set_if_neq(PlayerMark(health.0.max(0.0).ceil() as u32));
+    }
 }
 
 fn game_over_condition(
+    mut commands: Commands,
     player_mark: Res<PlayerMark>,
+    wave: Res<State<SpawnWave>>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
     if player_mark.0 == 0 {
-        next_screen.set(Screen::GameOver);
+        commands.insert_resource(FellOnWave(*wave.get()));
+        next_screen.set(Screen::Defeat);
     }
 }
 
-#[derive(Resource, Deref, DerefMut)]
+/// Whole points of [`Health`] the [`FinalTarget`] has left.
+#[derive(Resource, Deref, DerefMut, PartialEq)]
 pub struct PlayerMark(pub u32);
+
+/// The wave during which the [`FinalTarget`] fell, shown on
+/// [`Screen::Defeat`].
+#[derive(Resource, Deref, Debug, Clone, Copy)]
+pub struct FellOnWave(pub SpawnWave);
@@ -0,0 +1,226 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::character_controller::CharacterController;
+use crate::damage::{DamageEvent, DamageKind};
+use crate::enemy::Enemy;
+use crate::interaction::grab::CarryingHeavy;
+use crate::physics::GameLayer;
+use crate::tower::tower_attack::Invulnerable;
+
+/// Number of swings in a full combo, the last one is the
+/// finisher.
+pub const COMBO_LENGTH: usize = 3;
+/// Maximum enemies hit by a single swing.
+const MAX_HITS: u32 = 8;
+
+/// Lets players swing their bread at nearby enemies.
+pub(super) struct PlayerMeleePlugin;
+
+impl Plugin for PlayerMeleePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (tick_melee_combos, melee_swing).chain(),
+        );
+
+        app.register_type::<Melee>();
+    }
+}
+
+fn tick_melee_combos(
+    mut commands: Commands,
+    mut q_combos: Query<(
+        &mut MeleeCombo,
+        Option<&mut Swinging>,
+        Entity,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut combo, swinging, entity) in q_combos.iter_mut() {
+        combo.window -= time.delta_secs();
+
+        if let Some(mut swinging) = swinging {
+            if swinging.timer.tick(time.delta()).finished() {
+                commands.entity(entity).remove::<Swinging>();
+            }
+        }
+    }
+}
+
+/// Swing at the enemies in an arc in front of the player,
+/// continuing the combo if the last swing was recent enough.
+fn melee_swing(
+    mut commands: Commands,
+    mut q_players: Query<
+        (
+            &Melee,
+            &mut MeleeCombo,
+            &TargetAction,
+            &GlobalTransform,
+            Has<Swinging>,
+            Has<CarryingHeavy>,
+            Entity,
+        ),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_collider_ofs: Query<&ColliderOf>,
+    q_enemies: Query<
+        &GlobalTransform,
+        (With<Enemy>, Without<Invulnerable>),
+    >,
+    spatial_query: SpatialQuery,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (
+        melee,
+        mut combo,
+        target_action,
+        transform,
+        swinging,
+        carrying_heavy,
+        entity,
+    ) in q_players.iter_mut()
+    {
+        // Finish the current swing first, both hands are busy
+        // while carrying something heavy.
+        if swinging || carrying_heavy {
+            continue;
+        }
+
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::Melee))
+            == false
+        {
+            continue;
+        }
+
+        combo.step = match combo.window > 0.0 {
+            true => combo.step % COMBO_LENGTH + 1,
+            false => 1,
+        };
+        combo.window = melee.swing_duration + melee.combo_window;
+
+        commands.entity(entity).insert(Swinging {
+            step: combo.step,
+            timer: Timer::from_seconds(
+                melee.swing_duration,
+                TimerMode::Once,
+            ),
+        });
+
+        let multiplier = match combo.step == COMBO_LENGTH {
+            true => melee.finisher_multiplier,
+            false => 1.0,
+        };
+
+        let origin = transform.translation();
+        let forward = transform.forward();
+        // Sweep a sphere through the reach of the swing.
+        let radius = melee.range * 0.5;
+        let hits = spatial_query.shape_hits(
+            &Collider::sphere(radius),
+            origin,
+            Quat::IDENTITY,
+            forward,
+            MAX_HITS,
+            &ShapeCastConfig {
+                max_distance: melee.range - radius,
+                ..ShapeCastConfig::DEFAULT
+            },
+            &SpatialQueryFilter::default()
+                .with_mask(GameLayer::Enemy),
+        );
+
+        let mut hit_enemies = Vec::new();
+        for hit in hits {
+            let enemy = q_collider_ofs
+                .get(hit.entity)
+                .map(|c| c.body)
+                .unwrap_or(hit.entity);
+
+            if hit_enemies.contains(&enemy) {
+                continue;
+            }
+
+            let Ok(enemy_transform) = q_enemies.get(enemy) else {
+                continue;
+            };
+
+            // Only the arc in front of the player.
+            let direction = (enemy_transform.translation() - origin)
+                .with_y(0.0)
+                .normalize_or_zero();
+            if direction.angle_between(*forward) > melee.arc {
+                continue;
+            }
+
+            hit_enemies.push(enemy);
+            damage_events.write(DamageEvent {
+                source: entity,
+                target: enemy,
+                amount: melee.damage * multiplier,
+                kind: DamageKind::Melee,
+                knockback: direction * melee.knockback * multiplier,
+            });
+        }
+    }
+}
+
+/// Melee configuration of a [`CharacterController`].
+#[derive(Component, Reflect)]
+#[require(MeleeCombo)]
+#[reflect(Component, Default)]
+pub struct Melee {
+    /// Damage of a single swing.
+    pub damage: f32,
+    /// Reach of the swing from the player.
+    pub range: f32,
+    /// Half angle of the swing's arc in radians.
+    pub arc: f32,
+    /// Horizontal speed enemies are knocked back with.
+    pub knockback: f32,
+    /// Damage and knockback multiplier of the last swing in a
+    /// combo.
+    pub finisher_multiplier: f32,
+    /// Duration of a swing in seconds.
+    pub swing_duration: f32,
+    /// Seconds after a swing ends to continue the combo.
+    pub combo_window: f32,
+}
+
+impl Default for Melee {
+    fn default() -> Self {
+        Self {
+            damage: 15.0,
+            range: 2.0,
+            arc: 1.0,
+            knockback: 6.0,
+            finisher_multiplier: 2.0,
+            swing_duration: 0.3,
+            combo_window: 0.4,
+        }
+    }
+}
+
+/// Progress through the melee combo.
+#[derive(Component, Default, Debug)]
+pub struct MeleeCombo {
+    /// The last swing in the combo, starting from 1.
+    pub step: usize,
+    /// Seconds left to continue the combo.
+    window: f32,
+}
+
+/// Attached to the character for the duration of a swing, which
+/// plays the "Melee{step}" animation.
+#[derive(Component, Debug)]
+pub struct Swinging {
+    /// The swing in the combo, starting from 1.
+    pub step: usize,
+    timer: Timer,
+}
@@ -0,0 +1,166 @@
+use avian3d::prelude::*;
+use bevy::color::palettes::tailwind::ROSE_500;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::camera_controller::split_screen::{
+    CameraType, QueryCameras,
+};
+use crate::character_controller::CharacterController;
+use crate::enemy::Enemy;
+use crate::physics::GameLayer;
+use crate::player::PlayerType;
+use crate::tower::tower_attack::{Target, TargetPriority, Tower};
+
+/// Duration in seconds an enemy stays painted.
+const PAINT_DURATION: f32 = 5.0;
+/// Maximum distance from the camera an enemy can be painted.
+const PAINT_DISTANCE: f32 = 50.0;
+/// [`TargetPriority`] given to painted enemies.
+const PAINT_PRIORITY: i32 = 100;
+
+/// Lets players paint an enemy so that towers focus on it.
+pub(super) struct PlayerPaintPlugin;
+
+impl Plugin for PlayerPaintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (paint_enemy, tick_painted, draw_painted_indicator)
+                .chain(),
+        )
+        .add_observer(on_painted)
+        .add_observer(on_paint_removed);
+    }
+}
+
+/// Paint the enemy the player is aiming at.
+fn paint_enemy(
+    mut commands: Commands,
+    q_players: Query<
+        (&PlayerType, &TargetAction),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_collider_ofs: Query<&ColliderOf>,
+    q_enemies: Query<(), With<Enemy>>,
+    spatial_query: SpatialQuery,
+) {
+    for (player_type, target_action) in q_players.iter() {
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::Paint))
+            == false
+        {
+            continue;
+        }
+
+        let camera_type = match player_type {
+            PlayerType::A => CameraType::A,
+            PlayerType::B => CameraType::B,
+        };
+        let Ok(camera_transform) = q_cameras.get(camera_type) else {
+            continue;
+        };
+
+        // Same aim assist as the player's attack.
+        let Some(hit) = spatial_query.cast_shape(
+            &Collider::sphere(1.7),
+            camera_transform.translation(),
+            Quat::IDENTITY,
+            camera_transform.forward(),
+            &ShapeCastConfig {
+                max_distance: PAINT_DISTANCE,
+                ..ShapeCastConfig::DEFAULT
+            },
+            &SpatialQueryFilter::default()
+                .with_mask(GameLayer::Enemy),
+        ) else {
+            continue;
+        };
+
+        let enemy = q_collider_ofs
+            .get(hit.entity)
+            .map(|c| c.body)
+            .unwrap_or(hit.entity);
+
+        if q_enemies.contains(enemy) {
+            commands.entity(enemy).insert(Painted(
+                Timer::from_seconds(PAINT_DURATION, TimerMode::Once),
+            ));
+        }
+    }
+}
+
+/// Prioritize the painted enemy and force nearby towers to
+/// reconsider their targets.
+fn on_painted(
+    trigger: Trigger<OnInsert, Painted>,
+    mut commands: Commands,
+    q_towers: Query<Entity, (With<Tower>, With<Target>)>,
+) {
+    commands
+        .entity(trigger.target())
+        .insert(TargetPriority(PAINT_PRIORITY));
+
+    for entity in q_towers.iter() {
+        commands.entity(entity).remove::<Target>();
+    }
+}
+
+fn on_paint_removed(
+    trigger: Trigger<OnRemove, Painted>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(trigger.target())
+        .try_remove::<TargetPriority>();
+}
+
+/// Remove [`Painted`] once it runs out.
+fn tick_painted(
+    mut commands: Commands,
+    mut q_painted: Query<(&mut Painted, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut painted, entity) in q_painted.iter_mut() {
+        if painted.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Painted>();
+        }
+    }
+}
+
+/// Mark painted enemies, this is visible from both viewports.
+fn draw_painted_indicator(
+    q_painted: Query<(&Painted, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    for (painted, global_transform) in q_painted.iter() {
+        let translation = global_transform.translation();
+        // Fade out as the paint wears off.
+        let alpha = 1.0 - painted.fraction() * 0.7;
+        let pulse = (time.elapsed_secs() * 6.0).sin() * 0.1;
+
+        gizmos.circle(
+            Isometry3d::new(
+                translation + Vec3::Y * 0.05,
+                Quat::from_rotation_x(core::f32::consts::FRAC_PI_2),
+            ),
+            0.8 + pulse,
+            ROSE_500.with_alpha(alpha),
+        );
+        gizmos.arrow(
+            translation + Vec3::Y * 3.0,
+            translation + Vec3::Y * 2.0,
+            ROSE_500.with_alpha(alpha),
+        );
+    }
+}
+
+/// Attached to enemies painted by a player, towers within range
+/// prioritize them until the timer runs out.
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct Painted(Timer);
@@ -0,0 +1,234 @@
+//! Pings for coordinating without voice chat, placed where the
+//! player is looking and shown on both viewports.
+//!
+//! The kind of ping depends on what is pinged: enemies are a
+//! danger, interactables need ingredients and everything else is
+//! a spot to attack.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::audio::AudioCue;
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::enemy::Enemy;
+use crate::interaction::Interactable;
+use crate::physics::GameLayer;
+use crate::tower::ownership::owner_color;
+use crate::ui::Screen;
+use crate::ui::world_space::WorldUi;
+
+use super::PlayerType;
+
+/// Seconds a ping stays in the world.
+const PING_DURATION: f32 = 6.0;
+/// Maximum distance from the camera a ping can be placed.
+const PING_DISTANCE: f32 = 60.0;
+
+pub(super) struct PlayerPingPlugin;
+
+impl Plugin for PlayerPingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (place_ping, tick_pings, draw_pings)
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
+        )
+        .add_observer(spawn_ping_ui);
+    }
+}
+
+/// Ping what the player is looking at, replacing their previous
+/// ping.
+fn place_ping(
+    mut commands: Commands,
+    q_players: Query<
+        (&PlayerType, &TargetAction),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    q_cameras: QueryCameras<&GlobalTransform>,
+    q_collider_ofs: Query<&ColliderOf>,
+    q_enemies: Query<(), With<Enemy>>,
+    q_interactables: Query<(), With<Interactable>>,
+    q_pings: Query<(&Ping, Entity)>,
+    spatial_query: SpatialQuery,
+) {
+    let mut mask = LayerMask::ALL;
+    mask.remove([GameLayer::Player, GameLayer::Projectile]);
+    let filter = SpatialQueryFilter::default().with_mask(mask);
+
+    for (player_type, target_action) in q_players.iter() {
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::Ping))
+            == false
+        {
+            continue;
+        }
+
+        let Ok(camera_transform) =
+            q_cameras.get(player_type.camera_type())
+        else {
+            continue;
+        };
+
+        let Some(hit) = spatial_query.cast_ray(
+            camera_transform.translation(),
+            camera_transform.forward(),
+            PING_DISTANCE,
+            true,
+            &filter,
+        ) else {
+            continue;
+        };
+
+        let entity = q_collider_ofs
+            .get(hit.entity)
+            .map(|c| c.body)
+            .unwrap_or(hit.entity);
+
+        let kind = match (
+            q_enemies.contains(entity),
+            q_interactables.contains(entity),
+        ) {
+            (true, _) => PingKind::Danger,
+            (false, true) => PingKind::NeedIngredient,
+            (false, false) => PingKind::AttackHere,
+        };
+
+        for (ping, ping_entity) in q_pings.iter() {
+            if ping.player_type == *player_type {
+                commands.entity(ping_entity).despawn();
+            }
+        }
+
+        let translation = camera_transform.translation()
+            + camera_transform.forward() * hit.distance;
+
+        commands.spawn((
+            Ping {
+                kind,
+                player_type: *player_type,
+                timer: Timer::from_seconds(
+                    PING_DURATION,
+                    TimerMode::Once,
+                ),
+            },
+            Transform::from_translation(translation),
+            StateScoped(Screen::EnterLevel),
+        ));
+        commands.trigger(AudioCue::Ping(kind));
+    }
+}
+
+/// Label the ping on both viewports, the partner is pointed
+/// towards it when it is off-screen.
+fn spawn_ping_ui(
+    trigger: Trigger<OnAdd, Ping>,
+    mut commands: Commands,
+    q_pings: Query<&Ping>,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    let entity = trigger.target();
+    let ping = q_pings.get(entity)?;
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        let world_ui =
+            WorldUi::new(entity).with_world_offset(Vec3::Y);
+
+        commands.spawn((
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            match player_type == ping.player_type {
+                true => world_ui,
+                false => world_ui.with_edge_clamp(),
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(
+                owner_color(ping.player_type).with_alpha(0.7),
+            ),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+            Children::spawn(Spawn((
+                Text::new(ping.kind.label()),
+                TextFont::from_font_size(16.0),
+                TextShadow::default(),
+            ))),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Remove the pings once they run out.
+fn tick_pings(
+    mut commands: Commands,
+    mut q_pings: Query<(&mut Ping, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut ping, entity) in q_pings.iter_mut() {
+        if ping.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Ring the pinged spot in the color of the player.
+fn draw_pings(
+    q_pings: Query<(&Ping, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    for (ping, global_transform) in q_pings.iter() {
+        let translation = global_transform.translation();
+        // Fade out as the ping wears off.
+        let alpha = 1.0 - ping.timer.fraction() * 0.7;
+        let pulse = (time.elapsed_secs() * 4.0).sin() * 0.15;
+        let color = owner_color(ping.player_type).with_alpha(alpha);
+
+        gizmos.circle(
+            Isometry3d::new(
+                translation + Vec3::Y * 0.05,
+                Quat::from_rotation_x(core::f32::consts::FRAC_PI_2),
+            ),
+            1.0 + pulse,
+            color,
+        );
+        gizmos.line(translation, translation + Vec3::Y * 0.8, color);
+    }
+}
+
+/// What the player wants the partner to know about the spot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PingKind {
+    AttackHere,
+    NeedIngredient,
+    Danger,
+}
+
+impl PingKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PingKind::AttackHere => "Attack here",
+            PingKind::NeedIngredient => "Need ingredient",
+            PingKind::Danger => "Danger!",
+        }
+    }
+}
+
+/// A temporary marker placed by a player.
+#[derive(Component, Debug)]
+pub struct Ping {
+    pub kind: PingKind,
+    pub player_type: PlayerType,
+    timer: Timer,
+}
@@ -0,0 +1,313 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContextPass, EguiContexts};
+use bevy_inspector_egui::egui;
+
+use crate::enemy::Enemy;
+use crate::enemy::spawner::SpawnWave;
+use crate::level::RestartLevel;
+use crate::player::player_mark::PlayerMark;
+
+/// Replay a single wave over and over and compare the attempts.
+///
+/// Retrying restarts the level and skips straight to the practiced
+/// wave. Spawning doesn't use any randomness, so every attempt gets
+/// the same enemies at the same times.
+///
+/// *Note: There are no checkpoints yet, the towers and inventories
+/// built before the wave are not restored.*
+pub(super) struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Practice>()
+            .add_systems(
+                Update,
+                (
+                    skip_to_practice_wave
+                        .run_if(state_changed::<SpawnWave>),
+                    track_attempt,
+                ),
+            )
+            .add_systems(EguiContextPass, practice_panel)
+            .add_observer(count_removed_enemies);
+    }
+}
+
+fn skip_to_practice_wave(
+    practice: Res<Practice>,
+    spawn_wave: Res<State<SpawnWave>>,
+    mut next_wave: ResMut<NextState<SpawnWave>>,
+) {
+    let Some(wave) = practice.wave else {
+        return;
+    };
+
+    // Waves always start from the first one.
+    if *spawn_wave.get() == SpawnWave::One && wave != SpawnWave::One {
+        info!("Skipping to practice {wave:?}.");
+        next_wave.set(wave);
+    }
+}
+
+/// Start an attempt upon entering the practiced wave and finish it
+/// once the wave is over.
+fn track_attempt(
+    mut practice: ResMut<Practice>,
+    spawn_wave: Option<Res<State<SpawnWave>>>,
+    player_mark: Option<Res<PlayerMark>>,
+    time: Res<Time>,
+) {
+    let wave = spawn_wave.as_ref().map(|w| *w.get());
+    let player_mark = player_mark.map(|m| m.0).unwrap_or_default();
+
+    let Some(attempt) = practice.current.as_mut() else {
+        if let Some(wave) = practice.wave.filter(|w| Some(*w) == wave)
+        {
+            practice.current =
+                Some(WaveAttempt::new(wave, player_mark));
+        }
+        return;
+    };
+
+    if wave == Some(attempt.wave) {
+        attempt.duration += time.delta_secs();
+        attempt.marks_lost =
+            attempt.start_mark.saturating_sub(player_mark);
+        return;
+    }
+
+    // The wave only moves on while the level is still going.
+    attempt.cleared = wave.is_some();
+
+    if let Some(attempt) = practice.current.take() {
+        info!("Practice attempt finished: {attempt:?}");
+        practice.attempts.push(attempt);
+    }
+}
+
+/// Enemies are removed when defeated or when they reach
+/// the final target.
+fn count_removed_enemies(
+    _: Trigger<OnRemove, Enemy>,
+    mut practice: ResMut<Practice>,
+    spawn_wave: Option<Res<State<SpawnWave>>>,
+) {
+    let Some(attempt) = practice.current.as_mut() else {
+        return;
+    };
+
+    // Ignore the level being torn down.
+    if spawn_wave.is_some_and(|w| *w.get() == attempt.wave) {
+        attempt.removed_enemies += 1;
+    }
+}
+
+fn practice_panel(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut practice: ResMut<Practice>,
+    spawn_wave: Option<Res<State<SpawnWave>>>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let practice = practice.bypass_change_detection();
+
+    egui::Window::new("Practice").default_open(false).show(
+        ctx,
+        |ui| {
+            ui.horizontal(|ui| {
+                for wave in
+                    [SpawnWave::One, SpawnWave::Two, SpawnWave::Three]
+                {
+                    // Restarting only works from within the level.
+                    if ui
+                        .add_enabled(
+                            spawn_wave.is_some(),
+                            egui::Button::new(format!(
+                                "Retry {wave:?}"
+                            )),
+                        )
+                        .clicked()
+                    {
+                        practice.wave = Some(wave);
+                        practice.current = None;
+                        commands.trigger(RestartLevel);
+                    }
+                }
+            });
+
+            if let Some(wave) = practice.wave {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Practicing {wave:?}"));
+                    if ui.button("Stop").clicked() {
+                        practice.wave = None;
+                        practice.current = None;
+                    }
+                });
+            }
+
+            if ui.button("Clear attempts").clicked() {
+                practice.attempts.clear();
+            }
+
+            ui.separator();
+
+            if practice.attempts.is_empty() {
+                ui.label("No attempts finished yet.");
+                return;
+            }
+
+            egui::Grid::new("practice_attempts").striped(true).show(
+                ui,
+                |ui| {
+                    for header in [
+                        "#",
+                        "Wave",
+                        "Time (s)",
+                        "Defeated",
+                        "Marks lost",
+                        "Cleared",
+                    ] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for (i, attempt) in
+                        practice.attempts.iter().enumerate()
+                    {
+                        ui.label(format!("{}", i + 1));
+                        ui.label(format!("{:?}", attempt.wave));
+                        ui.label(format!("{:.1}", attempt.duration));
+                        ui.label(format!("{}", attempt.defeated()));
+                        ui.label(format!("{}", attempt.marks_lost));
+                        ui.label(match attempt.cleared {
+                            true => "Yes",
+                            false => "No",
+                        });
+                        ui.end_row();
+                    }
+                },
+            );
+
+            ui.separator();
+
+            egui::Grid::new("practice_summary").striped(true).show(
+                ui,
+                |ui| {
+                    for header in [
+                        "Wave",
+                        "Attempts",
+                        "Cleared",
+                        "Best time (s)",
+                        "Avg marks lost",
+                    ] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for wave in [
+                        SpawnWave::One,
+                        SpawnWave::Two,
+                        SpawnWave::Three,
+                    ] {
+                        let Some(summary) = practice.summary(wave)
+                        else {
+                            continue;
+                        };
+
+                        ui.label(format!("{wave:?}"));
+                        ui.label(format!("{}", summary.attempts));
+                        ui.label(format!("{}", summary.cleared));
+                        ui.label(match summary.best_time {
+                            Some(time) => format!("{time:.1}"),
+                            None => "-".to_string(),
+                        });
+                        ui.label(format!(
+                            "{:.1}",
+                            summary.average_marks_lost
+                        ));
+                        ui.end_row();
+                    }
+                },
+            );
+        },
+    );
+}
+
+#[derive(Resource, Default)]
+pub struct Practice {
+    /// The wave being practiced.
+    wave: Option<SpawnWave>,
+    /// The attempt in progress.
+    current: Option<WaveAttempt>,
+    attempts: Vec<WaveAttempt>,
+}
+
+impl Practice {
+    /// Aggregate of the finished attempts of a wave.
+    fn summary(&self, wave: SpawnWave) -> Option<AttemptSummary> {
+        let attempts = self
+            .attempts
+            .iter()
+            .filter(|a| a.wave == wave)
+            .collect::<Vec<_>>();
+
+        if attempts.is_empty() {
+            return None;
+        }
+
+        Some(AttemptSummary {
+            attempts: attempts.len(),
+            cleared: attempts.iter().filter(|a| a.cleared).count(),
+            best_time: attempts
+                .iter()
+                .filter(|a| a.cleared)
+                .map(|a| a.duration)
+                .min_by(f32::total_cmp),
+            average_marks_lost: attempts
+                .iter()
+                .map(|a| a.marks_lost as f32)
+                .sum::<f32>()
+                / attempts.len() as f32,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WaveAttempt {
+    wave: SpawnWave,
+    /// Seconds spent in the wave, including the countdown.
+    duration: f32,
+    start_mark: u32,
+    marks_lost: u32,
+    removed_enemies: u32,
+    /// Moved on to the next wave instead of leaving the level.
+    cleared: bool,
+}
+
+impl WaveAttempt {
+    fn new(wave: SpawnWave, start_mark: u32) -> Self {
+        Self {
+            wave,
+            duration: 0.0,
+            start_mark,
+            marks_lost: 0,
+            removed_enemies: 0,
+            cleared: false,
+        }
+    }
+
+    /// Every enemy that didn't take a mark was defeated.
+    fn defeated(&self) -> u32 {
+        self.removed_enemies.saturating_sub(self.marks_lost)
+    }
+}
+
+struct AttemptSummary {
+    attempts: usize,
+    cleared: usize,
+    best_time: Option<f32>,
+    average_marks_lost: f32,
+}
@@ -0,0 +1,130 @@
+//! Seeded randomness shared by every gameplay system.
+//!
+//! Every random decision must go through [`GameRng`] so that a level
+//! can be reproduced from its seed alone.
+
+use core::hash::BuildHasher;
+use core::ops::Range;
+use std::collections::hash_map::RandomState;
+
+use bevy::prelude::*;
+
+use crate::ui::Screen;
+
+pub(super) struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>()
+            .add_systems(OnEnter(Screen::EnterLevel), seed_game_rng);
+
+        app.register_type::<GameRng>();
+    }
+}
+
+/// Reseed upon entering (or restarting) a level.
+fn seed_game_rng(mut rng: ResMut<GameRng>, time: Res<Time<Real>>) {
+    let seed = rng.pinned.unwrap_or_else(|| {
+        // Only the seed needs to be unpredictable.
+        RandomState::new().hash_one(time.elapsed().as_nanos())
+    });
+
+    rng.reseed(seed);
+    info!("Seeded game rng with {seed}.");
+}
+
+/// A small SplitMix64 generator, seeded per level.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct GameRng {
+    /// Use this seed for every level instead of a new one,
+    /// for reproducing bug reports.
+    pub pinned: Option<u64>,
+    seed: u64,
+    state: u64,
+}
+
+impl GameRng {
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.state = seed;
+    }
+
+    /// The seed of the current level.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn f32(&mut self) -> f32 {
+        // The top 24 bits fit exactly in the mantissa.
+        (self.next_u64() >> 40) as f32 / (1 << 24) as f32
+    }
+
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.f32()
+    }
+
+    /// Uniform in `range`, returns `range.start` if it is empty.
+    pub fn range_u32(&mut self, range: Range<u32>) -> u32 {
+        let len = range.end.saturating_sub(range.start);
+        if len == 0 {
+            return range.start;
+        }
+
+        range.start + (self.next_u64() % len as u64) as u32
+    }
+
+    /// Returns true with the given probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.f32() < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_rolls() {
+        let mut a = GameRng::default();
+        let mut b = GameRng::default();
+        a.reseed(42);
+        b.reseed(42);
+
+        for _ in 0..64 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+
+        // Reseeding restarts the sequence.
+        a.reseed(42);
+        b.reseed(42);
+        assert_eq!(a.range_u32(3..9), b.range_u32(3..9));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = GameRng::default();
+        rng.reseed(7);
+
+        for _ in 0..1000 {
+            let f = rng.f32();
+            assert!((0.0..1.0).contains(&f));
+            assert!((2.0..5.0).contains(&rng.range_f32(2.0..5.0)));
+            assert!((3..9).contains(&rng.range_u32(3..9)));
+        }
+
+        assert_eq!(rng.range_u32(4..4), 4);
+        assert!(rng.chance(0.0) == false);
+        assert!(rng.chance(1.0));
+    }
+}
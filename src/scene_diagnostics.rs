@@ -0,0 +1,230 @@
+//! Validate the components spawned from the gltf extras (through
+//! `bevy_skein`) once a scene is ready.
+//!
+//! Misconfigured entities are reported in the "Scene Diagnostics"
+//! panel instead of silently failing at runtime.
+
+use avian3d::prelude::*;
+use bevy::ecs::name::NameOrEntityItem;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use bevy_inspector_egui::bevy_egui::{EguiContextPass, EguiContexts};
+use bevy_inspector_egui::egui;
+
+use crate::asset_pipeline::CurrentScene;
+use crate::machine::Machine;
+use crate::machine::recipe::{RecipeMetaAssetHandle, RecipeRegistry};
+use crate::tile::{Tile, TileMap};
+use crate::tower::tower_attack::Tower;
+
+/// Seconds to wait after the scene is ready before validating,
+/// colliders are only constructed after a few frames.
+const SCAN_DELAY: f32 = 0.5;
+/// Maximum distance from the tile grid before a tile is reported.
+const GRID_TOLERANCE: f32 = 0.01;
+
+pub(super) struct SceneDiagnosticsPlugin;
+
+impl Plugin for SceneDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneDiagnostics>()
+            .add_systems(
+                Update,
+                scan_scene
+                    .run_if(resource_exists::<RecipeMetaAssetHandle>),
+            )
+            .add_systems(EguiContextPass, diagnostics_panel)
+            .add_observer(schedule_scan);
+    }
+}
+
+/// Schedule a scan when the current scene finishes spawning.
+fn schedule_scan(
+    trigger: Trigger<SceneInstanceReady>,
+    current_scene: Res<CurrentScene>,
+    mut diagnostics: ResMut<SceneDiagnostics>,
+) {
+    if current_scene.get() != Some(trigger.target()) {
+        return;
+    }
+
+    diagnostics.schedule();
+}
+
+fn scan_scene(
+    mut diagnostics: ResMut<SceneDiagnostics>,
+    q_machines: Query<(&Machine, NameOrEntity)>,
+    q_towers: Query<NameOrEntity, With<Tower>>,
+    q_tiles: Query<(&GlobalTransform, NameOrEntity), With<Tile>>,
+    q_colliders: Query<(), With<Collider>>,
+    q_children: Query<&Children>,
+    recipe_registry: RecipeRegistry,
+    tile_map: Res<TileMap>,
+    time: Res<Time>,
+) {
+    let Some(timer) = diagnostics.scan_timer.as_mut() else {
+        return;
+    };
+
+    if timer.tick(time.delta()).finished() == false {
+        return;
+    }
+
+    // Colliders can be constructed on any child of the entity.
+    let has_collider = |entity: Entity| {
+        q_colliders.contains(entity)
+            || q_children
+                .iter_descendants(entity)
+                .any(|e| q_colliders.contains(e))
+    };
+
+    let mut issues = Vec::new();
+
+    for (machine, name) in q_machines.iter() {
+        if recipe_registry.get_recipe(&machine.recipe_id).is_none() {
+            issues.push(SceneIssue::new(
+                &name,
+                format!(
+                    "Machine recipe '{}' is not in the registry",
+                    machine.recipe_id
+                ),
+            ));
+        }
+
+        if has_collider(name.entity) == false {
+            issues.push(SceneIssue::new(
+                &name,
+                "Machine has no collider",
+            ));
+        }
+    }
+
+    for name in q_towers.iter() {
+        if has_collider(name.entity) == false {
+            issues.push(SceneIssue::new(
+                &name,
+                "Tower has no collider",
+            ));
+        }
+    }
+
+    for (transform, name) in q_tiles.iter() {
+        let translation = transform.translation();
+
+        let Some(coordinate) =
+            tile_map.translation_to_tile_coord(&translation)
+        else {
+            issues.push(SceneIssue::new(
+                &name,
+                format!(
+                    "Tile at {translation} is outside of the map"
+                ),
+            ));
+            continue;
+        };
+
+        let snapped = tile_map
+            .tile_coord_to_world_space(&coordinate.as_ivec3())
+            .xz();
+
+        if snapped.distance(translation.xz()) > GRID_TOLERANCE {
+            issues.push(SceneIssue::new(
+                &name,
+                format!(
+                    "Tile at {translation} is not on the tile grid \
+                     (closest: {snapped})"
+                ),
+            ));
+        }
+    }
+
+    for issue in issues.iter() {
+        warn!("{} ({}): {}", issue.name, issue.entity, issue.message);
+    }
+    info!("Scene diagnostics found {} issue(s).", issues.len());
+
+    diagnostics.issues = issues;
+    diagnostics.scan_timer = None;
+}
+
+fn diagnostics_panel(
+    mut contexts: EguiContexts,
+    mut diagnostics: ResMut<SceneDiagnostics>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new("Scene Diagnostics")
+        .default_open(false)
+        .show(ctx, |ui| {
+            if ui.button("Rescan").clicked() {
+                diagnostics.schedule();
+            }
+
+            ui.separator();
+
+            if diagnostics.scan_timer.is_some() {
+                ui.label("Scanning...");
+                return;
+            }
+
+            if diagnostics.issues.is_empty() {
+                ui.label("No issues found.");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("scene_diagnostics")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for issue in diagnostics.issues.iter() {
+                            ui.label(format!(
+                                "{} ({})",
+                                issue.name, issue.entity
+                            ));
+                            ui.colored_label(
+                                egui::Color32::LIGHT_RED,
+                                &issue.message,
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+}
+
+/// Issues found by the last scan of the current scene.
+#[derive(Resource, Default, Debug)]
+pub struct SceneDiagnostics {
+    issues: Vec<SceneIssue>,
+    /// Delay before the next scan, [`None`] if no scan is scheduled.
+    scan_timer: Option<Timer>,
+}
+
+impl SceneDiagnostics {
+    fn schedule(&mut self) {
+        self.scan_timer =
+            Some(Timer::from_seconds(SCAN_DELAY, TimerMode::Once));
+    }
+}
+
+#[derive(Debug)]
+struct SceneIssue {
+    entity: Entity,
+    name: String,
+    message: String,
+}
+
+impl SceneIssue {
+    fn new(
+        name: &NameOrEntityItem,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            entity: name.entity,
+            name: name.to_string(),
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Counts of the current run, recorded from the [`GameplayEvent`]s
+//! for the results screen and the scoreboard.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::gameplay_event::{GameplayEvent, GameplayEventAppExt};
+use crate::level::GameMode;
+use crate::player::PlayerType;
+use crate::tower::ownership::PlacedByPlayer;
+use crate::ui::Screen;
+
+pub(super) struct SessionStatsPlugin;
+
+impl Plugin for SessionStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SessionStats>()
+            .add_systems(
+                OnEnter(Screen::EnterLevel),
+                reset_session_stats,
+            )
+            .add_gameplay_subscriber(record_session_stats);
+    }
+}
+
+fn reset_session_stats(
+    mut stats: ResMut<SessionStats>,
+    game_mode: Res<GameMode>,
+) {
+    *stats = SessionStats {
+        endless: *game_mode == GameMode::Endless,
+        ..default()
+    };
+}
+
+fn record_session_stats(
+    mut events: EventReader<GameplayEvent>,
+    mut stats: ResMut<SessionStats>,
+    q_player_types: Query<&PlayerType>,
+    q_owners: Query<&PlacedByPlayer>,
+    // The player that started each machine.
+    mut cooks: Local<HashMap<Entity, PlayerType>>,
+) {
+    // Towers count towards the player that placed them.
+    let player_of = |source: Entity| {
+        q_player_types
+            .get(source)
+            .ok()
+            .copied()
+            .or_else(|| q_owners.get(source).ok().map(|o| **o))
+    };
+
+    for event in events.read() {
+        match event {
+            GameplayEvent::WaveStarted { number } => {
+                stats.furthest_wave =
+                    stats.furthest_wave.max(*number);
+            }
+            GameplayEvent::EnemyKilled { source, .. } => {
+                stats.enemies_killed += 1;
+                if let Some(player_type) = player_of(*source) {
+                    stats.player_mut(player_type).enemies_killed += 1;
+                }
+            }
+            GameplayEvent::CookingStarted {
+                machine, player, ..
+            } => match player {
+                Some(player_type) => {
+                    cooks.insert(*machine, *player_type);
+                }
+                None => {
+                    cooks.remove(machine);
+                }
+            },
+            GameplayEvent::RecipeCooked { machine, .. } => {
+                stats.recipes_cooked += 1;
+                if let Some(player_type) = cooks.remove(machine) {
+                    stats.player_mut(player_type).recipes_cooked += 1;
+                }
+            }
+            GameplayEvent::ItemCollected {
+                quantity, player, ..
+            } => {
+                stats.items_collected += quantity;
+                if let Some(player_type) = player {
+                    stats.player_mut(*player_type).items_collected +=
+                        quantity;
+                }
+            }
+            GameplayEvent::TowerPlaced { player, .. } => {
+                stats.towers_placed += 1;
+                stats.player_mut(*player).towers_placed += 1;
+            }
+            GameplayEvent::DamageDealt { source, amount, .. } => {
+                if let Some(player_type) = player_of(*source) {
+                    stats.player_mut(player_type).damage_dealt +=
+                        amount;
+                }
+            }
+            GameplayEvent::ProjectileFired { .. }
+            | GameplayEvent::TowerDestroyed { .. }
+            | GameplayEvent::LevelCleared => {}
+        }
+    }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SessionStats {
+    /// The run is in [`GameMode::Endless`], its furthest wave is
+    /// the score.
+    pub endless: bool,
+    pub furthest_wave: u32,
+    pub enemies_killed: u32,
+    pub recipes_cooked: u32,
+    pub items_collected: u32,
+    pub towers_placed: u32,
+    pub a: PlayerSessionStats,
+    pub b: PlayerSessionStats,
+}
+
+impl SessionStats {
+    pub fn player(
+        &self,
+        player_type: PlayerType,
+    ) -> &PlayerSessionStats {
+        match player_type {
+            PlayerType::A => &self.a,
+            PlayerType::B => &self.b,
+        }
+    }
+
+    pub fn player_mut(
+        &mut self,
+        player_type: PlayerType,
+    ) -> &mut PlayerSessionStats {
+        match player_type {
+            PlayerType::A => &mut self.a,
+            PlayerType::B => &mut self.b,
+        }
+    }
+}
+
+/// Counts of a single player, including the kills and damage of
+/// the towers they placed.
+#[derive(Default, Debug, Clone)]
+pub struct PlayerSessionStats {
+    pub enemies_killed: u32,
+    pub items_collected: u32,
+    pub recipes_cooked: u32,
+    pub towers_placed: u32,
+    pub damage_dealt: f32,
+}
@@ -0,0 +1,173 @@
+//! Boots the game, plays through each level with scripted inputs
+//! and exits with a status code, used for pre-release verification.
+//!
+//! Run with `cargo run -- --smoke-test`.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use bevy::ecs::error::{BevyError, ErrorContext, error};
+use bevy::prelude::*;
+
+use crate::action::PlayerAction;
+use crate::asset_pipeline::AssetState;
+use crate::level::{CurrentLevel, LEVELS, NextLevel, RestartLevel};
+use crate::player::{PlayerState, PlayerType};
+use crate::ui::Screen;
+
+/// Command line flag to enable the smoke test.
+pub const SMOKE_TEST_FLAG: &str = "--smoke-test";
+/// Number of frames to simulate in each level.
+const FRAMES_PER_LEVEL: u32 = 300;
+/// Number of frames to hold each scripted key for.
+const FRAMES_PER_KEY: u32 = 20;
+/// Maximum seconds to wait for the assets to load.
+const LOAD_TIMEOUT: f32 = 120.0;
+/// Keys pressed in sequence to drive both players around.
+const SCRIPTED_KEYS: &[KeyCode] = &[
+    KeyCode::KeyW,
+    KeyCode::KeyD,
+    KeyCode::Space,
+    KeyCode::KeyS,
+    KeyCode::KeyE,
+    KeyCode::KeyA,
+    KeyCode::KeyF,
+    KeyCode::Digit1,
+];
+
+/// Number of system errors that occurred during the smoke test.
+static ERROR_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Error handler that counts the errors before logging them.
+pub fn error_handler(err: BevyError, ctx: ErrorContext) {
+    ERROR_COUNT.fetch_add(1, Ordering::Relaxed);
+    error(err, ctx);
+}
+
+pub struct SmokeTestPlugin;
+
+impl Plugin for SmokeTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SmokeTest>()
+            .add_systems(
+                OnEnter(PlayerState::Possessing),
+                possess_players,
+            )
+            .add_systems(
+                Update,
+                (
+                    wait_for_assets
+                        .run_if(not(in_state(AssetState::Loaded))),
+                    start_level.run_if(in_state(Screen::Menu)),
+                    (release_scripted_keys, press_scripted_keys)
+                        .chain()
+                        .run_if(in_state(Screen::EnterLevel)),
+                    advance_levels,
+                ),
+            );
+    }
+}
+
+/// Let the keyboard possess both players.
+fn possess_players(
+    mut commands: Commands,
+    mut player_state: ResMut<NextState<PlayerState>>,
+) {
+    commands.spawn((PlayerAction::new_kbm(), PlayerType::A));
+    commands.spawn((PlayerAction::new_kbm(), PlayerType::B));
+
+    player_state.set(PlayerState::Possessed);
+}
+
+fn wait_for_assets(
+    time: Res<Time<Real>>,
+    state: Res<State<AssetState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if *state.get() == AssetState::Failed {
+        error!("Smoke test failed: assets failed to load.");
+        exit.write(AppExit::error());
+    } else if time.elapsed_secs() > LOAD_TIMEOUT {
+        error!("Smoke test failed: assets took too long to load.");
+        exit.write(AppExit::error());
+    }
+}
+
+fn start_level(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut current_level: ResMut<CurrentLevel>,
+) {
+    current_level.0 = 0;
+    info!("Smoke test: entering {}.", current_level.info().name);
+    next_screen.set(Screen::EnterLevel);
+}
+
+fn release_scripted_keys(
+    mut kbd_inputs: ResMut<ButtonInput<KeyCode>>,
+) {
+    kbd_inputs.release_all();
+}
+
+fn press_scripted_keys(
+    smoke_test: Res<SmokeTest>,
+    mut kbd_inputs: ResMut<ButtonInput<KeyCode>>,
+) {
+    let index = (smoke_test.frames / FRAMES_PER_KEY) as usize
+        % SCRIPTED_KEYS.len();
+    kbd_inputs.press(SCRIPTED_KEYS[index]);
+}
+
+/// Advance through every level in [`LEVELS`] after simulating
+/// it and exit after the last one has been played.
+fn advance_levels(
+    mut commands: Commands,
+    mut smoke_test: ResMut<SmokeTest>,
+    current_level: Res<CurrentLevel>,
+    screen: Option<Res<State<Screen>>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if screen.is_none_or(|s| *s.get() != Screen::EnterLevel) {
+        return;
+    }
+
+    smoke_test.frames += 1;
+    if smoke_test.frames < FRAMES_PER_LEVEL {
+        return;
+    }
+
+    smoke_test.frames = 0;
+
+    if let Some(next) = current_level.next() {
+        info!("Smoke test: advancing to {}.", LEVELS[next].name);
+        commands.trigger(NextLevel);
+        return;
+    }
+
+    // Play through the last level again after restarting it to
+    // make sure that tearing down works, even with a single level.
+    if smoke_test.restarted == false {
+        info!(
+            "Smoke test: restarting {}.",
+            current_level.info().name
+        );
+        smoke_test.restarted = true;
+        commands.trigger(RestartLevel);
+        return;
+    }
+
+    let error_count = ERROR_COUNT.load(Ordering::Relaxed);
+    if error_count == 0 {
+        info!("Smoke test passed!");
+        exit.write(AppExit::Success);
+    } else {
+        error!("Smoke test failed with {error_count} system errors.");
+        exit.write(AppExit::error());
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+struct SmokeTest {
+    /// Frames simulated in the current level.
+    frames: u32,
+    /// Whether the last level has been restarted.
+    restarted: bool,
+}
@@ -0,0 +1,222 @@
+use avian3d::prelude::*;
+use bevy::app::Animation;
+use bevy::prelude::*;
+
+use crate::character_controller::IsGrounded;
+use crate::tower::tower_attack::Health;
+
+/// Procedural squash and stretch deformation that is layered
+/// on top of the skeletal animation.
+pub(super) struct SquashStretchPlugin;
+
+impl Plugin for SquashStretchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_squash_stretch_bone,
+                (squash_on_grounded_changed, wobble_on_hit),
+                update_squash_stretch,
+            )
+                .chain(),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                restore_bone_scale.before(Animation),
+                apply_bone_scale
+                    .after(Animation)
+                    .before(TransformSystem::TransformPropagate),
+            ),
+        );
+
+        app.register_type::<SquashStretch>();
+    }
+}
+
+/// Find the bone that will be deformed by name.
+fn setup_squash_stretch_bone(
+    mut commands: Commands,
+    q_deformables: Query<
+        (&SquashStretch, Entity),
+        Without<SquashStretchBone>,
+    >,
+    q_children: Query<&Children>,
+    q_names: Query<&Name>,
+) {
+    for (squash_stretch, entity) in q_deformables.iter() {
+        // The bone might not be spawned yet.
+        let Some(bone) =
+            q_children.iter_descendants(entity).find(|e| {
+                q_names
+                    .get(*e)
+                    .is_ok_and(|n| n.as_str() == squash_stretch.bone)
+            })
+        else {
+            continue;
+        };
+
+        commands
+            .entity(entity)
+            .insert(SquashStretchBone { bone, applied: 1.0 });
+    }
+}
+
+/// Squash on landing and stretch on jumping.
+fn squash_on_grounded_changed(
+    mut q_deformables: Query<
+        (
+            &SquashStretch,
+            &mut SquashStretchState,
+            &IsGrounded,
+            &LinearVelocity,
+        ),
+        Changed<IsGrounded>,
+    >,
+) {
+    for (squash_stretch, mut state, is_grounded, linear_velocity) in
+        q_deformables.iter_mut()
+    {
+        if is_grounded.0 {
+            state.displacement -= squash_stretch.landing_squash;
+        } else if linear_velocity.y > 0.0 {
+            state.displacement += squash_stretch.jump_stretch;
+        }
+    }
+}
+
+/// Wobble whenever health is lost.
+fn wobble_on_hit(
+    mut q_deformables: Query<
+        (&SquashStretch, &mut SquashStretchState, Ref<Health>),
+        Changed<Health>,
+    >,
+) {
+    for (squash_stretch, mut state, health) in
+        q_deformables.iter_mut()
+    {
+        if health.is_added() {
+            continue;
+        }
+
+        state.displacement += squash_stretch.hit_wobble;
+    }
+}
+
+/// Spring the displacement back to rest.
+fn update_squash_stretch(
+    mut q_deformables: Query<(
+        &SquashStretch,
+        &mut SquashStretchState,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (squash_stretch, mut state) in q_deformables.iter_mut() {
+        let acceleration = -squash_stretch.stiffness
+            * state.displacement
+            - squash_stretch.damping * state.velocity;
+
+        state.velocity += acceleration * dt;
+        state.displacement += state.velocity * dt;
+        // Prevent the bone from collapsing or exploding.
+        state.displacement = state.displacement.clamp(-0.8, 2.0);
+    }
+}
+
+/// Undo the deformation from the last frame so that
+/// it does not accumulate on bones that are not animated.
+fn restore_bone_scale(
+    q_deformables: Query<&SquashStretchBone>,
+    mut q_transforms: Query<&mut Transform>,
+) {
+    for bone in q_deformables.iter() {
+        if let Ok(mut transform) = q_transforms.get_mut(bone.bone) {
+            transform.scale /=
+                SquashStretchState::scale(bone.applied);
+        }
+    }
+}
+
+/// Deform the bone after the animation has been applied.
+fn apply_bone_scale(
+    mut q_deformables: Query<(
+        &mut SquashStretchBone,
+        &SquashStretchState,
+    )>,
+    mut q_transforms: Query<&mut Transform>,
+) {
+    for (mut bone, state) in q_deformables.iter_mut() {
+        let Ok(mut transform) = q_transforms.get_mut(bone.bone)
+        else {
+            continue;
+        };
+
+        let stretch = 1.0 + state.displacement;
+        transform.scale *= SquashStretchState::scale(stretch);
+        bone.applied = stretch;
+    }
+}
+
+/// Per prefab configuration of the squash and stretch
+/// deformation.
+#[derive(Component, Reflect)]
+#[require(SquashStretchState)]
+#[reflect(Component, Default)]
+pub struct SquashStretch {
+    /// Name of the bone to deform, usually the root bone.
+    pub bone: String,
+    /// Amount to squash when landing.
+    pub landing_squash: f32,
+    /// Amount to stretch when jumping.
+    pub jump_stretch: f32,
+    /// Amount to wobble when getting hit.
+    pub hit_wobble: f32,
+    /// Spring stiffness pulling the deformation back to rest.
+    pub stiffness: f32,
+    /// Spring damping, lower values result in more wobble.
+    pub damping: f32,
+}
+
+impl Default for SquashStretch {
+    fn default() -> Self {
+        Self {
+            bone: "spine".to_string(),
+            landing_squash: 0.3,
+            jump_stretch: 0.2,
+            hit_wobble: 0.25,
+            stiffness: 250.0,
+            damping: 10.0,
+        }
+    }
+}
+
+/// Current state of the squash and stretch spring.
+#[derive(Component, Default, Debug)]
+pub struct SquashStretchState {
+    /// Positive values stretch, negative values squash.
+    pub displacement: f32,
+    pub velocity: f32,
+}
+
+impl SquashStretchState {
+    /// Volume preserving scale for a given vertical stretch.
+    fn scale(stretch: f32) -> Vec3 {
+        let side = stretch.recip().sqrt();
+        Vec3::new(side, stretch, side)
+    }
+}
+
+/// The bone being deformed and the stretch applied to it.
+#[derive(Component, Debug)]
+pub struct SquashStretchBone {
+    bone: Entity,
+    applied: f32,
+}
+
+impl SquashStretchBone {
+    pub fn bone(&self) -> Entity {
+        self.bone
+    }
+}
@@ -0,0 +1,99 @@
+//! Persistent storage for profiles, backed by files on native
+//! builds and by `localStorage` on web builds.
+//!
+//! ```ignore
+//! impl Profile for MyProfile {
+//!     const KEY: &'static str = "my.profile.ron";
+//! }
+//!
+//! let profile = MyProfile::load();
+//! profile.save();
+//! ```
+
+use bevy::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Load and deserialize the ron stored under the key.
+pub fn load<T: DeserializeOwned>(key: &str) -> Result<T> {
+    let ron_str = backend::read(key)?;
+    Ok(ron::from_str(&ron_str)?)
+}
+
+/// Serialize and store the value as ron under the key,
+/// replacing the previous value.
+pub fn save<T: Serialize>(key: &str, value: &T) -> Result {
+    let ron_str = ron::ser::to_string_pretty(value, default())?;
+    backend::write(key, &ron_str)
+}
+
+/// A resource persisted under its own key, falling back to the
+/// default when nothing was saved yet.
+pub trait Profile: Serialize + DeserializeOwned + Default {
+    /// Key the profile is stored under.
+    const KEY: &'static str;
+
+    /// Load the saved profile.
+    fn load() -> Self {
+        load(Self::KEY).unwrap_or_else(|e| {
+            info!("No profile loaded from {}: {e}", Self::KEY);
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        if let Err(e) = save(Self::KEY, self) {
+            warn!("Unable to save {}: {e}", Self::KEY);
+        }
+    }
+}
+
+/// Every key is a file in the working directory.
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use bevy::prelude::*;
+
+    pub fn read(key: &str) -> Result<String> {
+        Ok(std::fs::read_to_string(key)?)
+    }
+
+    pub fn write(key: &str, value: &str) -> Result {
+        Ok(std::fs::write(key, value)?)
+    }
+}
+
+/// Every key is a `localStorage` entry, which survives page
+/// refreshes.
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use bevy::prelude::*;
+
+    /// Games hosted on the same site (e.g. itch) share the
+    /// `localStorage`.
+    const KEY_PREFIX: &str = "recipe_game/";
+
+    fn local_storage() -> Result<web_sys::Storage> {
+        web_sys::window()
+            .ok_or("No browser window!")?
+            .local_storage()
+            .map_err(|e| {
+                format!("Unable to access localStorage: {e:?}")
+            })?
+            .ok_or("localStorage is not available!".into())
+    }
+
+    pub fn read(key: &str) -> Result<String> {
+        local_storage()?
+            .get_item(&format!("{KEY_PREFIX}{key}"))
+            .map_err(|e| format!("Unable to read {key}: {e:?}"))?
+            .ok_or(format!("{key} is not stored.").into())
+    }
+
+    pub fn write(key: &str, value: &str) -> Result {
+        local_storage()?
+            .set_item(&format!("{KEY_PREFIX}{key}"), value)
+            .map_err(|e| format!("Unable to write {key}: {e:?}"))?;
+
+        Ok(())
+    }
+}
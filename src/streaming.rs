@@ -0,0 +1,157 @@
+//! Stream level chunks in and out based on player positions.
+//!
+//! Large levels can be split into chunk gltfs which are referenced
+//! from the level scene by [`SceneChunk`] anchors (through
+//! `bevy_skein`). A chunk is only loaded while a player is within
+//! [`StreamingRadius::load`] and gets unloaded (freeing its assets)
+//! once every player is beyond [`StreamingRadius::unload`].
+//!
+//! *Note: [`Tile`](crate::tile::Tile)s and gameplay entities should
+//! stay in the level scene, chunks are meant for scenery.*
+
+use bevy::prelude::*;
+
+use crate::character_controller::CharacterController;
+
+pub(super) struct StreamingPlugin;
+
+impl Plugin for StreamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamingRadius>()
+            .add_systems(Update, stream_chunks);
+
+        app.register_type::<SceneChunk>()
+            .register_type::<StreamingRadius>();
+    }
+}
+
+fn stream_chunks(
+    mut commands: Commands,
+    q_chunks: Query<(
+        &SceneChunk,
+        &GlobalTransform,
+        Option<&LoadedChunk>,
+        Entity,
+    )>,
+    q_players: Query<&GlobalTransform, With<CharacterController>>,
+    radius: Res<StreamingRadius>,
+    asset_server: Res<AssetServer>,
+) {
+    // Keep chunks as they are until the players are spawned.
+    if q_players.is_empty() {
+        return;
+    }
+
+    for (chunk, chunk_transform, loaded_chunk, entity) in
+        q_chunks.iter()
+    {
+        let chunk_translation = chunk_transform.translation().xz();
+        let distance = q_players
+            .iter()
+            .map(|t| t.translation().xz().distance(chunk_translation))
+            .fold(f32::MAX, f32::min);
+
+        let loaded =
+            loaded_chunk.is_some_and(|c| c.is_empty() == false);
+
+        match (loaded, radius.should_load(distance, loaded)) {
+            (false, true) => {
+                info!("Streaming in chunk '{}'.", chunk.path);
+
+                commands.spawn((
+                    SceneRoot(
+                        asset_server.load(
+                            GltfAssetLabel::Scene(0)
+                                .from_asset(chunk.path.clone()),
+                        ),
+                    ),
+                    ChunkOf(entity),
+                    ChildOf(entity),
+                ));
+            }
+            (true, false) => {
+                info!("Streaming out chunk '{}'.", chunk.path);
+
+                // Dropping the scene handle unloads the chunk assets.
+                for chunk_scene in
+                    loaded_chunk.iter().flat_map(|c| c.iter())
+                {
+                    commands.entity(*chunk_scene).despawn();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Anchor of a chunk gltf that is streamed in around the players,
+/// the chunk scene is spawned as a child of the anchor.
+#[derive(Component, Reflect, Default, Debug, Clone)]
+#[reflect(Component, Default)]
+pub struct SceneChunk {
+    /// Asset path of the chunk gltf, e.g.
+    /// "scenes/bread_level_chunk_0.glb".
+    pub path: String,
+}
+
+/// Horizontal distances from the nearest player for streaming
+/// [`SceneChunk`]s.
+#[derive(Resource, Reflect, Debug, Clone, Copy)]
+#[reflect(Resource)]
+pub struct StreamingRadius {
+    /// Chunks within this distance are loaded.
+    pub load: f32,
+    /// Chunks beyond this distance are unloaded, should be larger
+    /// than [`Self::load`] to prevent chunks from reloading on the
+    /// boundary.
+    pub unload: f32,
+}
+
+impl StreamingRadius {
+    /// Returns `true` if the chunk should be loaded at `distance`
+    /// from the nearest player.
+    pub fn should_load(&self, distance: f32, loaded: bool) -> bool {
+        match loaded {
+            true => distance <= self.unload.max(self.load),
+            false => distance <= self.load,
+        }
+    }
+}
+
+impl Default for StreamingRadius {
+    fn default() -> Self {
+        Self {
+            load: 40.0,
+            unload: 50.0,
+        }
+    }
+}
+
+/// Attached to the scene root of a streamed in [`SceneChunk`].
+#[derive(Component, Deref, Debug)]
+#[relationship(relationship_target = LoadedChunk)]
+pub struct ChunkOf(pub Entity);
+
+/// Attached to the [`SceneChunk`] while it's streamed in.
+#[derive(Component, Deref, Default, Debug)]
+#[relationship_target(relationship = ChunkOf)]
+pub struct LoadedChunk(Vec<Entity>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_streaming_hysteresis() {
+        let radius = StreamingRadius {
+            load: 10.0,
+            unload: 15.0,
+        };
+
+        assert!(radius.should_load(5.0, false));
+        assert!(radius.should_load(12.0, false) == false);
+        // Stays loaded in between the radii.
+        assert!(radius.should_load(12.0, true));
+        assert!(radius.should_load(20.0, true) == false);
+    }
+}
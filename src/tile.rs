@@ -1,43 +1,64 @@
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use pathfinding::prelude::*;
 
+mod bake;
+#[cfg(feature = "dev")]
+mod debug;
+
 pub(super) struct TilePlugin;
 
 impl Plugin for TilePlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(bake::TileBakePlugin);
+
         app.init_resource::<TileMap>()
             .add_systems(
                 PostUpdate,
                 setup_tile.after(TransformSystem::TransformPropagate),
             )
+            .add_observer(configure_tile_map)
             .add_observer(on_placed)
             .add_observer(on_freed);
 
-        app.register_type::<Tile>();
+        app.register_type::<Tile>()
+            .register_type::<Ramp>()
+            .register_type::<TileMapConfig>();
 
         #[cfg(feature = "dev")]
-        app.register_type::<TileMap>();
+        app.add_plugins(debug::TileDebugPlugin)
+            .register_type::<TileMap>();
     }
 }
 
-/// The half size of the map, this should be
-/// greater or equal to the half size of the map.
-const HALF_MAP_SIZE: usize = 20;
+/// Rebuild the [`TileMap`] for the level's [`TileMapConfig`].
+fn configure_tile_map(
+    trigger: Trigger<OnAdd, TileMapConfig>,
+    mut commands: Commands,
+    q_configs: Query<&TileMapConfig>,
+) -> Result {
+    let config = q_configs.get(trigger.target())?;
+
+    info!("Configuring tile map: {config:?}");
+    commands.insert_resource(TileMap::new(*config));
+
+    Ok(())
+}
 
 /// Setup tile inside the [`TileMap`].
 fn setup_tile(
     q_tiles: Query<
-        (&GlobalTransform, Entity),
+        (&GlobalTransform, Has<Ramp>, Entity),
         (Or<(Added<Tile>, Added<GlobalTransform>)>, With<Tile>),
     >,
     mut tile_map: ResMut<TileMap>,
 ) -> Result {
-    for (transform, entity) in q_tiles.iter() {
+    for (transform, ramp, entity) in q_tiles.iter() {
         let translation = transform.translation();
 
         *tile_map.get_mut(&translation).ok_or(format!(
             "Unable to get tile for {entity}, {translation}"
-        ))? = Some(TileMeta::new(entity));
+        ))? = Some(TileMeta::new(entity, translation.y, ramp));
     }
 
     Ok(())
@@ -90,28 +111,55 @@ fn on_freed(
 #[derive(Resource, Deref)]
 #[cfg_attr(feature = "dev", derive(Reflect))]
 #[cfg_attr(feature = "dev", reflect(Resource))]
-pub struct TileMap(Vec<Option<TileMeta>>);
+pub struct TileMap {
+    config: TileMapConfig,
+    #[deref]
+    tiles: Vec<Option<TileMeta>>,
+}
 
 impl TileMap {
-    pub const KNIGHT: &[IVec2] = &[
+    pub const KNIGHT: &[IVec3] = &[
         // Top.
-        IVec2::new(0, 1),
+        IVec3::new(0, 0, 1),
         // Bottom.
-        IVec2::new(0, -1),
+        IVec3::new(0, 0, -1),
         // Left.
-        IVec2::new(-1, 0),
+        IVec3::new(-1, 0, 0),
         // Right.
-        IVec2::new(1, 0),
+        IVec3::new(1, 0, 0),
     ];
 
-    pub fn within_map_range(coordinate: &IVec2) -> bool {
-        const MAP_SIZE: i32 = HALF_MAP_SIZE as i32 * 2;
+    /// Extra pathfinding cost for moving up or down a layer,
+    /// enemies prefer staying on the same layer.
+    pub const LAYER_CHANGE_COST: i32 = 2;
+
+    /// Fraction of [`TileMapConfig::layer_height`] below a layer that
+    /// still counts as part of the layer.
+    const LAYER_TOLERANCE: f32 = 0.25;
+
+    pub fn new(config: TileMapConfig) -> Self {
+        Self {
+            config,
+            tiles: vec![
+                None;
+                (config.size.x * config.size.y * config.layers)
+                    as usize
+            ],
+        }
+    }
+
+    pub fn config(&self) -> &TileMapConfig {
+        &self.config
+    }
+
+    /// Tile coordinates are (x, layer, z).
+    pub fn within_map_range(&self, coordinate: &IVec3) -> bool {
+        let map_size = self.config.map_size().as_ivec3();
 
-        if coordinate.x < 0 || coordinate.y < 0 {
+        if coordinate.cmplt(IVec3::ZERO).any() {
             warn!("Attempt to obtain negative coordinate!");
             return false;
-        } else if coordinate.x >= MAP_SIZE || coordinate.y >= MAP_SIZE
-        {
+        } else if coordinate.cmpge(map_size).any() {
             warn!("Attempt to obtain out of bounds coordinate!");
             return false;
         }
@@ -120,48 +168,182 @@ impl TileMap {
     }
 
     /// Get the closest tile coordinate.
+    ///
+    /// The layer is the highest one at or below the translation,
+    /// clamped to the layers of the map.
     pub fn translation_to_tile_coord(
+        &self,
         translation: &Vec3,
-    ) -> Option<UVec2> {
-        // Prevent going negative.
-        let coordinate =
-            ((translation.xz() * 0.5).round().as_ivec2())
-                + HALF_MAP_SIZE as i32;
+    ) -> Option<UVec3> {
+        let TileMapConfig {
+            size,
+            tile_size,
+            origin,
+            layers,
+            layer_height,
+        } = self.config;
 
-        if TileMap::within_map_range(&coordinate) == false {
+        // Prevent going negative.
+        let column = ((translation.xz() - origin.xz()) / tile_size)
+            .round()
+            .as_ivec2()
+            + (size / 2).as_ivec2();
+        let layer = ((translation.y - origin.y) / layer_height
+            + Self::LAYER_TOLERANCE)
+            .floor() as i32;
+
+        let coordinate = IVec3::new(
+            column.x,
+            layer.clamp(0, layers.max(1) as i32 - 1),
+            column.y,
+        );
+
+        if self.within_map_range(&coordinate) == false {
             return None;
         }
 
-        Some(coordinate.as_uvec2())
+        Some(coordinate.as_uvec3())
     }
 
-    pub fn tile_coord_to_tile_idx(coordinate: &UVec2) -> usize {
-        let map_size = HALF_MAP_SIZE as u32 * 2;
-        (coordinate.x + coordinate.y * map_size) as usize
+    pub fn tile_coord_to_tile_idx(
+        &self,
+        coordinate: &UVec3,
+    ) -> usize {
+        let size = self.config.size;
+        (coordinate.x
+            + coordinate.z * size.x
+            + coordinate.y * size.x * size.y) as usize
     }
 
     pub fn translation_to_tile_idx(
+        &self,
         translation: &Vec3,
     ) -> Option<usize> {
-        TileMap::translation_to_tile_coord(translation)
-            .map(|coord| TileMap::tile_coord_to_tile_idx(&coord))
+        self.translation_to_tile_coord(translation)
+            .map(|coord| self.tile_coord_to_tile_idx(&coord))
+    }
+
+    /// World space position of a tile coordinate, the height is the
+    /// base height of the layer.
+    pub fn tile_coord_to_world_space(
+        &self,
+        coordinate: &IVec3,
+    ) -> Vec3 {
+        let TileMapConfig {
+            size,
+            tile_size,
+            origin,
+            layer_height,
+            ..
+        } = self.config;
+
+        let column = coordinate.xz() - (size / 2).as_ivec2();
+
+        Vec3::new(
+            column.x as f32 * tile_size,
+            coordinate.y as f32 * layer_height,
+            column.y as f32 * tile_size,
+        ) + origin
+    }
+
+    /// Iterate over every tile coordinate of the map.
+    pub fn coordinates(&self) -> impl Iterator<Item = IVec3> {
+        let map_size = self.config.map_size().as_ivec3();
+        (0..map_size.y).flat_map(move |y| {
+            (0..map_size.z).flat_map(move |z| {
+                (0..map_size.x).map(move |x| IVec3::new(x, y, z))
+            })
+        })
     }
 
-    pub fn tile_coord_to_world_space(coordinate: &IVec2) -> Vec2 {
-        (coordinate - HALF_MAP_SIZE as i32).as_vec2() * 2.0
+    /// Get the tile at a tile coordinate if it exists.
+    pub fn get_tile(&self, coordinate: &IVec3) -> Option<TileMeta> {
+        if self.within_map_range(coordinate) == false {
+            return None;
+        }
+
+        self.tiles
+            [self.tile_coord_to_tile_idx(&coordinate.as_uvec3())]
     }
 
     fn get_mut(
         &mut self,
         translation: &Vec3,
     ) -> Option<&mut Option<TileMeta>> {
-        TileMap::translation_to_tile_idx(translation)
-            .and_then(|index| self.0.get_mut(index))
+        self.translation_to_tile_idx(translation)
+            .and_then(|index| self.tiles.get_mut(index))
+    }
+
+    /// Walkable neighbours of a tile coordinate with their cost.
+    ///
+    /// Moving up a layer is only possible from a ramp and moving
+    /// down a layer only onto a ramp.
+    fn neighbours(
+        &self,
+        coordinate: IVec3,
+    ) -> impl Iterator<Item = (IVec3, i32)> {
+        let on_ramp =
+            self.get_tile(&coordinate).is_some_and(|t| t.ramp());
+
+        TileMap::KNIGHT
+            .iter()
+            .flat_map(|m| (-1..=1).map(move |dy| (*m, dy)))
+            .filter_map(move |(m, dy)| {
+                let coord = coordinate + m + IVec3::Y * dy;
+
+                // There is nothing below the ground or above the top.
+                if coord.y < 0 || coord.y >= self.config.layers as i32
+                {
+                    return None;
+                }
+
+                // Must be a valid coordinate.
+                if self.within_map_range(&coord) == false {
+                    return None;
+                }
+
+                // Must not be occupied.
+                let tile_meta = self
+                    .get_tile(&coord)
+                    .filter(|t| t.occupied() == false)?;
+
+                let reachable = match dy {
+                    1 => on_ramp,
+                    -1 => tile_meta.ramp(),
+                    _ => true,
+                };
+
+                reachable.then_some((
+                    coord,
+                    1 + dy.abs() * TileMap::LAYER_CHANGE_COST,
+                ))
+            })
+    }
+
+    /// Pathfinding cost of every tile that can reach the
+    /// target, including the target tile itself.
+    pub fn path_costs(
+        &self,
+        target_translation: &Vec3,
+    ) -> HashMap<IVec3, i32> {
+        let Some(target) = self
+            .translation_to_tile_coord(target_translation)
+            .map(|c| c.as_ivec3())
+        else {
+            return HashMap::default();
+        };
+
+        // Layer changes cost the same in both directions.
+        dijkstra_all(&target, |&current| self.neighbours(current))
+            .into_iter()
+            .map(|(coord, (_, cost))| (coord, cost))
+            .chain([(target, 0)])
+            .collect()
     }
 
     /// Find a path from start to end from the tile map.
     ///
-    /// If a path is found, a vector of world space [`IVec2`]
+    /// If a path is found, a vector of tile coordinates
     /// will be returned.
     ///
     /// None will be returned if there is no valid path.
@@ -170,41 +352,18 @@ impl TileMap {
         start_translation: &Vec3,
         end_translation: &Vec3,
         to_tower: bool,
-    ) -> Option<Vec<IVec2>> {
-        let start =
-            TileMap::translation_to_tile_coord(start_translation)?
-                .as_ivec2();
-        let end =
-            TileMap::translation_to_tile_coord(end_translation)?
-                .as_ivec2();
+    ) -> Option<Vec<IVec3>> {
+        let start = self
+            .translation_to_tile_coord(start_translation)?
+            .as_ivec3();
+        let end = self
+            .translation_to_tile_coord(end_translation)?
+            .as_ivec3();
 
         Some(
             astar(
                 &start,
-                |&current| {
-                    TileMap::KNIGHT
-                        .iter()
-                        .map(move |m| current + m)
-                        .filter(|coord| {
-                            // Must be a valid coordinate.
-                            if TileMap::within_map_range(coord)
-                                == false
-                            {
-                                return false;
-                            }
-                            let index =
-                                TileMap::tile_coord_to_tile_idx(
-                                    &coord.as_uvec2(),
-                                );
-                            let tile_meta = self[index];
-
-                            // Must not be occupied.
-                            tile_meta.is_some_and(|t| {
-                                t.occupied() == false
-                            })
-                        })
-                        .map(|p| (p, 1))
-                },
+                |&current| self.neighbours(current),
                 // Always find the closest to the target.
                 |potential| potential.distance_squared(end),
                 |&current| {
@@ -215,15 +374,15 @@ impl TileMap {
                             .map(move |m| current + m)
                             .any(|coord| {
                                 // Must be a valid coordinate.
-                                if TileMap::within_map_range(&coord)
+                                if self.within_map_range(&coord)
                                     == false
                                 {
                                     return false;
                                 }
 
-                                let index =
-                                    TileMap::tile_coord_to_tile_idx(
-                                        &coord.as_uvec2(),
+                                let index = self
+                                    .tile_coord_to_tile_idx(
+                                        &coord.as_uvec3(),
                                     );
                                 let tile_meta = self[index];
 
@@ -245,8 +404,45 @@ impl TileMap {
 
 impl Default for TileMap {
     fn default() -> Self {
-        const MAP_SIZE: usize = HALF_MAP_SIZE * 2;
-        Self(vec![None; MAP_SIZE * MAP_SIZE])
+        Self::new(TileMapConfig::default())
+    }
+}
+
+/// Dimensions of the [`TileMap`] of a level, add it to an entity
+/// in the level scene (through `bevy_skein`) to override the
+/// defaults.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct TileMapConfig {
+    /// Number of tiles along the x and z axes.
+    pub size: UVec2,
+    /// Size of a tile on the xz plane.
+    pub tile_size: f32,
+    /// World space position of the center of the ground layer.
+    pub origin: Vec3,
+    /// Number of vertical layers, platforms are placed on the
+    /// layers above the ground.
+    pub layers: u32,
+    /// Height between two layers.
+    pub layer_height: f32,
+}
+
+impl TileMapConfig {
+    /// Size of the map as (x, layers, z).
+    pub fn map_size(&self) -> UVec3 {
+        UVec3::new(self.size.x, self.layers, self.size.y)
+    }
+}
+
+impl Default for TileMapConfig {
+    fn default() -> Self {
+        Self {
+            size: UVec2::splat(40),
+            tile_size: 2.0,
+            origin: Vec3::ZERO,
+            layers: 1,
+            layer_height: 2.0,
+        }
     }
 }
 
@@ -255,13 +451,19 @@ pub struct TileMeta {
     #[allow(dead_code)]
     target: Entity,
     occupied: bool,
+    /// World space height of the tile surface.
+    height: f32,
+    /// Connects to the layer above.
+    ramp: bool,
 }
 
 impl TileMeta {
-    pub fn new(target: Entity) -> Self {
+    pub fn new(target: Entity, height: f32, ramp: bool) -> Self {
         Self {
             target,
             occupied: false,
+            height,
+            ramp,
         }
     }
 
@@ -272,6 +474,14 @@ impl TileMeta {
     pub fn target(&self) -> Entity {
         self.target
     }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn ramp(&self) -> bool {
+        self.ramp
+    }
 }
 
 /// Tag component for tiles that can be placed on.
@@ -279,6 +489,13 @@ impl TileMeta {
 #[reflect(Component)]
 pub struct Tile;
 
+/// A [`Tile`] that connects its layer to the layer above,
+/// the tile should sit halfway up the ramp.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+#[require(Tile)]
+pub struct Ramp;
+
 /// Attached to a [`Tile`] when it's being placed on.
 #[derive(Component, Deref, Default, Debug)]
 #[relationship_target(relationship = PlacedOn)]
@@ -295,15 +512,17 @@ mod test {
 
     #[test]
     fn test_within_range() {
+        let tile_map = TileMap::default();
         let tiles = [
-            IVec2::new(-1, 0),
-            IVec2::new(0, -1),
-            IVec2::new(HALF_MAP_SIZE as i32 * 2, 0),
-            IVec2::new(0, HALF_MAP_SIZE as i32 * 2),
+            IVec3::new(-1, 0, 0),
+            IVec3::new(0, 0, -1),
+            IVec3::new(40, 0, 0),
+            IVec3::new(0, 0, 40),
+            IVec3::new(0, 1, 0),
         ];
 
         for tile in tiles {
-            assert!(TileMap::within_map_range(&tile) == false);
+            assert!(tile_map.within_map_range(&tile) == false);
         }
     }
 
@@ -311,11 +530,67 @@ mod test {
     fn test_coordinate_spaces() {
         let translation = Vec3::new(2.0, 0.0, 4.0);
 
-        let coord = TileMap::translation_to_tile_coord(&translation)
-            .expect("Should be in range.");
-        let world_space =
-            TileMap::tile_coord_to_world_space(&coord.as_ivec2());
+        for tile_map in [
+            TileMap::default(),
+            TileMap::new(TileMapConfig {
+                size: UVec2::new(20, 30),
+                tile_size: 1.0,
+                origin: Vec3::new(-5.0, -2.0, 3.0),
+                layers: 2,
+                layer_height: 2.0,
+            }),
+        ] {
+            let coord = tile_map
+                .translation_to_tile_coord(&translation)
+                .expect("Should be in range.");
+            let world_space =
+                tile_map.tile_coord_to_world_space(&coord.as_ivec3());
+
+            assert_eq!(world_space, translation);
+        }
+    }
 
-        assert_eq!(world_space, translation.xz());
+    #[test]
+    fn test_ramp_pathfinding() {
+        let mut tile_map = TileMap::new(TileMapConfig {
+            size: UVec2::new(3, 1),
+            layers: 2,
+            ..default()
+        });
+
+        let ground = Vec3::new(-2.0, 0.0, 0.0);
+        let ramp = Vec3::new(0.0, 1.0, 0.0);
+        let platform = Vec3::new(2.0, 2.0, 0.0);
+
+        fn set_tile(
+            tile_map: &mut TileMap,
+            translation: Vec3,
+            ramp: bool,
+        ) {
+            *tile_map.get_mut(&translation).unwrap() =
+                Some(TileMeta::new(
+                    Entity::PLACEHOLDER,
+                    translation.y,
+                    ramp,
+                ));
+        }
+        set_tile(&mut tile_map, ground, false);
+        set_tile(&mut tile_map, ramp, true);
+        set_tile(&mut tile_map, platform, false);
+
+        let path = tile_map
+            .pathfind_to(&ground, &platform, false)
+            .expect("Should go up the ramp.");
+        assert_eq!(path.last(), Some(&IVec3::new(2, 1, 0)));
+
+        // Back down the ramp.
+        assert!(
+            tile_map.pathfind_to(&platform, &ground, false).is_some()
+        );
+
+        set_tile(&mut tile_map, ramp, false);
+        assert!(
+            tile_map.pathfind_to(&ground, &platform, false).is_none()
+        );
     }
 }
@@ -0,0 +1,219 @@
+//! Bake the [`TileMap`] from the static colliders of the level
+//! instead of placing every [`Tile`] by hand.
+//!
+//! Add [`TileBake`] to an entity in the level scene (through
+//! `bevy_skein`), the topmost surface of every cell of the map gets
+//! sampled and a tile is spawned if it's walkable and there is no
+//! authored [`Tile`] on it already. Tiles underneath platforms and
+//! [`Ramp`](super::Ramp)s still need to be authored by hand.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::physics::GameLayer;
+
+use super::{Tile, TileMap};
+
+/// Seconds to wait for the colliders of the scene to be added
+/// to the physics world before baking.
+const BAKE_DELAY: f32 = 0.2;
+/// Height to cast the rays down from.
+const CAST_HEIGHT: f32 = 50.0;
+
+pub(super) struct TileBakePlugin;
+
+impl Plugin for TileBakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TileBakeReport>()
+            .add_systems(Update, bake_tiles)
+            .add_observer(schedule_bake);
+
+        app.register_type::<TileBake>();
+    }
+}
+
+fn schedule_bake(
+    trigger: Trigger<OnAdd, TileBake>,
+    mut commands: Commands,
+) {
+    commands.entity(trigger.target()).insert(BakeDelay(
+        Timer::from_seconds(BAKE_DELAY, TimerMode::Once),
+    ));
+}
+
+fn bake_tiles(
+    mut commands: Commands,
+    mut q_bakes: Query<(
+        &TileBake,
+        &GlobalTransform,
+        &mut BakeDelay,
+        Entity,
+    )>,
+    q_collider_ofs: Query<&ColliderOf>,
+    q_bodies: Query<&RigidBody>,
+    q_sensors: Query<(), With<Sensor>>,
+    spatial_query: SpatialQuery,
+    tile_map: Res<TileMap>,
+    mut report: ResMut<TileBakeReport>,
+    time: Res<Time>,
+) {
+    // Only static level geometry counts, colliders without
+    // a rigid body are static too.
+    let is_static = |entity: Entity| {
+        q_sensors.contains(entity) == false
+            && q_collider_ofs.get(entity).map_or(true, |c| {
+                q_bodies.get(c.body).is_ok_and(|b| b.is_static())
+            })
+    };
+
+    for (bake, global_transform, mut delay, entity) in
+        q_bakes.iter_mut()
+    {
+        if delay.tick(time.delta()).finished() == false {
+            continue;
+        }
+
+        commands.entity(entity).remove::<BakeDelay>();
+
+        // Baked tiles are children of the bake entity.
+        let inverse = global_transform.affine().inverse();
+        let mut walkable_count = 0;
+        report.blocked.clear();
+
+        let tile_size = tile_map.config().tile_size;
+        // Rays only reach the topmost surface of each column.
+        for column in tile_map.coordinates().filter(|c| c.y == 0) {
+            match bake.sample(
+                tile_map.tile_coord_to_world_space(&column).xz(),
+                tile_size,
+                &spatial_query,
+                &is_static,
+            ) {
+                TileSample::Walkable(position) => {
+                    // Keep the authored tiles.
+                    if tile_map
+                        .translation_to_tile_coord(&position)
+                        .and_then(|c| {
+                            tile_map.get_tile(&c.as_ivec3())
+                        })
+                        .is_some()
+                    {
+                        continue;
+                    }
+
+                    walkable_count += 1;
+                    commands.spawn((
+                        Tile,
+                        BakedTile,
+                        Collider::cuboid(tile_size, 0.1, tile_size),
+                        Transform::from_translation(
+                            inverse.transform_point3(position),
+                        ),
+                        ChildOf(entity),
+                    ));
+                }
+                TileSample::Blocked(position) => {
+                    report.blocked.push(position);
+                }
+                TileSample::Empty => {}
+            }
+        }
+
+        info!(
+            "Baked {walkable_count} walkable and {} blocked tiles.",
+            report.blocked.len()
+        );
+    }
+}
+
+/// Bake tiles from the static colliders of the level.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component, Default)]
+pub struct TileBake {
+    /// Maximum slope in degrees of a walkable surface.
+    pub max_slope: f32,
+    /// Free space needed above a walkable surface.
+    pub clearance: f32,
+}
+
+impl TileBake {
+    fn sample(
+        &self,
+        center: Vec2,
+        tile_size: f32,
+        spatial_query: &SpatialQuery,
+        is_static: &dyn Fn(Entity) -> bool,
+    ) -> TileSample {
+        let origin = Vec3::new(center.x, CAST_HEIGHT, center.y);
+        let filter =
+            SpatialQueryFilter::from_mask(GameLayer::Default);
+
+        let Some(hit) = spatial_query.cast_ray_predicate(
+            origin,
+            Dir3::NEG_Y,
+            CAST_HEIGHT * 2.0,
+            true,
+            &filter,
+            is_static,
+        ) else {
+            return TileSample::Empty;
+        };
+
+        let position = origin + Vec3::NEG_Y * hit.distance;
+
+        if hit.normal.y < self.max_slope.to_radians().cos() {
+            return TileSample::Blocked(position);
+        }
+
+        // Leave a small gap so that the surface itself is not hit.
+        let obstacles = spatial_query.shape_intersections(
+            &Collider::cuboid(
+                tile_size * 0.8,
+                self.clearance,
+                tile_size * 0.8,
+            ),
+            position + Vec3::Y * (self.clearance * 0.5 + 0.1),
+            Quat::IDENTITY,
+            &filter,
+        );
+
+        match obstacles.into_iter().any(is_static) {
+            true => TileSample::Blocked(position),
+            false => TileSample::Walkable(position),
+        }
+    }
+}
+
+impl Default for TileBake {
+    fn default() -> Self {
+        Self {
+            max_slope: 30.0,
+            clearance: 1.5,
+        }
+    }
+}
+
+/// Tag component for tiles spawned by [`TileBake`].
+#[derive(Component, Debug)]
+#[require(
+    Sensor,
+    CollisionLayers::new(GameLayer::Default, LayerMask::NONE)
+)]
+pub struct BakedTile;
+
+/// Positions of the blocked tiles from the last bake.
+#[derive(Resource, Default, Debug)]
+pub struct TileBakeReport {
+    pub blocked: Vec<Vec3>,
+}
+
+#[derive(Component, Deref, DerefMut, Debug)]
+struct BakeDelay(Timer);
+
+enum TileSample {
+    /// Nothing to stand on.
+    Empty,
+    Walkable(Vec3),
+    /// Too steep or obstructed.
+    Blocked(Vec3),
+}
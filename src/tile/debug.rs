@@ -0,0 +1,188 @@
+//! World space overlay of the [`TileMap`] for debugging levels
+//! where enemies can't find a path.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::f32::consts::FRAC_PI_2;
+
+use crate::enemy::{Enemy, FinalTarget, Path, PathIndex};
+
+use super::TileMap;
+use super::bake::TileBakeReport;
+
+/// Draw the overlay slightly above the tiles to avoid z-fighting.
+const OVERLAY_OFFSET: f32 = 0.05;
+
+pub(super) struct TileDebugPlugin;
+
+impl Plugin for TileDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TileOverlay>().add_systems(
+            Update,
+            (
+                toggle_tile_overlay,
+                update_path_costs,
+                (draw_tiles, draw_enemy_paths),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Toggle the overlay with [`TileOverlay::KEY`].
+fn toggle_tile_overlay(
+    mut overlay: ResMut<TileOverlay>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+) {
+    if kbd_inputs.just_pressed(TileOverlay::KEY) {
+        overlay.show = !overlay.show;
+        // Recalculate the costs upon showing.
+        overlay.costs.clear();
+    }
+}
+
+/// Costs towards the [`FinalTarget`], only recalculated when the
+/// [`TileMap`] changes.
+fn update_path_costs(
+    mut overlay: ResMut<TileOverlay>,
+    q_final_target: Query<&GlobalTransform, With<FinalTarget>>,
+    tile_map: Res<TileMap>,
+) {
+    if overlay.show == false
+        || (tile_map.is_changed() == false
+            && overlay.costs.is_empty() == false)
+    {
+        return;
+    }
+
+    let Ok(final_target) = q_final_target.single() else {
+        return;
+    };
+
+    overlay.costs = tile_map.path_costs(&final_target.translation());
+    overlay.max_cost =
+        overlay.costs.values().copied().max().unwrap_or(0);
+}
+
+/// Draw every tile colored by its occupancy and pathfinding cost.
+///
+/// - Free tiles go from green (close to the [`FinalTarget`]) to red.
+/// - Occupied tiles are amber.
+/// - Ramps are outlined in blue.
+/// - Tiles that can't reach the [`FinalTarget`] are crossed out in
+///   fuchsia.
+/// - Cells the last bake found unwalkable are crossed out in red.
+fn draw_tiles(
+    overlay: Res<TileOverlay>,
+    tile_map: Res<TileMap>,
+    report: Res<TileBakeReport>,
+    mut gizmos: Gizmos,
+) {
+    if overlay.show == false {
+        return;
+    }
+
+    let tile_size = tile_map.config().tile_size;
+    let flat = Quat::from_rotation_x(FRAC_PI_2);
+
+    for coordinate in tile_map.coordinates() {
+        let Some(tile) = tile_map.get_tile(&coordinate) else {
+            continue;
+        };
+
+        let mut position =
+            tile_map.tile_coord_to_world_space(&coordinate);
+        position.y = tile.height() + OVERLAY_OFFSET;
+        let isometry = Isometry3d::new(position, flat);
+
+        let color: Color =
+            match (tile.occupied(), overlay.costs.get(&coordinate)) {
+                (true, _) => AMBER_400.into(),
+                (false, Some(cost)) => GREEN_400
+                    .mix(
+                        &RED_500,
+                        *cost as f32 / overlay.max_cost.max(1) as f32,
+                    )
+                    .into(),
+                (false, None) => {
+                    gizmos.cross(
+                        isometry,
+                        tile_size * 0.3,
+                        FUCHSIA_500,
+                    );
+                    FUCHSIA_500.into()
+                }
+            };
+
+        gizmos.rect(isometry, Vec2::splat(tile_size * 0.9), color);
+
+        if tile.ramp() {
+            gizmos.rect(
+                isometry,
+                Vec2::splat(tile_size * 0.7),
+                SKY_400,
+            );
+        }
+    }
+
+    for position in report.blocked.iter() {
+        gizmos.cross(
+            Isometry3d::new(
+                *position + Vec3::Y * OVERLAY_OFFSET,
+                flat,
+            ),
+            tile_size * 0.3,
+            RED_500,
+        );
+    }
+}
+
+/// Draw the remaining path of every enemy.
+fn draw_enemy_paths(
+    overlay: Res<TileOverlay>,
+    q_enemies: Query<
+        (&Path, &PathIndex, &GlobalTransform),
+        With<Enemy>,
+    >,
+    tile_map: Res<TileMap>,
+    mut gizmos: Gizmos,
+) {
+    if overlay.show == false {
+        return;
+    }
+
+    let waypoint = |coord: &IVec3| {
+        let mut position = tile_map.tile_coord_to_world_space(coord);
+        if let Some(tile) = tile_map.get_tile(coord) {
+            position.y = tile.height();
+        }
+        position + Vec3::Y * OVERLAY_OFFSET * 2.0
+    };
+
+    for (path, path_index, global_transform) in q_enemies.iter() {
+        gizmos.linestrip(
+            [global_transform.translation()]
+                .into_iter()
+                .chain(path.iter().skip(**path_index).map(waypoint)),
+            ORANGE_400,
+        );
+
+        // Mark where the enemy is heading.
+        if let Some(end) = path.last() {
+            gizmos.sphere(waypoint(end), 0.3, ORANGE_400);
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct TileOverlay {
+    show: bool,
+    /// Pathfinding cost of every tile towards the [`FinalTarget`].
+    costs: HashMap<IVec3, i32>,
+    max_cost: i32,
+}
+
+impl TileOverlay {
+    const KEY: KeyCode = KeyCode::F6;
+}
@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContextPass, EguiContexts};
+use bevy_inspector_egui::egui;
+
+use crate::enemy::Enemy;
+use crate::enemy::spawner::SpawnWave;
+use crate::inventory::Inventory;
+use crate::inventory::item::ItemType;
+use crate::player::player_mark::PlayerMark;
+use crate::tower::tower_attack::{Health, Tower};
+use crate::ui::Screen;
+
+/// Upper limit of snapshots kept (an hour of playtesting).
+const MAX_SNAPSHOTS: usize = 3600;
+
+/// Records a snapshot of the game state every second so that
+/// balance problems can be traced back during a playtest.
+pub(super) struct TimelinePlugin;
+
+impl Plugin for TimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Timeline>()
+            .add_systems(OnEnter(Screen::EnterLevel), reset_timeline)
+            .add_systems(
+                Update,
+                record_snapshot.run_if(in_state(Screen::EnterLevel)),
+            )
+            .add_systems(EguiContextPass, timeline_panel);
+    }
+}
+
+fn reset_timeline(mut timeline: ResMut<Timeline>) {
+    *timeline = Timeline::default();
+}
+
+fn record_snapshot(
+    mut timeline: ResMut<Timeline>,
+    q_enemies: Query<&Health, With<Enemy>>,
+    q_towers: Query<&Health, With<Tower>>,
+    q_inventories: Query<&Inventory>,
+    player_mark: Option<Res<PlayerMark>>,
+    spawn_wave: Option<Res<State<SpawnWave>>>,
+    time: Res<Time>,
+) {
+    timeline.elapsed += time.delta_secs();
+    if timeline.timer.tick(time.delta()).just_finished() == false {
+        return;
+    }
+
+    let snapshot = Snapshot {
+        elapsed: timeline.elapsed,
+        enemy_count: q_enemies.iter().len(),
+        enemy_health: q_enemies.iter().map(|h| h.0).sum(),
+        tower_count: q_towers.iter().len(),
+        tower_health: q_towers.iter().map(|h| h.0).sum(),
+        ingredients: q_inventories
+            .iter()
+            .flat_map(|i| i.stacks_of_type(ItemType::Ingredient))
+            .map(|(_, s)| s.quantity)
+            .sum(),
+        player_mark: player_mark.map(|m| m.0).unwrap_or_default(),
+        wave: spawn_wave.map(|w| *w.get()).unwrap_or_default(),
+    };
+
+    if timeline.snapshots.len() >= MAX_SNAPSHOTS {
+        timeline.snapshots.remove(0);
+    }
+    timeline.snapshots.push(snapshot);
+}
+
+/// Scrub through the recorded snapshots.
+fn timeline_panel(
+    mut contexts: EguiContexts,
+    mut timeline: ResMut<Timeline>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let timeline = timeline.bypass_change_detection();
+
+    egui::Window::new("Timeline").default_open(false).show(
+        ctx,
+        |ui| {
+            let Some(last) = timeline.snapshots.len().checked_sub(1)
+            else {
+                ui.label("No snapshots recorded yet.");
+                return;
+            };
+
+            ui.checkbox(&mut timeline.follow, "Follow latest");
+            if timeline.follow {
+                timeline.cursor = last;
+            }
+
+            if ui
+                .add(
+                    egui::Slider::new(&mut timeline.cursor, 0..=last)
+                        .text("Second"),
+                )
+                .changed()
+            {
+                timeline.follow = false;
+            }
+
+            let cursor = timeline.cursor.min(last);
+            let snapshot = &timeline.snapshots[cursor];
+            // Compare against the previous second.
+            let previous =
+                &timeline.snapshots[cursor.saturating_sub(1)];
+
+            egui::Grid::new("timeline_snapshot").striped(true).show(
+                ui,
+                |ui| {
+                    let mut row =
+                        |label: &str, value: f32, prev: f32| {
+                            ui.label(label);
+                            ui.label(format!("{value:.1}"));
+                            ui.label(format!("{:+.1}", value - prev));
+                            ui.end_row();
+                        };
+
+                    row(
+                        "Time (s)",
+                        snapshot.elapsed,
+                        previous.elapsed,
+                    );
+                    row(
+                        "Enemies",
+                        snapshot.enemy_count as f32,
+                        previous.enemy_count as f32,
+                    );
+                    row(
+                        "Enemy health",
+                        snapshot.enemy_health,
+                        previous.enemy_health,
+                    );
+                    row(
+                        "Towers",
+                        snapshot.tower_count as f32,
+                        previous.tower_count as f32,
+                    );
+                    row(
+                        "Tower health",
+                        snapshot.tower_health,
+                        previous.tower_health,
+                    );
+                    row(
+                        "Ingredients",
+                        snapshot.ingredients as f32,
+                        previous.ingredients as f32,
+                    );
+                    row(
+                        "Player mark",
+                        snapshot.player_mark as f32,
+                        previous.player_mark as f32,
+                    );
+                },
+            );
+
+            ui.label(format!("Wave: {:?}", snapshot.wave));
+        },
+    );
+}
+
+#[derive(Resource)]
+pub struct Timeline {
+    snapshots: Vec<Snapshot>,
+    timer: Timer,
+    /// Seconds since entering the level.
+    elapsed: f32,
+    /// Index of the snapshot being inspected.
+    cursor: usize,
+    /// Keep the cursor on the latest snapshot.
+    follow: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            snapshots: Vec::new(),
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            elapsed: 0.0,
+            cursor: 0,
+            follow: true,
+        }
+    }
+}
+
+/// Key state of the game at a point in time.
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    /// Seconds since entering the level.
+    elapsed: f32,
+    enemy_count: usize,
+    enemy_health: f32,
+    tower_count: usize,
+    tower_health: f32,
+    /// Ingredients held by the players, the game's currency.
+    ingredients: u32,
+    player_mark: u32,
+    wave: SpawnWave,
+}
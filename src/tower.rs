@@ -1,22 +1,32 @@
 use avian3d::prelude::*;
 use bevy::color::palettes::tailwind::*;
 use bevy::prelude::*;
+use bevy::render::view::VisibilitySystems;
 use leafwing_input_manager::prelude::*;
 
 use crate::action::{PlayerAction, TargetAction};
-use crate::asset_pipeline::{AssetState, CurrentScene, PrefabAssets};
+use crate::asset_pipeline::AssetState;
+use crate::camera_controller::split_screen::QueryCameras;
 use crate::camera_controller::{A_RENDER_LAYER, B_RENDER_LAYER};
-use crate::character_controller::CharacterController;
+use crate::character_controller::{
+    CharacterController, camera_relative_direction,
+};
+use crate::decoration::DecorationPlacement;
 use crate::inventory::Inventory;
 use crate::inventory::item::{ItemRegistry, ItemType};
 use crate::physics::GameLayer;
 use crate::player::{PlayerType, QueryPlayers};
-use crate::tile::{PlacedBy, PlacedOn, Tile};
+use crate::tile::{PlacedBy, Tile};
 use crate::util::PropagateComponentAppExt;
 
 mod animation;
+pub mod blueprint;
+pub mod naming;
+pub mod ownership;
 pub mod tower_attack;
 
+pub use blueprint::TowerBlueprint;
+
 pub struct TowerPlugin;
 
 impl Plugin for TowerPlugin {
@@ -24,6 +34,8 @@ impl Plugin for TowerPlugin {
         app.add_plugins((
             tower_attack::TowerAttackPlugin,
             animation::TowerAnimationPlugin,
+            ownership::OwnershipPlugin,
+            naming::NamingPlugin,
         ));
 
         app.propagate_component::<TowerPrefabName, Children>()
@@ -31,11 +43,17 @@ impl Plugin for TowerPlugin {
             .add_systems(
                 Update,
                 (
-                    tower_placement_and_preview
-                        .run_if(in_state(AssetState::Loaded)),
+                    place_tower.run_if(in_state(AssetState::Loaded)),
                     (enter_placement_mode, exit_placement_mode),
                 )
                     .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                predict_preview
+                    .run_if(in_state(AssetState::Loaded))
+                    .after(TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::VisibilityPropagate),
             );
     }
 }
@@ -69,28 +87,22 @@ fn enter_placement_mode(
     mut commands: Commands,
     mut q_players: Query<
         (&Inventory, &TargetAction, Entity),
-        (With<CharacterController>, Without<InPlacementMode>),
+        (
+            With<CharacterController>,
+            Without<InPlacementMode>,
+            Without<DecorationPlacement>,
+        ),
     >,
     q_actions: Query<&ActionState<PlayerAction>>,
 ) -> Result {
     for (inventory, target_action, entity) in q_players.iter_mut() {
         let action = q_actions.get(target_action.get())?;
 
-        if action.just_pressed(&PlayerAction::Placement) {
-            if let Some(selected_tower) =
-                inventory.selected_tower.as_ref()
-            {
-                // Enter placement mode only if there's a tower to place.
-                if inventory
-                    .towers()
-                    .get(selected_tower)
-                    .copied()
-                    .unwrap_or(0)
-                    > 0
-                {
-                    commands.entity(entity).insert(InPlacementMode);
-                }
-            }
+        // Enter placement mode only if there's a tower to place.
+        if action.just_pressed(&PlayerAction::Placement)
+            && inventory.selected().is_some_and(|s| s.quantity > 0)
+        {
+            commands.entity(entity).insert(InPlacementMode);
         }
     }
 
@@ -111,7 +123,9 @@ fn exit_placement_mode(
 
         if action.just_pressed(&PlayerAction::Cancel) {
             // Exit placement mode.
-            commands.entity(entity).remove::<InPlacementMode>();
+            commands
+                .entity(entity)
+                .remove::<(InPlacementMode, PlacementTile)>();
             *q_previews.get_mut(*player_type)? = Visibility::Hidden;
         }
     }
@@ -119,150 +133,217 @@ fn exit_placement_mode(
     Ok(())
 }
 
-fn tower_placement_and_preview(
+/// Place the selected tower on the [`PlacementTile`] shown by the
+/// preview.
+fn place_tower(
     mut commands: Commands,
     // Find players in placement mode.
+    q_players: Query<
+        (
+            &PlayerType,
+            &Inventory,
+            &PlacementTile,
+            &TargetAction,
+            Entity,
+        ),
+        (With<CharacterController>, With<InPlacementMode>),
+    >,
+    mut q_previews: QueryPlayers<&mut Visibility, With<Preview>>,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    item_registry: ItemRegistry,
+) -> Result {
+    for (
+        player_type,
+        inventory,
+        placement_tile,
+        target_action,
+        player_entity,
+    ) in q_players.iter()
+    {
+        if q_actions
+            .get(target_action.get())?
+            .just_pressed(&PlayerAction::Placement)
+            == false
+        {
+            continue;
+        }
+
+        // Exit placement mode regardless if placing is a success or not.
+        commands
+            .entity(player_entity)
+            .remove::<(InPlacementMode, PlacementTile)>();
+        *q_previews.get_mut(*player_type)? = Visibility::Hidden;
+
+        let Some(tile_entity) = placement_tile.0 else {
+            continue;
+        };
+
+        let Some(selected_index) = inventory.selected_index() else {
+            continue;
+        };
+
+        let Some((prefab_name, stack)) =
+            inventory.selected().and_then(|s| {
+                item_registry
+                    .get_item(&s.item_id)
+                    .filter(|i| i.item_type == ItemType::Tower)
+                    .and_then(|i| i.raw_prefab_name())
+                    .map(|p| (p, s))
+            })
+        else {
+            continue;
+        };
+
+        // Keep the modifiers of the selected stack.
+        let spawn_tower = TowerBlueprint::from_prefab(prefab_name)
+            .with_modifiers(stack.modifiers)
+            .with_owner(*player_type)
+            .spawn_at(tile_entity);
+
+        commands.queue(move |world: &mut World| -> Result {
+            spawn_tower.apply(world)?;
+
+            // Only use up the item once the tower is standing.
+            if let Some(mut inventory) =
+                world.get_mut::<Inventory>(player_entity)
+            {
+                inventory.take(selected_index, 1);
+            }
+
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+/// Pick the [`PlacementTile`] and move the preview cube onto it.
+///
+/// Runs after propagation using the player transforms of the
+/// current frame, and faces the player along this frame's movement
+/// input which the physics only applies on the next fixed step.
+fn predict_preview(
     mut q_players: Query<
         (
             &GlobalTransform,
             &PlayerType,
-            &mut Inventory,
             &TargetAction,
-            Entity,
+            &mut PlacementTile,
         ),
         (With<CharacterController>, With<InPlacementMode>),
     >,
     q_tiles: Query<&GlobalTransform, (With<Tile>, Without<PlacedBy>)>,
     mut q_previews: QueryPlayers<
-        (&mut Transform, &mut Visibility),
-        With<Preview>,
+        (&mut Transform, &mut GlobalTransform, &mut Visibility),
+        (With<Preview>, Without<Tile>, Without<CharacterController>),
     >,
+    q_cameras: QueryCameras<&GlobalTransform>,
     q_actions: Query<&ActionState<PlayerAction>>,
-    item_registry: ItemRegistry,
     spatial_query: SpatialQuery,
-    prefabs: Res<PrefabAssets>,
-    gltfs: Res<Assets<Gltf>>,
-    current_scene: Res<CurrentScene>,
 ) -> Result {
-    let Some(current_scene) = current_scene.get() else {
-        return Ok(());
-    };
-
     for (
         global_transform,
         player_type,
-        mut inventory,
         target_action,
-        player_entity,
+        mut placement_tile,
     ) in q_players.iter_mut()
     {
-        // In front of the player.
-        let target_position = global_transform.translation()
-            + global_transform.forward() * 2.0;
-
-        // Create a sphere collider for intersection testing around the camera target
-        let interaction_sphere = Collider::sphere(4.0);
-
-        // Check if the tile intersects with the interaction sphere
-        let intersections = spatial_query.shape_intersections(
-            &interaction_sphere,
-            target_position,
-            Quat::IDENTITY,
-            &SpatialQueryFilter::default(),
+        let movement = q_actions
+            .get(target_action.get())?
+            .clamped_axis_pair(&PlayerAction::Move);
+
+        let forward = q_cameras
+            .get(player_type.camera_type())
+            .ok()
+            .filter(|_| movement.length_squared() > f32::EPSILON)
+            .and_then(|cam| {
+                camera_relative_direction(cam, movement)
+                    .try_normalize()
+            })
+            .unwrap_or(global_transform.forward().as_vec3());
+
+        let tile = closest_free_tile(
+            global_transform.translation(),
+            forward,
+            &q_tiles,
+            &spatial_query,
         );
+        placement_tile
+            .set_if_neq(PlacementTile(tile.map(|(_, e)| e)));
+
+        let (mut transform, mut preview_global_transform, mut viz) =
+            q_previews.get_mut(*player_type)?;
 
-        // Find the closest valid tile.
-        let mut closest_distance = f32::MAX;
-        let mut closest_tile_data = None;
+        let Some((tile_position, _)) = tile else {
+            viz.set_if_neq(Visibility::Hidden);
+            continue;
+        };
 
-        for tile_entity in intersections {
-            let Ok(tile_position) =
-                q_tiles.get(tile_entity).map(|t| t.translation())
-            else {
-                continue;
-            };
+        viz.set_if_neq(Visibility::Inherited);
+        transform.translation = tile_position + Vec3::Y * 0.25;
+        // Transform propagation has already run for this frame.
+        *preview_global_transform = GlobalTransform::from(*transform);
+    }
 
-            let distance_sq =
-                target_position.distance_squared(tile_position);
+    Ok(())
+}
 
-            if distance_sq < closest_distance {
-                closest_distance = distance_sq;
-                closest_tile_data =
-                    Some((tile_position, tile_entity));
-            }
-        }
+/// Find the closest free tile in front of the player.
+fn closest_free_tile(
+    origin: Vec3,
+    forward: Vec3,
+    q_tiles: &Query<
+        &GlobalTransform,
+        (With<Tile>, Without<PlacedBy>),
+    >,
+    spatial_query: &SpatialQuery,
+) -> Option<(Vec3, Entity)> {
+    // In front of the player.
+    let target_position = origin + forward * 2.0;
+
+    // Create a sphere collider for intersection testing around the camera target
+    let interaction_sphere = Collider::sphere(4.0);
+
+    // Check if the tile intersects with the interaction sphere
+    let intersections = spatial_query.shape_intersections(
+        &interaction_sphere,
+        target_position,
+        Quat::IDENTITY,
+        &SpatialQueryFilter::default(),
+    );
 
-        let (mut preview_transform, mut preview_viz) =
-            q_previews.get_mut(*player_type)?;
+    // Find the closest valid tile.
+    let mut closest_distance = f32::MAX;
+    let mut closest_tile_data = None;
 
-        let Some((tile_position, tile_entity)) = closest_tile_data
+    for tile_entity in intersections {
+        let Ok(tile_position) =
+            q_tiles.get(tile_entity).map(|t| t.translation())
         else {
-            *preview_viz = Visibility::Hidden;
             continue;
         };
 
-        if q_actions
-            .get(target_action.get())?
-            .just_pressed(&PlayerAction::Placement)
-        {
-            // Exit placement mode regardless if placing is a success or not.
-            commands
-                .entity(player_entity)
-                .remove::<InPlacementMode>();
-
-            let Some(selected_tower) =
-                inventory.selected_tower.clone()
-            else {
-                continue;
-            };
-
-            let Some(item) = item_registry
-                .get_item(&selected_tower)
-                .filter(|i| i.item_type == ItemType::Tower)
-            else {
-                continue;
-            };
-
-            if inventory.remove_tower(&selected_tower, 1) == false {
-                continue;
-            }
+        let distance_sq =
+            target_position.distance_squared(tile_position);
 
-            // Spawn the tower.
-            commands.spawn((
-                TowerPrefabName(item.raw_prefab_name().to_string()),
-                SceneRoot(
-                    prefabs
-                        .get_gltf(item.prefab_name(), &gltfs)
-                        .ok_or(format!(
-                            "Can't find {selected_tower} prefab!"
-                        ))?
-                        .default_scene
-                        .clone()
-                        .ok_or(
-                            "Tower prefab should have a default scene.",
-                        )?,
-                ),
-                Transform::from_translation(tile_position),
-                PlacedOn(tile_entity),
-                ChildOf(current_scene),
-            ));
-
-            *preview_viz = Visibility::Hidden;
-        } else {
-            *preview_viz = Visibility::Inherited;
-            // Move the preview cube to the tile position.
-            preview_transform.translation =
-                tile_position + Vec3::Y * 0.25;
+        if distance_sq < closest_distance {
+            closest_distance = distance_sq;
+            closest_tile_data = Some((tile_position, tile_entity));
         }
     }
 
-    Ok(())
+    closest_tile_data
 }
 
 /// Tag component for players who are in placement mode.
 #[derive(Component)]
+#[require(PlacementTile)]
 pub struct InPlacementMode;
 
+/// The free tile a player in placement mode is about to place on.
+#[derive(Component, Default, PartialEq, Debug)]
+pub struct PlacementTile(Option<Entity>);
+
 /// Tag component for preview mesh.
 #[derive(Component, Clone, Copy)]
 pub struct Preview;
@@ -280,7 +361,12 @@ pub struct Projectile {
     pub velocity: Vec3,
     pub damage: f32,
     pub lifetime: f32,
+    /// Speed the target is knocked back with, along the
+    /// direction of the projectile.
+    pub knockback: f32,
+    /// The entity that fired it.
+    pub shooter: Entity,
 }
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Deref, Debug, Clone)]
 pub struct TowerPrefabName(String);
@@ -1,108 +1,103 @@
-use core::time::Duration;
-
 use bevy::animation::AnimationTarget;
 use bevy::prelude::*;
 
+use crate::asset_pipeline::animation_controller::{
+    AnimationControllerAppExt, AnimationControllerSystems,
+    AnimationGraphController, AnimationState, StateClip,
+};
 use crate::asset_pipeline::animation_pipeline::{
-    AnimationGraphMap, NodeMap,
+    AnimationGraphMap, AnimationOwner, NodeMap,
 };
 use crate::asset_pipeline::{AssetState, PrefabAssets, PrefabName};
 
-use super::TowerPrefabName;
 use super::tower_attack::Tower;
+use super::{Projectile, TowerPrefabName};
 
 pub(super) struct TowerAnimationPlugin;
 
 impl Plugin for TowerAnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            setup_animation_graph
-                .run_if(in_state(AssetState::Loaded)),
-        );
+        app.add_animation_controller::<TowerAnimation>()
+            .add_systems(
+                Update,
+                setup_animation_graph
+                    .before(AnimationControllerSystems)
+                    .run_if(in_state(AssetState::Loaded)),
+            )
+            .add_observer(shoot_animation);
     }
 }
 
-// fn movement_animation(
-//     q_enemies: Query<
-//         (&NodeMap, &AnimationTarget, Has<Target>),
-//         With<Tower>,
-//     >,
-//     mut q_animation_players: Query<(
-//         &mut AnimationPlayer,
-//         &mut AnimationTransitions,
-//     )>,
-// ) -> Result {
-//     for (node_map, animation_target, has_target) in q_enemies.iter() {
-//         let (mut anim_player, mut anim_transitions) =
-//             q_animation_players.get_mut(animation_target.player)?;
-
-//         if has_target {
-//             let eat_node = *node_map
-//                 .get("Eat")
-//                 .ok_or("No idle animation found for enemy!")?;
-
-//             if anim_player.is_playing_animation(eat_node) == false {
-//                 anim_transitions
-//                     .play(
-//                         &mut anim_player,
-//                         eat_node,
-//                         Duration::from_millis(200),
-//                     )
-//                     .repeat();
-//             }
-//         } else {
-//             let walk_node = *node_map
-//                 .get("Walk")
-//                 .ok_or("No walking animation found for enemy!")?;
-
-//             if anim_player.is_playing_animation(walk_node) == false {
-//                 anim_transitions
-//                     .play(
-//                         &mut anim_player,
-//                         walk_node,
-//                         Duration::from_millis(200),
-//                     )
-//                     .set_speed(1.5)
-//                     .repeat();
-//             }
-//         }
-//     }
+/// Recoil and reload after firing a projectile.
+fn shoot_animation(
+    trigger: Trigger<OnAdd, Projectile>,
+    q_projectiles: Query<&Projectile>,
+    mut q_towers: Query<
+        &mut AnimationGraphController<TowerAnimation>,
+    >,
+) {
+    let Ok(projectile) = q_projectiles.get(trigger.target()) else {
+        return;
+    };
 
-//     Ok(())
-// }
+    if let Ok(mut controller) = q_towers.get_mut(projectile.shooter) {
+        controller.set(TowerAnimation::Shoot);
+    }
+}
 
 fn setup_animation_graph(
     mut commands: Commands,
-    q_enemies: Query<
+    q_towers: Query<
         (&TowerPrefabName, &AnimationTarget, Entity),
         (With<Tower>, Without<NodeMap>),
     >,
-    mut q_animation_players: Query<&mut AnimationPlayer>,
     prefabs: Res<PrefabAssets>,
 ) -> Result {
-    for (prefab_name, animation_target, entity) in q_enemies.iter() {
+    for (prefab_name, animation_target, entity) in q_towers.iter() {
         let AnimationGraphMap { graph, node_map } = prefabs
             .get_animation(PrefabName::FileName(&prefab_name.0))
-            .ok_or("Unable to get animation for enemy!")?;
-
-        let place_node = *node_map
-            .get("OnPlace")
-            .ok_or("No place animation found for tower!")?;
+            .ok_or("Unable to get animation for tower!")?;
 
-        let mut anim_player =
-            q_animation_players.get_mut(animation_target.player)?;
-        let mut transition = AnimationTransitions::new();
-        transition.play(&mut anim_player, place_node, Duration::ZERO);
-
-        commands.entity(entity).insert(node_map.clone());
+        commands.entity(entity).insert((
+            node_map.clone(),
+            AnimationGraphController::new(TowerAnimation::Place),
+        ));
         commands.entity(animation_target.player).insert((
             AnimationGraphHandle(graph.clone()),
-            transition,
+            AnimationTransitions::new(),
+            AnimationOwner(entity),
         ));
 
-        info!("Setup animation graph for enemy.");
+        info!("Setup animation graph for {}.", prefab_name.0);
     }
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerAnimation {
+    Place,
+    Shoot,
+    Reload,
+}
+
+impl AnimationState for TowerAnimation {
+    fn clip(&self) -> StateClip {
+        match self {
+            TowerAnimation::Place => StateClip::new("OnPlace"),
+            TowerAnimation::Shoot => {
+                StateClip::new("OnShoot").with_transition(50)
+            }
+            TowerAnimation::Reload => {
+                StateClip::new("OnReload").with_transition(100)
+            }
+        }
+    }
+
+    fn next(&self) -> Option<Self> {
+        match self {
+            TowerAnimation::Shoot => Some(TowerAnimation::Reload),
+            _ => None,
+        }
+    }
+}
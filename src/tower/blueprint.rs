@@ -0,0 +1,201 @@
+//! Spawn towers programmatically without going through player
+//! placement.
+//!
+//! ```ignore
+//! commands.queue(
+//!     TowerBlueprint::new(stats)
+//!         .with_prefab("cannon_tower")
+//!         .spawn_at(tile_entity),
+//! );
+//! ```
+
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+
+use crate::asset_pipeline::{CurrentScene, PrefabAssets, PrefabName};
+use crate::inventory::item::StatModifiers;
+use crate::player::PlayerType;
+use crate::tile::{PlacedBy, PlacedOn, Tile};
+
+use super::TowerPrefabName;
+use super::naming::TowerKills;
+use super::ownership::PlacedByPlayer;
+use super::tower_attack::Tower;
+
+/// Prefab used by [`TowerBlueprint::new`].
+const DEFAULT_PREFAB: &str = "gun_tower";
+/// Modifiers can't make towers attack faster than this.
+const MIN_ATTACK_COOLDOWN: f32 = 0.1;
+
+/// Description of a tower to be spawned on a [`Tile`].
+#[derive(Debug, Clone)]
+pub struct TowerBlueprint {
+    prefab_name: String,
+    /// Overrides the [`Tower`] stats of the prefab if any.
+    stats: Option<Tower>,
+    /// Applied on top of the [`Tower`] stats.
+    modifiers: StatModifiers,
+    /// The player that placed the tower if any.
+    owner: Option<PlayerType>,
+}
+
+impl TowerBlueprint {
+    /// Create a blueprint with custom stats using the default
+    /// prefab.
+    pub fn new(stats: Tower) -> Self {
+        Self {
+            prefab_name: DEFAULT_PREFAB.to_string(),
+            stats: Some(stats),
+            modifiers: StatModifiers::default(),
+            owner: None,
+        }
+    }
+
+    /// Create a blueprint that keeps the stats of the prefab.
+    pub fn from_prefab(prefab_name: impl Into<String>) -> Self {
+        Self {
+            prefab_name: prefab_name.into(),
+            stats: None,
+            modifiers: StatModifiers::default(),
+            owner: None,
+        }
+    }
+
+    /// Use a different prefab (the file name in `prefabs/`).
+    pub fn with_prefab(
+        mut self,
+        prefab_name: impl Into<String>,
+    ) -> Self {
+        self.prefab_name = prefab_name.into();
+        self
+    }
+
+    /// Modify the stats of the tower, e.g. from the ingredients
+    /// used to craft it.
+    pub fn with_modifiers(
+        mut self,
+        modifiers: StatModifiers,
+    ) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Mark the tower as placed by the player.
+    pub fn with_owner(mut self, player_type: PlayerType) -> Self {
+        self.owner = Some(player_type);
+        self
+    }
+
+    /// Create a [`Command`] that spawns the tower on the tile.
+    pub fn spawn_at(self, tile: Entity) -> SpawnTower {
+        SpawnTower {
+            blueprint: self,
+            tile,
+        }
+    }
+}
+
+/// Spawns a [`TowerBlueprint`] on a tile, failing if the tile is
+/// invalid or already occupied.
+#[derive(Debug)]
+pub struct SpawnTower {
+    blueprint: TowerBlueprint,
+    tile: Entity,
+}
+
+impl Command<Result> for SpawnTower {
+    fn apply(self, world: &mut World) -> Result {
+        let Self { blueprint, tile } = self;
+        let tile_position = validate_tile(world, tile)?;
+
+        let current_scene = world
+            .resource::<CurrentScene>()
+            .get()
+            .ok_or("No scene to spawn the tower in.")?;
+
+        let scene = world
+            .resource::<PrefabAssets>()
+            .get_gltf(
+                PrefabName::FileName(&blueprint.prefab_name),
+                world.resource::<Assets<Gltf>>(),
+            )
+            .ok_or(format!(
+                "Can't find {} prefab!",
+                blueprint.prefab_name
+            ))?
+            .default_scene
+            .clone()
+            .ok_or("Tower prefab should have a default scene.")?;
+
+        let mut tower = world.spawn((
+            TowerPrefabName(blueprint.prefab_name),
+            TowerKills::default(),
+            SceneRoot(scene),
+            Transform::from_translation(tile_position),
+            PlacedOn(tile),
+            ChildOf(current_scene),
+        ));
+
+        if let Some(owner) = blueprint.owner {
+            tower.insert(PlacedByPlayer(owner));
+        }
+
+        let TowerBlueprint {
+            stats, modifiers, ..
+        } = blueprint;
+        if stats.is_some() || modifiers.is_zero() == false {
+            tower.observe(
+                move |trigger: Trigger<SceneInstanceReady>,
+                      mut commands: Commands,
+                      q_children: Query<&Children>,
+                      mut q_towers: Query<&mut Tower>| {
+                    for entity in
+                        q_children.iter_descendants(trigger.target())
+                    {
+                        let Ok(mut tower) = q_towers.get_mut(entity)
+                        else {
+                            continue;
+                        };
+
+                        if let Some(stats) = &stats {
+                            *tower = stats.clone();
+                        }
+                        tower.damage += modifiers.damage;
+                        tower.range += modifiers.range;
+                        tower.attack_cooldown = (tower.attack_cooldown
+                            + modifiers.attack_cooldown)
+                            .max(MIN_ATTACK_COOLDOWN);
+
+                        commands.entity(entity).insert(modifiers);
+                    }
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Make sure that the entity is an unoccupied [`Tile`], returning
+/// its world position.
+pub fn validate_tile(world: &World, tile: Entity) -> Result<Vec3> {
+    let tile_ref = world.get_entity(tile)?;
+
+    if tile_ref.contains::<Tile>() == false {
+        return Err(format!("{tile} is not a tile!").into());
+    }
+
+    if tile_ref
+        .get::<PlacedBy>()
+        .is_some_and(|p| p.is_empty() == false)
+    {
+        return Err(
+            format!("Tile {tile} is already occupied!").into()
+        );
+    }
+
+    Ok(tile_ref
+        .get::<GlobalTransform>()
+        .ok_or(format!("Tile {tile} has no transform!"))?
+        .translation())
+}
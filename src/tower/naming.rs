@@ -0,0 +1,524 @@
+//! Player given tower names, shown above the tower and in the
+//! kill feed.
+//!
+//! Keyboard players type the name, gamepad players pick one from
+//! the presets, which also include every name typed before.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{
+    InputLock, InputLocks, PlayerAction, TargetAction,
+};
+use crate::asset_pipeline::AssetState;
+use crate::camera_controller::split_screen::{
+    CameraType, QueryCameras,
+};
+use crate::character_controller::CharacterController;
+use crate::decoration::DecorationPlacement;
+use crate::player::PlayerType;
+use crate::storage::Profile;
+use crate::ui::Screen;
+use crate::ui::toast_ui::Toast;
+use crate::ui::world_space::{RelatedWorldUis, WorldUi};
+
+use super::InPlacementMode;
+use super::ownership::{
+    OwnerOnlyTowers, PlacedByPlayer, owner_color,
+};
+use super::tower_attack::TowerKill;
+
+const PRESET_NAMES: &[&str] = &[
+    "Crusty Pete",
+    "Sir Crumbs",
+    "Big Loaf",
+    "Sourdough Sam",
+    "Captain Croissant",
+    "Rye Guy",
+    "Butter Bomb",
+    "Dough Ray",
+];
+const MAX_NAME_LENGTH: usize = 16;
+/// Number of typed names kept in the [`TowerNameProfile`].
+const MAX_CUSTOM_NAMES: usize = 16;
+/// Towers further than this can't be renamed.
+const RENAME_RANGE: f32 = 4.0;
+/// Height of the name label above the tower.
+const LABEL_HEIGHT: f32 = 3.0;
+/// Kill counts announced in the kill feed.
+const KILL_MILESTONES: &[u32] = &[10, 25, 50, 100, 250, 500];
+
+pub(super) struct NamingPlugin;
+
+impl Plugin for NamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TowerNameProfile::load())
+            .add_systems(
+                Update,
+                // Editing first so that the key pressed to start
+                // renaming isn't typed.
+                (edit_tower_name, start_renaming)
+                    .chain()
+                    .run_if(in_state(AssetState::Loaded)),
+            )
+            .add_observer(finish_renaming)
+            .add_observer(update_name_labels)
+            .add_observer(count_tower_kill);
+
+        app.register_type::<TowerName>()
+            .register_type::<TowerKills>();
+    }
+}
+
+/// Start renaming the closest tower the player is allowed to
+/// modify.
+fn start_renaming(
+    mut commands: Commands,
+    q_players: Query<
+        (&GlobalTransform, &PlayerType, &TargetAction, Entity),
+        (
+            With<CharacterController>,
+            Without<Renaming>,
+            Without<InPlacementMode>,
+            Without<DecorationPlacement>,
+        ),
+    >,
+    mut q_actions: Query<(
+        &mut ActionState<PlayerAction>,
+        &mut InputLocks,
+        &InputMap<PlayerAction>,
+    )>,
+    q_towers: Query<(
+        &PlacedByPlayer,
+        &GlobalTransform,
+        Option<&TowerName>,
+        Entity,
+    )>,
+    q_cameras: QueryCameras<Entity>,
+    owner_only: Res<OwnerOnlyTowers>,
+    profile: Res<TowerNameProfile>,
+) -> Result {
+    for (global_transform, player_type, target_action, entity) in
+        q_players.iter()
+    {
+        let (mut action, mut locks, input_map) =
+            q_actions.get_mut(target_action.get())?;
+
+        if action.just_pressed(&PlayerAction::Rename) == false {
+            continue;
+        }
+
+        let translation = global_transform.translation();
+        let Some((tower_name, tower)) = q_towers
+            .iter()
+            .filter(|(placed_by, tower_transform, ..)| {
+                placed_by.can_modify(*player_type, &owner_only)
+                    && tower_transform
+                        .translation()
+                        .distance(translation)
+                        < RENAME_RANGE
+            })
+            .min_by(|(_, a, ..), (_, b, ..)| {
+                a.translation()
+                    .distance_squared(translation)
+                    .total_cmp(
+                        &b.translation()
+                            .distance_squared(translation),
+                    )
+            })
+            .map(|(_, _, name, tower)| (name, tower))
+        else {
+            commands.trigger(Toast {
+                player_type: *player_type,
+                message: "No tower to rename nearby".to_string(),
+            });
+            continue;
+        };
+
+        let renaming = match input_map.gamepad() {
+            Some(_) => Renaming {
+                tower,
+                text: profile.presets()[0].to_string(),
+                preset: Some(0),
+            },
+            None => Renaming {
+                tower,
+                text: tower_name
+                    .map(|n| n.0.clone())
+                    .unwrap_or_default(),
+                preset: None,
+            },
+        };
+
+        let hint = match renaming.preset {
+            Some(_) => "D-pad: Browse  South: Confirm  East: Cancel",
+            None => "Enter: Confirm  Esc: Cancel",
+        };
+
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(20.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            RenameWidget(entity),
+            Pickable::IGNORE,
+            Children::spawn(Spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::axes(
+                        Val::Px(16.0),
+                        Val::Px(8.0),
+                    ),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                BackgroundColor(ZINC_900.with_alpha(0.8).into()),
+                BorderRadius::all(Val::Px(6.0)),
+                Pickable::IGNORE,
+                Children::spawn((
+                    Spawn((
+                        Text::new(renaming.display()),
+                        TextFont::from_font_size(24.0),
+                        RenameText(entity),
+                    )),
+                    Spawn((
+                        Text::new(hint),
+                        TextFont::from_font_size(14.0),
+                        TextColor(ZINC_400.into()),
+                    )),
+                )),
+            ))),
+        ));
+
+        // Typing shouldn't move the player around.
+        locks.lock(InputLock::Renaming, &mut action);
+        commands.entity(entity).insert(renaming);
+    }
+
+    Ok(())
+}
+
+fn edit_tower_name(
+    mut commands: Commands,
+    mut q_players: Query<(&mut Renaming, &TargetAction, Entity)>,
+    q_input_maps: Query<&InputMap<PlayerAction>>,
+    q_gamepads: Query<&Gamepad>,
+    mut q_texts: Query<(&RenameText, &mut Text)>,
+    mut keyboard_inputs: EventReader<KeyboardInput>,
+    mut profile: ResMut<TowerNameProfile>,
+) -> Result {
+    // Always read to not type keys pressed before renaming.
+    let keys = keyboard_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .map(|input| input.logical_key.clone())
+        .collect::<Vec<_>>();
+
+    for (mut renaming, target_action, entity) in q_players.iter_mut()
+    {
+        let input_map = q_input_maps.get(target_action.get())?;
+
+        let edit = match input_map.gamepad() {
+            Some(gamepad) => {
+                renaming.browse(q_gamepads.get(gamepad)?, &profile)
+            }
+            None => renaming.type_keys(&keys),
+        };
+
+        match edit {
+            NameEdit::Editing => {
+                for (rename_text, mut text) in q_texts.iter_mut() {
+                    if rename_text.0 == entity {
+                        text.0 = renaming.display();
+                    }
+                }
+                continue;
+            }
+            NameEdit::Confirm => {
+                let name = renaming.text.trim();
+                if name.is_empty() == false {
+                    // The tower could be gone by now.
+                    commands
+                        .entity(renaming.tower)
+                        .try_insert(TowerName(name.to_string()));
+
+                    if renaming.preset.is_none() {
+                        profile.remember(name);
+                        profile.save();
+                    }
+                }
+            }
+            NameEdit::Cancel => {}
+        }
+
+        commands.entity(entity).remove::<Renaming>();
+    }
+
+    Ok(())
+}
+
+/// Close the widget and give the controls back, also when the
+/// player is despawned mid-way.
+fn finish_renaming(
+    trigger: Trigger<OnRemove, Renaming>,
+    mut commands: Commands,
+    q_target_actions: Query<&TargetAction>,
+    mut q_actions: Query<(
+        &mut ActionState<PlayerAction>,
+        &mut InputLocks,
+    )>,
+    q_widgets: Query<(&RenameWidget, Entity)>,
+) {
+    let entity = trigger.target();
+
+    for (widget, widget_entity) in q_widgets.iter() {
+        if widget.0 == entity {
+            commands.entity(widget_entity).try_despawn();
+        }
+    }
+
+    if let Ok((mut action, mut locks)) = q_target_actions
+        .get(entity)
+        .and_then(|t| q_actions.get_mut(t.get()))
+    {
+        locks.release(InputLock::Renaming, &mut action);
+    }
+}
+
+/// Spawn (or update) the name label above the tower on both
+/// viewports.
+fn update_name_labels(
+    trigger: Trigger<OnInsert, TowerName>,
+    mut commands: Commands,
+    q_towers: Query<(&TowerName, Option<&PlacedByPlayer>)>,
+    q_world_uis: Query<&RelatedWorldUis>,
+    mut q_labels: Query<&mut Text, With<TowerNameLabel>>,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    let entity = trigger.target();
+    let (name, placed_by) = q_towers.get(entity)?;
+
+    let mut updated = false;
+    for world_ui in
+        q_world_uis.get(entity).iter().flat_map(|w| w.iter())
+    {
+        if let Ok(mut text) = q_labels.get_mut(world_ui) {
+            text.0 = name.0.clone();
+            updated = true;
+        }
+    }
+
+    if updated {
+        return Ok(());
+    }
+
+    let color =
+        placed_by.map(|p| owner_color(p.0)).unwrap_or(Color::WHITE);
+
+    for camera_type in [CameraType::A, CameraType::B] {
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Text::new(name.0.clone()),
+            TextFont::from_font_size(16.0),
+            TextColor(color),
+            TextShadow::default(),
+            WorldUi::new(entity)
+                .with_world_offset(Vec3::Y * LABEL_HEIGHT),
+            UiTargetCamera(q_cameras.get(camera_type)?),
+            TowerNameLabel,
+            Pickable::IGNORE,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Count the kill on the tower and announce named towers
+/// reaching a milestone.
+fn count_tower_kill(
+    trigger: Trigger<TowerKill>,
+    mut commands: Commands,
+    q_child_ofs: Query<&ChildOf>,
+    mut q_kills: Query<(&mut TowerKills, Option<&TowerName>)>,
+) {
+    let entity = trigger.target();
+
+    // The tower stats live inside the tower scene.
+    let Some(tower) = core::iter::once(entity)
+        .chain(q_child_ofs.iter_ancestors(entity))
+        .find(|e| q_kills.contains(*e))
+    else {
+        return;
+    };
+
+    let Ok((mut kills, name)) = q_kills.get_mut(tower) else {
+        return;
+    };
+
+    kills.0 += 1;
+
+    let Some(name) = name else {
+        return;
+    };
+
+    if KILL_MILESTONES.contains(&kills.0) {
+        for player_type in [PlayerType::A, PlayerType::B] {
+            commands.trigger(Toast {
+                player_type,
+                message: format!(
+                    "'{}' destroyed {} enemies",
+                    name.0, kills.0
+                ),
+            });
+        }
+    }
+}
+
+/// Name given to a tower by a player.
+#[derive(Component, Reflect, Deref, Debug, Clone)]
+#[reflect(Component)]
+pub struct TowerName(pub String);
+
+/// Number of enemies defeated by the tower.
+#[derive(Component, Reflect, Deref, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct TowerKills(u32);
+
+/// Added to players while they are renaming a tower.
+#[derive(Component, Debug)]
+pub struct Renaming {
+    tower: Entity,
+    text: String,
+    /// Index into [`TowerNameProfile::presets`] for gamepads.
+    preset: Option<usize>,
+}
+
+impl Renaming {
+    fn display(&self) -> String {
+        match self.preset {
+            Some(_) => format!("< {} >", self.text),
+            None => format!("{}_", self.text),
+        }
+    }
+
+    fn type_keys(&mut self, keys: &[Key]) -> NameEdit {
+        for key in keys {
+            match key {
+                Key::Enter => return NameEdit::Confirm,
+                Key::Escape => return NameEdit::Cancel,
+                Key::Backspace => {
+                    self.text.pop();
+                }
+                Key::Space => self.push(' '),
+                Key::Character(chars) => {
+                    for c in chars.chars() {
+                        self.push(c);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        NameEdit::Editing
+    }
+
+    fn push(&mut self, c: char) {
+        if c.is_control() == false
+            && self.text.chars().count() < MAX_NAME_LENGTH
+        {
+            self.text.push(c);
+        }
+    }
+
+    fn browse(
+        &mut self,
+        gamepad: &Gamepad,
+        profile: &TowerNameProfile,
+    ) -> NameEdit {
+        if gamepad.just_pressed(GamepadButton::South) {
+            return NameEdit::Confirm;
+        }
+        if gamepad.just_pressed(GamepadButton::East) {
+            return NameEdit::Cancel;
+        }
+
+        let presets = profile.presets();
+        let index = self.preset.unwrap_or_default();
+
+        let index = if gamepad.just_pressed(GamepadButton::DPadRight)
+        {
+            (index + 1) % presets.len()
+        } else if gamepad.just_pressed(GamepadButton::DPadLeft) {
+            (index + presets.len() - 1) % presets.len()
+        } else {
+            return NameEdit::Editing;
+        };
+
+        self.preset = Some(index);
+        self.text = presets[index].to_string();
+
+        NameEdit::Editing
+    }
+}
+
+enum NameEdit {
+    Editing,
+    Confirm,
+    Cancel,
+}
+
+/// Names typed by players, offered as presets afterwards.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct TowerNameProfile {
+    custom: Vec<String>,
+}
+
+impl Profile for TowerNameProfile {
+    const KEY: &'static str = "tower_names.profile.ron";
+}
+
+impl TowerNameProfile {
+    /// The latest typed names followed by [`PRESET_NAMES`].
+    fn presets(&self) -> Vec<&str> {
+        self.custom
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .chain(PRESET_NAMES.iter().copied())
+            .collect()
+    }
+
+    fn remember(&mut self, name: &str) {
+        self.custom.retain(|n| n != name);
+        self.custom.push(name.to_string());
+
+        if self.custom.len() > MAX_CUSTOM_NAMES {
+            self.custom.remove(0);
+        }
+    }
+}
+
+/// The rename widget of a player.
+#[derive(Component)]
+struct RenameWidget(Entity);
+
+/// The text of the name being edited by a player.
+#[derive(Component)]
+struct RenameText(Entity);
+
+#[derive(Component)]
+struct TowerNameLabel;
@@ -0,0 +1,144 @@
+//! Track which player placed each tower, color-coded with a badge
+//! above the tower.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+
+use crate::player::PlayerType;
+use crate::ui::Screen;
+
+/// Height of the owner badge above the tower.
+const BADGE_HEIGHT: f32 = 2.5;
+
+pub(super) struct OwnershipPlugin;
+
+impl Plugin for OwnershipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlacementStats>()
+            .init_resource::<OwnerOnlyTowers>()
+            .add_systems(Startup, setup_badge_assets)
+            .add_systems(OnEnter(Screen::EnterLevel), reset_stats)
+            .add_observer(setup_owned_tower);
+
+        app.register_type::<PlacedByPlayer>()
+            .register_type::<OwnerOnlyTowers>();
+    }
+}
+
+fn setup_badge_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut material = |player_type: PlayerType| {
+        materials.add(StandardMaterial {
+            base_color: owner_color(player_type),
+            unlit: true,
+            ..default()
+        })
+    };
+
+    commands.insert_resource(BadgeAssets {
+        mesh: meshes.add(Cylinder::new(0.2, 0.05)),
+        a: material(PlayerType::A),
+        b: material(PlayerType::B),
+    });
+}
+
+fn reset_stats(mut stats: ResMut<PlacementStats>) {
+    *stats = PlacementStats::default();
+}
+
+/// Spawn the owner badge and count the placement.
+fn setup_owned_tower(
+    trigger: Trigger<OnAdd, PlacedByPlayer>,
+    mut commands: Commands,
+    q_owners: Query<&PlacedByPlayer>,
+    badge_assets: Res<BadgeAssets>,
+    mut stats: ResMut<PlacementStats>,
+) -> Result {
+    let entity = trigger.target();
+    let owner = **q_owners.get(entity)?;
+
+    *stats.get_mut(owner) += 1;
+
+    commands.entity(entity).with_child((
+        Mesh3d(badge_assets.mesh.clone()),
+        MeshMaterial3d(badge_assets.get(owner)),
+        Transform::from_xyz(0.0, BADGE_HEIGHT, 0.0),
+    ));
+
+    Ok(())
+}
+
+pub fn owner_color(player_type: PlayerType) -> Color {
+    match player_type {
+        PlayerType::A => AMBER_400.into(),
+        PlayerType::B => EMERALD_400.into(),
+    }
+}
+
+/// The player that placed the tower.
+#[derive(Component, Reflect, Deref, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct PlacedByPlayer(pub PlayerType);
+
+impl PlacedByPlayer {
+    /// Returns `true` if the player is allowed to sell or upgrade
+    /// the tower.
+    pub fn can_modify(
+        &self,
+        player_type: PlayerType,
+        owner_only: &OwnerOnlyTowers,
+    ) -> bool {
+        owner_only.0 == false || self.0 == player_type
+    }
+}
+
+/// Restrict selling and upgrading of towers to their owners,
+/// checked through [`PlacedByPlayer::can_modify`].
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct OwnerOnlyTowers(pub bool);
+
+/// Number of towers placed by each player in the current level.
+#[derive(Resource, Default, Debug)]
+pub struct PlacementStats {
+    pub a: u32,
+    pub b: u32,
+}
+
+impl PlacementStats {
+    pub fn get(&self, player_type: PlayerType) -> u32 {
+        match player_type {
+            PlayerType::A => self.a,
+            PlayerType::B => self.b,
+        }
+    }
+
+    fn get_mut(&mut self, player_type: PlayerType) -> &mut u32 {
+        match player_type {
+            PlayerType::A => &mut self.a,
+            PlayerType::B => &mut self.b,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct BadgeAssets {
+    mesh: Handle<Mesh>,
+    a: Handle<StandardMaterial>,
+    b: Handle<StandardMaterial>,
+}
+
+impl BadgeAssets {
+    fn get(
+        &self,
+        player_type: PlayerType,
+    ) -> Handle<StandardMaterial> {
+        match player_type {
+            PlayerType::A => self.a.clone(),
+            PlayerType::B => self.b.clone(),
+        }
+    }
+}
@@ -1,13 +1,21 @@
 use avian3d::prelude::*;
+use bevy::color::palettes::tailwind::*;
 use bevy::ecs::component::{ComponentHooks, Immutable, StorageType};
 use bevy::prelude::*;
 
 use crate::asset_pipeline::{
-    AssetState, CurrentScene, PrefabAssets, PrefabName,
+    AssetState, CurrentScene, PrefabAssets, PrefabId,
 };
-use crate::enemy::{Enemy, IsEnemy, Path};
+use crate::damage::{DamageEvent, DamageKind, Killed};
+use crate::death::Dying;
+use crate::enemy::spatial_grid::EnemyGrid;
+use crate::enemy::time_slice::{AiBudget, TimeSlicer};
+use crate::enemy::{Enemy, Path};
+use crate::environment::Environment;
 use crate::physics::GameLayer;
 use crate::player::player_attack::AttackCooldown;
+use crate::tile::PlacedOn;
+use crate::vfx::trail::Trail;
 
 use super::{Projectile, TowerPrefabName};
 
@@ -28,10 +36,14 @@ impl Plugin for TowerAttackPlugin {
                     .chain(),
                 handle_projectile_collisions,
                 projectile_movement,
-                despawn_on_death.run_if(in_state(AssetState::Loaded)),
+                update_invulnerability,
             ),
         );
 
+        app.add_observer(drop_corn_on_kill)
+            .add_observer(credit_tower_kill)
+            .add_observer(kill_tower_scene);
+
         app.register_type::<Tower>().register_type::<MaxHealth>();
     }
 }
@@ -56,56 +68,60 @@ fn check_target_range(
     Ok(())
 }
 
-/// Find and target the best enemy based on [`Path`] length (lower is better).
+/// Find and target the best enemy based on [`TargetPriority`]
+/// (higher is better) and then [`Path`] length (lower is better).
+/// Towers take turns within the [`AiBudget`].
 fn find_target(
     mut commands: Commands,
-    q_towers: Query<(&Tower, Entity), Without<Target>>,
-    q_collider_ofs: Query<&ColliderOf>,
-    q_enemies: Query<(&Path, Entity), With<Enemy>>,
-    q_global_transforms: Query<&GlobalTransform>,
-    spatial_query: SpatialQuery,
-) -> Result {
-    for (tower, tower_entity) in q_towers.iter() {
-        let tower_position =
-            q_global_transforms.get(tower_entity)?.translation();
-
-        // Find enemies in range using shape intersection.
-        let detection_sphere = Collider::sphere(tower.range);
-        let intersections = spatial_query.shape_intersections(
-            &detection_sphere,
-            tower_position,
-            Quat::IDENTITY,
-            &SpatialQueryFilter::default()
-                .with_mask(GameLayer::Enemy),
-        );
-
-        // Find best target from intersected entities.
-        let mut best_target = None;
-        let mut least_path = usize::MAX;
-
-        for entity in intersections {
-            let Ok((path, enemy_entity)) = q_enemies.get(
-                q_collider_ofs
-                    .get(entity)
-                    .map(|c| c.body)
-                    .unwrap_or(entity),
-            ) else {
-                continue;
-            };
-
-            // Check if this enemy has better priority
-            if path.len() < least_path {
-                least_path = path.len();
-                best_target = Some(enemy_entity);
+    q_towers: Query<
+        (&Tower, &GlobalTransform, Entity),
+        Without<Target>,
+    >,
+    q_enemies: Query<(&Path, Option<&TargetPriority>), With<Enemy>>,
+    enemy_grid: Res<EnemyGrid>,
+    budget: Res<AiBudget>,
+    mut slicer: Local<TimeSlicer>,
+) {
+    let towers = q_towers.iter();
+
+    slicer.run(
+        towers,
+        budget.0,
+        |(tower, global_transform, tower_entity)| {
+            // Find best target from enemies in range.
+            let mut best_target = None;
+            let mut highest_priority = i32::MIN;
+            let mut least_path = usize::MAX;
+
+            for (enemy_entity, _) in enemy_grid.within_radius(
+                global_transform.translation(),
+                tower.range,
+            ) {
+                let Ok((path, priority)) =
+                    q_enemies.get(enemy_entity)
+                else {
+                    continue;
+                };
+
+                let priority =
+                    priority.map(|p| p.0).unwrap_or_default();
+
+                // Check if this enemy has better priority
+                if priority > highest_priority
+                    || (priority == highest_priority
+                        && path.len() < least_path)
+                {
+                    highest_priority = priority;
+                    least_path = path.len();
+                    best_target = Some(enemy_entity);
+                }
             }
-        }
 
-        if let Some(target) = best_target {
-            commands.entity(tower_entity).insert(Target(target));
-        }
-    }
-
-    Ok(())
+            if let Some(target) = best_target {
+                commands.entity(tower_entity).insert(Target(target));
+            }
+        },
+    );
 }
 
 /// Rotate towers to face their targets.
@@ -164,12 +180,14 @@ fn tower_shooting(
             &mut AttackCooldown,
             &Target,
             &TowerPrefabName,
+            Entity,
         ),
         Without<Enemy>,
     >,
     q_enemies: Query<&GlobalTransform, With<Enemy>>,
     prefabs: Res<PrefabAssets>,
     gltfs: Res<Assets<Gltf>>,
+    environment: Res<Environment>,
 ) -> Result {
     // Minimum facing accuracy to fire.
     const MIN_FACING_ACCURACY: f32 = 0.9;
@@ -181,6 +199,7 @@ fn tower_shooting(
         mut cooldown,
         target,
         prefab_name,
+        entity,
     ) in q_towers.iter_mut()
     {
         if cooldown.0 > 0.0 {
@@ -206,28 +225,40 @@ fn tower_shooting(
         let direction =
             (target_position - projectile_start).normalize();
 
-        let model_name = match prefab_name.0.as_ref() {
-            "gun_tower" => "popcorn",
-            "cannon_tower" => "roasted_corn",
+        let (model_id, trail) = match prefab_name.0.as_ref() {
+            "gun_tower" => (
+                PrefabId::Popcorn,
+                Trail::new(YELLOW_100.with_alpha(0.8)),
+            ),
+            "cannon_tower" => (
+                PrefabId::RoastedCorn,
+                Trail::new(ORANGE_400.with_alpha(0.8))
+                    .with_width(0.3)
+                    .with_lifetime(0.3),
+            ),
             _ => return Err("Unrecognized tower...".into()),
         };
 
         let handle = prefabs
-            .get_gltf(PrefabName::FileName(model_name), &gltfs)
-            .ok_or(format!("Can't find {model_name} prefab!"))?
+            .get_gltf(model_id.into(), &gltfs)
+            .ok_or(format!("Can't find {model_id:?} prefab!"))?
             .default_scene
             .clone()
             .ok_or(format!(
-                "{model_name} prefab should have a default scene."
+                "{model_id:?} prefab should have a default scene."
             ))?;
 
         commands.spawn((
             Transform::from_translation(projectile_start),
             Projectile {
                 velocity: direction * tower.projectile_speed,
-                damage: tower.damage,
+                damage: tower.damage
+                    * environment.tower_damage(prefab_name),
                 lifetime: 3.0,
+                knockback: tower.knockback,
+                shooter: entity,
             },
+            trail,
             Visibility::Inherited,
             Children::spawn(Spawn((
                 SceneRoot(handle),
@@ -248,84 +279,143 @@ fn handle_projectile_collisions(
     mut collision_events: EventReader<CollisionStarted>,
     q_projectiles: Query<&Projectile>,
     q_collider_ofs: Query<&ColliderOf>,
-    q_is_enemy: Query<(), With<IsEnemy>>,
-    mut q_healths: Query<&mut Health>,
+    q_has_health: Query<(), With<Health>>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
     for CollisionStarted(entity1, entity2) in collision_events.read()
     {
-        // Check if one is projectile, other is enemy
-        let (projectile_entity, enemy_entity) = if q_projectiles
-            .contains(*entity1)
-            && q_is_enemy.contains(*entity2)
-        {
-            (*entity1, *entity2)
-        } else if q_projectiles.contains(*entity2)
-            && q_is_enemy.contains(*entity1)
-        {
-            (*entity2, *entity1)
-        } else {
+        // Check if one is projectile, the other is whatever its
+        // collision layers let it hit.
+        let (projectile_entity, other) =
+            if q_projectiles.contains(*entity1) {
+                (*entity1, *entity2)
+            } else if q_projectiles.contains(*entity2) {
+                (*entity2, *entity1)
+            } else {
+                continue;
+            };
+
+        let Ok(projectile) = q_projectiles.get(projectile_entity)
+        else {
             continue;
         };
 
-        // Get projectile data and apply damage
-        if let Ok(projectile) = q_projectiles.get(projectile_entity) {
-            let enemy_entity = q_collider_ofs
-                .get(enemy_entity)
+        // Colliders without their own health pass the hit to
+        // their body.
+        let target = match q_has_health.contains(other) {
+            true => other,
+            false => q_collider_ofs
+                .get(other)
                 .map(|c| c.body)
-                .unwrap_or(enemy_entity);
-
-            if let Ok(mut health) = q_healths.get_mut(enemy_entity) {
-                health.0 -= projectile.damage;
-            }
+                .unwrap_or(other),
+        };
 
-            // Despawn projectile after hit
-            commands.entity(projectile_entity).despawn();
+        // Fly past whoever fired it.
+        if target == projectile.shooter {
+            continue;
         }
+
+        damage_events.write(DamageEvent {
+            source: projectile.shooter,
+            target,
+            amount: projectile.damage,
+            kind: DamageKind::Projectile,
+            knockback: projectile
+                .velocity
+                .with_y(0.0)
+                .normalize_or_zero()
+                * projectile.knockback,
+        });
+
+        // Despawn projectile after hit
+        commands.entity(projectile_entity).despawn();
     }
 }
 
-fn despawn_on_death(
+/// Drop corn for the players where the enemy got killed.
+fn drop_corn_on_kill(
+    trigger: Trigger<Killed>,
     mut commands: Commands,
-    q_healths: Query<
-        (&Health, &GlobalTransform, Has<Enemy>, Entity),
-        Changed<Health>,
-    >,
+    q_enemies: Query<&GlobalTransform, With<Enemy>>,
     prefabs: Res<PrefabAssets>,
     gltfs: Res<Assets<Gltf>>,
     current_scene: Res<CurrentScene>,
 ) -> Result {
+    let Ok(global_transform) = q_enemies.get(trigger.target()) else {
+        return Ok(());
+    };
     let Some(current_scene) = current_scene.get() else {
         return Ok(());
     };
 
-    for (health, global_transform, is_enemy, entity) in
-        q_healths.iter()
+    let scene = prefabs
+        .get_gltf(PrefabId::Corn.into(), &gltfs)
+        .ok_or("Can't find corn prefab!")?
+        .default_scene
+        .clone()
+        .ok_or("Corn prefab shoould have a default scene.")?;
+
+    // Spawn new corns for the player.
+    commands.spawn((
+        SceneRoot(scene),
+        Transform::from_translation(
+            global_transform.translation() + Vec3::Y * 1.5,
+        ),
+        ChildOf(current_scene),
+    ));
+
+    Ok(())
+}
+
+/// Credit the tower with the finishing blow on an enemy.
+fn credit_tower_kill(
+    trigger: Trigger<Killed>,
+    mut commands: Commands,
+    q_towers: Query<(), With<Tower>>,
+    q_enemies: Query<(), With<Enemy>>,
+) {
+    let source = trigger.event().source;
+
+    if q_enemies.contains(trigger.target())
+        && q_towers.contains(source)
     {
-        if health.0 > 0.0 {
-            continue;
-        }
-        commands.entity(entity).despawn();
-
-        if is_enemy {
-            let scene = prefabs
-                .get_gltf(PrefabName::FileName("corn"), &gltfs)
-                .ok_or("Can't find corn prefab!")?
-                .default_scene
-                .clone()
-                .ok_or("Corn prefab shoould have a default scene.")?;
-
-            // Spawn new corns for the player.
-            commands.spawn((
-                SceneRoot(scene),
-                Transform::from_translation(
-                    global_transform.translation() + Vec3::Y * 1.5,
-                ),
-                ChildOf(current_scene),
-            ));
-        }
+        commands.trigger_targets(TowerKill, source);
     }
+}
 
-    Ok(())
+/// The tower lives inside the tower scene, the whole scene dies
+/// along with it.
+fn kill_tower_scene(
+    trigger: Trigger<Killed>,
+    mut commands: Commands,
+    q_towers: Query<(), With<Tower>>,
+    q_child_ofs: Query<&ChildOf>,
+    q_placed_ons: Query<(), With<PlacedOn>>,
+) {
+    let entity = trigger.target();
+    if q_towers.contains(entity) == false {
+        return;
+    }
+
+    if let Some(root) = q_child_ofs
+        .iter_ancestors(entity)
+        .find(|e| q_placed_ons.contains(*e))
+    {
+        commands.entity(root).insert(Dying::default());
+    }
+}
+
+/// Tick down and remove [`Invulnerable`] once it runs out.
+fn update_invulnerability(
+    mut commands: Commands,
+    mut q_invulnerables: Query<(&mut Invulnerable, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut invulnerable, entity) in q_invulnerables.iter_mut() {
+        if invulnerable.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
 }
 
 /// Move projectiles.
@@ -355,8 +445,12 @@ fn projectile_movement(
     }
 }
 
+/// Triggered on a [`Tower`] when its projectile defeats an enemy.
+#[derive(Event, Debug)]
+pub struct TowerKill;
+
 /// Tower component with stats only.
-#[derive(Component, Reflect, Debug)]
+#[derive(Component, Reflect, Debug, Clone)]
 #[reflect(Component)]
 #[require(
     AttackCooldown,
@@ -371,6 +465,12 @@ pub struct Tower {
     pub damage: f32,
     pub attack_cooldown: f32,
     pub projectile_speed: f32,
+    #[reflect(default = "default_tower_knockback")]
+    pub knockback: f32,
+}
+
+fn default_tower_knockback() -> f32 {
+    1.5
 }
 
 /// Health component for entities that can take damage
@@ -401,6 +501,17 @@ impl Component for MaxHealth {
 #[derive(Component, Deref, DerefMut, Debug)]
 pub struct Health(pub f32);
 
+/// Entities with this component will not take any damage
+/// until the timer finishes.
+#[derive(Component, Deref, DerefMut, Debug)]
+pub struct Invulnerable(Timer);
+
+impl Invulnerable {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
 /// Relationship components for tower targeting
 #[derive(Component, Deref, Debug)]
 #[relationship(relationship_target = TargetsOf)]
@@ -409,3 +520,8 @@ pub struct Target(Entity);
 #[derive(Component, Deref, Default, Debug)]
 #[relationship_target(relationship = Target)]
 pub struct TargetsOf(Vec<Entity>);
+
+/// Towers prefer enemies with higher priority regardless of
+/// their [`Path`] length. Enemies without it have a priority of 0.
+#[derive(Component, Deref, Default, Debug)]
+pub struct TargetPriority(pub i32);
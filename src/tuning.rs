@@ -0,0 +1,253 @@
+use bevy::asset::{AssetLoader, io::Reader};
+use bevy::asset::{AsyncReadExt, LoadContext};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::asset_pipeline::loading::TrackedAssets;
+use crate::enemy::aggro::Threat;
+use crate::interaction::InteractionPlayer;
+use crate::inventory::ItemMagnet;
+use crate::inventory::item::StatModifiers;
+use crate::tower::TowerPrefabName;
+use crate::tower::tower_attack::Tower;
+
+/// Central tuning of the targeting and aggro radii, loaded from
+/// "tuning.tuning.ron" and hot reloaded at runtime.
+pub(super) struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Tuning>()
+            .init_asset_loader::<TuningAssetLoader>()
+            .add_systems(PreStartup, load_tuning)
+            .add_systems(
+                Update,
+                (
+                    sync_tuning,
+                    apply_tuning.run_if(resource_exists::<Tuning>),
+                )
+                    .chain(),
+            );
+
+        app.register_type::<Tuning>();
+
+        #[cfg(feature = "dev")]
+        app.add_systems(
+            bevy_inspector_egui::bevy_egui::EguiContextPass,
+            tuning_panel.run_if(resource_exists::<Tuning>),
+        );
+    }
+}
+
+fn load_tuning(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut tracked_assets: ResMut<TrackedAssets>,
+) {
+    let handle = asset_server.load("tuning.tuning.ron");
+    tracked_assets.track(handle.clone());
+    commands.insert_resource(TuningHandle(handle));
+}
+
+/// Copy the [`Tuning`] asset into the resource whenever it's
+/// (re)loaded.
+fn sync_tuning(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<Tuning>>,
+    tunings: Res<Assets<Tuning>>,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::LoadedWithDependencies { id }
+        | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+
+        if let Some(tuning) = tunings.get(*id) {
+            info!("Applying tuning: {tuning:?}");
+            commands.insert_resource(tuning.clone());
+        }
+    }
+}
+
+/// Apply the [`Tuning`] to newly added components or to every
+/// component when the tuning changes.
+fn apply_tuning(
+    mut commands: Commands,
+    tuning: Res<Tuning>,
+    mut q_towers: Query<(
+        &mut Tower,
+        &TowerPrefabName,
+        Option<&StatModifiers>,
+    )>,
+    mut q_interaction_players: Query<&mut InteractionPlayer>,
+    mut q_threats: Query<(
+        &mut Threat,
+        Option<&AuthoredThreatRadius>,
+        Entity,
+    )>,
+    mut q_magnets: Query<&mut ItemMagnet>,
+) {
+    let changed = tuning.is_changed();
+
+    for (mut tower, prefab_name, modifiers) in q_towers.iter_mut() {
+        if changed || tower.is_added() {
+            if let Some(range) =
+                tuning.tower_ranges.get(&**prefab_name)
+            {
+                // Keep the range modifier from crafting.
+                tower.range =
+                    *range + modifiers.map_or(0.0, |m| m.range);
+            }
+        }
+    }
+
+    for mut player in q_interaction_players.iter_mut() {
+        if changed || player.is_added() {
+            player.range = tuning.interaction_range;
+            player.boundary_range = tuning.interaction_boundary_range;
+        }
+    }
+
+    for (mut threat, authored, entity) in q_threats.iter_mut() {
+        if changed || threat.is_added() {
+            // Scale relative to the radius the threat was spawned
+            // with, decoys author their own.
+            let authored = match authored {
+                Some(authored) => authored.0,
+                None => {
+                    commands
+                        .entity(entity)
+                        .insert(AuthoredThreatRadius(threat.radius));
+                    threat.radius
+                }
+            };
+
+            threat.radius = authored * tuning.aggro_radius_scale;
+        }
+    }
+
+    for mut magnet in q_magnets.iter_mut() {
+        if changed || magnet.is_added() {
+            magnet.radius = tuning.magnet_radius;
+        }
+    }
+}
+
+/// Sliders for tuning the radii without editing the asset.
+///
+/// *Note: Changes made here are overwritten when the asset
+/// gets reloaded.*
+#[cfg(feature = "dev")]
+fn tuning_panel(
+    mut contexts: bevy_inspector_egui::bevy_egui::EguiContexts,
+    mut tuning: ResMut<Tuning>,
+) {
+    use bevy_inspector_egui::egui;
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Only mark the tuning as changed when a slider is dragged.
+    let mut changed = false;
+
+    egui::Window::new("Tuning").show(ctx, |ui| {
+        let tuning = tuning.bypass_change_detection();
+
+        let mut tower_names =
+            tuning.tower_ranges.keys().cloned().collect::<Vec<_>>();
+        tower_names.sort();
+        for name in tower_names {
+            if let Some(range) = tuning.tower_ranges.get_mut(&name) {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(range, 0.0..=20.0)
+                            .text(format!("{name} range")),
+                    )
+                    .changed();
+            }
+        }
+
+        for (value, text) in [
+            (&mut tuning.interaction_range, "Interaction range"),
+            (
+                &mut tuning.interaction_boundary_range,
+                "Interaction boundary range",
+            ),
+            (&mut tuning.magnet_radius, "Magnet radius"),
+        ] {
+            changed |= ui
+                .add(egui::Slider::new(value, 0.0..=20.0).text(text))
+                .changed();
+        }
+
+        changed |= ui
+            .add(
+                egui::Slider::new(
+                    &mut tuning.aggro_radius_scale,
+                    0.0..=4.0,
+                )
+                .text("Aggro radius scale"),
+            )
+            .changed();
+    });
+
+    if changed {
+        tuning.set_changed();
+    }
+}
+
+#[derive(Resource)]
+pub struct TuningHandle(#[allow(dead_code)] Handle<Tuning>);
+
+/// Targeting and aggro radii of the game.
+#[derive(Asset, Resource, Reflect, Deserialize, Debug, Clone)]
+#[reflect(Resource)]
+pub struct Tuning {
+    /// [`Tower::range`] of each tower prefab.
+    pub tower_ranges: HashMap<String, f32>,
+    /// [`InteractionPlayer::range`].
+    pub interaction_range: f32,
+    /// [`InteractionPlayer::boundary_range`].
+    pub interaction_boundary_range: f32,
+    /// Multiplier of each [`Threat::radius`] within which enemies
+    /// get aggroed.
+    pub aggro_radius_scale: f32,
+    /// [`ItemMagnet::radius`].
+    pub magnet_radius: f32,
+}
+
+/// The [`Threat::radius`] before any [`Tuning`] was applied.
+#[derive(Component, Debug)]
+struct AuthoredThreatRadius(f32);
+
+#[derive(Default)]
+pub struct TuningAssetLoader;
+
+impl AssetLoader for TuningAssetLoader {
+    type Asset = Tuning;
+
+    type Settings = ();
+
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut ron_str = String::new();
+        reader.read_to_string(&mut ron_str).await?;
+
+        // Don't panic, a typo shouldn't crash a hot reload.
+        ron::from_str::<Tuning>(&ron_str)
+            .map_err(std::io::Error::other)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
@@ -6,12 +6,31 @@ use bevy::window::{CursorGrabMode, PrimaryWindow};
 use widgets::button::{ButtonBackground, LabelButton};
 
 use crate::asset_pipeline::{AssetState, SceneAssetsLoader};
+use crate::camera_controller::split_screen::ViewportLayout;
+use crate::camera_controller::viewport_style::ViewportStyles;
+use crate::level::{CurrentLevel, GameMode, LevelProgress};
+use crate::performance::GraphicsPreset;
+use crate::player::{PlayerType, SelectedCharacter};
 
+mod breath_ui;
 mod game_over_ui;
 mod health_bar_ui;
+mod highlight_reel_ui;
+mod hotbar_ui;
+mod interaction_prompt_ui;
 mod inventory_ui;
+pub mod item_tooltip;
+mod loading_ui;
+mod lock_on_ui;
+mod perks_ui;
 mod player_mark_ui;
+mod recipe_book_ui;
+mod scoreboard_ui;
+mod stamina_ui;
+pub mod toast_ui;
 mod wave_countdown_ui;
+mod wave_preview_ui;
+mod weapon_ui;
 pub mod widgets;
 pub mod world_space;
 
@@ -23,10 +42,25 @@ impl Plugin for UiPlugin {
             world_space::WorldSpaceUiPlugin,
             widgets::WidgetsPlugin,
             inventory_ui::InventoryUiPlugin,
+            loading_ui::LoadingUiPlugin,
+            hotbar_ui::HotbarUiPlugin,
             health_bar_ui::HealthBarUiPlugin,
+            interaction_prompt_ui::InteractionPromptUiPlugin,
+            lock_on_ui::LockOnUiPlugin,
             player_mark_ui::PlayerMarkUiPlugin,
+            recipe_book_ui::RecipeBookUiPlugin,
+            stamina_ui::StaminaUiPlugin,
             game_over_ui::GameOverUiPlugin,
+            highlight_reel_ui::HighlightReelUiPlugin,
+            toast_ui::ToastUiPlugin,
+        ))
+        .add_plugins((
             wave_countdown_ui::WaveCountdownUiPlugin,
+            wave_preview_ui::WavePreviewUiPlugin,
+            weapon_ui::WeaponUiPlugin,
+            perks_ui::PerksUiPlugin,
+            scoreboard_ui::ScoreboardUiPlugin,
+            breath_ui::BreathUiPlugin,
         ));
 
         app.add_sub_state::<Screen>()
@@ -41,12 +75,16 @@ impl Plugin for UiPlugin {
             .add_systems(
                 OnEnter(Screen::EnterLevel),
                 (
-                    load_level1,
+                    load_current_level,
                     set_cursor_grab_mode(CursorGrabMode::Locked),
                 ),
             )
             .add_systems(
-                OnEnter(Screen::GameOver),
+                OnEnter(Screen::Victory),
+                set_cursor_grab_mode(CursorGrabMode::None),
+            )
+            .add_systems(
+                OnEnter(Screen::Defeat),
                 set_cursor_grab_mode(CursorGrabMode::None),
             );
 
@@ -83,17 +121,32 @@ fn load_default_scene(mut scenes: SceneAssetsLoader) -> Result {
     scenes.load_default_scene()
 }
 
-fn load_level1(mut scenes: SceneAssetsLoader) -> Result {
-    scenes.load_level1()
+fn load_current_level(
+    mut scenes: SceneAssetsLoader,
+    current_level: Res<CurrentLevel>,
+) -> Result {
+    scenes.load_level(current_level.info())
 }
 
-fn setup_menu(mut commands: Commands) {
+fn setup_menu(
+    mut commands: Commands,
+    preset: Res<GraphicsPreset>,
+    styles: Res<ViewportStyles>,
+    layout: Res<ViewportLayout>,
+    game_mode: Res<GameMode>,
+    selected: Res<SelectedCharacter>,
+) {
     const FONT_SIZE: f32 = 30.0;
 
     let bg_color = Srgba::hex("BFB190").unwrap().with_alpha(0.4);
     let font_color = Srgba::hex("342C24").unwrap();
     let play_color = Srgba::hex("FFDE59").unwrap().with_alpha(0.45);
     let exit_color = Srgba::hex("856850").unwrap().with_alpha(0.45);
+    let preset_label = preset.label();
+    let game_mode_label = game_mode.label();
+    let orientation_label = layout.orientation.label();
+    let style_labels = [PlayerType::A, PlayerType::B]
+        .map(|p| (p, viewport_style_label(p, &styles, &selected)));
 
     commands.spawn((
         StateScoped(Screen::Menu),
@@ -141,6 +194,72 @@ fn setup_menu(mut commands: Commands) {
                         )
                         .observe(play_on_click);
 
+                    parent
+                        .spawn(
+                            LabelButton::new("Perks")
+                                .with_background(
+                                    ButtonBackground::new(exit_color),
+                                )
+                                .with_text_color(font_color)
+                                .with_font_size(FONT_SIZE * 0.6)
+                                .build(),
+                        )
+                        .observe(perks_on_click);
+
+                    parent
+                        .spawn(
+                            LabelButton::new(game_mode_label)
+                                .with_background(
+                                    ButtonBackground::new(exit_color),
+                                )
+                                .with_text_color(font_color)
+                                .with_font_size(FONT_SIZE * 0.6)
+                                .build(),
+                        )
+                        .observe(toggle_game_mode_on_click);
+
+                    parent
+                        .spawn(
+                            LabelButton::new(preset_label)
+                                .with_background(
+                                    ButtonBackground::new(exit_color),
+                                )
+                                .with_text_color(font_color)
+                                .with_font_size(FONT_SIZE * 0.6)
+                                .build(),
+                        )
+                        .observe(toggle_graphics_on_click);
+
+                    parent
+                        .spawn(
+                            LabelButton::new(orientation_label)
+                                .with_background(
+                                    ButtonBackground::new(exit_color),
+                                )
+                                .with_text_color(font_color)
+                                .with_font_size(FONT_SIZE * 0.6)
+                                .build(),
+                        )
+                        .observe(toggle_split_orientation_on_click);
+
+                    for (player_type, label) in style_labels {
+                        parent
+                            .spawn(
+                                LabelButton::new(label)
+                                    .with_background(
+                                        ButtonBackground::new(
+                                            exit_color,
+                                        ),
+                                    )
+                                    .with_text_color(font_color)
+                                    .with_font_size(FONT_SIZE * 0.6)
+                                    .build(),
+                            )
+                            .observe(cycle_viewport_style_on_click(
+                                player_type,
+                            ));
+                    }
+
                     // Only add exit button for non-web game.
                     #[cfg(not(target_arch = "wasm32"))]
                     parent
@@ -160,14 +279,116 @@ fn setup_menu(mut commands: Commands) {
     ));
 }
 
+/// Continue from the furthest unlocked level.
 fn play_on_click(
     _: Trigger<Pointer<Click>>,
     mut screen: ResMut<NextState<Screen>>,
+    mut current_level: ResMut<CurrentLevel>,
+    progress: Res<LevelProgress>,
 ) {
+    current_level.0 = progress.latest();
     // screen.set(Screen::LevelSelection);
     screen.set(Screen::EnterLevel);
 }
 
+fn perks_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut screen: ResMut<NextState<Screen>>,
+) {
+    screen.set(Screen::Perks);
+}
+
+/// Switch between clearing the authored waves and endless waves.
+fn toggle_game_mode_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut game_mode: ResMut<GameMode>,
+    q_children: Query<&Children>,
+    mut q_texts: Query<&mut Text>,
+) {
+    *game_mode = game_mode.toggled();
+
+    for child in q_children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = q_texts.get_mut(child) {
+            text.0 = game_mode.label().to_string();
+        }
+    }
+}
+
+/// Override the default [`GraphicsPreset`] of the platform.
+fn toggle_graphics_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut preset: ResMut<GraphicsPreset>,
+    q_children: Query<&Children>,
+    mut q_texts: Query<&mut Text>,
+) {
+    *preset = preset.toggled();
+    info!("Switched to {:?} graphics preset.", *preset);
+
+    for child in q_children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = q_texts.get_mut(child) {
+            text.0 = preset.label().to_string();
+        }
+    }
+}
+
+/// Switch between splitting the viewports side by side or stacked.
+fn toggle_split_orientation_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut layout: ResMut<ViewportLayout>,
+    q_children: Query<&Children>,
+    mut q_texts: Query<&mut Text>,
+) {
+    layout.orientation = layout.orientation.toggled();
+
+    for child in q_children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = q_texts.get_mut(child) {
+            text.0 = layout.orientation.label().to_string();
+        }
+    }
+}
+
+/// Cycle through the viewport styles of a player.
+fn cycle_viewport_style_on_click(
+    player_type: PlayerType,
+) -> impl Fn(
+    Trigger<'_, Pointer<Click>>,
+    ResMut<'_, ViewportStyles>,
+    Res<'_, SelectedCharacter>,
+    Query<'_, '_, &Children>,
+    Query<'_, '_, &mut Text>,
+) {
+    move |trigger: Trigger<Pointer<Click>>,
+          mut styles: ResMut<ViewportStyles>,
+          selected: Res<SelectedCharacter>,
+          q_children: Query<&Children>,
+          mut q_texts: Query<&mut Text>| {
+        let style = styles.get_mut(player_type);
+        *style = style.next();
+
+        for child in q_children.iter_descendants(trigger.target()) {
+            if let Ok(mut text) = q_texts.get_mut(child) {
+                text.0 = viewport_style_label(
+                    player_type,
+                    &styles,
+                    &selected,
+                );
+            }
+        }
+    }
+}
+
+fn viewport_style_label(
+    player_type: PlayerType,
+    styles: &ViewportStyles,
+    selected: &SelectedCharacter,
+) -> String {
+    format!(
+        "{} view: {}",
+        selected.get(player_type).name(),
+        styles.get(player_type).name()
+    )
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn exit_on_click(
     _: Trigger<Pointer<Click>>,
@@ -184,5 +405,13 @@ pub enum Screen {
     Menu,
     // LevelSelection,
     EnterLevel, // TODO: Create substates for levels (1, 2, 3, ...).
-    GameOver,
+    /// Transient screen for tearing down the level
+    /// before entering it again.
+    Restarting,
+    /// All waves were cleared.
+    Victory,
+    /// The [`FinalTarget`](crate::enemy::FinalTarget) fell.
+    Defeat,
+    /// Spending the perk points earned across levels.
+    Perks,
 }
@@ -0,0 +1,96 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::player::PlayerType;
+use crate::water::Breath;
+
+use super::Screen;
+use super::widgets::progress_bar::ProgressBar;
+
+/// Breath left below which the bar turns red.
+const DROWNING_FRACTION: f32 = 0.3;
+
+pub(super) struct BreathUiPlugin;
+
+impl Plugin for BreathUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::EnterLevel), spawn_breath_ui)
+            .add_systems(
+                Update,
+                update_breath_ui.run_if(in_state(Screen::EnterLevel)),
+            );
+    }
+}
+
+/// Spawn a hidden breath bar below the stamina bar on each
+/// player's viewport.
+fn spawn_breath_ui(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(4.0));
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                top: Val::Px(38.0),
+                width: Val::Px(200.0),
+                height: Val::Px(12.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(ZINC_900.with_alpha(0.6).into()),
+            RADIUS,
+            ProgressBar::new(SKY_400, RADIUS).with_init_progress(1.0),
+            Visibility::Hidden,
+            BreathBar(player_type),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Only show the breath while it's being held.
+fn update_breath_ui(
+    q_players: Query<(&Breath, &PlayerType), Changed<Breath>>,
+    mut q_bars: Query<(
+        &mut ProgressBar,
+        &mut Visibility,
+        &BreathBar,
+    )>,
+) {
+    for (breath, player_type) in q_players.iter() {
+        for (mut progress_bar, mut viz, breath_bar) in
+            q_bars.iter_mut()
+        {
+            if breath_bar.0 != *player_type {
+                continue;
+            }
+
+            let fraction = breath.fraction();
+            progress_bar.progress = fraction;
+            // Turn red when about to drown.
+            progress_bar.color = if fraction > DROWNING_FRACTION {
+                SKY_400.into()
+            } else {
+                RED_500.into()
+            };
+            viz.set_if_neq(match fraction < 1.0 {
+                true => Visibility::Inherited,
+                false => Visibility::Hidden,
+            });
+        }
+    }
+}
+
+/// The breath bar of a specific player.
+#[derive(Component, Debug)]
+pub struct BreathBar(PlayerType);
@@ -1,40 +1,167 @@
-use bevy::ecs::spawn::SpawnWith;
+use bevy::ecs::spawn::{SpawnIter, SpawnWith};
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
 
+use crate::achievements::LevelAchievements;
 use crate::camera_controller::UI_RENDER_LAYER;
-use crate::player::player_mark::PlayerMark;
+use crate::enemy::BASE_HEALTH;
+use crate::level::{
+    CurrentLevel, GameMode, LEVELS, LevelProgress, NextLevel,
+    RestartLevel, record_best_endless_wave,
+};
+use crate::player::player_mark::{FellOnWave, PlayerMark};
+use crate::player::{PlayerType, SelectedCharacter};
+use crate::rng::GameRng;
+use crate::session_stats::SessionStats;
+use crate::tower::ownership::{PlacementStats, owner_color};
 
 use super::Screen;
 use super::widgets::button::{ButtonBackground, LabelButton};
 
+const FONT_SIZE: f32 = 40.0;
+
 pub(super) struct GameOverUiPlugin;
 
 impl Plugin for GameOverUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(Screen::GameOver),
-            spawn_game_over_ui,
-        );
+        app.add_systems(OnEnter(Screen::Victory), spawn_victory_ui)
+            .add_systems(
+                OnEnter(Screen::Defeat),
+                spawn_defeat_ui.after(record_best_endless_wave),
+            );
     }
 }
 
-fn spawn_game_over_ui(
+fn spawn_victory_ui(
     mut commands: Commands,
+    current_level: Res<CurrentLevel>,
     player_mark: Res<PlayerMark>,
+    stats: Res<PlacementStats>,
+    selected: Res<SelectedCharacter>,
+    session_stats: Option<Res<SessionStats>>,
+    achievements: Option<Res<LevelAchievements>>,
+    rng: Res<GameRng>,
 ) {
-    const FONT_SIZE: f32 = 40.0;
+    let green_color = Srgba::hex("C1FF72").unwrap();
+    let menu_color = Srgba::hex("856850").unwrap();
+    let next_level = current_level.next();
 
-    let bg_color = Srgba::hex("BFB190").unwrap().with_alpha(0.4);
+    commands.spawn((
+        StateScoped(Screen::Victory),
+        level_end_ui(
+            "Victory!",
+            format!("{} cleared!", current_level.info().name),
+            vec![
+                (
+                    format!(
+                        "Base health: {}/{}",
+                        player_mark.0, BASE_HEALTH as u32
+                    ),
+                    Srgba::hex("342C24").unwrap().into(),
+                ),
+                tower_stats(&stats, &selected, PlayerType::A),
+                tower_stats(&stats, &selected, PlayerType::B),
+            ]
+            .into_iter()
+            .chain(session_stat_lines(session_stats.as_deref()))
+            .chain(achievement_lines(achievements.as_deref()))
+            .collect(),
+            rng.seed(),
+            move |parent: &mut ChildSpawner| {
+                if let Some(next_level) = next_level {
+                    parent
+                        .spawn(level_end_button(
+                            format!(
+                                "Next: {}",
+                                LEVELS[next_level].name
+                            ),
+                            green_color,
+                        ))
+                        .observe(next_level_on_click);
+                }
+
+                parent
+                    .spawn(level_end_button(
+                        "Menu",
+                        match next_level.is_some() {
+                            true => menu_color,
+                            false => green_color,
+                        },
+                    ))
+                    .observe(return_to_main_menu);
+            },
+        ),
+    ));
+}
+
+fn spawn_defeat_ui(
+    mut commands: Commands,
+    fell_on_wave: Option<Res<FellOnWave>>,
+    current_level: Res<CurrentLevel>,
+    game_mode: Res<GameMode>,
+    progress: Res<LevelProgress>,
+    placement: Res<PlacementStats>,
+    selected: Res<SelectedCharacter>,
+    session_stats: Option<Res<SessionStats>>,
+    achievements: Option<Res<LevelAchievements>>,
+    rng: Res<GameRng>,
+) {
     let red_color = Srgba::hex("FF5757").unwrap();
-    let green_color = Srgba::hex("C1FF72").unwrap();
-    let font_color = Srgba::hex("342C24").unwrap();
+    let menu_color = Srgba::hex("856850").unwrap();
+
+    let subtitle = match fell_on_wave.and_then(|w| w.number()) {
+        Some(number) => format!("The base fell on wave {number}..."),
+        None => "The base fell...".to_string(),
+    };
 
-    let win = player_mark.0 > 0;
+    let mut stats = Vec::new();
+    if *game_mode == GameMode::Endless {
+        stats.push((
+            format!(
+                "Best endless wave: {}",
+                progress.best_endless_wave(current_level.info().name)
+            ),
+            Srgba::hex("342C24").unwrap().into(),
+        ));
+    }
+    stats.push(tower_stats(&placement, &selected, PlayerType::A));
+    stats.push(tower_stats(&placement, &selected, PlayerType::B));
+    stats.extend(session_stat_lines(session_stats.as_deref()));
+    stats.extend(achievement_lines(achievements.as_deref()));
 
     commands.spawn((
+        StateScoped(Screen::Defeat),
+        level_end_ui(
+            "Defeated!",
+            subtitle,
+            stats,
+            rng.seed(),
+            move |parent: &mut ChildSpawner| {
+                parent
+                    .spawn(level_end_button("Retry", red_color))
+                    .observe(retry_level);
+
+                parent
+                    .spawn(level_end_button("Menu", menu_color))
+                    .observe(return_to_main_menu);
+            },
+        ),
+    ));
+}
+
+/// Panel shared by [`Screen::Victory`] and [`Screen::Defeat`].
+fn level_end_ui(
+    title: &'static str,
+    subtitle: String,
+    stats: Vec<(String, Color)>,
+    seed: u64,
+    buttons: impl FnOnce(&mut ChildSpawner) + Send + Sync + 'static,
+) -> impl Bundle {
+    let bg_color = Srgba::hex("BFB190").unwrap().with_alpha(0.4);
+    let font_color = Srgba::hex("342C24").unwrap();
+
+    (
         UI_RENDER_LAYER,
-        StateScoped(Screen::GameOver),
         // Root.
         Node {
             width: Val::Percent(100.0),
@@ -67,40 +194,148 @@ fn spawn_game_over_ui(
                         padding: UiRect::all(Val::Px(10.0)),
                         ..default()
                     },
-                    if win {
-                        Text::new("Victory!")
-                    } else {
-                        Text::new("Failed!")
-                    },
+                    Text::new(title),
                     TextColor(font_color.into()),
                     TextLayout::new_with_justify(JustifyText::Center),
                     TextFont::from_font_size(FONT_SIZE * 1.5),
                 )),
-                SpawnWith(move |parent: &mut ChildSpawner| {
-                    parent
-                        .spawn(
-                            LabelButton::new(if win {
-                                "Continue"
-                            } else {
-                                "Retry"
-                            })
-                            .with_background(ButtonBackground::new(
-                                if win {
-                                    green_color
-                                } else {
-                                    red_color
-                                }
-                                .with_alpha(0.45),
-                            ))
-                            .with_text_color(font_color)
-                            .with_font_size(FONT_SIZE)
-                            .build(),
-                        )
-                        .observe(return_to_main_menu);
-                }),
+                Spawn((
+                    Node {
+                        padding: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                    Text::new(subtitle),
+                    TextColor(font_color.into()),
+                    TextFont::from_font_size(FONT_SIZE * 0.6),
+                )),
+                Spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                    Children::spawn(SpawnIter(
+                        stats.into_iter().map(|(stat, color)| {
+                            (
+                                Text::new(stat),
+                                TextColor(color),
+                                TextFont::from_font_size(
+                                    FONT_SIZE * 0.5,
+                                ),
+                            )
+                        }),
+                    )),
+                )),
+                // For reproducing bug reports.
+                Spawn((
+                    Node {
+                        padding: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                    Text::new(format!("Seed: {seed}")),
+                    TextColor(font_color.with_alpha(0.6).into()),
+                    TextFont::from_font_size(FONT_SIZE * 0.4),
+                )),
+                Spawn((
+                    Node {
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    Children::spawn(SpawnWith(buttons)),
+                )),
             )),
         ))),
-    ));
+    )
+}
+
+fn level_end_button(
+    label: impl Into<String>,
+    color: Srgba,
+) -> impl Bundle {
+    LabelButton::new(label)
+        .with_background(ButtonBackground::new(
+            color.with_alpha(0.45),
+        ))
+        .with_text_color(Srgba::hex("342C24").unwrap())
+        .with_font_size(FONT_SIZE)
+        .build()
+}
+
+/// Towers placed by the player, in the player's color.
+fn tower_stats(
+    stats: &PlacementStats,
+    selected: &SelectedCharacter,
+    player_type: PlayerType,
+) -> (String, Color) {
+    (
+        format!(
+            "{}: {} towers",
+            selected.get(player_type).name(),
+            stats.get(player_type)
+        ),
+        owner_color(player_type),
+    )
+}
+
+/// Counts of the run, if they are being recorded.
+fn session_stat_lines(
+    session_stats: Option<&SessionStats>,
+) -> Vec<(String, Color)> {
+    let font_color: Color = Srgba::hex("342C24").unwrap().into();
+
+    let Some(session_stats) = session_stats else {
+        return Vec::new();
+    };
+
+    let mut lines = Vec::new();
+    if session_stats.endless {
+        lines.push((
+            format!(
+                "Endless run: wave {}",
+                session_stats.furthest_wave
+            ),
+            font_color,
+        ));
+    }
+
+    lines.extend([
+        (
+            format!(
+                "Enemies defeated: {}",
+                session_stats.enemies_killed
+            ),
+            font_color,
+        ),
+        (
+            format!(
+                "Recipes cooked: {}",
+                session_stats.recipes_cooked
+            ),
+            font_color,
+        ),
+    ]);
+    lines
+}
+
+/// Achievements unlocked during the level.
+fn achievement_lines(
+    achievements: Option<&LevelAchievements>,
+) -> Vec<(String, Color)> {
+    let Some(achievements) = achievements else {
+        return Vec::new();
+    };
+
+    achievements
+        .unlocked
+        .iter()
+        .map(|name| {
+            (
+                format!("Achievement unlocked: {name}"),
+                Srgba::hex("B8860B").unwrap().into(),
+            )
+        })
+        .collect()
 }
 
 fn return_to_main_menu(
@@ -109,3 +344,14 @@ fn return_to_main_menu(
 ) {
     next_screen.set(Screen::Menu)
 }
+
+fn retry_level(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.trigger(RestartLevel);
+}
+
+fn next_level_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+) {
+    commands.trigger(NextLevel);
+}
@@ -0,0 +1,283 @@
+//! Stat cards of the run's [`Highlights`] on the results screen,
+//! with a picture-in-picture replay of each moment.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::ecs::spawn::SpawnIter;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::UI_RENDER_LAYER;
+use crate::highlights::{
+    Highlight, Highlights, SAMPLE_INTERVAL, SnippetKind,
+};
+use crate::tower::ownership::owner_color;
+
+use super::Screen;
+
+/// Size of the replay window in pixels.
+const REPLAY_SIZE: f32 = 200.0;
+const REPLAY_PADDING: f32 = 12.0;
+const DOT_SIZE: f32 = 8.0;
+/// Maximum number of dots shown in a replay frame.
+const MAX_DOTS: usize = 64;
+/// Seconds between each card sliding in.
+const CARD_STAGGER: f32 = 0.25;
+const CARD_SLIDE_DURATION: f32 = 0.4;
+const CARD_SLIDE_DISTANCE: f32 = 40.0;
+
+pub(super) struct HighlightReelUiPlugin;
+
+impl Plugin for HighlightReelUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::Victory),
+            spawn_highlight_reel,
+        )
+        .add_systems(OnEnter(Screen::Defeat), spawn_highlight_reel)
+        .add_systems(
+            Update,
+            (animate_cards, play_replay).run_if(
+                in_state(Screen::Victory)
+                    .or(in_state(Screen::Defeat)),
+            ),
+        );
+    }
+}
+
+fn spawn_highlight_reel(
+    mut commands: Commands,
+    highlights: Res<Highlights>,
+    screen: Res<State<Screen>>,
+) {
+    let reel = highlights.reel();
+    if reel.is_empty() {
+        return;
+    }
+
+    let cards = reel
+        .iter()
+        .enumerate()
+        .map(|(index, (title, value, _))| {
+            (
+                Node {
+                    width: Val::Px(180.0),
+                    top: Val::Px(CARD_SLIDE_DISTANCE),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                BackgroundColor(ZINC_900.with_alpha(0.0).into()),
+                BorderColor(Color::NONE),
+                BorderRadius::all(Val::Px(12.0)),
+                StatCard { index, age: 0.0 },
+                Pickable::IGNORE,
+                Children::spawn((
+                    Spawn((
+                        Text::new(*title),
+                        TextFont::from_font_size(16.0),
+                        TextColor(ZINC_300.into()),
+                    )),
+                    Spawn((
+                        Text::new(value.clone()),
+                        TextFont::from_font_size(28.0),
+                    )),
+                )),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let dots = (0..MAX_DOTS).map(|_| {
+        (
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(DOT_SIZE),
+                height: Val::Px(DOT_SIZE),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::WHITE),
+            BorderRadius::MAX,
+            ReplayDot,
+        )
+    });
+
+    commands.spawn((
+        UI_RENDER_LAYER,
+        StateScoped(screen.get().clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(24.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::End,
+            column_gap: Val::Px(16.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+        Children::spawn((
+            SpawnIter(cards.into_iter()),
+            // Picture-in-picture replay.
+            Spawn((
+                Node {
+                    width: Val::Px(REPLAY_SIZE),
+                    height: Val::Px(REPLAY_SIZE),
+                    border: UiRect::all(Val::Px(3.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BackgroundColor(ZINC_900.with_alpha(0.85).into()),
+                BorderColor(ZINC_300.into()),
+                BorderRadius::all(Val::Px(12.0)),
+                ReplayWindow::default(),
+                Pickable::IGNORE,
+                Children::spawn(SpawnIter(dots)),
+            )),
+        )),
+    ));
+}
+
+/// Slide the cards in one after another.
+fn animate_cards(
+    mut q_cards: Query<(
+        &mut StatCard,
+        &mut Node,
+        &mut BackgroundColor,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut card, mut node, mut background) in q_cards.iter_mut() {
+        card.age += time.delta_secs();
+
+        let t = ((card.age - card.index as f32 * CARD_STAGGER)
+            / CARD_SLIDE_DURATION)
+            .clamp(0.0, 1.0);
+        // Ease out cubic.
+        let eased = 1.0 - (1.0 - t).powi(3);
+
+        node.top = Val::Px(CARD_SLIDE_DISTANCE * (1.0 - eased));
+        background.0 = ZINC_900.with_alpha(0.85 * eased).into();
+    }
+}
+
+/// Loop through the snippet of every highlight in turn,
+/// outlining the card being replayed.
+fn play_replay(
+    mut q_windows: Query<(&mut ReplayWindow, &Children)>,
+    mut q_dots: Query<
+        (&mut Node, &mut BackgroundColor),
+        With<ReplayDot>,
+    >,
+    mut q_cards: Query<(&StatCard, &mut BorderColor)>,
+    highlights: Res<Highlights>,
+    time: Res<Time>,
+) {
+    let reel = highlights.reel();
+
+    for (mut window, children) in q_windows.iter_mut() {
+        let Some((_, _, highlight)) = reel.get(window.index) else {
+            continue;
+        };
+
+        if window.timer.tick(time.delta()).just_finished() {
+            window.frame += 1;
+
+            if window.frame >= highlight.snippet.len() {
+                window.frame = 0;
+                window.index = (window.index + 1) % reel.len();
+            }
+        }
+
+        let Some((_, _, highlight)) = reel.get(window.index) else {
+            continue;
+        };
+
+        for (card, mut border) in q_cards.iter_mut() {
+            border.0 = match card.index == window.index {
+                true => AMBER_300.into(),
+                false => Color::NONE,
+            };
+        }
+
+        let frame = highlight
+            .snippet
+            .get(window.frame)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let to_window = snippet_bounds(highlight);
+
+        let mut dots = q_dots.iter_many_mut(children.iter());
+        let mut points = frame.iter();
+
+        while let Some((mut node, mut background)) = dots.fetch_next()
+        {
+            let Some(point) = points.next() else {
+                node.display = Display::None;
+                continue;
+            };
+
+            let position = to_window(point.position);
+            node.display = Display::Flex;
+            node.left = Val::Px(position.x - DOT_SIZE * 0.5);
+            node.top = Val::Px(position.y - DOT_SIZE * 0.5);
+            background.0 = match point.kind {
+                SnippetKind::Player(player_type) => {
+                    owner_color(player_type)
+                }
+                SnippetKind::Enemy => RED_500.into(),
+                SnippetKind::Target => Color::WHITE,
+            };
+        }
+    }
+}
+
+/// Fit every point of the snippet into the replay window,
+/// keeping the aspect ratio.
+fn snippet_bounds(highlight: &Highlight) -> impl Fn(Vec2) -> Vec2 {
+    let (min, max) = highlight.snippet.iter().flatten().fold(
+        (Vec2::MAX, Vec2::MIN),
+        |(min, max), point| {
+            (min.min(point.position), max.max(point.position))
+        },
+    );
+
+    let extent = (max - min).max_element().max(1.0);
+    let scale = (REPLAY_SIZE - REPLAY_PADDING * 2.0) / extent;
+
+    move |position| (position - min) * scale + REPLAY_PADDING
+}
+
+#[derive(Component)]
+struct StatCard {
+    /// Index of the highlight in [`Highlights::reel`].
+    index: usize,
+    /// Seconds since spawned.
+    age: f32,
+}
+
+#[derive(Component)]
+struct ReplayWindow {
+    /// Index of the highlight being replayed.
+    index: usize,
+    frame: usize,
+    timer: Timer,
+}
+
+impl Default for ReplayWindow {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            frame: 0,
+            timer: Timer::from_seconds(
+                SAMPLE_INTERVAL,
+                TimerMode::Repeating,
+            ),
+        }
+    }
+}
+
+#[derive(Component)]
+struct ReplayDot;
@@ -0,0 +1,184 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::UI_RENDER_LAYER;
+use crate::camera_controller::split_screen::ViewportNode;
+use crate::interaction::InteractionPlayer;
+use crate::inventory::item::ItemRegistry;
+use crate::inventory::{HOTBAR_SIZE, Inventory};
+use crate::player::PlayerType;
+
+pub(super) struct HotbarUiPlugin;
+
+impl Plugin for HotbarUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_hotbar_ui).add_systems(
+            Update,
+            (clear_hotbar_ui, spawn_hotbar_slots).chain(),
+        );
+    }
+}
+
+fn clear_hotbar_ui(mut commands: Commands, hotbar_ui: Res<HotbarUi>) {
+    for entity in [hotbar_ui.a, hotbar_ui.b] {
+        commands.entity(entity).despawn_related::<Children>();
+    }
+}
+
+/// Show the first [`HOTBAR_SIZE`] slots of each player's inventory.
+fn spawn_hotbar_slots(
+    mut commands: Commands,
+    q_players: Query<
+        (&Inventory, &PlayerType),
+        With<InteractionPlayer>,
+    >,
+    item_registry: ItemRegistry,
+    hotbar_ui: Res<HotbarUi>,
+) {
+    for (inventory, player_type) in q_players.iter() {
+        let hotbar_node = match player_type {
+            PlayerType::A => hotbar_ui.a,
+            PlayerType::B => hotbar_ui.b,
+        };
+
+        for (index, slot) in
+            inventory.slots().iter().take(HOTBAR_SIZE).enumerate()
+        {
+            let is_selected =
+                inventory.selected_index() == Some(index);
+            let (bg_color, border_color) = match is_selected {
+                true => (EMERALD_800, EMERALD_500),
+                false => (SLATE_800, SLATE_400),
+            };
+
+            let icon = slot
+                .as_ref()
+                .and_then(|s| item_registry.get_item(&s.item_id))
+                .map(|item| item.icon.clone());
+            let count = slot
+                .as_ref()
+                .map(|s| s.quantity.to_string())
+                .unwrap_or_default();
+
+            let slot_node = commands
+                .spawn((
+                    Node {
+                        width: Val::Px(64.0),
+                        height: Val::Px(64.0),
+                        margin: UiRect::horizontal(Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(bg_color.with_alpha(0.5).into()),
+                    BorderColor(border_color.with_alpha(0.7).into()),
+                    BorderRadius::all(Val::Px(8.0)),
+                    FocusPolicy::Pass,
+                    Pickable::IGNORE,
+                ))
+                .with_children(|parent| {
+                    if let Some(icon) = icon {
+                        parent.spawn((
+                            Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                padding: UiRect::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            ImageNode::new(icon),
+                        ));
+                    }
+
+                    // Slot number.
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(4.0),
+                            top: Val::Px(2.0),
+                            ..default()
+                        },
+                        Text::new((index + 1).to_string()),
+                        TextFont::from_font_size(12.0),
+                        TextColor(border_color.into()),
+                    ));
+
+                    // Stack count.
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            right: Val::Px(4.0),
+                            bottom: Val::Px(2.0),
+                            ..default()
+                        },
+                        Text::new(count),
+                        TextFont::from_font_size(14.0),
+                        TextColor(SLATE_200.into()),
+                    ));
+                })
+                .id();
+
+            commands.entity(hotbar_node).add_child(slot_node);
+        }
+    }
+}
+
+/// Create the hotbar at the bottom center of each viewport.
+fn setup_hotbar_ui(mut commands: Commands) {
+    let hotbar_bundle = || {
+        (
+            Node {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            FocusPolicy::Pass,
+            Pickable::IGNORE,
+        )
+    };
+
+    let a = commands.spawn(hotbar_bundle()).id();
+    let b = commands.spawn(hotbar_bundle()).id();
+
+    let split_bundle = |player_type: PlayerType| {
+        (
+            // Covers the player's viewport.
+            ViewportNode(player_type),
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::End,
+                align_items: AlignItems::Center,
+                // Stay above the inventory items.
+                padding: UiRect::bottom(Val::Px(150.0)),
+                ..default()
+            },
+            FocusPolicy::Pass,
+            Pickable::IGNORE,
+        )
+    };
+
+    commands
+        .spawn((
+            UI_RENDER_LAYER,
+            // Root node.
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            FocusPolicy::Pass,
+            Pickable::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent.spawn(split_bundle(PlayerType::A)).add_child(a);
+            parent.spawn(split_bundle(PlayerType::B)).add_child(b);
+        });
+
+    commands.insert_resource(HotbarUi { a, b });
+}
+
+#[derive(Resource, Debug)]
+pub struct HotbarUi {
+    pub a: Entity,
+    pub b: Entity,
+}
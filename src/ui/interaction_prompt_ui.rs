@@ -0,0 +1,169 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction, binding_labels};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::interaction::grab::Grabbable;
+use crate::interaction::push_pull::PushPull;
+use crate::interaction::{
+    InteractionPlayer, InteractionPrompt, MarkerOf, Occupied,
+};
+use crate::machine::Machine;
+use crate::player::PlayerType;
+
+use super::Screen;
+use super::world_space::WorldUi;
+
+pub(super) struct InteractionPromptUiPlugin;
+
+impl Plugin for InteractionPromptUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_interaction_prompt_ui
+                .run_if(in_state(Screen::EnterLevel)),
+        );
+    }
+}
+
+/// Show a prompt above the entity marked by each player with the
+/// interaction label and the player's bound inputs.
+fn update_interaction_prompt_ui(
+    mut commands: Commands,
+    q_players: Query<
+        (
+            &PlayerType,
+            &TargetAction,
+            Option<&MarkerOf>,
+            Has<Occupied>,
+            Entity,
+        ),
+        With<InteractionPlayer>,
+    >,
+    q_input_maps: Query<&InputMap<PlayerAction>>,
+    q_labels: Query<(
+        Option<&InteractionPrompt>,
+        Has<Machine>,
+        Has<Grabbable>,
+        Has<PushPull>,
+    )>,
+    q_prompts: Query<(
+        &InteractionPromptUi,
+        &WorldUi,
+        &Children,
+        Entity,
+    )>,
+    mut q_texts: Query<&mut Text>,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    for (player_type, target_action, marker_of, occupied, player) in
+        q_players.iter()
+    {
+        let prompt = q_prompts
+            .iter()
+            .find(|(prompt_ui, ..)| prompt_ui.player == player);
+
+        // Nothing to interact with while holding something.
+        let Some((target, label)) =
+            marker_of.filter(|_| occupied == false).and_then(|m| {
+                Some((
+                    m.entity(),
+                    interaction_label(m.entity(), &q_labels)?,
+                ))
+            })
+        else {
+            if let Some((.., prompt_entity)) = prompt {
+                commands.entity(prompt_entity).despawn();
+            }
+            continue;
+        };
+
+        let bindings = q_input_maps
+            .get(target_action.get())
+            .ok()
+            .and_then(|m| binding_labels(m, &PlayerAction::Interact))
+            .unwrap_or_default();
+        let content = format!("{bindings} — {label}");
+
+        match prompt {
+            // Still pointing at the same target, just update the text.
+            Some((_, world_ui, children, _))
+                if world_ui.target == target =>
+            {
+                for child in children.iter() {
+                    if let Ok(mut text) = q_texts.get_mut(child) {
+                        if text.0 != content {
+                            text.0 = content.clone();
+                        }
+                    }
+                }
+            }
+            _ => {
+                if let Some((.., prompt_entity)) = prompt {
+                    commands.entity(prompt_entity).despawn();
+                }
+
+                commands.spawn((
+                    InteractionPromptUi { player },
+                    StateScoped(Screen::EnterLevel),
+                    UiTargetCamera(
+                        q_cameras.get(player_type.camera_type())?,
+                    ),
+                    WorldUi::new(target)
+                        .with_world_offset(Vec3::Y * 2.0),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        padding: UiRect::axes(
+                            Val::Px(10.0),
+                            Val::Px(4.0),
+                        ),
+                        ..default()
+                    },
+                    BackgroundColor(ZINC_900.with_alpha(0.7).into()),
+                    BorderRadius::all(Val::Px(6.0)),
+                    Pickable::IGNORE,
+                    FocusPolicy::Pass,
+                    Children::spawn(Spawn((
+                        Text::new(content),
+                        TextFont::from_font_size(18.0),
+                    ))),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The action performed when interacting with the entity.
+fn interaction_label(
+    entity: Entity,
+    q_labels: &Query<(
+        Option<&InteractionPrompt>,
+        Has<Machine>,
+        Has<Grabbable>,
+        Has<PushPull>,
+    )>,
+) -> Option<String> {
+    let (prompt, is_machine, is_grabbable, is_push_pull) =
+        q_labels.get(entity).ok()?;
+
+    if let Some(prompt) = prompt {
+        return Some(prompt.0.clone());
+    }
+
+    match (is_machine, is_grabbable, is_push_pull) {
+        (true, ..) => Some("Cook".to_string()),
+        (_, true, _) => Some("Grab".to_string()),
+        (.., true) => Some("Push".to_string()),
+        _ => None,
+    }
+}
+
+/// World space prompt ui of a specific player.
+#[derive(Component, Debug)]
+pub struct InteractionPromptUi {
+    player: Entity,
+}
@@ -1,40 +1,302 @@
 use bevy::color::palettes::tailwind::*;
 use bevy::ecs::spawn::SpawnWith;
 use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
 use bevy::ui::FocusPolicy;
+use bevy::window::{CursorGrabMode, PrimaryWindow};
 
+use crate::asset_pipeline::{CurrentScene, PrefabAssets};
 use crate::camera_controller::UI_RENDER_LAYER;
+use crate::camera_controller::split_screen::ViewportNode;
+use crate::character_controller::CharacterController;
 use crate::interaction::InteractionPlayer;
-use crate::player::PlayerType;
+use crate::inventory::item::{ItemRegistry, ItemType};
+use crate::inventory::{CollectCooldown, Inventory, Item};
+use crate::machine::recipe::RecipeRegistry;
+use crate::player::{PlayerType, QueryPlayers};
 
-use crate::inventory::Inventory;
-use crate::inventory::item::ItemRegistry;
+use super::Screen;
+use super::item_tooltip::item_tooltip_bundle;
+
+/// Key to hold for freeing the cursor to drag items around.
+const DRAG_CURSOR_KEY: KeyCode = KeyCode::AltLeft;
 
 pub struct InventoryUiPlugin;
 
 impl Plugin for InventoryUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, split_screen_ui).add_systems(
-            Update,
-            (clear_inventory_ui, spawn_inventory_ui).chain(),
-        );
+        app.init_resource::<InventoryDrag>()
+            .init_resource::<HoveredSlot>()
+            .add_systems(Startup, split_screen_ui)
+            .add_systems(
+                Update,
+                (
+                    (clear_inventory_ui, spawn_inventory_ui)
+                        .chain()
+                        .run_if(
+                            inventory_changed
+                                .or(resource_changed::<InventoryDrag>)
+                                .or(resource_changed::<HoveredSlot>),
+                        ),
+                    free_cursor_for_drag
+                        .run_if(in_state(Screen::EnterLevel)),
+                ),
+            )
+            .add_observer(start_slot_drag)
+            .add_observer(drop_on_slot)
+            .add_observer(drop_on_drop_zone)
+            .add_observer(end_slot_drag)
+            .add_observer(hover_slot)
+            .add_observer(unhover_slot);
+    }
+}
+
+fn inventory_changed(
+    q_inventories: Query<(), Changed<Inventory>>,
+) -> bool {
+    q_inventories.is_empty() == false
+}
+
+/// Show the cursor while [`DRAG_CURSOR_KEY`] is held.
+fn free_cursor_for_drag(
+    mut q_windows: Query<&mut Window, With<PrimaryWindow>>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+) -> Result {
+    let grab_mode = if kbd_inputs.just_pressed(DRAG_CURSOR_KEY) {
+        CursorGrabMode::None
+    } else if kbd_inputs.just_released(DRAG_CURSOR_KEY) {
+        CursorGrabMode::Locked
+    } else {
+        return Ok(());
+    };
+
+    let mut window = q_windows.single_mut()?;
+    window.cursor_options.grab_mode = grab_mode;
+    window.cursor_options.visible = grab_mode == CursorGrabMode::None;
+
+    Ok(())
+}
+
+fn hover_slot(
+    trigger: Trigger<Pointer<Over>>,
+    q_slots: Query<&InventorySlotUi>,
+    mut hovered: ResMut<HoveredSlot>,
+) {
+    if let Ok(slot) = q_slots.get(trigger.target()) {
+        hovered.set_if_neq(HoveredSlot(Some(*slot)));
     }
 }
 
+fn unhover_slot(
+    trigger: Trigger<Pointer<Out>>,
+    q_slots: Query<&InventorySlotUi>,
+    mut hovered: ResMut<HoveredSlot>,
+) {
+    // Compare through a shared borrow to keep change detection
+    // quiet when another slot is hovered.
+    if q_slots
+        .get(trigger.target())
+        .is_ok_and(|s| hovered.0 == Some(*s))
+    {
+        hovered.set_if_neq(HoveredSlot(None));
+    }
+}
+
+/// Remember the slot being dragged, holding shift splits
+/// the stack in half.
+fn start_slot_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    q_slots: Query<&InventorySlotUi>,
+    kbd_inputs: Res<ButtonInput<KeyCode>>,
+    mut drag: ResMut<InventoryDrag>,
+) {
+    let Ok(slot) = q_slots.get(trigger.target()) else {
+        return;
+    };
+
+    drag.0 = Some(DraggedSlot {
+        slot: *slot,
+        split: kbd_inputs
+            .any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+    });
+}
+
+fn end_slot_drag(
+    _trigger: Trigger<Pointer<DragEnd>>,
+    mut drag: ResMut<InventoryDrag>,
+) {
+    if drag.0.is_some() {
+        drag.0 = None;
+    }
+}
+
+/// Move, merge or split the dragged stack into the slot.
+fn drop_on_slot(
+    trigger: Trigger<Pointer<DragDrop>>,
+    q_slots: Query<&InventorySlotUi>,
+    mut q_inventories: QueryPlayers<
+        &mut Inventory,
+        With<CharacterController>,
+    >,
+    item_registry: ItemRegistry,
+    mut drag: ResMut<InventoryDrag>,
+) -> Result {
+    let Ok(target) = q_slots.get(trigger.target()) else {
+        return Ok(());
+    };
+    let Some(dragged) = drag.0.take() else {
+        return Ok(());
+    };
+
+    // Items can only be rearranged within the same inventory.
+    if dragged.slot.player_type != target.player_type {
+        return Ok(());
+    }
+
+    let mut inventory = q_inventories.get_mut(target.player_type)?;
+
+    let Some(stack) =
+        inventory.slots().get(dragged.slot.index).cloned().flatten()
+    else {
+        return Ok(());
+    };
+
+    let max_stack_size = item_registry
+        .get_item(&stack.item_id)
+        .ok_or(format!(
+            "Item {} not found in registry",
+            stack.item_id
+        ))?
+        .max_stack_size;
+
+    let quantity = match dragged.split {
+        true => stack.quantity / 2,
+        false => stack.quantity,
+    };
+
+    inventory.move_items(
+        dragged.slot.index,
+        target.index,
+        quantity,
+        max_stack_size,
+    );
+
+    Ok(())
+}
+
+/// Drop the dragged stack into the world in front of the player.
+fn drop_on_drop_zone(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    q_drop_zones: Query<&InventoryDropZone>,
+    mut q_inventories: QueryPlayers<
+        (&mut Inventory, &GlobalTransform),
+        With<CharacterController>,
+    >,
+    item_registry: ItemRegistry,
+    prefabs: Res<PrefabAssets>,
+    gltfs: Res<Assets<Gltf>>,
+    current_scene: Res<CurrentScene>,
+    mut drag: ResMut<InventoryDrag>,
+) -> Result {
+    let Ok(drop_zone) = q_drop_zones.get(trigger.target()) else {
+        return Ok(());
+    };
+    let Some(dragged) = drag.0.take() else {
+        return Ok(());
+    };
+
+    if dragged.slot.player_type != drop_zone.0 {
+        return Ok(());
+    }
+
+    let Some(current_scene) = current_scene.get() else {
+        return Ok(());
+    };
+
+    let (mut inventory, player_tf) =
+        q_inventories.get_mut(drop_zone.0)?;
+
+    let Some(stack) =
+        inventory.slots().get(dragged.slot.index).cloned().flatten()
+    else {
+        return Ok(());
+    };
+
+    let item_meta = item_registry.get_item(&stack.item_id).ok_or(
+        format!("Item {} not found in registry", stack.item_id),
+    )?;
+
+    // Tower prefabs are fully functioning towers.
+    if item_meta.item_type != ItemType::Ingredient {
+        warn!("Only ingredients can be dropped into the world.");
+        return Ok(());
+    }
+
+    let scene = item_meta
+        .prefab_name()
+        .and_then(|name| prefabs.get_gltf(name, &gltfs))
+        .ok_or(format!("Can't find {} prefab!", stack.item_id))?
+        .default_scene
+        .clone()
+        .ok_or(format!(
+            "{} prefab should have a default scene.",
+            stack.item_id
+        ))?;
+
+    let quantity = match dragged.split {
+        true => stack.quantity / 2,
+        false => stack.quantity,
+    };
+    let Some(dropped) = inventory.take(dragged.slot.index, quantity)
+    else {
+        return Ok(());
+    };
+    if dropped.quantity == 0 {
+        return Ok(());
+    }
+
+    commands
+        .spawn((
+            SceneRoot(scene),
+            Transform::from_translation(
+                player_tf.translation()
+                    + player_tf.forward() * 1.5
+                    + Vec3::Y,
+            ),
+            ChildOf(current_scene),
+        ))
+        .observe(
+            move |trigger: Trigger<SceneInstanceReady>,
+                  mut commands: Commands,
+                  q_children: Query<&Children>,
+                  q_items: Query<(), With<Item>>| {
+                // Carry the dropped quantity over to the item.
+                for entity in q_children
+                    .iter_descendants(trigger.target())
+                    .filter(|e| q_items.contains(*e))
+                {
+                    commands.entity(entity).insert((
+                        Item {
+                            id: dropped.item_id.clone(),
+                            quantity: dropped.quantity,
+                        },
+                        CollectCooldown::default(),
+                    ));
+                }
+            },
+        );
+
+    Ok(())
+}
+
 fn clear_inventory_ui(
     mut commands: Commands,
     inventory_ui: Res<InventoryUi>,
 ) {
-    [
-        inventory_ui.a_towers,
-        inventory_ui.a_ingredients,
-        inventory_ui.b_towers,
-        inventory_ui.b_ingredients,
-    ]
-    .iter()
-    .for_each(|e| {
-        commands.entity(*e).despawn_related::<Children>();
-    });
+    for entity in [inventory_ui.a, inventory_ui.b] {
+        commands.entity(entity).despawn_related::<Children>();
+    }
 }
 
 fn spawn_inventory_ui(
@@ -44,137 +306,127 @@ fn spawn_inventory_ui(
         With<InteractionPlayer>,
     >,
     item_registry: ItemRegistry,
+    recipe_registry: RecipeRegistry,
     inventory_ui: Res<InventoryUi>,
-) -> Result {
+    drag: Res<InventoryDrag>,
+    hovered: Res<HoveredSlot>,
+) {
     for (inventory, player_type) in q_players.iter() {
-        let (tower_node, ingredient_node) = match player_type {
-            PlayerType::A => {
-                (inventory_ui.a_towers, inventory_ui.a_ingredients)
-            }
-            PlayerType::B => {
-                (inventory_ui.b_towers, inventory_ui.b_ingredients)
-            }
+        let slots_node = match player_type {
+            PlayerType::A => inventory_ui.a,
+            PlayerType::B => inventory_ui.b,
         };
 
-        let item_bundle =
-            |border_width: f32,
-             bg_color: Color,
-             border_color: Color,
-             item_id: &str,
-             item_count: u32| {
-                Result::<_, String>::Ok((
+        for (index, slot) in inventory.slots().iter().enumerate() {
+            let slot_ui = InventorySlotUi {
+                player_type: *player_type,
+                index,
+            };
+
+            let is_dragged =
+                drag.0.is_some_and(|d| d.slot == slot_ui);
+            let (bg_color, border_color) = match slot {
+                _ if is_dragged => (AMBER_800, AMBER_500),
+                // Highlight the selected tower.
+                Some(_)
+                    if inventory.selected_index() == Some(index) =>
+                {
+                    (EMERALD_800, EMERALD_500)
+                }
+                _ => (SLATE_800, SLATE_200),
+            };
+
+            let icon = slot
+                .as_ref()
+                .and_then(|s| item_registry.get_item(&s.item_id))
+                .map(|item| item.icon.clone());
+            let count = slot
+                .as_ref()
+                .map(|s| s.quantity.to_string())
+                .unwrap_or_default();
+
+            let slot_node = commands
+                .spawn((
+                    slot_ui,
                     Node {
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        flex_direction: FlexDirection::Column,
-                        border: UiRect::all(Val::Px(border_width)),
-                        margin: UiRect::horizontal(Val::Px(20.0)),
-                        overflow: Overflow::clip(),
+                        width: Val::Px(56.0),
+                        height: Val::Px(56.0),
+                        margin: UiRect::horizontal(Val::Px(2.0)),
+                        border: UiRect::all(Val::Px(2.0)),
                         ..default()
                     },
-                    BackgroundColor(bg_color.with_alpha(0.5)),
-                    BorderColor(border_color.with_alpha(0.7)),
+                    BackgroundColor(bg_color.with_alpha(0.5).into()),
+                    BorderColor(border_color.with_alpha(0.7).into()),
                     BorderRadius::all(Val::Px(8.0)),
-                    BoxShadow::new(
-                        bg_color.with_alpha(0.8),
-                        Val::Px(2.0),
-                        Val::Px(2.0),
-                        Val::Px(4.0),
-                        Val::Px(6.0),
-                    ),
-                    Children::spawn((
-                        Spawn((
+                ))
+                .with_children(|parent| {
+                    if let Some(icon) = icon {
+                        parent.spawn((
                             Node {
-                                width: Val::Px(80.0),
-                                height: Val::Px(80.0),
-                                margin: UiRect::bottom(Val::Px(4.0)),
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
                                 padding: UiRect::all(Val::Px(4.0)),
                                 ..default()
                             },
-                            ImageNode::new(
-                                item_registry
-                                    .get_item(item_id)
-                                    .ok_or(format!(
-                                        "No icon for tower {item_id}"
-                                    ))?
-                                    .icon
-                                    .clone(),
-                            ),
-                        )),
-                        Spawn((
-                            Text::new(item_count.to_string()),
-                            TextFont {
-                                font_size: 16.0,
-                                ..default()
-                            },
-                            TextColor(border_color),
-                        )),
-                    )),
-                ))
-            };
+                            ImageNode::new(icon),
+                            Pickable::IGNORE,
+                        ));
+                    }
 
-        for (tower_id, count) in
-            inventory.towers().iter().filter(|(_, count)| **count > 0)
-        {
-            // Check if this tower is selected
-            let is_selected =
-                inventory.selected_tower.as_ref() == Some(tower_id);
-
-            //  Determine colors and border based on selection state
-            let (bg_color, border_color) = if is_selected {
-                (EMERALD_800, EMERALD_500)
-            } else {
-                (SLATE_800, SLATE_200)
-            };
-
-            // Create the item node.
-            let tower_item_node = commands
-                .spawn(item_bundle(
-                    2.0,
-                    bg_color.into(),
-                    border_color.into(),
-                    tower_id,
-                    *count,
-                )?)
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            right: Val::Px(4.0),
+                            bottom: Val::Px(2.0),
+                            ..default()
+                        },
+                        Text::new(count),
+                        TextFont::from_font_size(14.0),
+                        TextColor(border_color.into()),
+                        Pickable::IGNORE,
+                    ));
+                })
                 .id();
 
-            commands.entity(tower_node).add_child(tower_item_node);
-        }
-
-        for (ingredient_id, count) in inventory
-            .ingredients()
-            .iter()
-            .filter(|(_, count)| **count > 0)
-        {
-            // Create the item node.
-            let ingredient_item_node = commands
-                .spawn(item_bundle(
-                    2.0,
-                    SLATE_800.into(),
-                    SLATE_200.into(),
-                    ingredient_id,
-                    *count,
-                )?)
-                .id();
+            // Show the tooltip above the hovered slot unless
+            // something is being dragged.
+            if drag.0.is_none() && hovered.0 == Some(slot_ui) {
+                if let Some(tooltip) = slot.as_ref().and_then(|s| {
+                    item_tooltip_bundle(
+                        Node {
+                            position_type: PositionType::Absolute,
+                            bottom: Val::Percent(100.0),
+                            left: Val::Px(0.0),
+                            width: Val::Px(200.0),
+                            margin: UiRect::bottom(Val::Px(4.0)),
+                            ..default()
+                        },
+                        &s.item_id,
+                        &item_registry,
+                        &recipe_registry,
+                    )
+                }) {
+                    commands.spawn((
+                        tooltip,
+                        GlobalZIndex(1),
+                        ChildOf(slot_node),
+                    ));
+                }
+            }
 
-            commands
-                .entity(ingredient_node)
-                .add_child(ingredient_item_node);
+            commands.entity(slots_node).add_child(slot_node);
         }
     }
-
-    Ok(())
 }
 
 /// Create split screen ui.
 fn split_screen_ui(mut commands: Commands) {
     let split_bundle =
-        |tower_node: Entity, ingreient_node: Entity| {
+        |slots_node: Entity, player_type: PlayerType| {
             (
+                // Covers the player's viewport.
+                ViewportNode(player_type),
                 Node {
-                    // Takes half the space.
-                    width: Val::Percent(50.0),
-                    height: Val::Percent(100.0),
                     // Push the child node towards the bottom.
                     flex_direction: FlexDirection::Column,
                     justify_content: JustifyContent::End,
@@ -191,6 +443,7 @@ fn split_screen_ui(mut commands: Commands) {
                                         FlexDirection::Row,
                                     justify_content:
                                         JustifyContent::SpaceBetween,
+                                    align_items: AlignItems::Center,
                                     padding: UiRect::all(Val::Px(
                                         20.0,
                                     )),
@@ -199,16 +452,38 @@ fn split_screen_ui(mut commands: Commands) {
                                 FocusPolicy::Pass,
                                 Pickable::IGNORE,
                             ))
-                            .add_children(&[
-                                tower_node,
-                                ingreient_node,
-                            ]);
+                            .add_child(slots_node)
+                            .with_child((
+                                InventoryDropZone(player_type),
+                                Node {
+                                    width: Val::Px(72.0),
+                                    height: Val::Px(56.0),
+                                    justify_content:
+                                        JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    ..default()
+                                },
+                                BackgroundColor(
+                                    RED_900.with_alpha(0.4).into(),
+                                ),
+                                BorderColor(
+                                    RED_500.with_alpha(0.7).into(),
+                                ),
+                                BorderRadius::all(Val::Px(8.0)),
+                                Children::spawn(Spawn((
+                                    Text::new("Drop"),
+                                    TextFont::from_font_size(14.0),
+                                    TextColor(RED_200.into()),
+                                    Pickable::IGNORE,
+                                ))),
+                            ));
                     },
                 )),
             )
         };
 
-    let items_bundle = (
+    let slots_bundle = (
         Node {
             flex_direction: FlexDirection::Row,
             ..default()
@@ -217,11 +492,8 @@ fn split_screen_ui(mut commands: Commands) {
         Pickable::IGNORE,
     );
 
-    let a_towers = commands.spawn(items_bundle.clone()).id();
-    let a_ingredients = commands.spawn(items_bundle.clone()).id();
-
-    let b_towers = commands.spawn(items_bundle.clone()).id();
-    let b_ingredients = commands.spawn(items_bundle).id();
+    let a = commands.spawn(slots_bundle.clone()).id();
+    let b = commands.spawn(slots_bundle).id();
 
     commands.spawn((
         UI_RENDER_LAYER,
@@ -235,23 +507,42 @@ fn split_screen_ui(mut commands: Commands) {
         FocusPolicy::Pass,
         Pickable::IGNORE,
         Children::spawn((
-            Spawn(split_bundle(a_towers, a_ingredients)),
-            Spawn(split_bundle(b_towers, b_ingredients)),
+            Spawn(split_bundle(a, PlayerType::A)),
+            Spawn(split_bundle(b, PlayerType::B)),
         )),
     ));
 
-    commands.insert_resource(InventoryUi {
-        a_towers,
-        a_ingredients,
-        b_towers,
-        b_ingredients,
-    });
+    commands.insert_resource(InventoryUi { a, b });
 }
 
 #[derive(Resource, Debug)]
 pub struct InventoryUi {
-    pub a_towers: Entity,
-    pub a_ingredients: Entity,
-    pub b_towers: Entity,
-    pub b_ingredients: Entity,
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// An [`Inventory`] slot shown in the ui.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InventorySlotUi {
+    player_type: PlayerType,
+    index: usize,
+}
+
+/// Dropping a slot here drops its items into the world.
+#[derive(Component, Debug)]
+pub struct InventoryDropZone(PlayerType);
+
+/// The slot that is currently hovered by the pointer.
+#[derive(Resource, Default, PartialEq, Debug)]
+pub struct HoveredSlot(Option<InventorySlotUi>);
+
+/// The slot that is currently being dragged.
+#[derive(Resource, Default, Debug)]
+pub struct InventoryDrag(Option<DraggedSlot>);
+
+#[derive(Clone, Copy, Debug)]
+struct DraggedSlot {
+    slot: InventorySlotUi,
+    /// Only move half of the stack.
+    split: bool,
 }
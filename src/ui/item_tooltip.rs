@@ -0,0 +1,119 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::ecs::spawn::SpawnIter;
+use bevy::prelude::*;
+
+use crate::inventory::item::ItemRegistry;
+use crate::machine::recipe::RecipeRegistry;
+
+/// Create a tooltip describing the item, its stats and the
+/// recipes that consume it, laid out using the given [`Node`].
+///
+/// Returns `None` if the item does not exist in the registry.
+pub fn item_tooltip_bundle(
+    node: Node,
+    item_id: &str,
+    item_registry: &ItemRegistry,
+    recipe_registry: &RecipeRegistry,
+) -> Option<impl Bundle + use<>> {
+    let item_meta = item_registry.get_item(item_id)?;
+
+    let mut lines = vec![(
+        item_id.replace('_', " ").to_uppercase(),
+        14.0,
+        SLATE_100,
+    )];
+
+    if item_meta.description.is_empty() == false {
+        lines.push((item_meta.description.clone(), 12.0, SLATE_300));
+    }
+
+    lines.push((
+        format!("Stack size: {}", item_meta.max_stack_size),
+        11.0,
+        GRAY_400,
+    ));
+
+    if let Some(stats) = item_meta.tower_stats {
+        lines.push((
+            format!(
+                "Damage: {:.1}  Range: {:.1}  Cooldown: {:.1}s",
+                stats.damage, stats.range, stats.attack_cooldown
+            ),
+            11.0,
+            AMBER_300,
+        ));
+    }
+
+    if let Some(stats) = &item_meta.weapon_stats {
+        lines.push((
+            format!(
+                "Damage: {:.1}  Speed: {:.1}  Cooldown: {:.1}s",
+                stats.damage,
+                stats.projectile_speed,
+                stats.attack_cooldown
+            ),
+            11.0,
+            AMBER_300,
+        ));
+
+        if let Some(ammo) = &stats.ammo {
+            lines.push((
+                format!(
+                    "Ammo: {} x{}  Reload: {:.1}s",
+                    ammo.item_id.replace('_', " "),
+                    ammo.magazine_size,
+                    ammo.reload_duration
+                ),
+                11.0,
+                AMBER_300,
+            ));
+        }
+    }
+
+    if item_meta.modifiers.is_zero() == false {
+        lines.push((
+            format!("As extra: {}", item_meta.modifiers),
+            11.0,
+            AMBER_300,
+        ));
+    }
+
+    if let Some(recipes) = recipe_registry.get() {
+        let mut used_in = recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.uses(item_id))
+            .map(|(_, recipe)| recipe.output_id.replace('_', " "))
+            .collect::<Vec<_>>();
+
+        if used_in.is_empty() == false {
+            used_in.sort();
+            lines.push((
+                format!("Used in: {}", used_in.join(", ")),
+                11.0,
+                EMERALD_300,
+            ));
+        }
+    }
+
+    Some((
+        Node {
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.0)),
+            row_gap: Val::Px(2.0),
+            ..node
+        },
+        BorderRadius::all(Val::Px(6.0)),
+        BackgroundColor(ZINC_900.with_alpha(0.9).into()),
+        Pickable::IGNORE,
+        Children::spawn(SpawnIter(lines.into_iter().map(
+            |(text, font_size, color)| {
+                (
+                    Text::new(text),
+                    TextFont::from_font_size(font_size),
+                    TextColor(color.into()),
+                    Pickable::IGNORE,
+                )
+            },
+        ))),
+    ))
+}
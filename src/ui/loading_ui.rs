@@ -0,0 +1,234 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::asset_pipeline::AssetState;
+use crate::asset_pipeline::loading::{
+    AssetLoadErrors, LoadingProgress,
+};
+use crate::camera_controller::UI_RENDER_LAYER;
+
+use super::widgets::button::{ButtonBackground, LabelButton};
+use super::widgets::progress_bar::ProgressBar;
+
+/// Radians per second of the spinner.
+const SPINNER_SPEED: f32 = 4.0;
+
+pub(super) struct LoadingUiPlugin;
+
+impl Plugin for LoadingUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AssetState::LoadingGltf),
+            spawn_loading_screen,
+        )
+        .add_systems(
+            OnEnter(AssetState::Loaded),
+            despawn_loading_screen,
+        )
+        .add_systems(
+            OnEnter(AssetState::Failed),
+            (despawn_loading_screen, spawn_error_panel),
+        )
+        .add_systems(
+            Update,
+            (
+                (update_loading_screen, rotate_spinner)
+                    .run_if(any_with_component::<LoadingScreen>),
+                update_error_panel
+                    .run_if(in_state(AssetState::Failed)),
+            ),
+        );
+    }
+}
+
+fn spawn_loading_screen(mut commands: Commands) {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(6.0));
+
+    let bg_color = Srgba::hex("BFB190").unwrap();
+    let font_color = Srgba::hex("342C24").unwrap();
+
+    commands.spawn((
+        UI_RENDER_LAYER,
+        LoadingScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        BackgroundColor(bg_color.into()),
+        FocusPolicy::Block,
+        Children::spawn((
+            Spawn((
+                Node {
+                    width: Val::Px(40.0),
+                    height: Val::Px(40.0),
+                    border: UiRect::all(Val::Px(6.0)),
+                    ..default()
+                },
+                BorderColor(font_color.into()),
+                BorderRadius::all(Val::Px(8.0)),
+                LoadingSpinner,
+            )),
+            Spawn((
+                Text::new(loading_label(&AssetState::LoadingGltf)),
+                TextFont::from_font_size(24.0),
+                TextColor(font_color.into()),
+                LoadingLabel,
+            )),
+            Spawn((
+                Node {
+                    width: Val::Px(320.0),
+                    height: Val::Px(12.0),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BackgroundColor(font_color.with_alpha(0.3).into()),
+                RADIUS,
+                ProgressBar::new(AMBER_300, RADIUS),
+                LoadingBar,
+            )),
+        )),
+    ));
+}
+
+fn despawn_loading_screen(
+    mut commands: Commands,
+    q_loading_screens: Query<Entity, With<LoadingScreen>>,
+) {
+    for entity in q_loading_screens.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_loading_screen(
+    mut q_labels: Query<&mut Text, With<LoadingLabel>>,
+    mut q_progress_bars: Query<&mut ProgressBar, With<LoadingBar>>,
+    progress: Res<LoadingProgress>,
+    state: Res<State<AssetState>>,
+) {
+    if state.is_changed() {
+        for mut text in q_labels.iter_mut() {
+            text.0 = loading_label(state.get()).to_string();
+        }
+    }
+
+    if progress.is_changed() == false {
+        return;
+    }
+
+    for mut progress_bar in q_progress_bars.iter_mut() {
+        progress_bar.progress = **progress;
+    }
+}
+
+fn rotate_spinner(
+    mut q_spinners: Query<&mut Transform, With<LoadingSpinner>>,
+    time: Res<Time>,
+) {
+    for mut transform in q_spinners.iter_mut() {
+        transform.rotate_z(time.delta_secs() * SPINNER_SPEED);
+    }
+}
+
+/// Block the game from going any further, the game can't be
+/// played with missing assets.
+fn spawn_error_panel(mut commands: Commands) {
+    const FONT_SIZE: f32 = 20.0;
+
+    let bg_color = Srgba::hex("342C24").unwrap();
+    let font_color = Srgba::hex("BFB190").unwrap();
+
+    let panel = commands
+        .spawn((
+            UI_RENDER_LAYER,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(40.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(bg_color.into()),
+            FocusPolicy::Block,
+            Children::spawn((
+                Spawn((
+                    Text::new("Failed to load assets!"),
+                    TextFont::from_font_size(FONT_SIZE * 1.5),
+                    TextColor(RED_400.into()),
+                )),
+                Spawn((
+                    Text::default(),
+                    TextFont::from_font_size(FONT_SIZE * 0.7),
+                    TextColor(font_color.into()),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    LoadErrorText,
+                )),
+            )),
+        ))
+        .id();
+
+    // Only add exit button for non-web game.
+    #[cfg(not(target_arch = "wasm32"))]
+    commands
+        .spawn((
+            LabelButton::new("Exit")
+                .with_background(ButtonBackground::new(
+                    Srgba::hex("856850").unwrap().with_alpha(0.45),
+                ))
+                .with_text_color(font_color)
+                .with_font_size(FONT_SIZE)
+                .build(),
+            ChildOf(panel),
+        ))
+        .observe(super::exit_on_click);
+}
+
+fn update_error_panel(
+    mut q_texts: Query<(&mut Text, Ref<LoadErrorText>)>,
+    errors: Res<AssetLoadErrors>,
+) {
+    for (mut text, error_text) in q_texts.iter_mut() {
+        if errors.is_changed() == false
+            && error_text.is_added() == false
+        {
+            continue;
+        }
+
+        text.0 = match errors.is_empty() {
+            true => "Check the logs for more details.".to_string(),
+            false => errors.join("\n"),
+        };
+    }
+}
+
+fn loading_label(state: &AssetState) -> &'static str {
+    match state {
+        AssetState::LoadingGltf => "Loading prefabs...",
+        AssetState::LoadingAnimation => "Building animations...",
+        AssetState::LoadingRegistries => "Loading registries...",
+        AssetState::Loaded | AssetState::Failed => "",
+    }
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingSpinner;
+
+#[derive(Component)]
+struct LoadingLabel;
+
+#[derive(Component)]
+struct LoadingBar;
+
+#[derive(Component)]
+struct LoadErrorText;
@@ -0,0 +1,76 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::player::PlayerType;
+use crate::player::lock_on::LockOn;
+
+use super::Screen;
+use super::world_space::WorldUi;
+
+const RETICLE_SIZE: f32 = 36.0;
+
+pub(super) struct LockOnUiPlugin;
+
+impl Plugin for LockOnUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_lock_on_reticle)
+            .add_observer(despawn_lock_on_reticle);
+    }
+}
+
+/// Show a reticle on the locked enemy, only in the viewport of
+/// the player.
+fn spawn_lock_on_reticle(
+    trigger: Trigger<OnInsert, LockOn>,
+    mut commands: Commands,
+    q_players: Query<(&LockOn, &PlayerType)>,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    let player = trigger.target();
+    let (lock_on, player_type) = q_players.get(player)?;
+
+    commands.spawn((
+        LockOnReticle { player },
+        StateScoped(Screen::EnterLevel),
+        UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+        // Stays as readable on far away targets.
+        WorldUi::new(lock_on.entity())
+            .with_world_offset(Vec3::Y)
+            .with_fixed_size(),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(RETICLE_SIZE),
+            height: Val::Px(RETICLE_SIZE),
+            border: UiRect::all(Val::Px(3.0)),
+            ..default()
+        },
+        BorderColor(ROSE_500.with_alpha(0.9).into()),
+        BorderRadius::MAX,
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+    ));
+
+    Ok(())
+}
+
+/// Also runs before a new enemy is locked onto. The reticle might
+/// be gone already along with the enemy.
+fn despawn_lock_on_reticle(
+    trigger: Trigger<OnReplace, LockOn>,
+    mut commands: Commands,
+    q_reticles: Query<(&LockOnReticle, Entity)>,
+) {
+    for (reticle, entity) in q_reticles.iter() {
+        if reticle.player == trigger.target() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+/// World space reticle of a specific player.
+#[derive(Component, Debug)]
+pub struct LockOnReticle {
+    player: Entity,
+}
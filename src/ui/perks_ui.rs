@@ -0,0 +1,174 @@
+use bevy::ecs::spawn::SpawnWith;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::perks::{Perk, PerkProfile};
+
+use super::Screen;
+use super::widgets::button::{ButtonBackground, LabelButton};
+
+const FONT_SIZE: f32 = 30.0;
+
+pub(super) struct PerksUiPlugin;
+
+impl Plugin for PerksUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::Perks), spawn_perks_ui)
+            .add_systems(
+                Update,
+                update_perk_labels.run_if(
+                    in_state(Screen::Perks)
+                        .and(resource_changed::<PerkProfile>),
+                ),
+            );
+    }
+}
+
+fn spawn_perks_ui(mut commands: Commands, profile: Res<PerkProfile>) {
+    let bg_color = Srgba::hex("BFB190").unwrap().with_alpha(0.4);
+    let font_color = Srgba::hex("342C24").unwrap();
+    let buy_color = Srgba::hex("FFDE59").unwrap().with_alpha(0.45);
+    let back_color = Srgba::hex("856850").unwrap().with_alpha(0.45);
+    let perk_labels =
+        Perk::ALL.map(|perk| (perk, perk_label(perk, &profile)));
+
+    commands.spawn((
+        StateScoped(Screen::Perks),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(40.0)),
+            justify_content: JustifyContent::End,
+            align_items: AlignItems::End,
+            ..default()
+        },
+        FocusPolicy::Pass,
+        Pickable::IGNORE,
+        Children::spawn(Spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            BackgroundColor(bg_color.into()),
+            BorderRadius::all(Val::Px(40.0)),
+            Children::spawn((
+                Spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    Text::new("Perks"),
+                    TextFont::from_font_size(FONT_SIZE * 1.5),
+                    TextColor(font_color.into()),
+                )),
+                Spawn((
+                    Text::new(points_label(&profile)),
+                    TextFont::from_font_size(FONT_SIZE * 0.6),
+                    TextColor(font_color.into()),
+                    PerkPointsLabel,
+                )),
+                SpawnWith(move |parent: &mut ChildSpawner| {
+                    for (perk, label) in perk_labels {
+                        parent
+                            .spawn((
+                                LabelButton::new(label)
+                                    .with_background(
+                                        ButtonBackground::new(
+                                            buy_color,
+                                        ),
+                                    )
+                                    .with_text_color(font_color)
+                                    .with_font_size(FONT_SIZE * 0.6)
+                                    .build(),
+                                PerkButton(perk),
+                            ))
+                            .observe(buy_perk_on_click);
+                    }
+
+                    parent
+                        .spawn(
+                            LabelButton::new("Back")
+                                .with_background(
+                                    ButtonBackground::new(back_color),
+                                )
+                                .with_text_color(font_color)
+                                .with_font_size(FONT_SIZE)
+                                .build(),
+                        )
+                        .observe(back_to_menu_on_click);
+                }),
+            )),
+        ))),
+    ));
+}
+
+fn buy_perk_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    q_buttons: Query<&PerkButton>,
+    mut profile: ResMut<PerkProfile>,
+) -> Result {
+    let perk = q_buttons.get(trigger.target())?.0;
+
+    if profile.buy(perk) {
+        info!("Bought {} rank {}.", perk.name(), profile.rank(perk));
+    }
+
+    Ok(())
+}
+
+fn back_to_menu_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Menu);
+}
+
+/// Refresh the points and ranks after buying a perk.
+fn update_perk_labels(
+    profile: Res<PerkProfile>,
+    q_buttons: Query<(&PerkButton, Entity)>,
+    mut q_points: Query<&mut Text, With<PerkPointsLabel>>,
+    q_children: Query<&Children>,
+    mut q_texts: Query<&mut Text, Without<PerkPointsLabel>>,
+) {
+    for mut text in q_points.iter_mut() {
+        text.0 = points_label(&profile);
+    }
+
+    for (button, entity) in q_buttons.iter() {
+        for child in q_children.iter_descendants(entity) {
+            if let Ok(mut text) = q_texts.get_mut(child) {
+                text.0 = perk_label(button.0, &profile);
+            }
+        }
+    }
+}
+
+fn points_label(profile: &PerkProfile) -> String {
+    format!("Points: {}", profile.points)
+}
+
+fn perk_label(perk: Perk, profile: &PerkProfile) -> String {
+    let rank = profile.rank(perk);
+    let max_rank = perk.max_rank();
+
+    match rank >= max_rank {
+        true => format!("{} {rank}/{max_rank} (max)", perk.name()),
+        false => format!(
+            "{} {rank}/{max_rank}: {} ({} pts)",
+            perk.name(),
+            perk.description(),
+            perk.cost()
+        ),
+    }
+}
+
+/// Buys the next rank of the perk on click.
+#[derive(Component, Debug, Clone, Copy)]
+struct PerkButton(Perk);
+
+#[derive(Component, Debug)]
+struct PerkPointsLabel;
@@ -2,10 +2,15 @@ use bevy::color::palettes::tailwind::*;
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
 
-use crate::camera_controller::UI_RENDER_LAYER;
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::enemy::FinalTarget;
+use crate::player::PlayerType;
 use crate::player::player_mark::{PlayerMark, init_player_mark};
+use crate::tower::tower_attack::{Health, MaxHealth};
 
 use super::Screen;
+use super::widgets::progress_bar::ProgressBar;
+use super::world_space::WorldUi;
 
 pub(super) struct PlayerMarkUiPlugin;
 
@@ -21,81 +26,151 @@ impl Plugin for PlayerMarkUiPlugin {
                 in_state(Screen::EnterLevel)
                     .and(resource_changed::<PlayerMark>),
             ),
-        );
+        )
+        .add_observer(spawn_base_marker);
     }
 }
 
-/// Spawn the player mark UI element
+/// Spawn the base health bar at the top of each player's
+/// viewport.
 fn spawn_player_mark_ui(
     mut commands: Commands,
     player_mark: Res<PlayerMark>,
+    q_cameras: QueryCameras<Entity>,
     asset_server: Res<AssetServer>,
-) {
-    commands.spawn((
-        UI_RENDER_LAYER,
-        StateScoped(Screen::EnterLevel),
-        // Root.
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            padding: UiRect::all(Val::Px(20.0)),
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Start,
-            align_items: AlignItems::Center,
-            ..default()
-        },
-        Pickable::IGNORE,
-        FocusPolicy::Pass,
-        Children::spawn(Spawn((
+) -> Result {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(6.0));
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            // Root.
             Node {
-                flex_direction: FlexDirection::Row,
-                align_self: AlignSelf::Center,
-                justify_self: JustifySelf::Center,
-                justify_content: JustifyContent::Center,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
                 align_items: AlignItems::Center,
-                padding: UiRect::all(Val::Px(10.0)),
                 ..default()
             },
             Pickable::IGNORE,
             FocusPolicy::Pass,
-            BackgroundColor(ZINC_900.with_alpha(0.4).into()),
-            BoxShadow::new(
-                ZINC_900.into(),
-                Val::ZERO,
-                Val::ZERO,
-                Val::Px(4.0),
-                Val::Px(8.0),
-            ),
-            BorderRadius::all(Val::Px(8.0)),
-            Children::spawn((
-                Spawn((
-                    Node {
-                        width: Val::Px(30.0),
-                        height: Val::Px(30.0),
-                        margin: UiRect::right(Val::Px(20.0)),
-                        ..default()
-                    },
-                    ImageNode::new(
-                        asset_server.load("icons/heart.png"),
-                    ),
-                )),
-                Spawn((
-                    Text::new(player_mark.to_string()),
-                    PlayerMarkUiText,
+            Children::spawn(Spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                FocusPolicy::Pass,
+                BackgroundColor(ZINC_900.with_alpha(0.4).into()),
+                BoxShadow::new(
+                    ZINC_900.into(),
+                    Val::ZERO,
+                    Val::ZERO,
+                    Val::Px(4.0),
+                    Val::Px(8.0),
+                ),
+                BorderRadius::all(Val::Px(8.0)),
+                Children::spawn((
+                    Spawn((
+                        Node {
+                            width: Val::Px(30.0),
+                            height: Val::Px(30.0),
+                            ..default()
+                        },
+                        ImageNode::new(
+                            asset_server.load("icons/heart.png"),
+                        ),
+                    )),
+                    Spawn((
+                        Node {
+                            width: Val::Px(240.0),
+                            height: Val::Px(16.0),
+                            overflow: Overflow::clip(),
+                            ..default()
+                        },
+                        BackgroundColor(
+                            ZINC_900.with_alpha(0.6).into(),
+                        ),
+                        RADIUS,
+                        ProgressBar::new(RED_500, RADIUS)
+                            .with_init_progress(1.0),
+                        BaseHealthBar,
+                    )),
+                    Spawn((
+                        Text::new(player_mark.to_string()),
+                        PlayerMarkUiText,
+                    )),
                 )),
-            )),
-        ))),
-    ));
+            ))),
+        ));
+    }
+
+    Ok(())
 }
 
-fn update_player_mark_ui(
-    player_mark: Res<PlayerMark>,
-    mut q_text: Query<&mut Text, With<PlayerMarkUiText>>,
+/// Keep the [`FinalTarget`] in sight on both viewports, pointing
+/// towards it from the edge when it is off-screen.
+fn spawn_base_marker(
+    trigger: Trigger<OnAdd, FinalTarget>,
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
 ) -> Result {
-    q_text.single_mut()?.0 = player_mark.to_string();
+    let entity = trigger.target();
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            WorldUi::new(entity)
+                .with_world_offset(Vec3::Y * 3.0)
+                .with_edge_clamp(),
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(RED_500.with_alpha(0.6).into()),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+            Children::spawn(Spawn((
+                Text::new("Base"),
+                TextFont::from_font_size(16.0),
+                TextShadow::default(),
+            ))),
+        ));
+    }
 
     Ok(())
 }
 
+fn update_player_mark_ui(
+    player_mark: Res<PlayerMark>,
+    q_final_targets: Query<(&Health, &MaxHealth), With<FinalTarget>>,
+    mut q_texts: Query<&mut Text, With<PlayerMarkUiText>>,
+    mut q_bars: Query<&mut ProgressBar, With<BaseHealthBar>>,
+) {
+    for mut text in q_texts.iter_mut() {
+        text.0 = player_mark.to_string();
+    }
+
+    let Ok((health, max_health)) = q_final_targets.single() else {
+        return;
+    };
+
+    for mut bar in q_bars.iter_mut() {
+        bar.progress = (health.0 / max_health.0).clamp(0.0, 1.0);
+    }
+}
+
 #[derive(Component)]
 pub struct PlayerMarkUiText;
+
+/// Health of the [`FinalTarget`] in one of the viewports.
+#[derive(Component)]
+pub struct BaseHealthBar;
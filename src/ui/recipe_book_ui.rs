@@ -0,0 +1,246 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::inventory::item::ItemRegistry;
+use crate::machine::recipe::{
+    DiscoveredRecipes, RecipeMeta, RecipeRegistry,
+};
+use crate::player::PlayerType;
+
+use super::Screen;
+
+pub(super) struct RecipeBookUiPlugin;
+
+impl Plugin for RecipeBookUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::EnterLevel),
+            spawn_recipe_books,
+        )
+        .add_systems(
+            Update,
+            (toggle_recipe_books, refresh_recipe_books)
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
+        );
+    }
+}
+
+/// Spawn a hidden recipe book on each player's viewport.
+fn spawn_recipe_books(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(10.0),
+                left: Val::Percent(10.0),
+                width: Val::Percent(80.0),
+                max_height: Val::Percent(70.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(16.0)),
+                row_gap: Val::Px(8.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(ZINC_900.with_alpha(0.9).into()),
+            BorderRadius::all(Val::Px(12.0)),
+            Visibility::Hidden,
+            RecipeBook(player_type),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+        ));
+    }
+
+    Ok(())
+}
+
+fn toggle_recipe_books(
+    q_players: Query<
+        (&PlayerType, &TargetAction),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_books: Query<(&RecipeBook, &mut Visibility)>,
+) {
+    for (player_type, target_action) in q_players.iter() {
+        if q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.just_pressed(&PlayerAction::RecipeBook))
+            == false
+        {
+            continue;
+        }
+
+        for (_, mut viz) in
+            q_books.iter_mut().filter(|(b, _)| b.0 == *player_type)
+        {
+            *viz = match *viz {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+/// Rebuild the content of open recipe books when they are
+/// opened or when a new recipe gets discovered.
+fn refresh_recipe_books(
+    mut commands: Commands,
+    q_books: Query<(Ref<Visibility>, Entity), With<RecipeBook>>,
+    recipe_registry: RecipeRegistry,
+    item_registry: ItemRegistry,
+    discovered_recipes: Res<DiscoveredRecipes>,
+) {
+    let Some(recipes) = recipe_registry.get() else {
+        return;
+    };
+
+    let mut recipe_ids = recipes.keys().collect::<Vec<_>>();
+    recipe_ids.sort();
+
+    for (viz, entity) in q_books.iter() {
+        if *viz == Visibility::Hidden
+            || (viz.is_changed() == false
+                && discovered_recipes.is_changed() == false)
+        {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_child((
+                Text::new(format!(
+                        "Recipe Book ({}/{})",
+                        recipe_ids
+                            .iter()
+                            .filter(|id| discovered_recipes
+                                .contains(**id))
+                            .count(),
+                        recipe_ids.len()
+                    )),
+                TextFont::from_font_size(24.0),
+                TextColor(AMBER_200.into()),
+                Pickable::IGNORE,
+            ));
+
+        for recipe_id in recipe_ids.iter() {
+            let recipe = &recipes[*recipe_id];
+            let discovered = discovered_recipes.contains(*recipe_id);
+
+            let row = recipe_row(
+                commands.reborrow(),
+                recipe,
+                discovered,
+                &item_registry,
+            );
+            commands.entity(entity).add_child(row);
+        }
+    }
+}
+
+/// A single row in the recipe book, undiscovered recipes
+/// are shown as silhouettes.
+fn recipe_row(
+    mut commands: Commands,
+    recipe: &RecipeMeta,
+    discovered: bool,
+    item_registry: &ItemRegistry,
+) -> Entity {
+    let icon = |item_id: &str, size: f32| {
+        let mut image = ImageNode::new(
+            item_registry
+                .get_item(item_id)
+                .map(|i| i.icon.clone())
+                .unwrap_or_default(),
+        );
+        if discovered == false {
+            image.color = Color::BLACK;
+        }
+
+        (
+            Node {
+                width: Val::Px(size),
+                height: Val::Px(size),
+                ..default()
+            },
+            image,
+            Pickable::IGNORE,
+        )
+    };
+
+    let text = |text: String, color: Srgba| {
+        (
+            Text::new(text),
+            TextFont::from_font_size(14.0),
+            TextColor(color.into()),
+            Pickable::IGNORE,
+        )
+    };
+
+    let (output_name, cook_time) = match discovered {
+        true => (
+            format!(
+                "{} x{}",
+                recipe.output_id.replace('_', " "),
+                recipe.output_quantity
+            ),
+            format!("{:.1}s", recipe.cooking_duration),
+        ),
+        false => ("???".to_string(), "?s".to_string()),
+    };
+
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(12.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(SLATE_800.with_alpha(0.6).into()),
+            BorderRadius::all(Val::Px(8.0)),
+            Pickable::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent.spawn(icon(&recipe.output_id, 48.0));
+            parent.spawn(text(output_name, SLATE_100));
+
+            for ingredient in recipe.ingredients.iter() {
+                parent.spawn(icon(&ingredient.item_id, 32.0));
+                parent.spawn(text(
+                    format!("x{}", ingredient.quantity),
+                    SLATE_300,
+                ));
+            }
+
+            parent.spawn(text(cook_time, GRAY_400));
+            // The machine is always shown so that players know
+            // where to experiment.
+            parent.spawn(text(
+                recipe
+                    .compatible_machines
+                    .iter()
+                    .map(|kind| format!("{kind:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                EMERALD_300,
+            ));
+        })
+        .id()
+}
+
+/// The recipe book of a specific player.
+#[derive(Component, Debug)]
+pub struct RecipeBook(PlayerType);
@@ -0,0 +1,177 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{PlayerAction, TargetAction};
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::player::{PlayerType, SelectedCharacter};
+use crate::session_stats::SessionStats;
+use crate::tower::ownership::owner_color;
+
+use super::Screen;
+
+const HEADERS: [&str; 6] =
+    ["", "Kills", "Items", "Recipes", "Towers", "Damage"];
+
+pub(super) struct ScoreboardUiPlugin;
+
+impl Plugin for ScoreboardUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::EnterLevel),
+            spawn_scoreboards,
+        )
+        .add_systems(
+            Update,
+            (show_scoreboards, refresh_scoreboards)
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
+        );
+    }
+}
+
+/// Spawn a hidden scoreboard on each player's viewport.
+fn spawn_scoreboards(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(20.0),
+                left: Val::Percent(15.0),
+                width: Val::Percent(70.0),
+                display: Display::Grid,
+                justify_content: JustifyContent::Center,
+                grid_template_columns: RepeatedGridTrack::auto(
+                    HEADERS.len() as u16,
+                ),
+                column_gap: Val::Px(16.0),
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            BackgroundColor(ZINC_900.with_alpha(0.85).into()),
+            BorderRadius::all(Val::Px(12.0)),
+            Visibility::Hidden,
+            Scoreboard(player_type),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Only show the scoreboards while the action is held.
+fn show_scoreboards(
+    q_players: Query<
+        (&PlayerType, &TargetAction),
+        With<CharacterController>,
+    >,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut q_scoreboards: Query<(&Scoreboard, &mut Visibility)>,
+) {
+    for (player_type, target_action) in q_players.iter() {
+        let pressed = q_actions
+            .get(target_action.get())
+            .is_ok_and(|a| a.pressed(&PlayerAction::Scoreboard));
+
+        for (_, mut viz) in q_scoreboards
+            .iter_mut()
+            .filter(|(s, _)| s.0 == *player_type)
+        {
+            viz.set_if_neq(match pressed {
+                true => Visibility::Inherited,
+                false => Visibility::Hidden,
+            });
+        }
+    }
+}
+
+/// Rebuild the content of shown scoreboards when they are
+/// opened or when the stats change.
+fn refresh_scoreboards(
+    mut commands: Commands,
+    q_scoreboards: Query<(Ref<Visibility>, Entity), With<Scoreboard>>,
+    session_stats: Option<Res<SessionStats>>,
+    selected: Res<SelectedCharacter>,
+) {
+    // Not recorded without the session stats.
+    let Some(session_stats) = session_stats else {
+        return;
+    };
+
+    for (viz, entity) in q_scoreboards.iter() {
+        if *viz == Visibility::Hidden
+            || (viz.is_changed() == false
+                && session_stats.is_changed() == false)
+        {
+            continue;
+        }
+
+        commands.entity(entity).despawn_related::<Children>();
+
+        if session_stats.endless {
+            commands.entity(entity).with_child((
+                cell(
+                    format!(
+                        "Endless wave {}",
+                        session_stats.furthest_wave
+                    ),
+                    AMBER_400.into(),
+                ),
+                Node {
+                    grid_column: GridPlacement::span(
+                        HEADERS.len() as u16
+                    ),
+                    justify_self: JustifySelf::Center,
+                    ..default()
+                },
+            ));
+        }
+
+        for header in HEADERS {
+            commands.entity(entity).with_child(cell(
+                header.to_string(),
+                ZINC_400.into(),
+            ));
+        }
+
+        for player_type in [PlayerType::A, PlayerType::B] {
+            let stats = session_stats.player(player_type);
+            let color = owner_color(player_type);
+
+            for value in [
+                selected.get(player_type).name().to_string(),
+                stats.enemies_killed.to_string(),
+                stats.items_collected.to_string(),
+                stats.recipes_cooked.to_string(),
+                stats.towers_placed.to_string(),
+                format!("{:.0}", stats.damage_dealt),
+            ] {
+                commands
+                    .entity(entity)
+                    .with_child(cell(value, color));
+            }
+        }
+    }
+}
+
+fn cell(text: String, color: Color) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont::from_font_size(20.0),
+        TextColor(color),
+        Pickable::IGNORE,
+    )
+}
+
+/// Scoreboard of the level, held open by the player.
+#[derive(Component)]
+struct Scoreboard(PlayerType);
@@ -0,0 +1,83 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::Stamina;
+use crate::player::PlayerType;
+
+use super::Screen;
+use super::widgets::progress_bar::ProgressBar;
+
+pub(super) struct StaminaUiPlugin;
+
+impl Plugin for StaminaUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::EnterLevel),
+            spawn_stamina_ui,
+        )
+        .add_systems(
+            Update,
+            update_stamina_ui.run_if(in_state(Screen::EnterLevel)),
+        );
+    }
+}
+
+/// Spawn a stamina bar on each player's viewport.
+fn spawn_stamina_ui(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    const RADIUS: BorderRadius = BorderRadius::all(Val::Px(4.0));
+
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                top: Val::Px(20.0),
+                width: Val::Px(200.0),
+                height: Val::Px(12.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(ZINC_900.with_alpha(0.6).into()),
+            RADIUS,
+            ProgressBar::new(LIME_400, RADIUS)
+                .with_init_progress(1.0),
+            StaminaBar(player_type),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+        ));
+    }
+
+    Ok(())
+}
+
+fn update_stamina_ui(
+    q_players: Query<(&Stamina, &PlayerType), Changed<Stamina>>,
+    mut q_bars: Query<(&mut ProgressBar, &StaminaBar)>,
+) {
+    for (stamina, player_type) in q_players.iter() {
+        for (mut progress_bar, stamina_bar) in q_bars.iter_mut() {
+            if stamina_bar.0 != *player_type {
+                continue;
+            }
+
+            progress_bar.progress = stamina.fraction();
+            // Turn red when exhausted.
+            progress_bar.color = if stamina.current > 0.0 {
+                LIME_400.into()
+            } else {
+                RED_500.into()
+            };
+        }
+    }
+}
+
+/// The stamina bar of a specific player.
+#[derive(Component, Debug)]
+pub struct StaminaBar(PlayerType);
@@ -0,0 +1,113 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::player::PlayerType;
+
+use super::Screen;
+
+/// Seconds a toast stays on screen.
+const TOAST_DURATION: f32 = 3.0;
+
+pub(super) struct ToastUiPlugin;
+
+impl Plugin for ToastUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::EnterLevel),
+            spawn_toast_containers,
+        )
+        .add_systems(
+            Update,
+            expire_toasts.run_if(in_state(Screen::EnterLevel)),
+        )
+        .add_observer(show_toast);
+    }
+}
+
+/// Spawn a column of toasts on each player's viewport.
+fn spawn_toast_containers(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(12.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            ToastContainer(player_type),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+        ));
+    }
+
+    Ok(())
+}
+
+fn show_toast(
+    trigger: Trigger<Toast>,
+    mut commands: Commands,
+    q_containers: Query<(&ToastContainer, Entity)>,
+) {
+    let toast = trigger.event();
+
+    let Some((_, container)) =
+        q_containers.iter().find(|(c, _)| c.0 == toast.player_type)
+    else {
+        return;
+    };
+
+    commands.entity(container).with_child((
+        Node {
+            padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(ZINC_900.with_alpha(0.8).into()),
+        BorderRadius::all(Val::Px(6.0)),
+        ToastTimer(Timer::from_seconds(
+            TOAST_DURATION,
+            TimerMode::Once,
+        )),
+        Pickable::IGNORE,
+        Children::spawn(Spawn((
+            Text::new(toast.message.clone()),
+            TextFont::from_font_size(14.0),
+            TextColor(SLATE_100.into()),
+            Pickable::IGNORE,
+        ))),
+    ));
+}
+
+fn expire_toasts(
+    mut commands: Commands,
+    mut q_toasts: Query<(&mut ToastTimer, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut timer, entity) in q_toasts.iter_mut() {
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Show a short message on a player's viewport.
+#[derive(Event, Debug)]
+pub struct Toast {
+    pub player_type: PlayerType,
+    pub message: String,
+}
+
+#[derive(Component, Debug)]
+struct ToastContainer(PlayerType);
+
+#[derive(Component, Deref, DerefMut, Debug)]
+struct ToastTimer(Timer);
@@ -2,9 +2,13 @@ use bevy::color::palettes::css::WHITE;
 use bevy::color::palettes::tailwind::*;
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
+use leafwing_input_manager::prelude::*;
 
+use crate::action::{PlayerAction, binding_labels};
 use crate::camera_controller::UI_RENDER_LAYER;
+use crate::enemy::ready_up::{ReadyUp, is_build_phase};
 use crate::enemy::spawner::{SpawnWave, WaveCountdown};
+use crate::player::companion::Companion;
 use crate::ui::Screen;
 
 pub(super) struct WaveCountdownUiPlugin;
@@ -21,7 +25,8 @@ impl Plugin for WaveCountdownUiPlugin {
                 .run_if(in_state(Screen::EnterLevel))
                 .run_if(
                     resource_changed::<WaveCountdown>
-                        .or(state_changed::<SpawnWave>),
+                        .or(state_changed::<SpawnWave>)
+                        .or(resource_changed::<ReadyUp>),
                 ),
         );
     }
@@ -45,7 +50,7 @@ fn spawn_wave_countdown_ui(mut commands: Commands) {
         FocusPolicy::Pass,
         Children::spawn(Spawn((
             Node {
-                flex_direction: FlexDirection::Row,
+                flex_direction: FlexDirection::Column,
                 align_self: AlignSelf::End,
                 justify_self: JustifySelf::End,
                 justify_content: JustifyContent::Center,
@@ -64,12 +69,20 @@ fn spawn_wave_countdown_ui(mut commands: Commands) {
                 Val::Px(8.0),
             ),
             BorderRadius::all(Val::Px(8.0)),
-            Children::spawn(Spawn((
-                Text::new("Wave 1 - 00:00"),
-                TextFont::from_font_size(24.0),
-                TextColor(WHITE.into()),
-                WaveCountdownText,
-            ))),
+            Children::spawn((
+                Spawn((
+                    Text::new("Wave 1 - 00:00"),
+                    TextFont::from_font_size(24.0),
+                    TextColor(WHITE.into()),
+                    WaveCountdownText,
+                )),
+                Spawn((
+                    Text::default(),
+                    TextFont::from_font_size(16.0),
+                    TextColor(ZINC_300.into()),
+                    ReadyUpText,
+                )),
+            )),
         ))),
     ));
 }
@@ -77,20 +90,49 @@ fn spawn_wave_countdown_ui(mut commands: Commands) {
 fn update_wave_countdown_ui(
     countdown: Res<WaveCountdown>,
     current_wave: Res<State<SpawnWave>>,
+    ready_up: Res<ReadyUp>,
+    // The companion readies up on its own.
+    q_input_maps: Query<&InputMap<PlayerAction>, Without<Companion>>,
     mut q_text: Query<
         (&mut Text, &mut TextColor),
         With<WaveCountdownText>,
     >,
+    mut q_ready_text: Query<
+        &mut Text,
+        (With<ReadyUpText>, Without<WaveCountdownText>),
+    >,
 ) {
+    if let Ok(mut ready_text) = q_ready_text.single_mut() {
+        **ready_text =
+            match is_build_phase(&countdown, *current_wave.get()) {
+                true => {
+                    let mut bindings = q_input_maps
+                        .iter()
+                        .filter_map(|m| {
+                            binding_labels(m, &PlayerAction::Ready)
+                        })
+                        .map(|labels| format!("[{labels}]"))
+                        .collect::<Vec<_>>();
+                    // Both players on the same kind of input.
+                    bindings.dedup();
+
+                    format!(
+                        "Ready {}/2 - {}",
+                        ready_up.count(),
+                        bindings.join(" / ")
+                    )
+                }
+                false => String::new(),
+            };
+    }
+
     let Ok((mut text, mut text_color)) = q_text.single_mut() else {
         return;
     };
 
-    let wave_name = match current_wave.get() {
-        SpawnWave::None => "Waiting",
-        SpawnWave::One => "Wave 1",
-        SpawnWave::Two => "Wave 2",
-        SpawnWave::Three => "Wave 3",
+    let wave_name = match current_wave.number() {
+        Some(number) => format!("Wave {number}"),
+        None => "Waiting".to_string(),
     };
 
     let remaining = countdown.duration() - countdown.elapsed();
@@ -98,7 +140,7 @@ fn update_wave_countdown_ui(
 
     if remaining_seconds <= 0.0 {
         // When countdown finished, just show wave name
-        **text = wave_name.to_string();
+        **text = wave_name;
         text_color.0 = RED_400.into();
     } else {
         // Show countdown timer
@@ -120,3 +162,7 @@ fn update_wave_countdown_ui(
 
 #[derive(Component)]
 pub struct WaveCountdownText;
+
+/// Number of players ready during the build phase.
+#[derive(Component)]
+pub struct ReadyUpText;
@@ -0,0 +1,179 @@
+use bevy::color::palettes::css::WHITE;
+use bevy::color::palettes::tailwind::*;
+use bevy::ecs::spawn::SpawnIter;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::asset_pipeline::PrefabId;
+use crate::camera_controller::UI_RENDER_LAYER;
+use crate::enemy::ready_up::is_build_phase;
+use crate::enemy::spawner::{
+    SpawnSchedule, SpawnWave, WaveCountdown, reset_spawn_schedules,
+};
+use crate::ui::Screen;
+
+pub(super) struct WavePreviewUiPlugin;
+
+impl Plugin for WavePreviewUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(Screen::EnterLevel),
+            spawn_wave_preview_ui,
+        )
+        .add_systems(
+            Update,
+            (
+                rebuild_wave_preview
+                    .after(reset_spawn_schedules)
+                    .run_if(state_changed::<SpawnWave>),
+                show_wave_preview.run_if(
+                    resource_changed::<WaveCountdown>
+                        .or(state_changed::<SpawnWave>),
+                ),
+            )
+                .chain()
+                .run_if(in_state(Screen::EnterLevel)),
+        );
+    }
+}
+
+/// Spawn the wave preview below the wave countdown.
+fn spawn_wave_preview_ui(mut commands: Commands) {
+    commands.spawn((
+        UI_RENDER_LAYER,
+        StateScoped(Screen::EnterLevel),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            top: Val::Px(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(6.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        FocusPolicy::Pass,
+        BackgroundColor(ZINC_900.with_alpha(0.4).into()),
+        BorderRadius::all(Val::Px(8.0)),
+        WavePreview,
+    ));
+}
+
+/// Total spawn rate (enemies per second) of the wave for each
+/// danger icon shown.
+const DANGER_RATE_STEP: f32 = 1.0;
+const MAX_DANGER: usize = 3;
+
+/// List the enemies left to spawn in the upcoming wave by type.
+fn rebuild_wave_preview(
+    mut commands: Commands,
+    q_preview: Query<Entity, With<WavePreview>>,
+    q_schedules: Query<&SpawnSchedule>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(preview) = q_preview.single() else {
+        return;
+    };
+
+    commands.entity(preview).despawn_related::<Children>();
+
+    let mut counts = Vec::<(PrefabId, usize)>::new();
+    let mut spawn_rate = 0.0;
+    for schedule in q_schedules.iter().filter(|s| s.remaining() > 0) {
+        spawn_rate += schedule.spawn_rate();
+        match counts
+            .iter_mut()
+            .find(|(id, _)| *id == schedule.enemy())
+        {
+            Some((_, count)) => *count += schedule.remaining(),
+            None => {
+                counts.push((schedule.enemy(), schedule.remaining()))
+            }
+        }
+    }
+
+    let danger = ((spawn_rate / DANGER_RATE_STEP).floor() as usize)
+        .min(MAX_DANGER);
+
+    let rows = counts
+        .into_iter()
+        .map(|(enemy, count)| {
+            (
+                Node {
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                Children::spawn((
+                    SpawnIter(
+                        enemy_icon(enemy)
+                            .map(|path| icon(asset_server.load(path)))
+                            .into_iter(),
+                    ),
+                    Spawn((
+                        Text::new(format!("x{count}")),
+                        TextFont::from_font_size(16.0),
+                        TextColor(WHITE.into()),
+                    )),
+                )),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let danger_icon = asset_server.load("icons/danger.png");
+    commands.entity(preview).insert(Children::spawn((
+        SpawnIter(rows.into_iter()),
+        Spawn((
+            Node {
+                column_gap: Val::Px(4.0),
+                ..default()
+            },
+            Children::spawn(SpawnIter(
+                std::iter::repeat_n(danger_icon, danger).map(icon),
+            )),
+        )),
+    )));
+}
+
+/// Only preview during the build phase.
+fn show_wave_preview(
+    mut q_preview: Query<&mut Visibility, With<WavePreview>>,
+    countdown: Res<WaveCountdown>,
+    current_wave: Res<State<SpawnWave>>,
+) {
+    let Ok(mut visibility) = q_preview.single_mut() else {
+        return;
+    };
+
+    visibility.set_if_neq(
+        match is_build_phase(&countdown, *current_wave.get()) {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        },
+    );
+}
+
+fn icon(image: Handle<Image>) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(24.0),
+            height: Val::Px(24.0),
+            ..default()
+        },
+        ImageNode::new(image),
+    )
+}
+
+/// Icon of each enemy prefab shown in the wave preview.
+fn enemy_icon(enemy: PrefabId) -> Option<&'static str> {
+    match enemy {
+        PrefabId::MouseA => Some("icons/mouse.png"),
+        _ => None,
+    }
+}
+
+/// Root of the upcoming wave's enemy composition.
+#[derive(Component)]
+struct WavePreview;
@@ -0,0 +1,124 @@
+use bevy::color::palettes::tailwind::*;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::camera_controller::split_screen::QueryCameras;
+use crate::character_controller::CharacterController;
+use crate::inventory::Inventory;
+use crate::player::player_attack::{
+    EquippedWeapon, Magazine, PlayerWeapon, Reloading,
+};
+use crate::player::{PlayerType, QueryPlayers};
+
+use super::Screen;
+
+pub(super) struct WeaponUiPlugin;
+
+impl Plugin for WeaponUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::EnterLevel), spawn_weapon_ui)
+            .add_systems(
+                Update,
+                update_weapon_ui.run_if(in_state(Screen::EnterLevel)),
+            );
+    }
+}
+
+/// Spawn the equipped weapon and ammo label below the stamina bar of each
+/// player's viewport.
+fn spawn_weapon_ui(
+    mut commands: Commands,
+    q_cameras: QueryCameras<Entity>,
+) -> Result {
+    for player_type in [PlayerType::A, PlayerType::B] {
+        commands.spawn((
+            StateScoped(Screen::EnterLevel),
+            UiTargetCamera(q_cameras.get(player_type.camera_type())?),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                top: Val::Px(40.0),
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(ZINC_900.with_alpha(0.6).into()),
+            BorderRadius::all(Val::Px(4.0)),
+            Pickable::IGNORE,
+            FocusPolicy::Pass,
+            Children::spawn(Spawn((
+                WeaponLabel(player_type),
+                Text::new(weapon_label(
+                    &EquippedWeapon::default(),
+                    None,
+                )),
+                TextFont::from_font_size(14.0),
+                TextColor(AMBER_300.into()),
+            ))),
+        ));
+    }
+
+    Ok(())
+}
+
+fn update_weapon_ui(
+    q_weapons: Query<(
+        &EquippedWeapon,
+        &PlayerWeapon,
+        &Magazine,
+        Has<Reloading>,
+        &PlayerType,
+    )>,
+    q_inventories: QueryPlayers<
+        &Inventory,
+        With<CharacterController>,
+    >,
+    mut q_labels: Query<(&mut Text, &WeaponLabel)>,
+) {
+    for (equipped, weapon, magazine, reloading, player_type) in
+        q_weapons.iter()
+    {
+        let ammo = weapon.ammo.as_ref().map(|ammo| {
+            if reloading {
+                return "Reloading...".to_string();
+            }
+
+            let reserve = q_inventories
+                .get(*player_type)
+                .map_or(0, |inventory| {
+                    inventory.count(&ammo.item_id)
+                });
+            format!(
+                "{}/{} (+{reserve} {})",
+                magazine.rounds,
+                ammo.magazine_size,
+                ammo.item_id.replace('_', " ")
+            )
+        });
+        let new_text = weapon_label(equipped, ammo);
+
+        for (mut text, label) in q_labels.iter_mut() {
+            // Avoid relayouts when nothing changed.
+            if label.0 == *player_type && text.0 != new_text {
+                text.0 = new_text.clone();
+            }
+        }
+    }
+}
+
+fn weapon_label(
+    equipped: &EquippedWeapon,
+    ammo: Option<String>,
+) -> String {
+    let name = equipped
+        .as_deref()
+        .map(|id| id.replace('_', " "))
+        .unwrap_or_else(|| "starter".to_string());
+
+    match ammo {
+        Some(ammo) => format!("Weapon: {name}  Ammo: {ammo}"),
+        None => format!("Weapon: {name}"),
+    }
+}
+
+#[derive(Component)]
+struct WeaponLabel(PlayerType);
@@ -1,6 +1,14 @@
 use bevy::prelude::*;
 use bevy::ui::UiSystem;
 
+/// Distance from the camera at which [`WorldUi`]s are at their
+/// full size by default.
+const SCALE_DISTANCE: f32 = 12.0;
+/// Smallest scale of far away [`WorldUi`]s.
+const MIN_SCALE: f32 = 0.4;
+/// Gap between edge clamped [`WorldUi`]s and the viewport's edges.
+const EDGE_PADDING: f32 = 24.0;
+
 pub(super) struct WorldSpaceUiPlugin;
 
 impl Plugin for WorldSpaceUiPlugin {
@@ -10,22 +18,63 @@ impl Plugin for WorldSpaceUiPlugin {
             update_world_ui
                 .after(UiSystem::Layout)
                 .after(TransformSystem::TransformPropagate),
-        );
+        )
+        .add_observer(spawn_edge_arrow);
     }
 }
 
+/// Give edge clamped [`WorldUi`]s an arrow towards their target.
+fn spawn_edge_arrow(
+    trigger: Trigger<OnAdd, WorldUi>,
+    mut commands: Commands,
+    q_world_uis: Query<&WorldUi>,
+) -> Result {
+    let entity = trigger.target();
+
+    if q_world_uis.get(entity)?.edge_clamp == false {
+        return Ok(());
+    }
+
+    commands.entity(entity).with_child((
+        EdgeArrow,
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Text::new(">"),
+        TextFont::from_font_size(24.0),
+        TextShadow::default(),
+        // Hidden instead of removed from the layout to keep its size.
+        Visibility::Hidden,
+    ));
+
+    Ok(())
+}
+
 fn update_world_ui(
     q_camera_transform: Query<(&GlobalTransform, &Camera)>,
     q_global_transforms: Query<&GlobalTransform, Without<Camera>>,
     mut q_world_space_uis: Query<(
         &WorldUi,
         &mut Node,
+        &mut Transform,
         &ComputedNode,
         &UiTargetCamera,
+        Option<&Children>,
     )>,
+    mut q_arrows: Query<
+        (&mut Node, &mut Transform, &mut Visibility, &ComputedNode),
+        (With<EdgeArrow>, Without<WorldUi>),
+    >,
 ) {
-    for (world_ui, mut node, computed_node, target_camera) in
-        q_world_space_uis.iter_mut()
+    for (
+        world_ui,
+        mut node,
+        mut transform,
+        computed_node,
+        target_camera,
+        children,
+    ) in q_world_space_uis.iter_mut()
     {
         let Ok((camera_transform, camera)) =
             q_camera_transform.get(target_camera.entity())
@@ -48,34 +97,142 @@ fn update_world_ui(
             continue;
         };
 
-        node.display = Display::DEFAULT;
-
         let rect = camera.logical_viewport_rect().unwrap_or_default();
+        let world_position =
+            target_transform.translation() + world_ui.world_offset;
 
-        match camera.world_to_viewport(
-            camera_transform,
-            target_transform.translation() + world_ui.world_offset,
-        ) {
-            Ok(viewport) => {
-                let viewport =
-                    viewport + world_ui.ui_offset - rect.min;
-                let half_size = computed_node.size * 0.5;
-
-                node.left = Val::Px(viewport.x - half_size.x);
-                node.top = Val::Px(viewport.y - half_size.y);
-            }
-            Err(err) => {
-                // Hide the node..
-                node.display = Display::None;
-                debug!(
-                    "Unable to get viewport location for target: {} ({err})",
-                    world_ui.target
+        let scale = match world_ui.scale_distance {
+            Some(scale_distance) => (scale_distance
+                / camera_transform
+                    .translation()
+                    .distance(world_position))
+            .clamp(MIN_SCALE, 1.0),
+            None => 1.0,
+        };
+        let half_size = computed_node.size
+            * computed_node.inverse_scale_factor
+            * 0.5;
+        let scaled_half_size = half_size * scale;
+
+        let projected = camera
+            .world_to_viewport(camera_transform, world_position)
+            .map(|viewport| viewport + world_ui.ui_offset - rect.min);
+
+        let (position, arrow_direction) = match world_ui.edge_clamp {
+            true => {
+                let bounds = Rect::from_corners(
+                    Vec2::splat(EDGE_PADDING) + scaled_half_size,
+                    rect.size() - EDGE_PADDING - scaled_half_size,
                 );
+
+                match projected {
+                    Ok(viewport) if bounds.contains(viewport) => {
+                        (viewport, None)
+                    }
+                    _ => {
+                        let direction = match projected {
+                            Ok(viewport) => {
+                                viewport - bounds.center()
+                            }
+                            // Points behind the camera don't project,
+                            // go by the direction in view space.
+                            Err(_) => {
+                                let view = camera_transform
+                                    .affine()
+                                    .inverse()
+                                    .transform_point3(world_position);
+                                Vec2::new(view.x, -view.y)
+                            }
+                        }
+                        .normalize_or(Vec2::Y);
+
+                        (
+                            clamp_to_edge(bounds, direction),
+                            Some(direction),
+                        )
+                    }
+                }
             }
+            false => match projected {
+                // Cull by the camera's frustum, so that the node
+                // never spills over to the other viewport.
+                Ok(viewport)
+                    if Rect::from_corners(
+                        -scaled_half_size,
+                        rect.size() + scaled_half_size,
+                    )
+                    .contains(viewport) =>
+                {
+                    (viewport, None)
+                }
+                Ok(_) => {
+                    node.display = Display::None;
+                    continue;
+                }
+                Err(err) => {
+                    // Hide the node..
+                    node.display = Display::None;
+                    debug!(
+                        "Unable to get viewport location for target: {} ({err})",
+                        world_ui.target
+                    );
+                    continue;
+                }
+            },
+        };
+
+        node.display = Display::DEFAULT;
+        node.left = Val::Px(position.x - half_size.x);
+        node.top = Val::Px(position.y - half_size.y);
+        transform.scale = Vec3::new(scale, scale, 1.0);
+
+        let Some(children) = children else {
+            continue;
+        };
+
+        let mut arrows = q_arrows.iter_many_mut(children);
+        while let Some((
+            mut arrow_node,
+            mut arrow_transform,
+            mut arrow_viz,
+            arrow_computed_node,
+        )) = arrows.fetch_next()
+        {
+            let Some(direction) = arrow_direction else {
+                *arrow_viz = Visibility::Hidden;
+                continue;
+            };
+
+            let arrow_half_size = arrow_computed_node.size
+                * arrow_computed_node.inverse_scale_factor
+                * 0.5;
+            // Just outside of the node, towards the target.
+            let arrow_position = clamp_to_edge(
+                Rect::from_center_half_size(
+                    half_size,
+                    half_size + arrow_half_size,
+                ),
+                direction,
+            );
+
+            *arrow_viz = Visibility::Inherited;
+            arrow_node.left =
+                Val::Px(arrow_position.x - arrow_half_size.x);
+            arrow_node.top =
+                Val::Px(arrow_position.y - arrow_half_size.y);
+            arrow_transform.rotation =
+                Quat::from_rotation_z(direction.to_angle());
         }
     }
 }
 
+/// Point on the edge of the bounds in the direction from its center.
+fn clamp_to_edge(bounds: Rect, direction: Vec2) -> Vec2 {
+    // Axes the direction doesn't move along reach infinitely far.
+    let reach = bounds.half_size() / direction.abs();
+    bounds.center() + direction * reach.min_element()
+}
+
 /// Attached to the target entity of [`WorldUi`]s.
 #[derive(Component, Deref, Default, Debug)]
 #[relationship_target(relationship = WorldUi, linked_spawn)]
@@ -83,6 +240,9 @@ pub struct RelatedWorldUis(Vec<Entity>);
 
 /// Component for ui nodes to be transformed into world space
 /// based on the target entity's [`GlobalTransform`].
+///
+/// Nodes are hidden when their target is outside of the target
+/// camera's view, unless they are [`WorldUi::edge_clamp`]ed.
 #[derive(Component)]
 #[component(immutable)]
 #[relationship(relationship_target = RelatedWorldUis)]
@@ -91,6 +251,12 @@ pub struct WorldUi {
     pub target: Entity,
     pub ui_offset: Vec2,
     pub world_offset: Vec3,
+    /// Distance from the camera at which the node is at its full
+    /// size, shrinking further away. `None` to keep a fixed size.
+    pub scale_distance: Option<f32>,
+    /// Keep the node on the viewport's edge with an arrow towards
+    /// the target when it is off-screen, for important markers.
+    pub edge_clamp: bool,
 }
 
 impl WorldUi {
@@ -99,6 +265,8 @@ impl WorldUi {
             target,
             ui_offset: Vec2::ZERO,
             world_offset: Vec3::ZERO,
+            scale_distance: Some(SCALE_DISTANCE),
+            edge_clamp: false,
         }
     }
 
@@ -113,4 +281,19 @@ impl WorldUi {
         self.ui_offset = offset;
         self
     }
+
+    /// Keep the size regardless of the distance.
+    pub fn with_fixed_size(mut self) -> Self {
+        self.scale_distance = None;
+        self
+    }
+
+    pub fn with_edge_clamp(mut self) -> Self {
+        self.edge_clamp = true;
+        self
+    }
 }
+
+/// Points towards the target of an edge clamped [`WorldUi`].
+#[derive(Component)]
+struct EdgeArrow;
@@ -0,0 +1,409 @@
+//! Reusable particle effects, spawned from the [`GameplayEvent`]s,
+//! by triggering a [`Vfx`] burst or with a [`VfxEmitter`] that
+//! keeps bursting from its entity.
+//!
+//! Particles are small unlit spheres that fly off, fall or rise
+//! with the [`VfxPreset`]'s gravity and shrink away.
+
+use bevy::color::palettes::tailwind::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+
+use crate::gameplay_event::{GameplayEvent, GameplayEventAppExt};
+use crate::ui::Screen;
+
+pub mod trail;
+
+pub(super) struct VfxPlugin;
+
+impl Plugin for VfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(trail::TrailPlugin);
+
+        app.add_systems(Startup, setup_vfx_assets)
+            .add_systems(
+                Update,
+                (emit_vfx, update_vfx_particles)
+                    .run_if(in_state(Screen::EnterLevel)),
+            )
+            .add_gameplay_subscriber(spawn_gameplay_vfx)
+            .add_observer(burst_vfx);
+    }
+}
+
+fn setup_vfx_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let materials = VfxPreset::ALL
+        .into_iter()
+        .map(|preset| {
+            let color = preset.emitter().color;
+            let material = materials.add(StandardMaterial {
+                base_color: color.into(),
+                alpha_mode: match color.alpha < 1.0 {
+                    true => AlphaMode::Blend,
+                    false => AlphaMode::Opaque,
+                },
+                unlit: true,
+                ..default()
+            });
+
+            (preset, material)
+        })
+        .collect();
+
+    commands.insert_resource(VfxAssets {
+        mesh: meshes.add(Sphere::new(1.0)),
+        materials,
+    });
+}
+
+/// Match the notable gameplay moments with their effects.
+fn spawn_gameplay_vfx(
+    mut commands: Commands,
+    mut events: EventReader<GameplayEvent>,
+    transform_helper: TransformHelper,
+) {
+    for event in events.read() {
+        let (preset, entity) = match event {
+            GameplayEvent::ProjectileFired { projectile, .. } => {
+                (VfxPreset::MuzzleFlash, *projectile)
+            }
+            GameplayEvent::DamageDealt { target, .. } => {
+                (VfxPreset::HitSpark, *target)
+            }
+            GameplayEvent::EnemyKilled { enemy, .. } => {
+                (VfxPreset::DeathPoof, *enemy)
+            }
+            GameplayEvent::CookingStarted { machine, .. } => {
+                commands.entity(*machine).try_insert(
+                    VfxEmitter::new(VfxPreset::Steam)
+                        .with_offset(Vec3::Y * 2.0),
+                );
+                continue;
+            }
+            GameplayEvent::RecipeCooked { machine, .. } => {
+                commands.entity(*machine).try_remove::<VfxEmitter>();
+                (VfxPreset::Steam, *machine)
+            }
+            GameplayEvent::TowerPlaced { tower, .. } => {
+                (VfxPreset::PlacementDust, *tower)
+            }
+            GameplayEvent::WaveStarted { .. }
+            | GameplayEvent::ItemCollected { .. }
+            | GameplayEvent::TowerDestroyed { .. }
+            | GameplayEvent::LevelCleared => continue,
+        };
+
+        // Freshly spawned entities are not propagated yet.
+        let Ok(transform) =
+            transform_helper.compute_global_transform(entity)
+        else {
+            continue;
+        };
+
+        commands.trigger(Vfx {
+            preset,
+            translation: transform.translation(),
+        });
+    }
+}
+
+fn emit_vfx(
+    mut commands: Commands,
+    mut q_emitters: Query<(&mut VfxEmitter, &GlobalTransform)>,
+    time: Res<Time>,
+) {
+    for (mut emitter, global_transform) in q_emitters.iter_mut() {
+        if emitter.timer.tick(time.delta()).just_finished() == false {
+            continue;
+        }
+
+        commands.trigger(Vfx {
+            preset: emitter.preset,
+            translation: global_transform.translation()
+                + emitter.offset,
+        });
+    }
+}
+
+/// Spawn the particles of a [`Vfx`] burst.
+fn burst_vfx(
+    trigger: Trigger<Vfx>,
+    mut commands: Commands,
+    assets: Res<VfxAssets>,
+    mut sequence: Local<u32>,
+) {
+    let Vfx {
+        preset,
+        translation,
+    } = *trigger.event();
+    let emitter = preset.emitter();
+
+    let Some(material) = assets.materials.get(&preset) else {
+        return;
+    };
+
+    for _ in 0..emitter.count {
+        // Spread evenly around with the golden angle.
+        let i = *sequence as f32;
+        *sequence = sequence.wrapping_add(1);
+        let angle = i * 2.399_963;
+        let variation = (i * 0.618_034).fract();
+
+        let direction = Vec3::new(
+            angle.cos() * emitter.spread,
+            1.0,
+            angle.sin() * emitter.spread,
+        )
+        .normalize();
+
+        commands.spawn((
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(translation)
+                .with_scale(Vec3::splat(emitter.size)),
+            VfxParticle {
+                velocity: direction
+                    * emitter.speed
+                    * (0.6 + variation * 0.4),
+                gravity: emitter.gravity,
+                size: emitter.size,
+                growth: emitter.growth,
+                lifetime: Timer::from_seconds(
+                    emitter.lifetime * (0.8 + variation * 0.4),
+                    TimerMode::Once,
+                ),
+            },
+            StateScoped(Screen::EnterLevel),
+        ));
+    }
+}
+
+/// Move the particles, grow and then shrink them away.
+fn update_vfx_particles(
+    mut commands: Commands,
+    mut q_particles: Query<(
+        &mut VfxParticle,
+        &mut Transform,
+        Entity,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut particle, mut transform, entity) in
+        q_particles.iter_mut()
+    {
+        if particle.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation += particle.velocity * dt;
+
+        let t = particle.lifetime.fraction();
+        transform.scale = Vec3::splat(
+            particle.size
+                * (1.0 + t * particle.growth)
+                * (1.0 - t * t),
+        );
+    }
+}
+
+/// Burst the preset once at the translation.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Vfx {
+    pub preset: VfxPreset,
+    pub translation: Vec3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VfxPreset {
+    MuzzleFlash,
+    HitSpark,
+    /// Burning food.
+    Smoke,
+    /// Cooking food.
+    Steam,
+    DeathPoof,
+    PlacementDust,
+    /// Footstep on a hard surface.
+    StepDust,
+    /// Footstep on a baked surface.
+    StepCrumbs,
+}
+
+impl VfxPreset {
+    pub const ALL: [Self; 8] = [
+        Self::MuzzleFlash,
+        Self::HitSpark,
+        Self::Smoke,
+        Self::Steam,
+        Self::DeathPoof,
+        Self::PlacementDust,
+        Self::StepDust,
+        Self::StepCrumbs,
+    ];
+
+    fn emitter(&self) -> EmitterConfig {
+        match self {
+            VfxPreset::MuzzleFlash => EmitterConfig {
+                count: 4,
+                interval: 0.1,
+                color: AMBER_300,
+                size: 0.12,
+                lifetime: 0.12,
+                speed: 2.0,
+                spread: 0.5,
+                gravity: 0.0,
+                growth: 0.0,
+            },
+            VfxPreset::HitSpark => EmitterConfig {
+                count: 6,
+                interval: 0.1,
+                color: ORANGE_400,
+                size: 0.05,
+                lifetime: 0.25,
+                speed: 4.0,
+                spread: 2.0,
+                gravity: 9.8,
+                growth: 0.0,
+            },
+            VfxPreset::Smoke => EmitterConfig {
+                count: 1,
+                interval: 0.25,
+                color: GRAY_500.with_alpha(0.6),
+                size: 0.2,
+                lifetime: 1.5,
+                speed: 1.2,
+                spread: 0.1,
+                gravity: 0.0,
+                growth: 2.0,
+            },
+            VfxPreset::Steam => EmitterConfig {
+                count: 1,
+                interval: 0.2,
+                color: SLATE_100.with_alpha(0.5),
+                size: 0.15,
+                lifetime: 1.0,
+                speed: 1.5,
+                spread: 0.15,
+                gravity: 0.0,
+                growth: 1.5,
+            },
+            VfxPreset::DeathPoof => EmitterConfig {
+                count: 10,
+                interval: 0.1,
+                color: STONE_200.with_alpha(0.8),
+                size: 0.18,
+                lifetime: 0.5,
+                speed: 2.5,
+                spread: 1.5,
+                // Puffs up a little.
+                gravity: -1.0,
+                growth: 1.0,
+            },
+            VfxPreset::PlacementDust => EmitterConfig {
+                count: 12,
+                interval: 0.1,
+                color: AMBER_200.with_alpha(0.7),
+                size: 0.15,
+                lifetime: 0.6,
+                speed: 3.0,
+                // Mostly along the ground.
+                spread: 4.0,
+                gravity: 2.0,
+                growth: 1.0,
+            },
+            VfxPreset::StepDust => EmitterConfig {
+                count: 3,
+                interval: 0.1,
+                color: STONE_300.with_alpha(0.5),
+                size: 0.06,
+                lifetime: 0.4,
+                speed: 0.8,
+                // Mostly along the ground.
+                spread: 4.0,
+                gravity: 1.0,
+                growth: 1.5,
+            },
+            VfxPreset::StepCrumbs => EmitterConfig {
+                count: 4,
+                interval: 0.1,
+                color: AMBER_600,
+                size: 0.04,
+                lifetime: 0.35,
+                speed: 1.5,
+                spread: 2.0,
+                gravity: 9.8,
+                growth: 0.0,
+            },
+        }
+    }
+}
+
+struct EmitterConfig {
+    /// Particles per burst.
+    count: usize,
+    /// Seconds between bursts of a [`VfxEmitter`].
+    interval: f32,
+    color: Srgba,
+    /// Radius of each particle.
+    size: f32,
+    lifetime: f32,
+    speed: f32,
+    /// Horizontal spread of the directions, 0 to fly straight up.
+    spread: f32,
+    /// Downwards acceleration, negative to rise.
+    gravity: f32,
+    /// Growth before shrinking away, 0 to only shrink.
+    growth: f32,
+}
+
+/// Keeps bursting the preset from the entity every
+/// [`EmitterConfig::interval`].
+#[derive(Component, Debug)]
+pub struct VfxEmitter {
+    pub preset: VfxPreset,
+    /// Offset from the entity's translation.
+    pub offset: Vec3,
+    timer: Timer,
+}
+
+impl VfxEmitter {
+    pub fn new(preset: VfxPreset) -> Self {
+        Self {
+            preset,
+            offset: Vec3::ZERO,
+            timer: Timer::from_seconds(
+                preset.emitter().interval,
+                TimerMode::Repeating,
+            ),
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+#[derive(Component, Debug)]
+struct VfxParticle {
+    velocity: Vec3,
+    gravity: f32,
+    size: f32,
+    growth: f32,
+    lifetime: Timer,
+}
+
+#[derive(Resource)]
+struct VfxAssets {
+    mesh: Handle<Mesh>,
+    materials: HashMap<VfxPreset, Handle<StandardMaterial>>,
+}
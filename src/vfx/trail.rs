@@ -0,0 +1,243 @@
+//! Ribbon trails following fast moving entities (e.g.
+//! [`Projectile`](crate::tower::Projectile)s), so that they stay
+//! readable from far away in split-screen.
+//!
+//! The ribbon is made of two crossed strips, one flat and one
+//! upright, giving it width from every camera angle without
+//! facing each viewport.
+
+use std::collections::VecDeque;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::view::NoFrustumCulling;
+
+use crate::ui::Screen;
+
+/// Points closer than this to the last one replace it instead.
+const MIN_SEGMENT_LENGTH: f32 = 0.05;
+
+pub(super) struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_trail_material)
+            .add_systems(
+                PostUpdate,
+                update_trail_ribbons
+                    .after(TransformSystem::TransformPropagate),
+            )
+            .add_observer(spawn_trail_ribbon);
+    }
+}
+
+fn setup_trail_material(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(TrailMaterial(materials.add(
+        StandardMaterial {
+            base_color: Color::WHITE,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        },
+    )));
+}
+
+/// The ribbon lives in world space, so that it stays behind
+/// after the [`Trail`] is gone.
+fn spawn_trail_ribbon(
+    trigger: Trigger<OnAdd, Trail>,
+    mut commands: Commands,
+    q_trails: Query<&Trail>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material: Res<TrailMaterial>,
+) -> Result {
+    let source = trigger.target();
+    let trail = *q_trails.get(source)?;
+
+    commands.spawn((
+        Mesh3d(meshes.add(Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        ))),
+        MeshMaterial3d(material.0.clone()),
+        Transform::IDENTITY,
+        TrailRibbon {
+            source,
+            trail,
+            points: VecDeque::new(),
+        },
+        // The bounds change every frame.
+        NoFrustumCulling,
+        NotShadowCaster,
+        StateScoped(Screen::EnterLevel),
+    ));
+
+    Ok(())
+}
+
+/// Follow the sources, age the points and rebuild the ribbons.
+fn update_trail_ribbons(
+    mut commands: Commands,
+    mut q_ribbons: Query<(&mut TrailRibbon, &Mesh3d, Entity)>,
+    q_sources: Query<&GlobalTransform, With<Trail>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+
+    for (mut ribbon, mesh, entity) in q_ribbons.iter_mut() {
+        let lifetime = ribbon.trail.lifetime;
+
+        if let Ok(global_transform) = q_sources.get(ribbon.source) {
+            let translation = global_transform.translation();
+
+            if ribbon.points.back().is_some_and(|(point, _)| {
+                point.distance(translation) < MIN_SEGMENT_LENGTH
+            }) {
+                ribbon.points.pop_back();
+            }
+            ribbon.points.push_back((translation, now));
+        }
+
+        while ribbon
+            .points
+            .front()
+            .is_some_and(|(_, time)| now - time > lifetime)
+        {
+            ribbon.points.pop_front();
+        }
+
+        // The source is gone and the trail faded out.
+        if ribbon.points.is_empty() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            ribbon.build_mesh(mesh, now);
+        }
+    }
+}
+
+/// Leave a ribbon behind the entity.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Trail {
+    pub color: Color,
+    /// Width at the head, tapering off towards the tail.
+    pub width: f32,
+    /// Seconds each point of the trail lasts.
+    pub lifetime: f32,
+}
+
+impl Trail {
+    pub fn new(color: impl Into<Color>) -> Self {
+        Self {
+            color: color.into(),
+            ..default()
+        }
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+}
+
+impl Default for Trail {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE.with_alpha(0.8),
+            width: 0.15,
+            lifetime: 0.2,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct TrailRibbon {
+    /// The entity with the [`Trail`].
+    source: Entity,
+    trail: Trail,
+    /// World space positions and the time they were added, from
+    /// the tail to the head.
+    points: VecDeque<(Vec3, f32)>,
+}
+
+impl TrailRibbon {
+    fn build_mesh(&self, mesh: &mut Mesh, now: f32) {
+        let color = self.trail.color.to_linear();
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+
+        let points = self.points.iter().collect::<Vec<_>>();
+        if points.len() >= 2 {
+            for upright in [false, true] {
+                let start = positions.len() as u32;
+
+                for (i, (point, time)) in points.iter().enumerate() {
+                    // Direction towards the head.
+                    let next =
+                        points[(i + 1).min(points.len() - 1)].0;
+                    let previous = points[i.saturating_sub(1)].0;
+                    let direction =
+                        (next - previous).normalize_or(Vec3::X);
+
+                    let flat_side = direction
+                        .cross(Vec3::Y)
+                        .normalize_or(Vec3::Z);
+                    let side = match upright {
+                        true => direction
+                            .cross(flat_side)
+                            .normalize_or(Vec3::Y),
+                        false => flat_side,
+                    };
+
+                    let fade = 1.0
+                        - ((now - time) / self.trail.lifetime)
+                            .clamp(0.0, 1.0);
+                    let offset = side * self.trail.width * 0.5 * fade;
+
+                    positions.push((*point + offset).to_array());
+                    positions.push((*point - offset).to_array());
+
+                    let color = color.with_alpha(color.alpha * fade);
+                    colors.push(color.to_f32_array());
+                    colors.push(color.to_f32_array());
+                }
+
+                for i in 0..points.len() as u32 - 1 {
+                    let a = start + i * 2;
+                    indices.extend([
+                        a,
+                        a + 1,
+                        a + 2,
+                        a + 1,
+                        a + 3,
+                        a + 2,
+                    ]);
+                }
+            }
+        }
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh.insert_indices(Indices::U32(indices));
+    }
+}
+
+/// Shared by every ribbon, tinted by the vertex colors.
+#[derive(Resource)]
+struct TrailMaterial(Handle<StandardMaterial>);
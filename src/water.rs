@@ -0,0 +1,208 @@
+//! Water volumes slow down the characters and enemies wading
+//! through them, characters that stay under for too long drown and
+//! get [`Downed`].
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::character_controller::{
+    CharacterController, check_grounded,
+};
+use crate::enemy::Enemy;
+use crate::player::downed::Downed;
+use crate::tower::tower_attack::Invulnerable;
+
+/// Height above the origin that needs to be in the water to wade.
+const WADE_HEIGHT: f32 = 0.2;
+/// Height above the origin that needs to be in the water to be
+/// submerged.
+const HEAD_HEIGHT: f32 = 1.0;
+
+pub(super) struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (detect_water, update_breath)
+                .chain()
+                .before(check_grounded),
+        );
+
+        app.register_type::<WaterVolume>().register_type::<Breath>();
+    }
+}
+
+/// Insert [`InWater`] on everything inside a [`WaterVolume`].
+fn detect_water(
+    mut commands: Commands,
+    q_volumes: Query<(&WaterVolume, &Collider, &GlobalTransform)>,
+    q_waders: Query<
+        (&GlobalTransform, Option<&InWater>, Entity),
+        Or<(With<CharacterController>, With<Enemy>)>,
+    >,
+) {
+    for (global_transform, in_water, entity) in q_waders.iter() {
+        let translation = global_transform.translation();
+        let contains = |collider: &Collider,
+                        volume_transform: &GlobalTransform,
+                        height: f32| {
+            collider.contains_point(
+                volume_transform.translation(),
+                volume_transform.rotation(),
+                translation + Vec3::Y * height,
+            )
+        };
+
+        let wading = q_volumes.iter().find(|(_, collider, gt)| {
+            contains(collider, gt, WADE_HEIGHT)
+        });
+
+        let new_in_water =
+            wading.map(|(volume, collider, gt)| InWater {
+                volume: *volume,
+                submerged: contains(collider, gt, HEAD_HEIGHT),
+            });
+
+        if new_in_water.as_ref() == in_water {
+            continue;
+        }
+
+        match new_in_water {
+            Some(new_in_water) => {
+                commands.entity(entity).insert(new_in_water);
+            }
+            None => {
+                commands.entity(entity).remove::<InWater>();
+            }
+        }
+    }
+}
+
+/// Hold the breath while submerged, getting [`Downed`] and pulled
+/// back ashore once it runs out.
+fn update_breath(
+    mut commands: Commands,
+    mut q_characters: Query<
+        (
+            &mut Breath,
+            &mut Position,
+            &mut LinearVelocity,
+            Option<&InWater>,
+            Has<Invulnerable>,
+            Entity,
+        ),
+        Without<Downed>,
+    >,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (
+        mut breath,
+        mut position,
+        mut linear_velocity,
+        in_water,
+        invulnerable,
+        entity,
+    ) in q_characters.iter_mut()
+    {
+        if in_water.is_some_and(|w| w.submerged) == false {
+            // Not worth updating the breath ui for.
+            if in_water.is_none() {
+                breath.bypass_change_detection().ashore =
+                    Some(position.0);
+            }
+
+            if breath.current < breath.max {
+                breath.current = (breath.current
+                    + breath.regen_rate * dt)
+                    .min(breath.max);
+            }
+            continue;
+        }
+
+        breath.current = (breath.current - dt).max(0.0);
+        // Hold off drowning while invulnerable (e.g. dashing).
+        if breath.current > 0.0 || invulnerable {
+            continue;
+        }
+
+        info!("{entity} drowned.");
+        commands.entity(entity).insert(Downed::default());
+        breath.current = breath.max;
+        if let Some(ashore) = breath.ashore {
+            position.0 = ashore;
+            linear_velocity.0 = Vec3::ZERO;
+        }
+    }
+}
+
+/// Water that slows down whatever is inside, add it (through
+/// `bevy_skein`) next to a collider in the level scene.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[require(Sensor, CollisionLayers::NONE)]
+#[reflect(Component, Default)]
+pub struct WaterVolume {
+    /// Multiplier of the gravity of characters.
+    pub gravity_scale: f32,
+    /// Damping applied on top of the characters' own, including
+    /// the vertical movement (should be below 1.0).
+    pub damping: f32,
+    /// Multiplier of the maximum movement speed.
+    pub speed_scale: f32,
+}
+
+impl Default for WaterVolume {
+    fn default() -> Self {
+        Self {
+            gravity_scale: 0.3,
+            damping: 0.9,
+            speed_scale: 0.5,
+        }
+    }
+}
+
+/// Inserted on characters and enemies inside a [`WaterVolume`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct InWater {
+    pub volume: WaterVolume,
+    /// The head is under water too.
+    pub submerged: bool,
+}
+
+/// Seconds a character can stay submerged before drowning.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct Breath {
+    pub current: f32,
+    pub max: f32,
+    /// Breath regenerated per second while above the water.
+    pub regen_rate: f32,
+    /// Last position out of the water, drowned characters are
+    /// pulled back to it.
+    #[reflect(ignore)]
+    ashore: Option<Vec3>,
+}
+
+impl Breath {
+    /// Remaining breath in percentage from 0 - 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+
+        self.current / self.max
+    }
+}
+
+impl Default for Breath {
+    fn default() -> Self {
+        Self {
+            current: 8.0,
+            max: 8.0,
+            regen_rate: 4.0,
+            ashore: None,
+        }
+    }
+}